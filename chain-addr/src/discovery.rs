@@ -0,0 +1,252 @@
+//! Addresses of an Ed25519-BIP32 account.
+//!
+//! The account keys follow the `m/1852'/1815'/account'/role/index` layout:
+//! role 0 is the external chain, role 1 the internal (change) chain and role
+//! 2 the staking keys. Everything under the account key is soft derived, so
+//! the addresses can be computed from the account public key alone.
+
+use crate::{Address, Discrimination, Kind, KindType};
+use chain_crypto::derivation::{
+    DerivationError, DerivationIndex, DerivationPath, DerivationScheme,
+};
+use chain_crypto::{Ed25519, Ed25519Bip32, PublicKey};
+
+pub const PURPOSE: u32 = 1852;
+pub const COIN_TYPE: u32 = 1815;
+const STAKING_ROLE: u32 = 2;
+
+/// The path of the account key `account'`
+pub fn account_path(account: u32) -> Option<DerivationPath> {
+    Some(DerivationPath::from(vec![
+        DerivationIndex::hardened(PURPOSE)?,
+        DerivationIndex::hardened(COIN_TYPE)?,
+        DerivationIndex::hardened(account)?,
+    ]))
+}
+
+/// The chain of the spending keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Chain {
+    External,
+    Internal,
+}
+
+impl Chain {
+    fn role(self) -> u32 {
+        match self {
+            Chain::External => 0,
+            Chain::Internal => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryError {
+    IndexInvalid(u32),
+    KindUnsupported(KindType),
+    Derivation(DerivationError),
+}
+
+impl std::fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DiscoveryError::IndexInvalid(index) => write!(f, "invalid address index {}", index),
+            DiscoveryError::KindUnsupported(kind) => {
+                write!(f, "cannot derive addresses of kind {:?}", kind)
+            }
+            DiscoveryError::Derivation(_) => write!(f, "key derivation failed"),
+        }
+    }
+}
+
+impl std::error::Error for DiscoveryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DiscoveryError::Derivation(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<DerivationError> for DiscoveryError {
+    fn from(error: DerivationError) -> Self {
+        DiscoveryError::Derivation(error)
+    }
+}
+
+/// Compute the addresses of an account from its public key
+pub struct AccountDiscovery {
+    account: PublicKey<Ed25519Bip32>,
+    scheme: DerivationScheme,
+    discrimination: Discrimination,
+}
+
+impl AccountDiscovery {
+    pub fn new(
+        account: PublicKey<Ed25519Bip32>,
+        scheme: DerivationScheme,
+        discrimination: Discrimination,
+    ) -> Self {
+        AccountDiscovery {
+            account,
+            scheme,
+            discrimination,
+        }
+    }
+
+    fn key(&self, role: u32, index: u32) -> Result<PublicKey<Ed25519>, DiscoveryError> {
+        let index = DerivationIndex::soft(index).ok_or(DiscoveryError::IndexInvalid(index))?;
+        let path = DerivationPath::from(vec![DerivationIndex::from(role), index]);
+        Ok(self.account.derive_path(self.scheme, &path)?.to_ed25519())
+    }
+
+    /// The staking key of the account, at `2/0`
+    pub fn stake_key(&self) -> Result<PublicKey<Ed25519>, DiscoveryError> {
+        self.key(STAKING_ROLE, 0)
+    }
+
+    /// A single address with the spending key at `chain/index`
+    pub fn single(&self, chain: Chain, index: u32) -> Result<Address, DiscoveryError> {
+        Ok(Address(
+            self.discrimination,
+            Kind::Single(self.key(chain.role(), index)?),
+        ))
+    }
+
+    /// A group address with the spending key at `chain/index`, delegating
+    /// with the staking key of the account
+    pub fn group(&self, chain: Chain, index: u32) -> Result<Address, DiscoveryError> {
+        Ok(Address(
+            self.discrimination,
+            Kind::Group(self.key(chain.role(), index)?, self.stake_key()?),
+        ))
+    }
+
+    /// An account address with the staking key at `2/index`
+    pub fn account(&self, index: u32) -> Result<Address, DiscoveryError> {
+        Ok(Address(
+            self.discrimination,
+            Kind::Account(self.key(STAKING_ROLE, index)?),
+        ))
+    }
+
+    /// The address of kind `kind` at `index`, the chain being ignored for
+    /// the account addresses
+    pub fn address(
+        &self,
+        kind: KindType,
+        chain: Chain,
+        index: u32,
+    ) -> Result<Address, DiscoveryError> {
+        match kind {
+            KindType::Single => self.single(chain, index),
+            KindType::Group => self.group(chain, index),
+            KindType::Account => self.account(index),
+            KindType::Multisig | KindType::Script => Err(DiscoveryError::KindUnsupported(kind)),
+        }
+    }
+
+    /// Scan the addresses of kind `kind` from index 0, stopping after
+    /// `gap_limit` consecutive addresses for which `is_used` is false.
+    ///
+    /// Returns the used addresses along with their index.
+    pub fn discover<F>(
+        &self,
+        kind: KindType,
+        chain: Chain,
+        gap_limit: u32,
+        mut is_used: F,
+    ) -> Result<Vec<(u32, Address)>, DiscoveryError>
+    where
+        F: FnMut(&Address) -> bool,
+    {
+        let mut used = Vec::new();
+        let mut gap = 0;
+        let mut index = 0;
+        while gap < gap_limit && index < DerivationIndex::HARDENED_THRESHOLD {
+            let address = self.address(kind, chain, index)?;
+            if is_used(&address) {
+                used.push((index, address));
+                gap = 0;
+            } else {
+                gap += 1;
+            }
+            index += 1;
+        }
+        Ok(used)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chain_crypto::{KeyPair, SecretKey};
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    fn discovery(root: &SecretKey<Ed25519Bip32>) -> AccountDiscovery {
+        let account = root
+            .derive_path(DerivationScheme::V2, &account_path(0).unwrap())
+            .to_public();
+        AccountDiscovery::new(account, DerivationScheme::V2, Discrimination::Test)
+    }
+
+    #[proptest]
+    fn addresses_match_secret_derivation(root: KeyPair<Ed25519Bip32>, index: u16) {
+        let index = index as u32;
+        let discovery = discovery(root.private_key());
+        let secret = |path: String| {
+            root.private_key()
+                .derive_path(DerivationScheme::V2, &path.parse().unwrap())
+                .to_public()
+                .to_ed25519()
+        };
+
+        let spending = secret(format!("m/1852'/1815'/0'/1/{}", index));
+        let stake = secret("m/1852'/1815'/0'/2/0".to_owned());
+        prop_assert_eq!(
+            discovery.single(Chain::Internal, index).unwrap(),
+            Address(Discrimination::Test, Kind::Single(spending.clone()))
+        );
+        prop_assert_eq!(
+            discovery.group(Chain::Internal, index).unwrap(),
+            Address(Discrimination::Test, Kind::Group(spending, stake))
+        );
+        prop_assert_eq!(
+            discovery.account(index).unwrap(),
+            Address(
+                Discrimination::Test,
+                Kind::Account(secret(format!("m/1852'/1815'/0'/2/{}", index)))
+            )
+        );
+    }
+
+    #[proptest]
+    fn discover_stops_at_gap_limit(root: KeyPair<Ed25519Bip32>) {
+        let discovery = discovery(root.private_key());
+        let used: Vec<_> = [0, 3, 7]
+            .iter()
+            .map(|index| discovery.single(Chain::External, *index).unwrap())
+            .collect();
+        // index 12 is more than 4 addresses after the last used one
+        let unreachable = discovery.single(Chain::External, 12).unwrap();
+
+        let found = discovery
+            .discover(KindType::Single, Chain::External, 4, |address| {
+                used.contains(address) || address == &unreachable
+            })
+            .unwrap();
+        prop_assert_eq!(
+            found,
+            vec![
+                (0, used[0].clone()),
+                (3, used[1].clone()),
+                (7, used[2].clone())
+            ]
+        );
+        prop_assert_eq!(
+            discovery.discover(KindType::Multisig, Chain::External, 4, |_| true),
+            Err(DiscoveryError::KindUnsupported(KindType::Multisig))
+        );
+    }
+}
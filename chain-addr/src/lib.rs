@@ -38,6 +38,8 @@ use chain_crypto::{Ed25519, PublicKey, PublicKeyError};
 use chain_core::mempack::{ReadBuf, ReadError, Readable};
 use chain_core::property::{self, Serialize as PropertySerialize};
 
+pub mod discovery;
+
 #[cfg(any(test, feature = "property-test-api"))]
 mod testing;
 #[cfg(any(test, feature = "property-test-api"))]
@@ -0,0 +1,41 @@
+use chain_crypto::{Ed25519, Ed25519BatchVerifier, PublicKey, SecretKey, Signature, Verification};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand_core::OsRng;
+
+type Signed = (PublicKey<Ed25519>, Signature<[u8], Ed25519>, [u8; 64]);
+
+fn signatures(n: usize) -> Vec<Signed> {
+    (0..n)
+        .map(|i| {
+            let sk = SecretKey::<Ed25519>::generate(OsRng);
+            let msg = [i as u8; 64];
+            (sk.to_public(), sk.sign_slice(&msg), msg)
+        })
+        .collect()
+}
+
+fn verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ed25519_verify");
+    for n in [16, 64, 256].iter() {
+        let signatures = signatures(*n);
+        group.bench_with_input(BenchmarkId::new("single", n), &signatures, |b, sigs| {
+            b.iter(|| {
+                sigs.iter()
+                    .all(|(pk, sig, msg)| sig.verify_slice(pk, msg) == Verification::Success)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", n), &signatures, |b, sigs| {
+            b.iter(|| {
+                let mut batch = Ed25519BatchVerifier::with_capacity(sigs.len());
+                let queued = sigs
+                    .iter()
+                    .all(|(pk, sig, msg)| batch.queue_slice(pk, sig, msg));
+                queued && batch.verify(OsRng) == Verification::Success
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, verify);
+criterion_main!(benches);
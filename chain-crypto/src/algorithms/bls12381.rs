@@ -0,0 +1,487 @@
+//! BLS signatures on the BLS12-381 curve
+//!
+//! Public keys are points of G1 (48 bytes compressed) and signatures are
+//! points of G2 (96 bytes compressed), following the proof of possession
+//! ciphersuite `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_` of the IETF
+//! BLS signature draft.
+//!
+//! Signatures can be aggregated into a single signature, of many messages
+//! (`verify_aggregate`) or of the same message (`verify_multi_signature`).
+//! Aggregation is only safe against rogue key attacks when every public key
+//! involved comes with a verified `ProofOfPossession`.
+
+use crate::bech32::{self, Bech32};
+use crate::key::{
+    AsymmetricKey, AsymmetricPublicKey, PublicKey, PublicKeyError, SecretKey, SecretKeyError,
+    SecretKeySizeStatic,
+};
+use crate::sign::{
+    Signature, SignatureError, SigningAlgorithm, Verification, VerificationAlgorithm,
+};
+
+use bls12_381::{
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+const SECRET_KEY_LENGTH: usize = 32;
+const PUBLIC_KEY_LENGTH: usize = 48;
+const SIGNATURE_LENGTH: usize = 96;
+
+const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+const PROOF_OF_POSSESSION_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// BLS12-381 Signing Algorithm
+pub struct Bls12381;
+
+#[derive(Clone)]
+pub struct Priv {
+    bytes: [u8; SECRET_KEY_LENGTH],
+    scalar: Scalar,
+}
+
+#[derive(Clone)]
+pub struct Pub {
+    bytes: [u8; PUBLIC_KEY_LENGTH],
+    point: G1Affine,
+}
+
+#[derive(Clone)]
+pub struct Sig {
+    bytes: [u8; SIGNATURE_LENGTH],
+    point: G2Affine,
+}
+
+impl Priv {
+    fn from_scalar(scalar: Scalar) -> Self {
+        Priv {
+            bytes: scalar.to_bytes(),
+            scalar,
+        }
+    }
+}
+
+impl Pub {
+    fn from_point(point: G1Affine) -> Self {
+        Pub {
+            bytes: point.to_compressed(),
+            point,
+        }
+    }
+}
+
+impl Sig {
+    fn from_point(point: G2Affine) -> Self {
+        Sig {
+            bytes: point.to_compressed(),
+            point,
+        }
+    }
+}
+
+impl AsRef<[u8]> for Priv {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for Pub {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for Sig {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl PartialEq for Pub {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes[..] == other.bytes[..]
+    }
+}
+
+impl Eq for Pub {}
+
+impl Hash for Pub {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state)
+    }
+}
+
+fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Affine {
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, dst).into()
+}
+
+/// check `e(pk[0], h[0]) * ... * e(pk[n], h[n]) == e(g1, sig)`
+fn pairing_check(terms: &[(G1Affine, G2Affine)], signature: &G2Affine) -> Verification {
+    let minus_g1 = -G1Affine::generator();
+    let prepared: Vec<_> = terms
+        .iter()
+        .map(|(pk, h)| (pk, G2Prepared::from(*h)))
+        .chain(std::iter::once((&minus_g1, G2Prepared::from(*signature))))
+        .collect();
+    let terms: Vec<_> = prepared.iter().map(|(pk, h)| (*pk, h)).collect();
+    (multi_miller_loop(&terms).final_exponentiation() == Gt::identity()).into()
+}
+
+impl AsymmetricPublicKey for Bls12381 {
+    type Public = Pub;
+
+    const PUBLIC_BECH32_HRP: &'static str = "bls12381_pk";
+    const PUBLIC_KEY_SIZE: usize = PUBLIC_KEY_LENGTH;
+
+    fn public_from_binary(data: &[u8]) -> Result<Self::Public, PublicKeyError> {
+        if data.len() != PUBLIC_KEY_LENGTH {
+            return Err(PublicKeyError::SizeInvalid);
+        }
+        let mut buf = [0; PUBLIC_KEY_LENGTH];
+        buf.copy_from_slice(data);
+        // the decoding checks that the point is in the prime order subgroup
+        let point: Option<G1Affine> = G1Affine::from_compressed(&buf).into();
+        match point {
+            Some(point) if !bool::from(point.is_identity()) => Ok(Pub { bytes: buf, point }),
+            _ => Err(PublicKeyError::StructureInvalid),
+        }
+    }
+}
+
+impl AsymmetricKey for Bls12381 {
+    type Secret = Priv;
+    type PubAlg = Bls12381;
+
+    const SECRET_BECH32_HRP: &'static str = "bls12381_sk";
+
+    fn generate<T: RngCore + CryptoRng>(mut rng: T) -> Self::Secret {
+        loop {
+            let mut bytes = [0u8; 64];
+            rng.fill_bytes(&mut bytes);
+            let scalar = Scalar::from_bytes_wide(&bytes);
+            if scalar != Scalar::zero() {
+                return Priv::from_scalar(scalar);
+            }
+        }
+    }
+
+    fn compute_public(key: &Self::Secret) -> <Self::PubAlg as AsymmetricPublicKey>::Public {
+        Pub::from_point((G1Affine::generator() * key.scalar).into())
+    }
+
+    fn secret_from_binary(data: &[u8]) -> Result<Self::Secret, SecretKeyError> {
+        if data.len() != SECRET_KEY_LENGTH {
+            return Err(SecretKeyError::SizeInvalid);
+        }
+        let mut buf = [0; SECRET_KEY_LENGTH];
+        buf.copy_from_slice(data);
+        let scalar: Option<Scalar> = Scalar::from_bytes(&buf).into();
+        match scalar {
+            Some(scalar) if scalar != Scalar::zero() => Ok(Priv { bytes: buf, scalar }),
+            _ => Err(SecretKeyError::StructureInvalid),
+        }
+    }
+}
+
+impl SecretKeySizeStatic for Bls12381 {
+    const SECRET_KEY_SIZE: usize = SECRET_KEY_LENGTH;
+}
+
+impl VerificationAlgorithm for Bls12381 {
+    type Signature = Sig;
+
+    const SIGNATURE_SIZE: usize = SIGNATURE_LENGTH;
+    const SIGNATURE_BECH32_HRP: &'static str = "bls12381_sig";
+
+    fn signature_from_bytes(data: &[u8]) -> Result<Self::Signature, SignatureError> {
+        if data.len() != SIGNATURE_LENGTH {
+            return Err(SignatureError::SizeInvalid {
+                expected: SIGNATURE_LENGTH,
+                got: data.len(),
+            });
+        }
+        let mut buf = [0; SIGNATURE_LENGTH];
+        buf.copy_from_slice(data);
+        let point: Option<G2Affine> = G2Affine::from_compressed(&buf).into();
+        match point {
+            Some(point) => Ok(Sig { bytes: buf, point }),
+            None => Err(SignatureError::StructureInvalid),
+        }
+    }
+
+    fn verify_bytes(
+        pubkey: &Self::Public,
+        signature: &Self::Signature,
+        msg: &[u8],
+    ) -> Verification {
+        pairing_check(
+            &[(pubkey.point, hash_to_g2(msg, SIGNATURE_DST))],
+            &signature.point,
+        )
+    }
+}
+
+impl SigningAlgorithm for Bls12381 {
+    fn sign(key: &Self::Secret, msg: &[u8]) -> Sig {
+        Sig::from_point((hash_to_g2(msg, SIGNATURE_DST) * key.scalar).into())
+    }
+}
+
+/// Aggregate signatures into a single signature of the same size. Returns
+/// `None` if there are no signatures.
+pub fn aggregate_signatures<'a, T, I>(signatures: I) -> Option<Signature<T, Bls12381>>
+where
+    T: ?Sized + 'a,
+    I: IntoIterator<Item = &'a Signature<T, Bls12381>>,
+{
+    let mut signatures = signatures.into_iter().peekable();
+    signatures.peek()?;
+    let aggregate = signatures.fold(G2Projective::identity(), |acc, signature| {
+        acc + G2Projective::from(signature.signdata.point)
+    });
+    Some(Signature {
+        signdata: Sig::from_point(aggregate.into()),
+        phantom: PhantomData,
+    })
+}
+
+/// Aggregate public keys into the public key of the multi-signatures of
+/// their owners. Returns `None` if there are no public keys, or if they
+/// cancel each other.
+pub fn aggregate_public_keys<'a, I>(public_keys: I) -> Option<PublicKey<Bls12381>>
+where
+    I: IntoIterator<Item = &'a PublicKey<Bls12381>>,
+{
+    let aggregate = public_keys
+        .into_iter()
+        .fold(G1Projective::identity(), |acc, public_key| {
+            acc + G1Projective::from(public_key.0.point)
+        });
+    if bool::from(aggregate.is_identity()) {
+        return None;
+    }
+    Some(PublicKey(Pub::from_point(aggregate.into())))
+}
+
+/// Verify the aggregate of the signatures of each object by its public key.
+/// The public keys need to have a verified proof of possession.
+#[must_use]
+pub fn verify_aggregate<T>(
+    signed: &[(&PublicKey<Bls12381>, &T)],
+    signature: &Signature<T, Bls12381>,
+) -> Verification
+where
+    T: ?Sized + AsRef<[u8]>,
+{
+    if signed.is_empty() {
+        return Verification::Failed;
+    }
+    let terms: Vec<_> = signed
+        .iter()
+        .map(|(public_key, object)| {
+            (
+                public_key.0.point,
+                hash_to_g2(object.as_ref(), SIGNATURE_DST),
+            )
+        })
+        .collect();
+    pairing_check(&terms, &signature.signdata.point)
+}
+
+/// Verify the aggregate of the signatures of the same object by all the
+/// public keys. The public keys need to have a verified proof of possession.
+#[must_use]
+pub fn verify_multi_signature<'a, T, I>(
+    public_keys: I,
+    signature: &Signature<T, Bls12381>,
+    object: &T,
+) -> Verification
+where
+    T: ?Sized + AsRef<[u8]>,
+    I: IntoIterator<Item = &'a PublicKey<Bls12381>>,
+{
+    match aggregate_public_keys(public_keys) {
+        Some(public_key) => signature.verify(&public_key, object),
+        None => Verification::Failed,
+    }
+}
+
+/// Proof that the owner of a public key knows its secret key, to be checked
+/// before aggregating signatures of this public key.
+#[derive(Clone)]
+pub struct ProofOfPossession(Sig);
+
+impl ProofOfPossession {
+    pub const SIZE: usize = SIGNATURE_LENGTH;
+
+    pub fn generate(secret_key: &SecretKey<Bls12381>) -> Self {
+        let public_key = secret_key.to_public();
+        let h = hash_to_g2(public_key.as_ref(), PROOF_OF_POSSESSION_DST);
+        ProofOfPossession(Sig::from_point((h * secret_key.0.scalar).into()))
+    }
+
+    #[must_use]
+    pub fn verify(&self, public_key: &PublicKey<Bls12381>) -> Verification {
+        pairing_check(
+            &[(
+                public_key.0.point,
+                hash_to_g2(public_key.as_ref(), PROOF_OF_POSSESSION_DST),
+            )],
+            &self.0.point,
+        )
+    }
+
+    pub fn from_binary(data: &[u8]) -> Result<Self, SignatureError> {
+        Bls12381::signature_from_bytes(data).map(ProofOfPossession)
+    }
+}
+
+impl AsRef<[u8]> for ProofOfPossession {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl std::fmt::Debug for ProofOfPossession {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.as_ref()))
+    }
+}
+
+impl Bech32 for ProofOfPossession {
+    const BECH32_HRP: &'static str = "bls12381_pop";
+    const BYTES_LEN: usize = Self::SIZE;
+
+    fn try_from_bech32_str(bech32_str: &str) -> Result<Self, bech32::Error> {
+        let bytes = bech32::try_from_bech32_to_bytes::<Self>(bech32_str)?;
+        Self::from_binary(&bytes).map_err(bech32::Error::data_invalid)
+    }
+
+    fn to_bech32_str(&self) -> String {
+        bech32::to_bech32_from_bytes::<Self>(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::key::KeyPair;
+    use crate::sign::test::{keypair_signing_ko, keypair_signing_ok};
+
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn sign_ok(input: (KeyPair<Bls12381>, Vec<u8>)) {
+        prop_assert!(keypair_signing_ok(input))
+    }
+
+    #[proptest]
+    fn sign_ko(input: (KeyPair<Bls12381>, KeyPair<Bls12381>, Vec<u8>)) {
+        prop_assert!(keypair_signing_ko(input))
+    }
+
+    #[proptest]
+    fn aggregate_signatures_of_different_messages(
+        keys: (KeyPair<Bls12381>, KeyPair<Bls12381>, KeyPair<Bls12381>),
+        msgs: (Vec<u8>, Vec<u8>, Vec<u8>),
+    ) {
+        let signed = [
+            (keys.0.public_key(), &msgs.0),
+            (keys.1.public_key(), &msgs.1),
+            (keys.2.public_key(), &msgs.2),
+        ];
+        let signatures = [
+            keys.0.private_key().sign(&msgs.0),
+            keys.1.private_key().sign(&msgs.1),
+            keys.2.private_key().sign(&msgs.2),
+        ];
+        let aggregate = aggregate_signatures(signatures.iter()).unwrap();
+        prop_assert_eq!(verify_aggregate(&signed, &aggregate), Verification::Success);
+        prop_assert_eq!(
+            verify_aggregate(&signed[..2], &aggregate),
+            Verification::Failed
+        );
+
+        let wrong_msg = [msgs.2.as_slice(), b"wrong"].concat();
+        let wrong_signed = [signed[0], signed[1], (keys.2.public_key(), &wrong_msg)];
+        prop_assert_eq!(
+            verify_aggregate(&wrong_signed, &aggregate),
+            Verification::Failed
+        );
+    }
+
+    #[proptest]
+    fn multi_signature(
+        keys: (KeyPair<Bls12381>, KeyPair<Bls12381>, KeyPair<Bls12381>),
+        msg: Vec<u8>,
+    ) {
+        let public_keys = [
+            keys.0.public_key().clone(),
+            keys.1.public_key().clone(),
+            keys.2.public_key().clone(),
+        ];
+        let signatures = [
+            keys.0.private_key().sign(&msg),
+            keys.1.private_key().sign(&msg),
+            keys.2.private_key().sign(&msg),
+        ];
+        let aggregate = aggregate_signatures(signatures.iter()).unwrap();
+        prop_assert_eq!(
+            verify_multi_signature(public_keys.iter(), &aggregate, &msg),
+            Verification::Success
+        );
+        prop_assert_eq!(
+            verify_multi_signature(public_keys[1..].iter(), &aggregate, &msg),
+            Verification::Failed
+        );
+    }
+
+    #[proptest]
+    fn proof_of_possession(keys: (KeyPair<Bls12381>, KeyPair<Bls12381>)) {
+        let proof = ProofOfPossession::generate(keys.0.private_key());
+        prop_assert_eq!(proof.verify(keys.0.public_key()), Verification::Success);
+        prop_assert_eq!(proof.verify(keys.1.public_key()), Verification::Failed);
+
+        // a proof of possession is not a signature of the public key
+        let signature = keys.0.private_key().sign(keys.0.public_key());
+        let proof = ProofOfPossession::from_binary(signature.as_ref()).unwrap();
+        prop_assert_eq!(proof.verify(keys.0.public_key()), Verification::Failed);
+    }
+
+    #[test]
+    fn empty_aggregates() {
+        let signatures: [Signature<[u8], Bls12381>; 0] = [];
+        assert!(aggregate_signatures(signatures.iter()).is_none());
+        assert!(aggregate_public_keys(std::iter::empty()).is_none());
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        assert_eq!(
+            Bls12381::public_from_binary(&G1Affine::identity().to_compressed()).err(),
+            Some(PublicKeyError::StructureInvalid)
+        );
+        assert_eq!(
+            Bls12381::public_from_binary(&[0xff; PUBLIC_KEY_LENGTH]).err(),
+            Some(PublicKeyError::StructureInvalid)
+        );
+        assert!(Bls12381::signature_from_bytes(&[0xff; SIGNATURE_LENGTH]).is_err());
+        assert_eq!(
+            Bls12381::secret_from_binary(&[0; SECRET_KEY_LENGTH]).err(),
+            Some(SecretKeyError::StructureInvalid)
+        );
+    }
+
+    #[proptest]
+    fn bech32_roundtrip(key: KeyPair<Bls12381>) {
+        let proof = ProofOfPossession::generate(key.private_key());
+        let decoded = ProofOfPossession::try_from_bech32_str(&proof.to_bech32_str()).unwrap();
+        prop_assert_eq!(decoded.as_ref(), proof.as_ref());
+        prop_assert!(proof.to_bech32_str().starts_with("bls12381_pop1"));
+    }
+}
@@ -0,0 +1,381 @@
+//! Batch verification of Ed25519 signatures
+//!
+//! Verifying `n` signatures at once with a random linear combination of the
+//! verification equations is much cheaper than `n` single verifications. The
+//! batch only tells if all the signatures are valid: when it fails, the
+//! signatures need to be verified one by one to find the invalid ones.
+//!
+//! Like the single verification, the batch uses the cofactorless equation,
+//! but the random linear combination does not account for the small order
+//! components of the points: a signature crafted with a small order
+//! component could make the batch pass while its single verification fails.
+//! Such signatures are not added to the batch and have to be verified on
+//! their own. Honestly generated signatures never have them.
+
+use super::ed25519::Ed25519;
+use super::ed25519_derive::Ed25519Bip32;
+use crate::key::PublicKey;
+use crate::sign::{Signature, Verification, VerificationAlgorithm};
+
+use curve25519_dalek_ng::{
+    constants::ED25519_BASEPOINT_POINT,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
+};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+const PUBLIC_KEY_LENGTH: usize = 32;
+const SIGNATURE_LENGTH: usize = 64;
+
+/// Verification algorithms whose signatures are plain Ed25519 signatures
+/// and that can be verified in batch. This also covers `Ed25519Extended`,
+/// whose public keys are `Ed25519` public keys.
+pub trait Ed25519BatchVerification: VerificationAlgorithm {
+    fn ed25519_public_key(public: &Self::Public) -> &[u8; PUBLIC_KEY_LENGTH];
+
+    /// check the `s` part of a signature the same way the single
+    /// verification of the algorithm does
+    fn ed25519_scalar_accepted(s: &[u8; 32]) -> bool;
+}
+
+impl Ed25519BatchVerification for Ed25519 {
+    fn ed25519_public_key(public: &Self::Public) -> &[u8; PUBLIC_KEY_LENGTH] {
+        &public.0
+    }
+
+    fn ed25519_scalar_accepted(s: &[u8; 32]) -> bool {
+        // the single verification compares the little endian scalar with
+        // the big endian group order, so non canonical scalars may pass
+        const L: [u8; 32] = [
+            0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x14, 0xde, 0xf9, 0xde, 0xa2, 0xf7, 0x9c, 0xd6, 0x58, 0x12, 0x63, 0x1a,
+            0x5c, 0xf5, 0xd3, 0xed,
+        ];
+        s.iter().rev().cmp(L.iter().rev()) == std::cmp::Ordering::Less
+    }
+}
+
+impl Ed25519BatchVerification for Ed25519Bip32 {
+    fn ed25519_public_key(public: &Self::Public) -> &[u8; PUBLIC_KEY_LENGTH] {
+        public.public_key_bytes()
+    }
+
+    fn ed25519_scalar_accepted(s: &[u8; 32]) -> bool {
+        Scalar::from_canonical_bytes(*s).is_some()
+    }
+}
+
+#[derive(Clone)]
+struct BatchEntry {
+    public_key: [u8; PUBLIC_KEY_LENGTH],
+    signature: [u8; SIGNATURE_LENGTH],
+    message: Vec<u8>,
+    scalar_accepted: bool,
+}
+
+/// Collect Ed25519 signatures, possibly of different algorithms, to verify
+/// them all at once.
+#[derive(Clone, Default)]
+pub struct Ed25519BatchVerifier {
+    entries: Vec<BatchEntry>,
+}
+
+impl Ed25519BatchVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Ed25519BatchVerifier {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// add to the batch the signature of `object` by `publickey`. Returns
+    /// `false` if the signature cannot be verified in batch and has not
+    /// been added, see `queue_slice`.
+    #[must_use]
+    pub fn queue<A, T>(
+        &mut self,
+        publickey: &PublicKey<A>,
+        signature: &Signature<T, A>,
+        object: &T,
+    ) -> bool
+    where
+        A: Ed25519BatchVerification,
+        T: ?Sized + AsRef<[u8]>,
+    {
+        self.queue_slice(publickey, signature, object.as_ref())
+    }
+
+    /// add to the batch the signature of `slice` by `publickey`. Returns
+    /// `false` if the public key or the `R` part of the signature has a
+    /// small order component: the signature is not added to the batch and
+    /// has to be verified on its own.
+    #[must_use]
+    pub fn queue_slice<A, T>(
+        &mut self,
+        publickey: &PublicKey<A>,
+        signature: &Signature<T, A>,
+        slice: &[u8],
+    ) -> bool
+    where
+        A: Ed25519BatchVerification,
+        T: ?Sized,
+    {
+        let mut sig = [0; SIGNATURE_LENGTH];
+        sig.copy_from_slice(signature.as_ref());
+        let public_key = *A::ed25519_public_key(&publickey.0);
+        let mut r = [0; 32];
+        r.copy_from_slice(&sig[0..32]);
+        if has_torsion(public_key) || has_torsion(r) {
+            return false;
+        }
+        let mut s = [0; 32];
+        s.copy_from_slice(&sig[32..64]);
+        self.entries.push(BatchEntry {
+            public_key,
+            signature: sig,
+            message: slice.to_vec(),
+            scalar_accepted: A::ed25519_scalar_accepted(&s),
+        });
+        true
+    }
+
+    /// Verify all the queued signatures at once. Returns `Success` if the
+    /// batch is empty.
+    #[must_use]
+    pub fn verify<R: RngCore + CryptoRng>(&self, mut rng: R) -> Verification {
+        let n = self.entries.len();
+        let mut scalars = Vec::with_capacity(2 * n + 1);
+        let mut points = Vec::with_capacity(2 * n + 1);
+        let mut basepoint_scalar = Scalar::zero();
+
+        for entry in self.entries.iter() {
+            let (r, s, k, a) = match decode_entry(entry) {
+                Some(decoded) => decoded,
+                None => return Verification::Failed,
+            };
+            let mut z = [0u8; 16];
+            rng.fill_bytes(&mut z);
+            let z = Scalar::from(u128::from_le_bytes(z));
+
+            basepoint_scalar -= z * s;
+            scalars.push(z);
+            points.push(r);
+            scalars.push(z * k);
+            points.push(a);
+        }
+        scalars.push(basepoint_scalar);
+        points.push(ED25519_BASEPOINT_POINT);
+
+        // ∑ z[i]R[i] + ∑ z[i]k[i]A[i] - (∑ z[i]s[i])B = 0 since [s]B = R + [k]A
+        EdwardsPoint::vartime_multiscalar_mul(scalars, points)
+            .is_identity()
+            .into()
+    }
+}
+
+/// points that cannot be decoded are left to the batch, which fails on them
+fn has_torsion(point: [u8; 32]) -> bool {
+    CompressedEdwardsY(point)
+        .decompress()
+        .map(|point| !point.is_torsion_free())
+        .unwrap_or(false)
+}
+
+/// decode the R, s, H(R || A || M) and A of the entry, with the same
+/// restrictions on the encodings as the single verification
+fn decode_entry(entry: &BatchEntry) -> Option<(EdwardsPoint, Scalar, Scalar, EdwardsPoint)> {
+    if !entry.scalar_accepted || entry.public_key.iter().all(|b| *b == 0) {
+        return None;
+    }
+    let a = CompressedEdwardsY(entry.public_key).decompress()?;
+
+    let mut r_bytes = [0; 32];
+    r_bytes.copy_from_slice(&entry.signature[0..32]);
+    let r = CompressedEdwardsY(r_bytes).decompress()?;
+    if r.compress().as_bytes() != &r_bytes {
+        return None;
+    }
+
+    let mut s_bytes = [0; 32];
+    s_bytes.copy_from_slice(&entry.signature[32..64]);
+    let s = Scalar::from_bytes_mod_order(s_bytes);
+
+    let k = Scalar::from_hash(
+        Sha512::new()
+            .chain(r_bytes)
+            .chain(entry.public_key)
+            .chain(&entry.message),
+    );
+    Some((r, s, k, a))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::Ed25519Extended;
+    use crate::key::{AsymmetricKey, KeyPair, SecretKey};
+    use crate::testing::TestCryptoGen;
+
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    fn signed<A>(
+        sk: &SecretKey<A>,
+        msg: &[u8],
+    ) -> (PublicKey<A::PubAlg>, Signature<[u8], A::PubAlg>)
+    where
+        A: AsymmetricKey + crate::sign::SigningAlgorithm,
+        A::PubAlg: VerificationAlgorithm,
+    {
+        (sk.to_public(), sk.sign_slice(msg))
+    }
+
+    #[proptest]
+    fn batch_of_valid_signatures(
+        keys: (
+            KeyPair<Ed25519>,
+            KeyPair<Ed25519Extended>,
+            KeyPair<Ed25519Bip32>,
+        ),
+        msgs: Vec<Vec<u8>>,
+        gen: TestCryptoGen,
+    ) {
+        let mut batch = Ed25519BatchVerifier::new();
+        for msg in msgs.iter().take(8) {
+            let (pk, sig) = signed(keys.0.private_key(), msg);
+            prop_assert!(batch.queue_slice(&pk, &sig, msg));
+            let (pk, sig) = signed(keys.1.private_key(), msg);
+            prop_assert!(batch.queue_slice(&pk, &sig, msg));
+            let (pk, sig) = signed(keys.2.private_key(), msg);
+            prop_assert!(batch.queue_slice(&pk, &sig, msg));
+        }
+        prop_assert_eq!(batch.len(), 3 * msgs.len().min(8));
+        prop_assert_eq!(batch.verify(gen.get_rng(0)), Verification::Success);
+    }
+
+    #[proptest]
+    fn batch_with_an_invalid_signature(
+        keys: (KeyPair<Ed25519>, KeyPair<Ed25519Bip32>),
+        msg: Vec<u8>,
+        gen: TestCryptoGen,
+    ) {
+        let other_msg = [msg.as_slice(), b"other"].concat();
+
+        let (pk, sig) = signed(keys.0.private_key(), &msg);
+        let (xpk, xsig) = signed(keys.1.private_key(), &msg);
+
+        let mut batch = Ed25519BatchVerifier::new();
+        prop_assert!(batch.queue_slice(&pk, &sig, &msg));
+        prop_assert!(batch.queue_slice(&xpk, &xsig, &other_msg));
+        prop_assert_eq!(batch.verify(gen.get_rng(0)), Verification::Failed);
+
+        let mut batch = Ed25519BatchVerifier::new();
+        prop_assert!(batch.queue_slice(&xpk, &xsig, &msg));
+        prop_assert!(batch.queue_slice(keys.1.public_key(), &xsig, &msg));
+        prop_assert!(batch.queue_slice(keys.0.public_key(), &sig, &other_msg));
+        prop_assert_eq!(batch.verify(gen.get_rng(0)), Verification::Failed);
+    }
+
+    #[test]
+    fn empty_batch() {
+        let gen = TestCryptoGen(0);
+        assert_eq!(
+            Ed25519BatchVerifier::new().verify(gen.get_rng(0)),
+            Verification::Success
+        );
+    }
+
+    #[test]
+    fn non_canonical_scalar() {
+        fn check<A: AsymmetricKey + crate::sign::SigningAlgorithm>(gen: &TestCryptoGen)
+        where
+            A::PubAlg: Ed25519BatchVerification,
+        {
+            let sk = SecretKey::<A>::generate(gen.get_rng(0));
+            let (pk, sig) = signed(&sk, b"message");
+
+            // s + l
+            let mut bytes = sig.as_ref().to_vec();
+            add_group_order(&mut bytes[32..]);
+            let sig = Signature::<[u8], A::PubAlg>::from_binary(&bytes).unwrap();
+
+            let mut batch = Ed25519BatchVerifier::new();
+            assert!(batch.queue_slice(&pk, &sig, b"message"));
+            assert_eq!(
+                batch.verify(gen.get_rng(1)),
+                sig.verify_slice(&pk, b"message")
+            );
+        }
+
+        for i in 0..16 {
+            let gen = TestCryptoGen(i);
+            check::<Ed25519>(&gen);
+            check::<Ed25519Extended>(&gen);
+            check::<Ed25519Bip32>(&gen);
+        }
+    }
+
+    #[test]
+    fn small_order_r() {
+        use curve25519_dalek_ng::constants::EIGHT_TORSION;
+
+        let mut rng = TestCryptoGen(0).get_rng(0);
+        let mut random_scalar = || {
+            let mut bytes = [0; 64];
+            rng.fill_bytes(&mut bytes);
+            Scalar::from_bytes_mod_order_wide(&bytes)
+        };
+        let a = random_scalar();
+        let r = random_scalar();
+        let public_key = (a * ED25519_BASEPOINT_POINT).compress();
+
+        // [s]B = R + [k]A only holds up to the small order component of R,
+        // which the batch equation misses whenever 8 divides z
+        let r_point = (r * ED25519_BASEPOINT_POINT + EIGHT_TORSION[1]).compress();
+        let k = Scalar::from_hash(
+            Sha512::new()
+                .chain(r_point.as_bytes())
+                .chain(public_key.as_bytes())
+                .chain(b"message"),
+        );
+        let s = r + k * a;
+
+        let pk = PublicKey::<Ed25519>::from_binary(public_key.as_bytes()).unwrap();
+        let sig =
+            Signature::<[u8], Ed25519>::from_binary(&[r_point.to_bytes(), s.to_bytes()].concat())
+                .unwrap();
+        assert_eq!(sig.verify_slice(&pk, b"message"), Verification::Failed);
+
+        let mut batch = Ed25519BatchVerifier::new();
+        assert!(!batch.queue_slice(&pk, &sig, b"message"));
+        assert!(batch.is_empty());
+    }
+
+    /// add the group order to the little endian scalar
+    fn add_group_order(scalar: &mut [u8]) {
+        const L: [u8; 32] = [
+            0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9,
+            0xde, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x10,
+        ];
+        let mut carry = 0u16;
+        for (byte, l) in scalar.iter_mut().zip(L.iter()) {
+            let sum = *byte as u16 + *l as u16 + carry;
+            *byte = sum as u8;
+            carry = sum >> 8;
+        }
+    }
+}
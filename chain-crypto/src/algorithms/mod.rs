@@ -1,6 +1,11 @@
+#[cfg(feature = "bls12381")]
+pub mod bls12381;
 mod ed25519;
+mod ed25519_batch;
 mod ed25519_derive;
 mod ed25519_extended;
+#[cfg(feature = "p256k1")]
+pub mod secp256k1;
 pub mod vrf;
 pub mod zkps;
 
@@ -9,8 +14,13 @@ mod sumed25519;
 #[cfg(feature = "with-bench")]
 pub mod sumed25519;
 
+#[cfg(feature = "bls12381")]
+pub use bls12381::Bls12381;
 pub use ed25519::Ed25519;
+pub use ed25519_batch::{Ed25519BatchVerification, Ed25519BatchVerifier};
 pub use ed25519_derive::Ed25519Bip32;
 pub use ed25519_extended::Ed25519Extended;
-pub use sumed25519::SumEd25519_12;
-pub use vrf::RistrettoGroup2HashDh;
+#[cfg(feature = "p256k1")]
+pub use secp256k1::{Secp256k1Ecdsa, Secp256k1Schnorr};
+pub use sumed25519::{SumEd25519_12, SumEd25519_14, SumEd25519_6, SumEd25519_7};
+pub use vrf::{EcVrfEdwards25519Sha512, RistrettoGroup2HashDh};
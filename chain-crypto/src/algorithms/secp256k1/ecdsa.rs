@@ -0,0 +1,454 @@
+use super::{sha256, Priv, Rfc6979};
+use crate::ec::p256k1::{GroupElement, Scalar};
+use crate::key::{
+    AsymmetricKey, AsymmetricPublicKey, PublicKey, PublicKeyError, SecretKey, SecretKeyError,
+    SecretKeySizeStatic,
+};
+use crate::sign::{
+    Signature, SignatureError, SigningAlgorithm, Verification, VerificationAlgorithm,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+const PUBLIC_KEY_LENGTH: usize = GroupElement::COMPRESSED_BYTES_LEN;
+const SIGNATURE_LENGTH: usize = 65;
+const DIGEST_LENGTH: usize = 32;
+
+/// half of the group order, in big endian
+const HALF_ORDER: [u8; Scalar::BYTES_LEN] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// ECDSA on secp256k1, with public key recovery
+pub struct Secp256k1Ecdsa;
+
+/// SEC1 compressed public key
+#[derive(Clone)]
+pub struct Pub {
+    bytes: [u8; PUBLIC_KEY_LENGTH],
+    point: GroupElement,
+}
+
+/// `r || s || recovery id`, with `s` at most half of the group order
+#[derive(Clone)]
+pub struct Sig {
+    bytes: [u8; SIGNATURE_LENGTH],
+    r: Scalar,
+    s: Scalar,
+}
+
+impl Pub {
+    fn from_point(point: GroupElement) -> Option<Self> {
+        Some(Pub {
+            bytes: point.to_compressed_bytes()?,
+            point,
+        })
+    }
+
+    /// SEC1 uncompressed encoding of the public key, as used to derive
+    /// Ethereum addresses
+    pub fn to_uncompressed(&self) -> [u8; GroupElement::BYTES_LEN] {
+        self.point.to_bytes()
+    }
+}
+
+impl Sig {
+    pub fn recovery_id(&self) -> u8 {
+        self.bytes[SIGNATURE_LENGTH - 1]
+    }
+}
+
+impl AsRef<[u8]> for Pub {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for Sig {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl PartialEq for Pub {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for Pub {}
+
+impl Hash for Pub {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state)
+    }
+}
+
+fn is_high(s: &Scalar) -> bool {
+    s.to_bytes() > HALF_ORDER
+}
+
+impl AsymmetricPublicKey for Secp256k1Ecdsa {
+    type Public = Pub;
+
+    const PUBLIC_BECH32_HRP: &'static str = "secp256k1_pk";
+    const PUBLIC_KEY_SIZE: usize = PUBLIC_KEY_LENGTH;
+
+    fn public_from_binary(data: &[u8]) -> Result<Self::Public, PublicKeyError> {
+        if data.len() != PUBLIC_KEY_LENGTH {
+            return Err(PublicKeyError::SizeInvalid);
+        }
+        GroupElement::from_compressed_bytes(data)
+            .and_then(Pub::from_point)
+            .ok_or(PublicKeyError::StructureInvalid)
+    }
+}
+
+impl AsymmetricKey for Secp256k1Ecdsa {
+    type Secret = Priv;
+    type PubAlg = Secp256k1Ecdsa;
+
+    const SECRET_BECH32_HRP: &'static str = "secp256k1_sk";
+
+    fn generate<T: RngCore + CryptoRng>(rng: T) -> Self::Secret {
+        Priv::generate(rng)
+    }
+
+    fn compute_public(key: &Self::Secret) -> <Self::PubAlg as AsymmetricPublicKey>::Public {
+        Pub::from_point(GroupElement::generator() * &key.scalar).unwrap()
+    }
+
+    fn secret_from_binary(data: &[u8]) -> Result<Self::Secret, SecretKeyError> {
+        Priv::from_binary(data)
+    }
+}
+
+impl SecretKeySizeStatic for Secp256k1Ecdsa {
+    const SECRET_KEY_SIZE: usize = super::SECRET_KEY_LENGTH;
+}
+
+impl VerificationAlgorithm for Secp256k1Ecdsa {
+    type Signature = Sig;
+
+    const SIGNATURE_SIZE: usize = SIGNATURE_LENGTH;
+    const SIGNATURE_BECH32_HRP: &'static str = "secp256k1_sig";
+
+    fn signature_from_bytes(data: &[u8]) -> Result<Self::Signature, SignatureError> {
+        if data.len() != SIGNATURE_LENGTH {
+            return Err(SignatureError::SizeInvalid {
+                expected: SIGNATURE_LENGTH,
+                got: data.len(),
+            });
+        }
+        let r = Scalar::from_bytes(&data[0..32]).filter(|r| *r != Scalar::zero());
+        let s = Scalar::from_bytes(&data[32..64]).filter(|s| *s != Scalar::zero() && !is_high(s));
+        match (r, s) {
+            (Some(r), Some(s)) if data[64] <= 3 => {
+                let mut bytes = [0; SIGNATURE_LENGTH];
+                bytes.copy_from_slice(data);
+                Ok(Sig { bytes, r, s })
+            }
+            _ => Err(SignatureError::StructureInvalid),
+        }
+    }
+
+    fn verify_bytes(
+        pubkey: &Self::Public,
+        signature: &Self::Signature,
+        msg: &[u8],
+    ) -> Verification {
+        verify(pubkey, signature, &sha256(&[msg]))
+    }
+}
+
+impl SigningAlgorithm for Secp256k1Ecdsa {
+    fn sign(key: &Self::Secret, msg: &[u8]) -> Sig {
+        sign(key, &sha256(&[msg]))
+    }
+}
+
+fn sign(key: &Priv, digest: &[u8; DIGEST_LENGTH]) -> Sig {
+    let z = Scalar::from_bytes_mod_order(digest);
+    let mut nonces = Rfc6979::new(&key.scalar, digest);
+    loop {
+        let k = nonces.next_nonce();
+        let point = GroupElement::generator() * &k;
+        let x = point.x_coordinate().unwrap();
+        let r = Scalar::from_bytes_mod_order(&x);
+        if r == Scalar::zero() {
+            continue;
+        }
+        let mut s = k.inverse() * (&z + &r * &key.scalar);
+        if s == Scalar::zero() {
+            continue;
+        }
+        let mut recovery_id = point.y_is_odd() as u8 | ((x >= Scalar::ORDER) as u8) << 1;
+        if is_high(&s) {
+            s = s.negate();
+            recovery_id ^= 1;
+        }
+
+        let mut bytes = [0; SIGNATURE_LENGTH];
+        bytes[0..32].copy_from_slice(&r.to_bytes());
+        bytes[32..64].copy_from_slice(&s.to_bytes());
+        bytes[64] = recovery_id;
+        return Sig { bytes, r, s };
+    }
+}
+
+fn verify(pubkey: &Pub, signature: &Sig, digest: &[u8; DIGEST_LENGTH]) -> Verification {
+    // checking the recovered public key also checks the recovery id,
+    // so that a valid signature has a single encoding
+    match recover(signature, digest) {
+        Some(recovered) => (recovered.bytes == pubkey.bytes).into(),
+        None => Verification::Failed,
+    }
+}
+
+fn recover(signature: &Sig, digest: &[u8; DIGEST_LENGTH]) -> Option<Pub> {
+    let recovery_id = signature.recovery_id();
+    let mut x = signature.r.to_bytes();
+    if recovery_id & 2 != 0 {
+        // x = r + n, which needs to be below the field modulus
+        let mut carry = 0u16;
+        for (byte, order) in x.iter_mut().zip(Scalar::ORDER.iter()).rev() {
+            let sum = *byte as u16 + *order as u16 + carry;
+            *byte = sum as u8;
+            carry = sum >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    let point = GroupElement::from_x_coordinate(&x, recovery_id & 1 != 0)?;
+    let z = Scalar::from_bytes_mod_order(digest);
+    let r_inverse = signature.r.inverse();
+
+    // Q = r^-1 (sR - zG)
+    let public_key = GroupElement::vartime_multiscalar_multiplication(
+        vec![&r_inverse * &signature.s, (&r_inverse * &z).negate()],
+        vec![point, GroupElement::generator()],
+    );
+    Pub::from_point(public_key)
+}
+
+/// Recover the public key of the signature of `object`. Returns `None` if
+/// no public key can be recovered from it.
+pub fn recover_public_key<T: ?Sized + AsRef<[u8]>>(
+    signature: &Signature<T, Secp256k1Ecdsa>,
+    object: &T,
+) -> Option<PublicKey<Secp256k1Ecdsa>> {
+    recover(&signature.signdata, &sha256(&[object.as_ref()])).map(PublicKey)
+}
+
+/// Sign a digest of the message computed by the caller, rather than its
+/// SHA-256 hash, e.g. the keccak256 hash used by Ethereum or the double
+/// SHA-256 used by Bitcoin.
+pub fn sign_prehashed<T: ?Sized>(
+    key: &SecretKey<Secp256k1Ecdsa>,
+    digest: &[u8; DIGEST_LENGTH],
+) -> Signature<T, Secp256k1Ecdsa> {
+    Signature {
+        signdata: sign(&key.0, digest),
+        phantom: PhantomData,
+    }
+}
+
+/// Verify the signature of a digest computed by the caller, see
+/// `sign_prehashed`.
+#[must_use]
+pub fn verify_prehashed<T: ?Sized>(
+    publickey: &PublicKey<Secp256k1Ecdsa>,
+    signature: &Signature<T, Secp256k1Ecdsa>,
+    digest: &[u8; DIGEST_LENGTH],
+) -> Verification {
+    verify(&publickey.0, &signature.signdata, digest)
+}
+
+/// Recover the public key of the signature of a digest computed by the
+/// caller, see `sign_prehashed`. Returns `None` if no public key can be
+/// recovered from it.
+pub fn recover_prehashed<T: ?Sized>(
+    signature: &Signature<T, Secp256k1Ecdsa>,
+    digest: &[u8; DIGEST_LENGTH],
+) -> Option<PublicKey<Secp256k1Ecdsa>> {
+    recover(&signature.signdata, digest).map(PublicKey)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::key::{KeyPair, SecretKey};
+    use crate::sign::test::{keypair_signing_ko, keypair_signing_ok};
+
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn sign_ok(input: (KeyPair<Secp256k1Ecdsa>, Vec<u8>)) {
+        prop_assert!(keypair_signing_ok(input))
+    }
+
+    #[proptest]
+    fn sign_ko(input: (KeyPair<Secp256k1Ecdsa>, KeyPair<Secp256k1Ecdsa>, Vec<u8>)) {
+        prop_assert!(keypair_signing_ko(input))
+    }
+
+    #[proptest]
+    fn recover_signer(key: KeyPair<Secp256k1Ecdsa>, msg: Vec<u8>) {
+        let signature = key.private_key().sign(&msg);
+        prop_assert_eq!(
+            recover_public_key(&signature, &msg),
+            Some(key.public_key().clone())
+        );
+
+        let other_msg = [msg.as_slice(), b"other"].concat();
+        prop_assert_ne!(
+            recover_public_key(&signature, &other_msg),
+            Some(key.public_key().clone())
+        );
+    }
+
+    #[proptest]
+    fn malleated_signatures_are_rejected(key: KeyPair<Secp256k1Ecdsa>, msg: Vec<u8>) {
+        let signature = key.private_key().sign(&msg);
+
+        // the other recovery ids
+        for recovery_id in 0..4 {
+            let mut bytes = signature.as_ref().to_vec();
+            if bytes[64] == recovery_id {
+                continue;
+            }
+            bytes[64] = recovery_id;
+            if let Ok(signature) = Signature::<Vec<u8>, _>::from_binary(&bytes) {
+                prop_assert_eq!(
+                    signature.verify(key.public_key(), &msg),
+                    Verification::Failed
+                );
+            }
+        }
+
+        // n - s
+        let mut bytes = signature.as_ref().to_vec();
+        let s = Scalar::from_bytes(&bytes[32..64]).unwrap().negate();
+        bytes[32..64].copy_from_slice(&s.to_bytes());
+        bytes[64] ^= 1;
+        prop_assert!(Signature::<Vec<u8>, Secp256k1Ecdsa>::from_binary(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_vector() {
+        // private key 1, RFC 6979 nonce, message "Satoshi Nakamoto"
+        let mut secret = [0u8; 32];
+        secret[31] = 1;
+        let key = SecretKey::<Secp256k1Ecdsa>::from_binary(&secret).unwrap();
+        let signature = key.sign(b"Satoshi Nakamoto");
+        assert_eq!(
+            hex::encode(&signature.as_ref()[..64]),
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8\
+             2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+        assert_eq!(
+            recover_public_key(&signature, b"Satoshi Nakamoto").unwrap(),
+            key.to_public()
+        );
+    }
+
+    fn digest(hex_digest: &str) -> [u8; DIGEST_LENGTH] {
+        let mut digest = [0; DIGEST_LENGTH];
+        digest.copy_from_slice(&hex::decode(hex_digest).unwrap());
+        digest
+    }
+
+    #[test]
+    fn ethereum_test_vector() {
+        use cryptoxide::digest::Digest;
+        use cryptoxide::sha3::Keccak256;
+
+        fn keccak256(data: &[u8]) -> String {
+            let mut hasher = Keccak256::new();
+            hasher.input(data);
+            hasher.result_str()
+        }
+
+        // the transaction signing example of EIP-155, with chain id 1
+        let secret = hex::decode("46".repeat(32)).unwrap();
+        let key = SecretKey::<Secp256k1Ecdsa>::from_binary(&secret).unwrap();
+        let rlp = hex::decode(
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             80018080",
+        )
+        .unwrap();
+        let hash = keccak256(&rlp);
+        assert_eq!(
+            hash,
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+        let hash = digest(&hash);
+
+        let signature: Signature<[u8], _> = sign_prehashed(&key, &hash);
+        // v = 37 = recovery id + 35 + 2 * chain id
+        assert_eq!(
+            hex::encode(signature.as_ref()),
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276\
+             67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83\
+             00"
+        );
+        assert_eq!(
+            verify_prehashed(&key.to_public(), &signature, &hash),
+            Verification::Success
+        );
+
+        // ecrecover
+        let recovered = recover_prehashed(&signature, &hash).unwrap();
+        let address = keccak256(&recovered.0.to_uncompressed()[1..]);
+        assert_eq!(&address[24..], "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+    }
+
+    #[test]
+    fn bitcoin_test_vector() {
+        // private key 1, RFC 6979 nonce, double SHA-256 of "Satoshi Nakamoto"
+        let mut secret = [0u8; 32];
+        secret[31] = 1;
+        let key = SecretKey::<Secp256k1Ecdsa>::from_binary(&secret).unwrap();
+        let hash = sha256(&[&sha256(&[b"Satoshi Nakamoto"])]);
+        assert_eq!(
+            hash,
+            digest("21ee5734569831d26b398831435f01a137102322ea4fceff2cd5f0ae99080727")
+        );
+
+        let signature: Signature<[u8], _> = sign_prehashed(&key, &hash);
+        assert_eq!(
+            hex::encode(signature.as_ref()),
+            "1db4e63a273c868a1fdc5078e69f7c48e8ec1bec7ed96a3d410650454d67849d\
+             7a1a63d3a5163fb95a641e56f0d415d68047f048463f283c7340febc471825f3\
+             01"
+        );
+        assert_eq!(
+            verify_prehashed(&key.to_public(), &signature, &hash),
+            Verification::Success
+        );
+        assert_eq!(recover_prehashed(&signature, &hash), Some(key.to_public()));
+
+        // the prehashed signature is not a signature of the message itself
+        assert_eq!(
+            signature.verify_slice(&key.to_public(), b"Satoshi Nakamoto"),
+            Verification::Failed
+        );
+    }
+
+    #[proptest]
+    fn prehashed_matches_hashed(key: KeyPair<Secp256k1Ecdsa>, msg: Vec<u8>) {
+        let hash = sha256(&[&msg]);
+        let signature: Signature<[u8], _> = sign_prehashed(key.private_key(), &hash);
+        let hashed_signature: Signature<[u8], _> = key.private_key().sign_slice(&msg);
+        prop_assert_eq!(signature.as_ref(), hashed_signature.as_ref());
+        prop_assert_eq!(
+            recover_prehashed(&signature, &hash),
+            Some(key.public_key().clone())
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Signature algorithms on the secp256k1 curve, for interoperability with
+//! other ecosystems:
+//!
+//! * `Secp256k1Ecdsa`: ECDSA over the SHA-256 hash of the message, with
+//!   deterministic nonces (RFC 6979), low `s` and a recovery id so that the
+//!   public key can be recovered from a signature. `sign_prehashed` and its
+//!   counterparts take a digest computed by the caller instead;
+//! * `Secp256k1Schnorr`: Schnorr signatures with x-only public keys, as
+//!   specified by BIP-340.
+
+mod ecdsa;
+mod schnorr;
+
+pub use ecdsa::{
+    recover_prehashed, recover_public_key, sign_prehashed, verify_prehashed, Secp256k1Ecdsa,
+};
+pub use schnorr::Secp256k1Schnorr;
+
+use crate::ec::p256k1::Scalar;
+use crate::key::SecretKeyError;
+use cryptoxide::digest::Digest;
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::Mac;
+use cryptoxide::sha2::Sha256;
+use rand_core::{CryptoRng, RngCore};
+
+const SECRET_KEY_LENGTH: usize = Scalar::BYTES_LEN;
+
+/// Secret key of the secp256k1 algorithms: a non zero scalar
+#[derive(Clone)]
+pub struct Priv {
+    bytes: [u8; SECRET_KEY_LENGTH],
+    scalar: Scalar,
+}
+
+impl AsRef<[u8]> for Priv {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl Priv {
+    fn generate<T: RngCore + CryptoRng>(mut rng: T) -> Self {
+        loop {
+            let scalar = Scalar::random(&mut rng);
+            if scalar != Scalar::zero() {
+                return Priv {
+                    bytes: scalar.to_bytes(),
+                    scalar,
+                };
+            }
+        }
+    }
+
+    fn from_binary(data: &[u8]) -> Result<Self, SecretKeyError> {
+        if data.len() != SECRET_KEY_LENGTH {
+            return Err(SecretKeyError::SizeInvalid);
+        }
+        match Scalar::from_bytes(data) {
+            Some(scalar) if scalar != Scalar::zero() => {
+                let mut bytes = [0; SECRET_KEY_LENGTH];
+                bytes.copy_from_slice(data);
+                Ok(Priv { bytes, scalar })
+            }
+            _ => Err(SecretKeyError::StructureInvalid),
+        }
+    }
+}
+
+fn sha256(chunks: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for chunk in chunks {
+        hasher.input(chunk);
+    }
+    let mut out = [0; 32];
+    hasher.result(&mut out);
+    out
+}
+
+fn hmac_sha256(key: &[u8], chunks: &[&[u8]]) -> [u8; 32] {
+    let mut hmac = Hmac::new(Sha256::new(), key);
+    for chunk in chunks {
+        hmac.input(chunk);
+    }
+    let mut out = [0; 32];
+    hmac.raw_result(&mut out);
+    out
+}
+
+/// Deterministic generation of the ECDSA nonces (RFC 6979 section 3.2)
+/// with HMAC-SHA256
+struct Rfc6979 {
+    k: [u8; 32],
+    v: [u8; 32],
+}
+
+impl Rfc6979 {
+    fn new(secret: &Scalar, hash: &[u8; 32]) -> Self {
+        let x = secret.to_bytes();
+        let h = Scalar::from_bytes_mod_order(hash).to_bytes();
+        let v = [0x01; 32];
+        let k = hmac_sha256(&[0x00; 32], &[&v, &[0x00], &x, &h]);
+        let v = hmac_sha256(&k, &[&v]);
+        let k = hmac_sha256(&k, &[&v, &[0x01], &x, &h]);
+        let v = hmac_sha256(&k, &[&v]);
+        Rfc6979 { k, v }
+    }
+
+    /// the next candidate nonce, in the range [1, n-1]
+    fn next_nonce(&mut self) -> Scalar {
+        loop {
+            self.v = hmac_sha256(&self.k, &[&self.v]);
+            let candidate = Scalar::from_bytes(&self.v);
+            self.k = hmac_sha256(&self.k, &[&self.v, &[0x00]]);
+            self.v = hmac_sha256(&self.k, &[&self.v]);
+            match candidate {
+                Some(nonce) if nonce != Scalar::zero() => return nonce,
+                _ => continue,
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use super::{sha256, Priv};
+use crate::ec::p256k1::{Coordinate, GroupElement, Scalar};
+use crate::key::{
+    AsymmetricKey, AsymmetricPublicKey, PublicKeyError, SecretKeyError, SecretKeySizeStatic,
+};
+use crate::sign::{SignatureError, SigningAlgorithm, Verification, VerificationAlgorithm};
+use rand_core::{CryptoRng, RngCore};
+
+const PUBLIC_KEY_LENGTH: usize = Coordinate::BYTES_LEN;
+const SIGNATURE_LENGTH: usize = 64;
+
+/// Schnorr signatures on secp256k1 (BIP-340)
+pub struct Secp256k1Schnorr;
+
+/// x coordinate of the point with an even y coordinate
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Pub([u8; PUBLIC_KEY_LENGTH]);
+
+/// `R.x || s`
+#[derive(Clone)]
+pub struct Sig([u8; SIGNATURE_LENGTH]);
+
+impl AsRef<[u8]> for Pub {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Sig {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+fn tagged_hash(tag: &[u8], chunks: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = sha256(&[tag]);
+    let mut all = vec![&tag_hash[..], &tag_hash[..]];
+    all.extend_from_slice(chunks);
+    sha256(&all)
+}
+
+fn challenge(r: &[u8], public_key: &[u8], msg: &[u8]) -> Scalar {
+    Scalar::from_bytes_mod_order(&tagged_hash(b"BIP0340/challenge", &[r, public_key, msg]))
+}
+
+impl AsymmetricPublicKey for Secp256k1Schnorr {
+    type Public = Pub;
+
+    const PUBLIC_BECH32_HRP: &'static str = "secp256k1_schnorr_pk";
+    const PUBLIC_KEY_SIZE: usize = PUBLIC_KEY_LENGTH;
+
+    fn public_from_binary(data: &[u8]) -> Result<Self::Public, PublicKeyError> {
+        if data.len() != PUBLIC_KEY_LENGTH {
+            return Err(PublicKeyError::SizeInvalid);
+        }
+        GroupElement::from_x_coordinate(data, false).ok_or(PublicKeyError::StructureInvalid)?;
+        let mut bytes = [0; PUBLIC_KEY_LENGTH];
+        bytes.copy_from_slice(data);
+        Ok(Pub(bytes))
+    }
+}
+
+impl AsymmetricKey for Secp256k1Schnorr {
+    type Secret = Priv;
+    type PubAlg = Secp256k1Schnorr;
+
+    const SECRET_BECH32_HRP: &'static str = "secp256k1_schnorr_sk";
+
+    fn generate<T: RngCore + CryptoRng>(rng: T) -> Self::Secret {
+        Priv::generate(rng)
+    }
+
+    fn compute_public(key: &Self::Secret) -> <Self::PubAlg as AsymmetricPublicKey>::Public {
+        Pub((GroupElement::generator() * &key.scalar)
+            .x_coordinate()
+            .unwrap())
+    }
+
+    fn secret_from_binary(data: &[u8]) -> Result<Self::Secret, SecretKeyError> {
+        Priv::from_binary(data)
+    }
+}
+
+impl SecretKeySizeStatic for Secp256k1Schnorr {
+    const SECRET_KEY_SIZE: usize = super::SECRET_KEY_LENGTH;
+}
+
+impl VerificationAlgorithm for Secp256k1Schnorr {
+    type Signature = Sig;
+
+    const SIGNATURE_SIZE: usize = SIGNATURE_LENGTH;
+    const SIGNATURE_BECH32_HRP: &'static str = "secp256k1_schnorr_sig";
+
+    fn signature_from_bytes(data: &[u8]) -> Result<Self::Signature, SignatureError> {
+        if data.len() != SIGNATURE_LENGTH {
+            return Err(SignatureError::SizeInvalid {
+                expected: SIGNATURE_LENGTH,
+                got: data.len(),
+            });
+        }
+        if Coordinate::from_bytes(&data[0..32]).is_none()
+            || Scalar::from_bytes(&data[32..64]).is_none()
+        {
+            return Err(SignatureError::StructureInvalid);
+        }
+        let mut bytes = [0; SIGNATURE_LENGTH];
+        bytes.copy_from_slice(data);
+        Ok(Sig(bytes))
+    }
+
+    fn verify_bytes(
+        pubkey: &Self::Public,
+        signature: &Self::Signature,
+        msg: &[u8],
+    ) -> Verification {
+        let public_key = match GroupElement::from_x_coordinate(&pubkey.0, false) {
+            Some(point) => point,
+            None => return Verification::Failed,
+        };
+        let r = &signature.0[0..32];
+        let s = match Scalar::from_bytes(&signature.0[32..64]) {
+            Some(s) => s,
+            None => return Verification::Failed,
+        };
+        let e = challenge(r, &pubkey.0, msg);
+
+        // R = sG - eP
+        let point = GroupElement::vartime_multiscalar_multiplication(
+            vec![s, e.negate()],
+            vec![GroupElement::generator(), public_key],
+        );
+        match point.x_coordinate() {
+            Some(x) => (!point.y_is_odd() && x[..] == *r).into(),
+            None => Verification::Failed,
+        }
+    }
+}
+
+impl SigningAlgorithm for Secp256k1Schnorr {
+    fn sign(key: &Self::Secret, msg: &[u8]) -> Sig {
+        sign_with_aux(key, msg, &[0; 32])
+    }
+}
+
+/// BIP-340 signing, with the auxiliary random data `aux`. `SigningAlgorithm`
+/// signs with zeroed auxiliary data, which BIP-340 allows and keeps the
+/// signatures deterministic.
+fn sign_with_aux(key: &Priv, msg: &[u8], aux: &[u8; 32]) -> Sig {
+    let public_key = GroupElement::generator() * &key.scalar;
+    let public_key_x = public_key.x_coordinate().unwrap();
+    let d = if public_key.y_is_odd() {
+        key.scalar.negate()
+    } else {
+        key.scalar.clone()
+    };
+
+    let mut t = d.to_bytes();
+    for (t, a) in t.iter_mut().zip(tagged_hash(b"BIP0340/aux", &[aux]).iter()) {
+        *t ^= a;
+    }
+    let nonce =
+        Scalar::from_bytes_mod_order(&tagged_hash(b"BIP0340/nonce", &[&t, &public_key_x, msg]));
+    // a zero nonce only happens with negligible probability
+    let point = GroupElement::generator() * &nonce;
+    let r = point.x_coordinate().unwrap();
+    let k = if point.y_is_odd() {
+        nonce.negate()
+    } else {
+        nonce
+    };
+    let e = challenge(&r, &public_key_x, msg);
+    let s = k + e * d;
+
+    let mut bytes = [0; SIGNATURE_LENGTH];
+    bytes[0..32].copy_from_slice(&r);
+    bytes[32..64].copy_from_slice(&s.to_bytes());
+    Sig(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::key::KeyPair;
+    use crate::sign::test::{keypair_signing_ko, keypair_signing_ok};
+
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn sign_ok(input: (KeyPair<Secp256k1Schnorr>, Vec<u8>)) {
+        prop_assert!(keypair_signing_ok(input))
+    }
+
+    #[proptest]
+    fn sign_ko(
+        input: (
+            KeyPair<Secp256k1Schnorr>,
+            KeyPair<Secp256k1Schnorr>,
+            Vec<u8>,
+        ),
+    ) {
+        prop_assert!(keypair_signing_ko(input))
+    }
+
+    fn from_hex<const N: usize>(s: &str) -> [u8; N] {
+        let mut bytes = [0; N];
+        hex::decode_to_slice(s, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// test vectors of BIP-340
+    #[test]
+    fn test_vectors() {
+        let signing = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215\
+                 25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+            ),
+            (
+                "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+                "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+                "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE3341\
+                 8906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+            ),
+        ];
+        for (secret, public, aux, msg, sig) in signing.iter() {
+            let key = Priv::from_binary(&from_hex::<32>(secret)).unwrap();
+            let public_key = Secp256k1Schnorr::compute_public(&key);
+            assert_eq!(public_key.0, from_hex::<32>(public));
+            let msg = from_hex::<32>(msg);
+            let signature = sign_with_aux(&key, &msg, &from_hex(aux));
+            assert_eq!(signature.0[..], from_hex::<64>(sig)[..]);
+            assert_eq!(
+                Secp256k1Schnorr::verify_bytes(&public_key, &signature, &msg),
+                Verification::Success
+            );
+        }
+
+        let verifying = [
+            (
+                "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9",
+                "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703",
+                "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C63\
+                 76AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+                Verification::Success,
+            ),
+            // R has an odd y coordinate
+            (
+                "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+                "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+                "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563\
+                 CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+                Verification::Failed,
+            ),
+        ];
+        for (public, msg, sig, expected) in verifying.iter() {
+            let public_key = Secp256k1Schnorr::public_from_binary(&from_hex::<32>(public)).unwrap();
+            let signature = Secp256k1Schnorr::signature_from_bytes(&from_hex::<64>(sig)).unwrap();
+            assert_eq!(
+                Secp256k1Schnorr::verify_bytes(&public_key, &signature, &from_hex::<32>(msg)),
+                *expected
+            );
+        }
+
+        // public key not on the curve
+        assert_eq!(
+            Secp256k1Schnorr::public_from_binary(&from_hex::<32>(
+                "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34"
+            ))
+            .err(),
+            Some(PublicKeyError::StructureInvalid)
+        );
+    }
+}
@@ -17,105 +17,143 @@ use crate::key::{AsymmetricKey, AsymmetricPublicKey, PublicKeyError, SecretKeyEr
 use crate::sign::{SignatureError, SigningAlgorithm, Verification, VerificationAlgorithm};
 use rand_core::{CryptoRng, RngCore};
 
-// MMM sum scheme instanciated over the Ed25519 signature system
-// and a specific depth of 12
-pub struct SumEd25519_12;
-
-const DEPTH: common::Depth = common::Depth(12);
-
-impl AsymmetricPublicKey for SumEd25519_12 {
-    type Public = sum::PublicKey;
-    const PUBLIC_BECH32_HRP: &'static str = "kes25519-12-pk";
-    const PUBLIC_KEY_SIZE: usize = 32;
-    fn public_from_binary(data: &[u8]) -> Result<Self::Public, PublicKeyError> {
-        sum::PublicKey::from_bytes(data).map_err(|e| match e {
-            sum::Error::InvalidPublicKeySize(_) => PublicKeyError::SizeInvalid,
-            _ => PublicKeyError::StructureInvalid,
-        })
-    }
-}
+macro_rules! sum_ed25519 {
+    ($(#[$meta:meta])* $name:ident, $depth:literal) => {
+        $(#[$meta])*
+        pub struct $name;
 
-impl AsymmetricKey for SumEd25519_12 {
-    type Secret = sum::SecretKey;
-    type PubAlg = SumEd25519_12;
+        impl $name {
+            /// depth of the binary tree of keys, the key evolves `2^DEPTH - 1` times
+            pub const DEPTH: usize = $depth;
+            /// number of periods a key can sign for
+            pub const PERIODS: u32 = 1 << $depth;
 
-    const SECRET_BECH32_HRP: &'static str = "kes25519-12-sk";
-    fn generate<T: RngCore + CryptoRng>(mut rng: T) -> Self::Secret {
-        let mut priv_bytes = [0u8; common::Seed::SIZE];
-        rng.fill_bytes(&mut priv_bytes);
+            const TREE_DEPTH: common::Depth = common::Depth($depth);
+        }
 
-        let seed = common::Seed::from_bytes(priv_bytes);
+        impl AsymmetricPublicKey for $name {
+            type Public = sum::PublicKey;
+            const PUBLIC_BECH32_HRP: &'static str = concat!("kes25519-", $depth, "-pk");
+            const PUBLIC_KEY_SIZE: usize = 32;
+            fn public_from_binary(data: &[u8]) -> Result<Self::Public, PublicKeyError> {
+                sum::PublicKey::from_bytes(data).map_err(|e| match e {
+                    sum::Error::InvalidPublicKeySize(_) => PublicKeyError::SizeInvalid,
+                    _ => PublicKeyError::StructureInvalid,
+                })
+            }
+        }
 
-        let (sk, _) = sum::keygen(DEPTH, &seed);
-        sk
-    }
+        impl AsymmetricKey for $name {
+            type Secret = sum::SecretKey;
+            type PubAlg = $name;
 
-    fn compute_public(key: &Self::Secret) -> sum::PublicKey {
-        key.compute_public()
-    }
+            const SECRET_BECH32_HRP: &'static str = concat!("kes25519-", $depth, "-sk");
+            fn generate<T: RngCore + CryptoRng>(mut rng: T) -> Self::Secret {
+                let mut priv_bytes = [0u8; common::Seed::SIZE];
+                rng.fill_bytes(&mut priv_bytes);
 
-    fn secret_from_binary(data: &[u8]) -> Result<Self::Secret, SecretKeyError> {
-        sum::SecretKey::from_bytes(DEPTH, data).map_err(|e| match e {
-            sum::Error::InvalidSecretKeySize(_) => SecretKeyError::SizeInvalid,
-            _ => SecretKeyError::StructureInvalid,
-        })
-    }
-}
+                let seed = common::Seed::from_bytes(priv_bytes);
 
-impl VerificationAlgorithm for SumEd25519_12 {
-    type Signature = sum::Signature;
+                let (sk, _) = sum::keygen(Self::TREE_DEPTH, &seed);
+                sk
+            }
 
-    const SIGNATURE_SIZE: usize = sum::signature_size(DEPTH);
-    const SIGNATURE_BECH32_HRP: &'static str = "kes25519-12-sig";
+            fn compute_public(key: &Self::Secret) -> sum::PublicKey {
+                key.compute_public()
+            }
 
-    fn signature_from_bytes(data: &[u8]) -> Result<Self::Signature, SignatureError> {
-        sum::Signature::from_bytes(DEPTH, data).map_err(|e| match e {
-            sum::Error::InvalidSignatureSize(_) => SignatureError::SizeInvalid {
-                expected: Self::SIGNATURE_SIZE,
-                got: data.len(),
-            },
-            _ => SignatureError::StructureInvalid,
-        })
-    }
+            fn secret_from_binary(data: &[u8]) -> Result<Self::Secret, SecretKeyError> {
+                sum::SecretKey::from_bytes(Self::TREE_DEPTH, data).map_err(|e| match e {
+                    sum::Error::InvalidSecretKeySize(_) => SecretKeyError::SizeInvalid,
+                    _ => SecretKeyError::StructureInvalid,
+                })
+            }
+        }
 
-    fn verify_bytes(
-        pubkey: &Self::Public,
-        signature: &Self::Signature,
-        msg: &[u8],
-    ) -> Verification {
-        if sum::verify(pubkey, msg, signature) {
-            Verification::Success
-        } else {
-            Verification::Failed
+        impl VerificationAlgorithm for $name {
+            type Signature = sum::Signature;
+
+            const SIGNATURE_SIZE: usize = sum::signature_size(Self::TREE_DEPTH);
+            const SIGNATURE_BECH32_HRP: &'static str = concat!("kes25519-", $depth, "-sig");
+
+            fn signature_from_bytes(data: &[u8]) -> Result<Self::Signature, SignatureError> {
+                sum::Signature::from_bytes(Self::TREE_DEPTH, data).map_err(|e| match e {
+                    sum::Error::InvalidSignatureSize(_) => SignatureError::SizeInvalid {
+                        expected: Self::SIGNATURE_SIZE,
+                        got: data.len(),
+                    },
+                    _ => SignatureError::StructureInvalid,
+                })
+            }
+
+            fn verify_bytes(
+                pubkey: &Self::Public,
+                signature: &Self::Signature,
+                msg: &[u8],
+            ) -> Verification {
+                if sum::verify(pubkey, msg, signature) {
+                    Verification::Success
+                } else {
+                    Verification::Failed
+                }
+            }
         }
-    }
-}
 
-impl SigningAlgorithm for SumEd25519_12 {
-    fn sign(key: &Self::Secret, msg: &[u8]) -> sum::Signature {
-        sum::sign(key, msg)
-    }
-}
+        impl SigningAlgorithm for $name {
+            fn sign(key: &Self::Secret, msg: &[u8]) -> sum::Signature {
+                sum::sign(key, msg)
+            }
+        }
 
-impl KeyEvolvingAlgorithm for SumEd25519_12 {
-    fn get_period(sec: &Self::Secret) -> u32 {
-        sec.t() as u32
-    }
-    fn update(key: &mut Self::Secret) -> EvolvingStatus {
-        if sum::update(key).is_ok() {
-            EvolvingStatus::Success
-        } else {
-            EvolvingStatus::Failed
+        impl KeyEvolvingAlgorithm for $name {
+            fn get_period(sec: &Self::Secret) -> u32 {
+                sec.t() as u32
+            }
+            fn update(key: &mut Self::Secret) -> EvolvingStatus {
+                if sum::update(key).is_ok() {
+                    EvolvingStatus::Success
+                } else {
+                    EvolvingStatus::Failed
+                }
+            }
         }
-    }
-}
 
-impl KeyEvolvingSignatureAlgorithm for SumEd25519_12 {
-    fn get_period(sig: &Self::Signature) -> u32 {
-        sig.t() as u32
-    }
+        impl KeyEvolvingSignatureAlgorithm for $name {
+            fn get_period(sig: &Self::Signature) -> u32 {
+                sig.t() as u32
+            }
+        }
+    };
 }
 
+sum_ed25519!(
+    /// MMM sum scheme instanciated over the Ed25519 signature system
+    /// and a depth of 6 (64 periods)
+    SumEd25519_6,
+    6
+);
+
+sum_ed25519!(
+    /// MMM sum scheme instanciated over the Ed25519 signature system
+    /// and a depth of 7 (128 periods)
+    SumEd25519_7,
+    7
+);
+
+sum_ed25519!(
+    /// MMM sum scheme instanciated over the Ed25519 signature system
+    /// and a depth of 12 (4096 periods)
+    SumEd25519_12,
+    12
+);
+
+sum_ed25519!(
+    /// MMM sum scheme instanciated over the Ed25519 signature system
+    /// and a depth of 14 (16384 periods)
+    SumEd25519_14,
+    14
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +210,74 @@ mod tests {
             matches!(signature, Err(SignatureError::SizeInvalid { .. }))
         );
     }
+
+    fn sign_and_evolve<A>(depth: usize, periods: u32, signature_size: usize)
+    where
+        A: AsymmetricKey<PubAlg = A, Secret = sum::SecretKey>
+            + AsymmetricPublicKey<Public = sum::PublicKey>
+            + VerificationAlgorithm<Signature = sum::Signature>
+            + SigningAlgorithm
+            + KeyEvolvingAlgorithm
+            + KeyEvolvingSignatureAlgorithm,
+    {
+        assert_eq!(signature_size, 100 + 32 * depth);
+        assert_eq!(periods, 1 << depth);
+        assert_eq!(
+            A::SIGNATURE_BECH32_HRP,
+            format!("kes25519-{}-sig", depth).as_str()
+        );
+
+        let mut key = A::generate(rand::thread_rng());
+        let public = A::compute_public(&key);
+        assert_eq!(A::update(&mut key), EvolvingStatus::Success);
+        let signature = A::sign(&key, b"message");
+        assert_eq!(signature.as_ref().len(), signature_size);
+        assert_eq!(
+            <A as KeyEvolvingSignatureAlgorithm>::get_period(&signature),
+            1
+        );
+        assert_eq!(
+            A::verify_bytes(&public, &signature, b"message"),
+            Verification::Success
+        );
+        assert!(A::signature_from_bytes(signature.as_ref()).is_ok());
+    }
+
+    #[test]
+    fn sign_and_evolve_all_depths() {
+        sign_and_evolve::<SumEd25519_6>(
+            SumEd25519_6::DEPTH,
+            SumEd25519_6::PERIODS,
+            SumEd25519_6::SIGNATURE_SIZE,
+        );
+        sign_and_evolve::<SumEd25519_7>(
+            SumEd25519_7::DEPTH,
+            SumEd25519_7::PERIODS,
+            SumEd25519_7::SIGNATURE_SIZE,
+        );
+        sign_and_evolve::<SumEd25519_12>(
+            SumEd25519_12::DEPTH,
+            SumEd25519_12::PERIODS,
+            SumEd25519_12::SIGNATURE_SIZE,
+        );
+        sign_and_evolve::<SumEd25519_14>(
+            SumEd25519_14::DEPTH,
+            SumEd25519_14::PERIODS,
+            SumEd25519_14::SIGNATURE_SIZE,
+        );
+    }
+
+    #[test]
+    fn signature_of_another_depth_is_rejected() {
+        let key = SumEd25519_6::generate(rand::thread_rng());
+        let signature = SumEd25519_6::sign(&key, b"message");
+        assert!(matches!(
+            SumEd25519_12::signature_from_bytes(signature.as_ref()),
+            Err(SignatureError::SizeInvalid { .. })
+        ));
+        assert!(matches!(
+            SumEd25519_14::secret_from_binary(key.as_ref()),
+            Err(SecretKeyError::SizeInvalid)
+        ));
+    }
 }
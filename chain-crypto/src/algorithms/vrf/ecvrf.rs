@@ -0,0 +1,358 @@
+//! ECVRF-EDWARDS25519-SHA512-TAI as specified by RFC 9381.
+//!
+//! The keys are Ed25519 keys: the secret key is a 32 bytes seed expanded
+//! with SHA512 the same way as for the signatures, and the public key is the
+//! compressed Edwards point. The proofs are 80 bytes (`Gamma || c || s`) and
+//! the output (`beta`) is 64 bytes, so proofs and outputs can be verified by
+//! any implementation of the RFC.
+
+use crate::hash::Blake2b256;
+use crate::key::{PublicKeyError, SecretKeyError};
+
+use curve25519_dalek_ng::{
+    constants::ED25519_BASEPOINT_TABLE,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::VartimeMultiscalarMul,
+};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use std::hash::{Hash, Hasher};
+
+const SUITE_STRING: u8 = 0x03;
+const ENCODE_TO_CURVE_DOMAIN_SEPARATOR_FRONT: u8 = 0x01;
+const CHALLENGE_GENERATION_DOMAIN_SEPARATOR_FRONT: u8 = 0x02;
+const PROOF_TO_HASH_DOMAIN_SEPARATOR_FRONT: u8 = 0x03;
+const DOMAIN_SEPARATOR_BACK: u8 = 0x00;
+
+const POINT_SIZE: usize = 32;
+const CHALLENGE_SIZE: usize = 16;
+const SCALAR_SIZE: usize = 32;
+
+#[derive(Clone)]
+pub struct SecretKey {
+    seed: [u8; SecretKey::BYTES_LEN],
+    scalar: Scalar,
+    nonce_prefix: [u8; 32],
+    public: PublicKey,
+}
+
+#[derive(Debug, Clone)]
+pub struct PublicKey {
+    compressed: CompressedEdwardsY,
+    point: EdwardsPoint,
+}
+
+/// The proof `pi` of a VRF evaluation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    gamma: EdwardsPoint,
+    c: [u8; CHALLENGE_SIZE],
+    s: Scalar,
+}
+
+/// The output `beta` of a VRF evaluation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output([u8; Output::BYTES_LEN]);
+
+/// decode a point, rejecting the non canonical encodings as RFC 8032 does
+fn string_to_point(bytes: &[u8; POINT_SIZE]) -> Option<EdwardsPoint> {
+    let point = CompressedEdwardsY(*bytes).decompress()?;
+    if point.compress().as_bytes() == bytes {
+        Some(point)
+    } else {
+        None
+    }
+}
+
+fn challenge_to_scalar(c: &[u8; CHALLENGE_SIZE]) -> Scalar {
+    let mut bytes = [0; 32];
+    bytes[..CHALLENGE_SIZE].copy_from_slice(c);
+    Scalar::from_bits(bytes)
+}
+
+fn encode_to_curve(public: &PublicKey, alpha: &[u8]) -> EdwardsPoint {
+    (0..=u8::MAX)
+        .find_map(|ctr| {
+            let hash = Sha512::new()
+                .chain([SUITE_STRING, ENCODE_TO_CURVE_DOMAIN_SEPARATOR_FRONT])
+                .chain(public.compressed.as_bytes())
+                .chain(alpha)
+                .chain([ctr, DOMAIN_SEPARATOR_BACK])
+                .finalize();
+            let mut candidate = [0; POINT_SIZE];
+            candidate.copy_from_slice(&hash[..POINT_SIZE]);
+            string_to_point(&candidate).map(|point| point.mul_by_cofactor())
+        })
+        // about half of the strings are valid points, never failing 256
+        // times in a row
+        .expect("ECVRF encode to curve failed")
+}
+
+fn challenge_generation(points: [&EdwardsPoint; 5]) -> [u8; CHALLENGE_SIZE] {
+    let mut hasher =
+        Sha512::new().chain([SUITE_STRING, CHALLENGE_GENERATION_DOMAIN_SEPARATOR_FRONT]);
+    for point in points.iter() {
+        hasher.update(point.compress().as_bytes());
+    }
+    let hash = hasher.chain([DOMAIN_SEPARATOR_BACK]).finalize();
+    let mut c = [0; CHALLENGE_SIZE];
+    c.copy_from_slice(&hash[..CHALLENGE_SIZE]);
+    c
+}
+
+impl SecretKey {
+    pub const BYTES_LEN: usize = 32;
+
+    pub fn from_seed(seed: [u8; Self::BYTES_LEN]) -> Self {
+        let hash = Sha512::digest(&seed);
+        let mut scalar_bytes = [0; 32];
+        scalar_bytes.copy_from_slice(&hash[..32]);
+        scalar_bytes[0] &= 248;
+        scalar_bytes[31] &= 127;
+        scalar_bytes[31] |= 64;
+        let scalar = Scalar::from_bits(scalar_bytes);
+        let mut nonce_prefix = [0; 32];
+        nonce_prefix.copy_from_slice(&hash[32..]);
+
+        let point = &scalar * &ED25519_BASEPOINT_TABLE;
+        SecretKey {
+            seed,
+            scalar,
+            nonce_prefix,
+            public: PublicKey {
+                compressed: point.compress(),
+                point,
+            },
+        }
+    }
+
+    pub fn random<T: RngCore + CryptoRng>(mut rng: T) -> Self {
+        let mut seed = [0; Self::BYTES_LEN];
+        rng.fill_bytes(&mut seed);
+        Self::from_seed(seed)
+    }
+
+    pub fn public(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    /// Evaluate the VRF on `alpha`, the proof is deterministic
+    pub fn prove(&self, alpha: &[u8]) -> Proof {
+        let h = encode_to_curve(&self.public, alpha);
+        let gamma = self.scalar * h;
+        let k = Scalar::from_hash(
+            Sha512::new()
+                .chain(self.nonce_prefix)
+                .chain(h.compress().as_bytes()),
+        );
+        let c = challenge_generation([
+            &self.public.point,
+            &h,
+            &gamma,
+            &(&k * &ED25519_BASEPOINT_TABLE),
+            &(k * h),
+        ]);
+        let s = k + challenge_to_scalar(&c) * self.scalar;
+        Proof { gamma, c, s }
+    }
+}
+
+impl AsRef<[u8]> for SecretKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.seed
+    }
+}
+
+impl PublicKey {
+    pub const BYTES_LEN: usize = POINT_SIZE;
+
+    /// Decode and validate a public key, the small order points are
+    /// rejected
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PublicKeyError> {
+        if bytes.len() != Self::BYTES_LEN {
+            return Err(PublicKeyError::SizeInvalid);
+        }
+        let mut buf = [0; POINT_SIZE];
+        buf.copy_from_slice(bytes);
+        match string_to_point(&buf) {
+            Some(point) if !point.is_small_order() => Ok(PublicKey {
+                compressed: CompressedEdwardsY(buf),
+                point,
+            }),
+            _ => Err(PublicKeyError::StructureInvalid),
+        }
+    }
+}
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.compressed.as_bytes()
+    }
+}
+
+impl PartialEq for PublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.compressed == other.compressed
+    }
+}
+
+impl Eq for PublicKey {}
+
+impl Hash for PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.compressed.as_bytes().hash(state)
+    }
+}
+
+impl Proof {
+    pub const BYTES_LEN: usize = POINT_SIZE + CHALLENGE_SIZE + SCALAR_SIZE;
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES_LEN] {
+        let mut bytes = [0; Self::BYTES_LEN];
+        bytes[..POINT_SIZE].copy_from_slice(self.gamma.compress().as_bytes());
+        bytes[POINT_SIZE..POINT_SIZE + CHALLENGE_SIZE].copy_from_slice(&self.c);
+        bytes[POINT_SIZE + CHALLENGE_SIZE..].copy_from_slice(self.s.as_bytes());
+        bytes
+    }
+
+    /// Decode a proof, without verifying it
+    pub fn from_bytes_unverified(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES_LEN {
+            return None;
+        }
+        let mut gamma = [0; POINT_SIZE];
+        gamma.copy_from_slice(&bytes[..POINT_SIZE]);
+        let gamma = string_to_point(&gamma)?;
+        let mut c = [0; CHALLENGE_SIZE];
+        c.copy_from_slice(&bytes[POINT_SIZE..POINT_SIZE + CHALLENGE_SIZE]);
+        let mut s = [0; SCALAR_SIZE];
+        s.copy_from_slice(&bytes[POINT_SIZE + CHALLENGE_SIZE..]);
+        let s = Scalar::from_canonical_bytes(s)?;
+        Some(Proof { gamma, c, s })
+    }
+
+    /// The VRF output of the proof, which is only meaningful once the proof
+    /// is verified
+    pub fn to_output(&self) -> Output {
+        let hash = Sha512::new()
+            .chain([SUITE_STRING, PROOF_TO_HASH_DOMAIN_SEPARATOR_FRONT])
+            .chain(self.gamma.mul_by_cofactor().compress().as_bytes())
+            .chain([DOMAIN_SEPARATOR_BACK])
+            .finalize();
+        let mut output = [0; Output::BYTES_LEN];
+        output.copy_from_slice(&hash);
+        Output(output)
+    }
+
+    pub fn verify(&self, public: &PublicKey, alpha: &[u8]) -> bool {
+        let h = encode_to_curve(public, alpha);
+        let c = challenge_to_scalar(&self.c);
+        // U = s*B - c*Y and V = s*H - c*Gamma
+        let u = EdwardsPoint::vartime_double_scalar_mul_basepoint(&-c, &public.point, &self.s);
+        let v = EdwardsPoint::vartime_multiscalar_mul(&[self.s, -c], &[h, self.gamma]);
+        challenge_generation([&public.point, &h, &self.gamma, &u, &v]) == self.c
+    }
+}
+
+impl Output {
+    pub const BYTES_LEN: usize = 64;
+
+    /// Get the output for this input and a known suffix
+    pub fn to_output(&self, input: &[u8], suffix: &[u8]) -> Blake2b256 {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(input);
+        buf.extend_from_slice(&self.0);
+        buf.extend_from_slice(suffix);
+
+        Blake2b256::new(&buf)
+    }
+}
+
+impl AsRef<[u8]> for Output {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+pub(super) fn secret_from_binary(data: &[u8]) -> Result<SecretKey, SecretKeyError> {
+    if data.len() != SecretKey::BYTES_LEN {
+        return Err(SecretKeyError::SizeInvalid);
+    }
+    let mut seed = [0; SecretKey::BYTES_LEN];
+    seed.copy_from_slice(data);
+    Ok(SecretKey::from_seed(seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestVector {
+        sk: &'static str,
+        pk: &'static str,
+        alpha: &'static str,
+        pi: &'static str,
+        beta: &'static str,
+    }
+
+    // RFC 9381, appendix B.3
+    const TEST_VECTORS: [TestVector; 3] = [
+        TestVector {
+            sk: "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            alpha: "",
+            pi: "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab1268a1b0db10836d9826a528ca76567805",
+            beta: "90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae",
+        },
+        TestVector {
+            sk: "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            pk: "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            alpha: "72",
+            pi: "f3141cd382dc42909d19ec5110469e4feae18300e94f304590abdced48aed5933bf0864a62558b3ed7f2fea45c92a465301b3bbf5e3e54ddf2d935be3b67926da3ef39226bbc355bdc9850112c8f4b02",
+            beta: "eb4440665d3891d668e7e0fcaf587f1b4bd7fbfe99d0eb2211ccec90496310eb5e33821bc613efb94db5e5b54c70a848a0bef4553a41befc57663b56373a5031",
+        },
+        TestVector {
+            sk: "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            pk: "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            alpha: "af82",
+            pi: "9bc0f79119cc5604bf02d23b4caede71393cedfbb191434dd016d30177ccbf8096bb474e53895c362d8628ee9f9ea3c0e52c7a5c691b6c18c9979866568add7a2d41b00b05081ed0f58ee5e31b3a970e",
+            beta: "645427e5d00c62a23fb703732fa5d892940935942101e456ecca7bb217c61c452118fec1219202a0edcf038bb6373241578be7217ba85a2687f7a0310b2df19f",
+        },
+    ];
+
+    #[test]
+    fn rfc9381_test_vectors() {
+        for vector in TEST_VECTORS.iter() {
+            let sk = secret_from_binary(&hex::decode(vector.sk).unwrap()).unwrap();
+            let pk = PublicKey::from_bytes(&hex::decode(vector.pk).unwrap()).unwrap();
+            assert!(sk.public() == pk);
+
+            let alpha = hex::decode(vector.alpha).unwrap();
+            let proof = sk.prove(&alpha);
+            assert_eq!(hex::encode(proof.to_bytes()), vector.pi);
+            assert_eq!(hex::encode(proof.to_output()), vector.beta);
+
+            let proof = Proof::from_bytes_unverified(&hex::decode(vector.pi).unwrap()).unwrap();
+            assert!(proof.verify(&pk, &alpha));
+            assert!(!proof.verify(&pk, b"other input"));
+        }
+    }
+
+    #[test]
+    fn small_order_public_key_rejected() {
+        let identity = EdwardsPoint::default().compress();
+        assert!(matches!(
+            PublicKey::from_bytes(identity.as_bytes()),
+            Err(PublicKeyError::StructureInvalid)
+        ));
+    }
+
+    #[test]
+    fn non_canonical_proof_scalar_rejected() {
+        let sk = SecretKey::from_seed([7; 32]);
+        let mut bytes = sk.prove(b"alpha").to_bytes();
+        bytes[Proof::BYTES_LEN - 1] |= 0xf0;
+        assert!(Proof::from_bytes_unverified(&bytes).is_none());
+    }
+}
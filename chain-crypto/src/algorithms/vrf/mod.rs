@@ -1,3 +1,4 @@
+pub mod ecvrf;
 #[allow(clippy::module_inception)]
 pub mod vrf;
 
@@ -12,6 +13,9 @@ pub use vrf::ProvenOutputSeed;
 /// VRF
 pub struct RistrettoGroup2HashDh;
 
+/// ECVRF-EDWARDS25519-SHA512-TAI, the VRF of RFC 9381 over Ed25519 keys
+pub struct EcVrfEdwards25519Sha512;
+
 impl AsymmetricPublicKey for RistrettoGroup2HashDh {
     type Public = vrf::PublicKey;
     const PUBLIC_BECH32_HRP: &'static str = "vrf_pk";
@@ -85,6 +89,70 @@ impl VerifiableRandomFunction for RistrettoGroup2HashDh {
     }
 }
 
+impl AsymmetricPublicKey for EcVrfEdwards25519Sha512 {
+    type Public = ecvrf::PublicKey;
+    const PUBLIC_BECH32_HRP: &'static str = "ecvrf_pk";
+    const PUBLIC_KEY_SIZE: usize = ecvrf::PublicKey::BYTES_LEN;
+    fn public_from_binary(data: &[u8]) -> Result<Self::Public, PublicKeyError> {
+        ecvrf::PublicKey::from_bytes(data)
+    }
+}
+
+impl AsymmetricKey for EcVrfEdwards25519Sha512 {
+    type Secret = ecvrf::SecretKey;
+    type PubAlg = EcVrfEdwards25519Sha512;
+
+    const SECRET_BECH32_HRP: &'static str = "ecvrf_sk";
+
+    fn generate<T: RngCore + CryptoRng>(rng: T) -> Self::Secret {
+        Self::Secret::random(rng)
+    }
+
+    fn compute_public(key: &Self::Secret) -> <Self::PubAlg as AsymmetricPublicKey>::Public {
+        key.public()
+    }
+
+    fn secret_from_binary(data: &[u8]) -> Result<Self::Secret, SecretKeyError> {
+        ecvrf::secret_from_binary(data)
+    }
+}
+
+impl SecretKeySizeStatic for EcVrfEdwards25519Sha512 {
+    const SECRET_KEY_SIZE: usize = ecvrf::SecretKey::BYTES_LEN;
+}
+
+impl VerifiableRandomFunction for EcVrfEdwards25519Sha512 {
+    type VerifiedRandomOutput = ecvrf::Proof;
+    type RandomOutput = ecvrf::Output;
+    type Input = [u8];
+
+    const VERIFIED_RANDOM_SIZE: usize = ecvrf::Proof::BYTES_LEN;
+
+    fn evaluate_and_prove<T: RngCore + CryptoRng>(
+        secret: &Self::Secret,
+        input: &Self::Input,
+        _rng: T,
+    ) -> Self::VerifiedRandomOutput {
+        secret.prove(input)
+    }
+
+    fn verify(
+        public: &Self::Public,
+        input: &Self::Input,
+        vrand: &Self::VerifiedRandomOutput,
+    ) -> VrfVerification {
+        if vrand.verify(public, input) {
+            VrfVerification::Success
+        } else {
+            VrfVerification::Failed
+        }
+    }
+
+    fn strip_verification_output(vr: &Self::VerifiedRandomOutput) -> Self::RandomOutput {
+        vr.to_output()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
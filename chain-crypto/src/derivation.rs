@@ -0,0 +1,474 @@
+//! Ed25519-BIP32 key derivation along textual derivation paths.
+//!
+//! A path is written `m/1852'/1815'/0'/0/12`: indices followed by `'` (or
+//! `h`, `H`) are hardened and can only be derived from a secret key, the
+//! other ones are soft and can also be derived from a public key, which
+//! allows watch-only wallets to compute the public keys of an account.
+//!
+//! Two derivation schemes are supported: V2, used by all the current
+//! wallets, and V1, the legacy scheme of the Byron era random wallets. The
+//! same path yields different keys under the two schemes.
+
+use crate::algorithms::{Ed25519, Ed25519Bip32};
+use crate::key::{PublicKey, SecretKey};
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::Mac;
+use cryptoxide::sha2::Sha512;
+use curve25519_dalek_ng::{
+    constants::ED25519_BASEPOINT_TABLE, edwards::CompressedEdwardsY, scalar::Scalar,
+};
+use ed25519_bip32::{XPrv, XPub};
+use std::fmt;
+use std::str::FromStr;
+
+pub use ed25519_bip32::DerivationError;
+
+/// The way child keys are computed from their parent key
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DerivationScheme {
+    /// the legacy scheme of the Byron era random wallets
+    V1,
+    #[default]
+    V2,
+}
+
+/// Index of a child key, the highest bit is set for hardened derivation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DerivationIndex(u32);
+
+impl DerivationIndex {
+    pub const HARDENED_THRESHOLD: u32 = 0x8000_0000;
+
+    /// the soft index `index`, `None` if `index` is out of the soft range
+    pub fn soft(index: u32) -> Option<Self> {
+        if index < Self::HARDENED_THRESHOLD {
+            Some(DerivationIndex(index))
+        } else {
+            None
+        }
+    }
+
+    /// the hardened index `index'`, `None` if `index` is out of the
+    /// hardened range
+    pub fn hardened(index: u32) -> Option<Self> {
+        if index < Self::HARDENED_THRESHOLD {
+            Some(DerivationIndex(index | Self::HARDENED_THRESHOLD))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_hardened(self) -> bool {
+        self.0 >= Self::HARDENED_THRESHOLD
+    }
+
+    /// the index without the hardened bit
+    pub fn index(self) -> u32 {
+        self.0 & !Self::HARDENED_THRESHOLD
+    }
+}
+
+impl From<u32> for DerivationIndex {
+    fn from(raw: u32) -> Self {
+        DerivationIndex(raw)
+    }
+}
+
+impl From<DerivationIndex> for u32 {
+    fn from(index: DerivationIndex) -> Self {
+        index.0
+    }
+}
+
+impl fmt::Display for DerivationIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_hardened() {
+            write!(f, "{}'", self.index())
+        } else {
+            write!(f, "{}", self.index())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivationPathError {
+    MissingRoot,
+    IndexInvalid(String),
+    IndexOutOfRange(String),
+}
+
+impl fmt::Display for DerivationPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DerivationPathError::MissingRoot => {
+                write!(f, "derivation path does not start with 'm'")
+            }
+            DerivationPathError::IndexInvalid(index) => {
+                write!(f, "invalid derivation index '{}'", index)
+            }
+            DerivationPathError::IndexOutOfRange(index) => {
+                write!(f, "derivation index '{}' is out of range", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DerivationPathError {}
+
+/// A sequence of derivation indices from a root key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DerivationPath(Vec<DerivationIndex>);
+
+impl DerivationPath {
+    /// the empty path, `m`
+    pub fn root() -> Self {
+        DerivationPath(Vec::new())
+    }
+
+    pub fn push(&mut self, index: DerivationIndex) {
+        self.0.push(index)
+    }
+
+    /// a new path with `index` appended to this one
+    pub fn child(&self, index: DerivationIndex) -> Self {
+        let mut path = self.clone();
+        path.push(index);
+        path
+    }
+
+    /// a new path with `other` appended to this one
+    pub fn join(&self, other: &DerivationPath) -> Self {
+        let mut path = self.clone();
+        path.0.extend_from_slice(&other.0);
+        path
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = DerivationIndex> + '_ {
+        self.0.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// true if the path can be derived from a public key
+    pub fn is_soft(&self) -> bool {
+        self.iter().all(|index| !index.is_hardened())
+    }
+}
+
+impl From<Vec<DerivationIndex>> for DerivationPath {
+    fn from(indices: Vec<DerivationIndex>) -> Self {
+        DerivationPath(indices)
+    }
+}
+
+impl AsRef<[DerivationIndex]> for DerivationPath {
+    fn as_ref(&self) -> &[DerivationIndex] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in self.iter() {
+            write!(f, "/{}", index)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationIndex {
+    type Err = DerivationPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, hardened) = match s.strip_suffix(&['\'', 'h', 'H'][..]) {
+            Some(digits) => (digits, true),
+            None => (s, false),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(DerivationPathError::IndexInvalid(s.to_owned()));
+        }
+        let index = digits
+            .parse::<u32>()
+            .ok()
+            .and_then(|index| {
+                if hardened {
+                    DerivationIndex::hardened(index)
+                } else {
+                    DerivationIndex::soft(index)
+                }
+            })
+            .ok_or_else(|| DerivationPathError::IndexOutOfRange(s.to_owned()))?;
+        Ok(index)
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = DerivationPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.split('/');
+        if components.next() != Some("m") {
+            return Err(DerivationPathError::MissingRoot);
+        }
+        components
+            .map(DerivationIndex::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map(DerivationPath)
+    }
+}
+
+fn hmac_sha512(key: &[u8], chunks: &[&[u8]]) -> [u8; 64] {
+    let mut hmac = Hmac::new(Sha512::new(), key);
+    for chunk in chunks {
+        hmac.input(chunk);
+    }
+    let mut out = [0; 64];
+    hmac.raw_result(&mut out);
+    out
+}
+
+/// the V1 multiplication by 8, which does not propagate all the carried bits
+/// but has to be kept as is to find the keys of the legacy wallets
+fn multiply8_v1(bytes: &[u8]) -> Scalar {
+    let mut out = [0; 32];
+    let mut carry = 0;
+    for (out, byte) in out.iter_mut().zip(bytes) {
+        *out = (byte << 3).wrapping_add(carry & 0x8);
+        carry = byte >> 5;
+    }
+    Scalar::from_bytes_mod_order(out)
+}
+
+fn derive_private_v1(xprv: &XPrv, index: DerivationIndex) -> XPrv {
+    let ekey = xprv.extended_secret_key_bytes();
+    let chain_code = xprv.chain_code();
+    let index_bytes = index.0.to_be_bytes();
+
+    let (z, i) = if index.is_hardened() {
+        (
+            hmac_sha512(chain_code, &[&[0x00], ekey, &index_bytes]),
+            hmac_sha512(chain_code, &[&[0x01], ekey, &index_bytes]),
+        )
+    } else {
+        let public = xprv.public();
+        let pk = public.public_key_bytes();
+        (
+            hmac_sha512(chain_code, &[&[0x02], pk, &index_bytes]),
+            hmac_sha512(chain_code, &[&[0x03], pk, &index_bytes]),
+        )
+    };
+
+    let mut kl = [0; 32];
+    kl.copy_from_slice(&ekey[..32]);
+    let kl = Scalar::from_bytes_mod_order(kl) + multiply8_v1(&z[..32]);
+
+    let mut child = [0; 64];
+    child[..32].copy_from_slice(kl.as_bytes());
+    for (k, (kr, zr)) in child[32..].iter_mut().zip(ekey[32..].iter().zip(&z[32..])) {
+        *k = kr.wrapping_add(*zr);
+    }
+
+    let mut child_chain_code = [0; 32];
+    child_chain_code.copy_from_slice(&i[32..]);
+    XPrv::from_extended_and_chaincode(&child, &child_chain_code)
+}
+
+fn derive_public_v1(xpub: &XPub, index: DerivationIndex) -> Result<XPub, DerivationError> {
+    if index.is_hardened() {
+        return Err(DerivationError::ExpectedSoftDerivation);
+    }
+    let pk = xpub.public_key_bytes();
+    let chain_code = xpub.chain_code();
+    let index_bytes = index.0.to_be_bytes();
+
+    let z = hmac_sha512(chain_code, &[&[0x02], pk, &index_bytes]);
+    let i = hmac_sha512(chain_code, &[&[0x03], pk, &index_bytes]);
+
+    let point = CompressedEdwardsY(*pk)
+        .decompress()
+        .ok_or(DerivationError::InvalidAddition)?;
+    let child = point + &multiply8_v1(&z[..32]) * &ED25519_BASEPOINT_TABLE;
+
+    let mut child_chain_code = [0; 32];
+    child_chain_code.copy_from_slice(&i[32..]);
+    Ok(XPub::from_pk_and_chaincode(
+        child.compress().as_bytes(),
+        &child_chain_code,
+    ))
+}
+
+impl SecretKey<Ed25519Bip32> {
+    /// derive the child key at `index`
+    pub fn derive(&self, scheme: DerivationScheme, index: DerivationIndex) -> Self {
+        match scheme {
+            DerivationScheme::V1 => SecretKey(derive_private_v1(&self.0, index)),
+            DerivationScheme::V2 => {
+                SecretKey(self.0.derive(ed25519_bip32::DerivationScheme::V2, index.0))
+            }
+        }
+    }
+
+    /// derive the descendant key at the end of `path`
+    pub fn derive_path(&self, scheme: DerivationScheme, path: &DerivationPath) -> Self {
+        path.iter()
+            .fold(self.clone(), |key, index| key.derive(scheme, index))
+    }
+}
+
+impl PublicKey<Ed25519Bip32> {
+    /// derive the child key at `index`, which must be a soft index
+    pub fn derive(
+        &self,
+        scheme: DerivationScheme,
+        index: DerivationIndex,
+    ) -> Result<Self, DerivationError> {
+        match scheme {
+            DerivationScheme::V1 => derive_public_v1(&self.0, index).map(PublicKey),
+            DerivationScheme::V2 => self
+                .0
+                .derive(ed25519_bip32::DerivationScheme::V2, index.0)
+                .map(PublicKey),
+        }
+    }
+
+    /// derive the descendant key at the end of `path`, which must only
+    /// contain soft indices
+    pub fn derive_path(
+        &self,
+        scheme: DerivationScheme,
+        path: &DerivationPath,
+    ) -> Result<Self, DerivationError> {
+        path.iter()
+            .try_fold(self.clone(), |key, index| key.derive(scheme, index))
+    }
+
+    /// the Ed25519 public key, without the chain code
+    pub fn to_ed25519(&self) -> PublicKey<Ed25519> {
+        PublicKey::from_binary(self.0.public_key_slice())
+            .expect("ed25519 public keys are any 32 bytes")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::key::KeyPair;
+    use crate::sign::Verification;
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    #[test]
+    fn parse_path() {
+        let path: DerivationPath = "m/1852'/1815h/0H/0/12".parse().unwrap();
+        assert_eq!(
+            path.as_ref(),
+            &[
+                DerivationIndex::hardened(1852).unwrap(),
+                DerivationIndex::hardened(1815).unwrap(),
+                DerivationIndex::hardened(0).unwrap(),
+                DerivationIndex::soft(0).unwrap(),
+                DerivationIndex::soft(12).unwrap(),
+            ]
+        );
+        assert_eq!(path.to_string(), "m/1852'/1815'/0'/0/12");
+        assert!(!path.is_soft());
+
+        assert_eq!("m".parse(), Ok(DerivationPath::root()));
+        assert_eq!(
+            "1852'/0".parse::<DerivationPath>(),
+            Err(DerivationPathError::MissingRoot)
+        );
+        assert_eq!(
+            "m/0/".parse::<DerivationPath>(),
+            Err(DerivationPathError::IndexInvalid("".to_owned()))
+        );
+        assert_eq!(
+            "m/+1".parse::<DerivationPath>(),
+            Err(DerivationPathError::IndexInvalid("+1".to_owned()))
+        );
+        assert_eq!(
+            "m/2147483648".parse::<DerivationPath>(),
+            Err(DerivationPathError::IndexOutOfRange(
+                "2147483648".to_owned()
+            ))
+        );
+        assert_eq!(
+            "m/2147483648'".parse::<DerivationPath>(),
+            Err(DerivationPathError::IndexOutOfRange(
+                "2147483648'".to_owned()
+            ))
+        );
+    }
+
+    #[proptest]
+    fn path_display_parse(indices: Vec<u32>) {
+        let path = DerivationPath::from(
+            indices
+                .into_iter()
+                .map(DerivationIndex::from)
+                .collect::<Vec<_>>(),
+        );
+        prop_assert_eq!(path.to_string().parse(), Ok(path));
+    }
+
+    fn scheme() -> impl Strategy<Value = DerivationScheme> {
+        prop_oneof![Just(DerivationScheme::V1), Just(DerivationScheme::V2)]
+    }
+
+    #[proptest]
+    fn soft_derivation_matches_public_derivation(
+        key: KeyPair<Ed25519Bip32>,
+        #[strategy(scheme())] scheme: DerivationScheme,
+        indices: Vec<u32>,
+    ) {
+        let path = DerivationPath::from(
+            indices
+                .into_iter()
+                .map(|index| DerivationIndex::soft(index >> 1).unwrap())
+                .collect::<Vec<_>>(),
+        );
+        let secret = key.private_key().derive_path(scheme, &path);
+        let public = key.public_key().derive_path(scheme, &path).unwrap();
+        prop_assert_eq!(secret.to_public(), public.clone());
+
+        let signature =
+            crate::Signature::<[u8], Ed25519>::from_binary(secret.sign(b"message").as_ref())
+                .unwrap();
+        prop_assert_eq!(
+            signature.verify_slice(&public.to_ed25519(), b"message"),
+            Verification::Success
+        );
+    }
+
+    #[proptest]
+    fn hardened_derivation_requires_secret(
+        key: KeyPair<Ed25519Bip32>,
+        #[strategy(scheme())] scheme: DerivationScheme,
+    ) {
+        let path: DerivationPath = "m/0/1'".parse().unwrap();
+        prop_assert_eq!(
+            key.public_key().derive_path(scheme, &path),
+            Err(DerivationError::ExpectedSoftDerivation)
+        );
+    }
+
+    #[proptest]
+    fn schemes_derive_different_keys(key: KeyPair<Ed25519Bip32>, index: u32) {
+        let index = DerivationIndex::from(index);
+        prop_assert_ne!(
+            key.private_key()
+                .derive(DerivationScheme::V1, index)
+                .to_public(),
+            key.private_key()
+                .derive(DerivationScheme::V2, index)
+                .to_public()
+        );
+    }
+}
@@ -54,6 +54,9 @@ impl GroupElement {
     /// Size of the byte representation of `GroupElement`.
     pub const BYTES_LEN: usize = 65;
 
+    /// Size of the compressed byte representation of `GroupElement`.
+    pub const COMPRESSED_BYTES_LEN: usize = 33;
+
     /// Serialized GroupElement::zero
     const BYTES_ZERO: [u8; Self::BYTES_LEN] = [0; Self::BYTES_LEN];
 
@@ -91,6 +94,50 @@ impl GroupElement {
         )?)))
     }
 
+    /// Point with the given x coordinate, in big endian, whose y coordinate
+    /// has the given parity
+    pub fn from_x_coordinate(bytes: &[u8], y_is_odd: bool) -> Option<Self> {
+        let point = Self::from_x_bytes(bytes, Sign(Positive))?;
+        if point.y_is_odd() == y_is_odd {
+            Some(point)
+        } else {
+            Some(Self::zero() - point)
+        }
+    }
+
+    /// The x coordinate of the point in big endian, or `None` for the
+    /// point at infinity
+    pub fn x_coordinate(&self) -> Option<[u8; Coordinate::BYTES_LEN]> {
+        self.0.to_affine().map(|pa| pa.to_coordinate().0.to_bytes())
+    }
+
+    /// Whether the y coordinate of the point is odd. The point at infinity
+    /// is considered even.
+    pub fn y_is_odd(&self) -> bool {
+        self.to_bytes()[Self::BYTES_LEN - 1] & 1 == 1
+    }
+
+    /// SEC1 compressed encoding of the point, or `None` for the point at
+    /// infinity
+    pub fn to_compressed_bytes(&self) -> Option<[u8; Self::COMPRESSED_BYTES_LEN]> {
+        let x = self.x_coordinate()?;
+        let mut bytes = [0u8; Self::COMPRESSED_BYTES_LEN];
+        bytes[0] = if self.y_is_odd() { 0x3 } else { 0x2 };
+        bytes[1..].copy_from_slice(&x);
+        Some(bytes)
+    }
+
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::COMPRESSED_BYTES_LEN {
+            return None;
+        }
+        match bytes[0] {
+            0x2 => Self::from_x_coordinate(&bytes[1..], false),
+            0x3 => Self::from_x_coordinate(&bytes[1..], true),
+            _ => None,
+        }
+    }
+
     pub fn generator() -> Self {
         GroupElement(Point::generator())
     }
@@ -175,6 +222,13 @@ impl GroupElement {
 impl Scalar {
     pub const BYTES_LEN: usize = 32;
 
+    /// Order of the group, in big endian
+    pub const ORDER: [u8; Self::BYTES_LEN] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+        0x41, 0x41,
+    ];
+
     /// additive identity
     pub fn zero() -> Self {
         Scalar(IScalar::zero())
@@ -207,6 +261,21 @@ impl Scalar {
         IScalar::from_slice(slice).map(Scalar)
     }
 
+    /// Scalar from 32 bytes in big endian, reduced modulo the group order
+    pub fn from_bytes_mod_order(bytes: &[u8; Self::BYTES_LEN]) -> Self {
+        // the group order is above 2^255, so subtracting it once is enough
+        Self::from_bytes(bytes).unwrap_or_else(|| {
+            let mut reduced = *bytes;
+            let mut borrow = 0i16;
+            for (byte, order) in reduced.iter_mut().zip(Self::ORDER.iter()).rev() {
+                let diff = *byte as i16 - *order as i16 - borrow;
+                *byte = diff as u8;
+                borrow = (diff < 0) as i16;
+            }
+            Self::from_bytes(&reduced).unwrap()
+        })
+    }
+
     pub fn hash_to_scalar(b: &Blake2b) -> Scalar {
         let mut h = [0u8; 64];
         let mut i = 0u8;
@@ -444,6 +513,39 @@ mod test {
         .expect("This point is on the curve");
         assert_eq!(element, element2);
     }
+
+    #[test]
+    fn from_bytes_mod_order() {
+        let mut order_plus_one = Scalar::ORDER;
+        order_plus_one[31] += 1;
+        assert_eq!(Scalar::from_bytes_mod_order(&Scalar::ORDER), Scalar::zero());
+        assert_eq!(Scalar::from_bytes_mod_order(&order_plus_one), Scalar::one());
+        assert_eq!(
+            Scalar::from_bytes_mod_order(&Scalar::from_u64(7).to_bytes()),
+            Scalar::from_u64(7)
+        );
+    }
+
+    #[test]
+    fn compressed_bytes() {
+        for i in 1..16 {
+            let point = GroupElement::generator() * Scalar::from_u64(i);
+            let bytes = point.to_compressed_bytes().unwrap();
+            assert_eq!(bytes[0] == 0x3, point.y_is_odd());
+            assert_eq!(
+                GroupElement::from_compressed_bytes(&bytes),
+                Some(point.clone())
+            );
+
+            let x = point.x_coordinate().unwrap();
+            let negated = GroupElement::zero() - &point;
+            assert_eq!(
+                GroupElement::from_x_coordinate(&x, !point.y_is_odd()),
+                Some(negated)
+            );
+        }
+        assert_eq!(GroupElement::zero().to_compressed_bytes(), None);
+    }
 }
 
 gen_group_tests!();
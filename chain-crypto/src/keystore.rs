@@ -0,0 +1,653 @@
+//! Password protected storage of secret keys:
+//!
+//! * scrypt as memory-hard KDF, with a random salt;
+//! * chacha20poly1305 as AEAD, with a random nonce;
+//! * the metadata (KDF parameters, bech32 HRP of the secret key and period of
+//!   the key evolving algorithms) is authenticated as additional data.
+//!
+//! The binary format is:
+//!
+//! ```text
+//!     MAGIC || VERSION || LOG_N || R || P || SALT || NONCE
+//!           || HRP_LEN || HRP || PERIOD_FLAG || PERIOD
+//!           || ENCRYPTED-KEY || POLY1305-TAG
+//! ```
+//!
+//! with the integers in big endian, `R`, `P` and `PERIOD` on 4 bytes and
+//! `PERIOD_FLAG` being 1 when the key is a key evolving one, 0 otherwise.
+
+use crate::evolving::{EvolvingStatus, KeyEvolvingAlgorithm};
+use crate::key::{AsymmetricKey, SecretKey, SecretKeyError};
+use cryptoxide::chacha20poly1305::ChaCha20Poly1305;
+use cryptoxide::scrypt::{scrypt, ScryptParams};
+use rand_core::{CryptoRng, RngCore};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"CKEY";
+const VERSION: u8 = 1;
+const SALT_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const SYMMETRIC_KEY_SIZE: usize = 32;
+
+/// Parameters of the scrypt KDF
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl KdfParams {
+    /// the maximum parameters accepted when reading a keystore, so that a
+    /// keystore cannot require an unreasonable amount of memory or time to
+    /// open. scrypt uses `128 * r * N` bytes of memory, at most 1GB.
+    const MAX_MEMORY: u64 = 1 << 30;
+    const MAX_R: u32 = 32;
+    const MAX_P: u32 = 16;
+
+    /// N = 2^15, r = 8 and p = 1, which uses 32MB of memory
+    pub const DEFAULT: Self = KdfParams {
+        log_n: 15,
+        r: 8,
+        p: 1,
+    };
+
+    pub fn new(log_n: u8, r: u32, p: u32) -> Option<Self> {
+        let valid = log_n > 0
+            && log_n < 32
+            && r > 0
+            && r <= Self::MAX_R
+            && p > 0
+            && p <= Self::MAX_P
+            && (log_n as u32) < r * 16
+            && (128 * r as u64) << log_n <= Self::MAX_MEMORY;
+        if valid {
+            Some(KdfParams { log_n, r, p })
+        } else {
+            None
+        }
+    }
+
+    fn derive(&self, password: &[u8], salt: &[u8]) -> [u8; SYMMETRIC_KEY_SIZE] {
+        let mut key = [0; SYMMETRIC_KEY_SIZE];
+        scrypt(
+            password,
+            salt,
+            &ScryptParams::new(self.log_n, self.r, self.p),
+            &mut key,
+        );
+        key
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams::DEFAULT
+    }
+}
+
+#[derive(Debug)]
+pub enum KeystoreError {
+    Io(io::Error),
+    Malformed,
+    VersionUnsupported(u8),
+    KdfParamsInvalid,
+    AlgorithmMismatch {
+        expected: &'static str,
+        actual: String,
+    },
+    /// the password is invalid, or the keystore has been tampered with
+    DecryptionFailed,
+    SecretKeyInvalid(SecretKeyError),
+    PeriodMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    NotEvolving,
+    EvolvingFailed,
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeystoreError::Io(_) => write!(f, "keystore I/O error"),
+            KeystoreError::Malformed => write!(f, "malformed keystore"),
+            KeystoreError::VersionUnsupported(version) => {
+                write!(f, "unsupported keystore version {}", version)
+            }
+            KeystoreError::KdfParamsInvalid => write!(f, "invalid keystore KDF parameters"),
+            KeystoreError::AlgorithmMismatch { expected, actual } => write!(
+                f,
+                "keystore holds a '{}' key, expected a '{}' key",
+                actual, expected
+            ),
+            KeystoreError::DecryptionFailed => {
+                write!(f, "invalid password or tampered keystore")
+            }
+            KeystoreError::SecretKeyInvalid(_) => write!(f, "invalid secret key in keystore"),
+            KeystoreError::PeriodMismatch { expected, actual } => write!(
+                f,
+                "keystore key is at period {}, the metadata says period {}",
+                actual, expected
+            ),
+            KeystoreError::NotEvolving => write!(f, "keystore does not hold a key evolving key"),
+            KeystoreError::EvolvingFailed => {
+                write!(f, "keystore key cannot evolve past its last period")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KeystoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KeystoreError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for KeystoreError {
+    fn from(error: io::Error) -> Self {
+        KeystoreError::Io(error)
+    }
+}
+
+/// A secret key encrypted with a password
+#[derive(Clone)]
+pub struct Keystore {
+    kdf_params: KdfParams,
+    salt: [u8; SALT_SIZE],
+    nonce: [u8; NONCE_SIZE],
+    hrp: String,
+    period: Option<u32>,
+    // encrypted key followed by the tag
+    encrypted: Vec<u8>,
+}
+
+/// overwrite a buffer that held secret material
+fn wipe(buf: &mut [u8]) {
+    for byte in buf.iter_mut() {
+        // volatile so that the writes to a buffer about to be freed are kept
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+impl Keystore {
+    /// Encrypt `key` with `password`
+    pub fn encrypt<A, R>(
+        rng: &mut R,
+        password: &[u8],
+        kdf_params: KdfParams,
+        key: &SecretKey<A>,
+    ) -> Self
+    where
+        A: AsymmetricKey,
+        R: RngCore + CryptoRng,
+    {
+        Self::seal(rng, password, kdf_params, A::SECRET_BECH32_HRP, None, key)
+    }
+
+    /// Encrypt the key evolving `key` with `password`, its current period is
+    /// recorded in the metadata
+    pub fn encrypt_evolving<A, R>(
+        rng: &mut R,
+        password: &[u8],
+        kdf_params: KdfParams,
+        key: &SecretKey<A>,
+    ) -> Self
+    where
+        A: KeyEvolvingAlgorithm,
+        R: RngCore + CryptoRng,
+    {
+        let period = A::get_period(&key.0);
+        Self::seal(
+            rng,
+            password,
+            kdf_params,
+            A::SECRET_BECH32_HRP,
+            Some(period),
+            key,
+        )
+    }
+
+    fn seal<A: AsymmetricKey, R: RngCore + CryptoRng>(
+        rng: &mut R,
+        password: &[u8],
+        kdf_params: KdfParams,
+        hrp: &str,
+        period: Option<u32>,
+        key: &SecretKey<A>,
+    ) -> Self {
+        let mut keystore = Keystore {
+            kdf_params,
+            salt: [0; SALT_SIZE],
+            nonce: [0; NONCE_SIZE],
+            hrp: hrp.to_owned(),
+            period,
+            encrypted: Vec::new(),
+        };
+        rng.fill_bytes(&mut keystore.salt);
+        rng.fill_bytes(&mut keystore.nonce);
+
+        let plain = key.0.as_ref();
+        let mut symmetric_key = kdf_params.derive(password, &keystore.salt);
+        let mut cipher =
+            ChaCha20Poly1305::new(&symmetric_key, &keystore.nonce, &keystore.metadata());
+        wipe(&mut symmetric_key);
+
+        keystore.encrypted = vec![0; plain.len() + TAG_SIZE];
+        let (encrypted, tag) = keystore.encrypted.split_at_mut(plain.len());
+        cipher.encrypt(plain, encrypted, tag);
+        keystore
+    }
+
+    /// The bech32 HRP of the secret key, which identifies its algorithm
+    pub fn hrp(&self) -> &str {
+        &self.hrp
+    }
+
+    /// The period of the key, for the key evolving algorithms
+    pub fn period(&self) -> Option<u32> {
+        self.period
+    }
+
+    pub fn kdf_params(&self) -> KdfParams {
+        self.kdf_params
+    }
+
+    /// Decrypt the key with `password`
+    pub fn decrypt<A: AsymmetricKey>(
+        &self,
+        password: &[u8],
+    ) -> Result<SecretKey<A>, KeystoreError> {
+        if self.hrp != A::SECRET_BECH32_HRP {
+            return Err(KeystoreError::AlgorithmMismatch {
+                expected: A::SECRET_BECH32_HRP,
+                actual: self.hrp.clone(),
+            });
+        }
+
+        let mut symmetric_key = self.kdf_params.derive(password, &self.salt);
+        let mut cipher = ChaCha20Poly1305::new(&symmetric_key, &self.nonce, &self.metadata());
+        wipe(&mut symmetric_key);
+
+        let (encrypted, tag) = self.encrypted.split_at(self.encrypted.len() - TAG_SIZE);
+        let mut plain = vec![0; encrypted.len()];
+        let key = if cipher.decrypt(encrypted, &mut plain, tag) {
+            SecretKey::from_binary(&plain).map_err(KeystoreError::SecretKeyInvalid)
+        } else {
+            Err(KeystoreError::DecryptionFailed)
+        };
+        wipe(&mut plain);
+        key
+    }
+
+    /// Decrypt the key evolving key with `password`, checking that its
+    /// period is the one of the metadata
+    pub fn decrypt_evolving<A: KeyEvolvingAlgorithm>(
+        &self,
+        password: &[u8],
+    ) -> Result<SecretKey<A>, KeystoreError> {
+        let expected = self.period.ok_or(KeystoreError::NotEvolving)?;
+        let key = self.decrypt::<A>(password)?;
+        let actual = A::get_period(&key.0);
+        if actual != expected {
+            return Err(KeystoreError::PeriodMismatch { expected, actual });
+        }
+        Ok(key)
+    }
+
+    /// Evolve the key to its next period and encrypt it again, with a new
+    /// salt and nonce, replacing the previous encrypted key.
+    ///
+    /// Returns the evolved key.
+    pub fn update<A, R>(
+        &mut self,
+        rng: &mut R,
+        password: &[u8],
+    ) -> Result<SecretKey<A>, KeystoreError>
+    where
+        A: KeyEvolvingAlgorithm,
+        R: RngCore + CryptoRng,
+    {
+        let mut key = self.decrypt_evolving::<A>(password)?;
+        if SecretKey::evolve(&mut key) == EvolvingStatus::Failed {
+            return Err(KeystoreError::EvolvingFailed);
+        }
+        let updated = Self::encrypt_evolving(rng, password, self.kdf_params, &key);
+        wipe(&mut self.encrypted);
+        *self = updated;
+        Ok(key)
+    }
+
+    fn metadata(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64 + self.hrp.len());
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(self.kdf_params.log_n);
+        out.extend_from_slice(&self.kdf_params.r.to_be_bytes());
+        out.extend_from_slice(&self.kdf_params.p.to_be_bytes());
+        out.extend_from_slice(&self.salt);
+        out.extend_from_slice(&self.nonce);
+        out.push(self.hrp.len() as u8);
+        out.extend_from_slice(self.hrp.as_bytes());
+        match self.period {
+            None => {
+                out.push(0);
+                out.extend_from_slice(&[0; 4]);
+            }
+            Some(period) => {
+                out.push(1);
+                out.extend_from_slice(&period.to_be_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.metadata();
+        out.extend_from_slice(&self.encrypted);
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, KeystoreError> {
+        fn take<'a>(data: &mut &'a [u8], n: usize) -> Result<&'a [u8], KeystoreError> {
+            if data.len() < n {
+                return Err(KeystoreError::Malformed);
+            }
+            let (taken, rest) = data.split_at(n);
+            *data = rest;
+            Ok(taken)
+        }
+        fn take_u32(data: &mut &[u8]) -> Result<u32, KeystoreError> {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(take(data, 4)?);
+            Ok(u32::from_be_bytes(bytes))
+        }
+
+        let mut data = data;
+        if take(&mut data, MAGIC.len())? != MAGIC {
+            return Err(KeystoreError::Malformed);
+        }
+        let version = take(&mut data, 1)?[0];
+        if version != VERSION {
+            return Err(KeystoreError::VersionUnsupported(version));
+        }
+        let log_n = take(&mut data, 1)?[0];
+        let r = take_u32(&mut data)?;
+        let p = take_u32(&mut data)?;
+        let kdf_params = KdfParams::new(log_n, r, p).ok_or(KeystoreError::KdfParamsInvalid)?;
+
+        let mut salt = [0; SALT_SIZE];
+        salt.copy_from_slice(take(&mut data, SALT_SIZE)?);
+        let mut nonce = [0; NONCE_SIZE];
+        nonce.copy_from_slice(take(&mut data, NONCE_SIZE)?);
+
+        let hrp_len = take(&mut data, 1)?[0] as usize;
+        let hrp = std::str::from_utf8(take(&mut data, hrp_len)?)
+            .map_err(|_| KeystoreError::Malformed)?
+            .to_owned();
+        let period_flag = take(&mut data, 1)?[0];
+        let period = take_u32(&mut data)?;
+        let period = match (period_flag, period) {
+            (0, 0) => None,
+            (1, period) => Some(period),
+            _ => return Err(KeystoreError::Malformed),
+        };
+
+        if data.len() < TAG_SIZE {
+            return Err(KeystoreError::Malformed);
+        }
+
+        Ok(Keystore {
+            kdf_params,
+            salt,
+            nonce,
+            hrp,
+            period,
+            encrypted: data.to_vec(),
+        })
+    }
+}
+
+/// Read the keystore file at `path`
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Keystore, KeystoreError> {
+    Keystore::from_bytes(&std::fs::read(path)?)
+}
+
+/// Write the keystore to a new file at `path`, failing if the file exists
+pub fn write_file<P: AsRef<Path>>(path: P, keystore: &Keystore) -> Result<(), KeystoreError> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(&keystore.to_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Evolve the key of the keystore file at `path` to its next period.
+///
+/// The updated keystore is written to a temporary file in the same
+/// directory, which then replaces the file at `path`, so that a crash
+/// leaves either the previous or the updated keystore. On Unix, the
+/// replaced file is then overwritten with zeros so that the key of the
+/// previous period is not left on the storage. The storage may still keep
+/// copies of overwritten blocks (copy-on-write filesystems, journals, SSD
+/// wear leveling), which is out of the reach of this function.
+///
+/// Returns the evolved key.
+pub fn update_file<A, R, P>(
+    path: P,
+    rng: &mut R,
+    password: &[u8],
+) -> Result<SecretKey<A>, KeystoreError>
+where
+    A: KeyEvolvingAlgorithm,
+    R: RngCore + CryptoRng,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut previous = Vec::new();
+    file.read_to_end(&mut previous)?;
+
+    let mut keystore = Keystore::from_bytes(&previous)?;
+    let key = keystore.update::<A, R>(rng, password)?;
+
+    // the file cannot be replaced while open on other platforms
+    #[cfg(not(unix))]
+    drop(file);
+
+    let temporary = temporary_path(path, rng)?;
+    let replaced = write_file(&temporary, &keystore)
+        .and_then(|()| std::fs::rename(&temporary, path).map_err(KeystoreError::from));
+    if let Err(error) = replaced {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(error);
+    }
+    sync_parent_directory(path)?;
+
+    // the replaced file is still open and can be written to on Unix
+    #[cfg(unix)]
+    {
+        use std::io::{Seek, SeekFrom};
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&vec![0; previous.len()])?;
+        file.sync_data()?;
+    }
+    Ok(key)
+}
+
+/// a path for a new file next to the file at `path`
+fn temporary_path<R: RngCore>(path: &Path, rng: &mut R) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+    let mut temporary = std::ffi::OsString::from(".");
+    temporary.push(name);
+    temporary.push(format!(".{:016x}.tmp", rng.next_u64()));
+    Ok(path.with_file_name(temporary))
+}
+
+/// make the renaming of a file of the directory durable
+#[cfg(unix)]
+fn sync_parent_directory(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_directory(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Ed25519, SumEd25519_12, SumEd25519_6};
+    use rand_core::OsRng;
+
+    // cheap parameters, the tests are not about the KDF strength
+    fn kdf_params() -> KdfParams {
+        KdfParams::new(4, 8, 1).unwrap()
+    }
+
+    #[test]
+    fn kdf_params_memory_limit() {
+        // 128 * r * N = 1GB
+        assert!(KdfParams::new(22, 2, 1).is_some());
+        assert!(KdfParams::new(20, 8, 1).is_some());
+        assert!(KdfParams::new(15, 32, 16).is_some());
+        // 2GB and beyond
+        assert!(KdfParams::new(23, 2, 1).is_none());
+        assert!(KdfParams::new(21, 8, 1).is_none());
+        assert!(KdfParams::new(20, 16, 1).is_none());
+        assert!(KdfParams::new(31, 2, 1).is_none());
+        assert!(KdfParams::new(255, 32, 1).is_none());
+
+        let key = SecretKey::<Ed25519>::generate(OsRng);
+        let mut bytes = Keystore::encrypt(&mut OsRng, b"password", kdf_params(), &key).to_bytes();
+        // LOG_N = 21 with r = 8
+        bytes[MAGIC.len() + 1] = 21;
+        assert!(matches!(
+            Keystore::from_bytes(&bytes),
+            Err(KeystoreError::KdfParamsInvalid)
+        ));
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let key = SecretKey::<Ed25519>::generate(OsRng);
+        let keystore = Keystore::encrypt(&mut OsRng, b"password", kdf_params(), &key);
+        assert_eq!(keystore.hrp(), "ed25519_sk");
+        assert_eq!(keystore.period(), None);
+
+        let keystore = Keystore::from_bytes(&keystore.to_bytes()).unwrap();
+        let decrypted = keystore.decrypt::<Ed25519>(b"password").unwrap();
+        assert_eq!(decrypted.to_public(), key.to_public());
+
+        assert!(matches!(
+            keystore.decrypt::<Ed25519>(b"wrong password"),
+            Err(KeystoreError::DecryptionFailed)
+        ));
+        assert!(matches!(
+            keystore.decrypt::<SumEd25519_12>(b"password"),
+            Err(KeystoreError::AlgorithmMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn metadata_is_authenticated() {
+        let key = SecretKey::<SumEd25519_6>::generate(OsRng);
+        let keystore = Keystore::encrypt_evolving(&mut OsRng, b"password", kdf_params(), &key);
+        let bytes = keystore.to_bytes();
+
+        // the last byte of the period
+        let period_offset = bytes.len() - key.0.as_ref().len() - TAG_SIZE - 1;
+        let mut tampered = bytes.clone();
+        tampered[period_offset] ^= 1;
+        let tampered = Keystore::from_bytes(&tampered).unwrap();
+        assert_eq!(tampered.period(), Some(1));
+        assert!(matches!(
+            tampered.decrypt_evolving::<SumEd25519_6>(b"password"),
+            Err(KeystoreError::DecryptionFailed)
+        ));
+
+        let mut truncated = bytes;
+        truncated.truncate(period_offset);
+        assert!(matches!(
+            Keystore::from_bytes(&truncated),
+            Err(KeystoreError::Malformed)
+        ));
+    }
+
+    #[test]
+    fn update_evolving_key() {
+        let key = SecretKey::<SumEd25519_6>::generate(OsRng);
+        let mut keystore = Keystore::encrypt_evolving(&mut OsRng, b"password", kdf_params(), &key);
+        assert_eq!(keystore.period(), Some(0));
+
+        let previous = keystore.clone();
+        let evolved = keystore
+            .update::<SumEd25519_6, _>(&mut OsRng, b"password")
+            .unwrap();
+        assert_eq!(SecretKey::get_period(&evolved), 1);
+        assert_eq!(keystore.period(), Some(1));
+        assert_ne!(keystore.salt, previous.salt);
+        assert_eq!(
+            SecretKey::get_period(
+                &keystore
+                    .decrypt_evolving::<SumEd25519_6>(b"password")
+                    .unwrap()
+            ),
+            1
+        );
+
+        assert!(matches!(
+            Keystore::encrypt(&mut OsRng, b"password", kdf_params(), &key)
+                .update::<SumEd25519_6, _>(&mut OsRng, b"password"),
+            Err(KeystoreError::NotEvolving)
+        ));
+    }
+
+    #[test]
+    fn update_file_in_place() {
+        let path = std::env::temp_dir().join(format!("chain-crypto-keystore-{}", OsRng.next_u64()));
+        let key = SecretKey::<SumEd25519_6>::generate(OsRng);
+        let keystore = Keystore::encrypt_evolving(&mut OsRng, b"password", kdf_params(), &key);
+        write_file(&path, &keystore).unwrap();
+        assert!(write_file(&path, &keystore).is_err());
+
+        let evolved = update_file::<SumEd25519_6, _, _>(&path, &mut OsRng, b"password").unwrap();
+        let stored = read_file(&path).unwrap();
+        assert_eq!(stored.period(), Some(1));
+        assert_eq!(
+            stored
+                .decrypt_evolving::<SumEd25519_6>(b"password")
+                .unwrap()
+                .0
+                .as_ref(),
+            evolved.0.as_ref()
+        );
+
+        // no temporary file is left behind
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let leftovers = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter(|entry| {
+                let entry = entry.as_ref().unwrap().file_name();
+                let entry = entry.to_str().unwrap_or("");
+                entry.contains(name) && entry != name
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -4,6 +4,7 @@ pub mod testing;
 pub mod algorithms;
 pub mod asymlock;
 pub mod bech32;
+pub mod derivation;
 pub mod digest;
 mod evolving;
 #[macro_use]
@@ -11,6 +12,7 @@ pub mod ec;
 pub mod hash;
 mod kes;
 mod key;
+pub mod keystore;
 pub mod multilock;
 mod sign;
 mod vrf;
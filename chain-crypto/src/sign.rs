@@ -54,8 +54,8 @@ where
 }
 
 pub struct Signature<T: ?Sized, A: VerificationAlgorithm> {
-    signdata: A::Signature,
-    phantom: PhantomData<T>,
+    pub(crate) signdata: A::Signature,
+    pub(crate) phantom: PhantomData<T>,
 }
 
 impl<A: VerificationAlgorithm, T: ?Sized> fmt::Debug for Signature<T, A> {
@@ -2,6 +2,7 @@ use crate::certificate::PoolId;
 use crate::date::Epoch;
 use crate::value::*;
 use imhamt::HamtIter;
+use std::fmt;
 
 use super::{LastRewards, LedgerError};
 
@@ -17,6 +18,17 @@ pub enum DelegationType {
     Ratio(DelegationRatio),
 }
 
+impl DelegationType {
+    /// Check if the delegation assigns some stake to the given pool
+    pub fn delegates_to(&self, pool_id: &PoolId) -> bool {
+        match self {
+            DelegationType::NonDelegated => false,
+            DelegationType::Full(pool) => pool == pool_id,
+            DelegationType::Ratio(ratio) => ratio.pools().iter().any(|(pool, _)| pool == pool_id),
+        }
+    }
+}
+
 /// Delegation Ratio type express a number of parts
 /// and a list of pools and their individual parts
 ///
@@ -77,9 +89,11 @@ impl DelegationRatio {
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct AccountState<Extra> {
-    pub counter: SpendingCounter,
+    pub spending: SpendingCounterIncreasing,
     pub delegation: DelegationType,
     pub value: Value,
+    pub rewards: Value,
+    pub deposit: Value,
     pub last_rewards: LastRewards,
     pub extra: Extra,
 }
@@ -88,16 +102,26 @@ impl<Extra> AccountState<Extra> {
     /// Create a new account state with a specific start value
     pub fn new(v: Value, e: Extra) -> Self {
         Self {
-            counter: SpendingCounter(0),
+            spending: SpendingCounterIncreasing::default(),
             delegation: DelegationType::NonDelegated,
             value: v,
+            rewards: Value::zero(),
+            deposit: Value::zero(),
             last_rewards: LastRewards::default(),
             extra: e,
         }
     }
 
+    /// Create a new empty account state holding the deposit of its registration
+    pub fn new_registered(deposit: Value, extra: Extra) -> Self {
+        let mut st = Self::new(Value::zero(), extra);
+        st.deposit = deposit;
+        st
+    }
+
     pub fn new_reward(epoch: Epoch, v: Value, extra: Extra) -> Self {
-        let mut st = Self::new(v, extra);
+        let mut st = Self::new(Value::zero(), extra);
+        st.rewards = v;
         st.last_rewards.add_for(epoch, v);
         st
     }
@@ -116,8 +140,26 @@ impl<Extra> AccountState<Extra> {
         self.value
     }
 
-    pub fn get_counter(&self) -> u32 {
-        self.counter.into()
+    /// The rewards accrued and not withdrawn yet, they are not spendable
+    /// until moved to the value with a reward withdrawal
+    pub fn rewards(&self) -> Value {
+        self.rewards
+    }
+
+    /// The deposit held by the registration of the account
+    pub fn deposit(&self) -> Value {
+        self.deposit
+    }
+
+    /// The value and the rewards of the account, this is what the account
+    /// weights in the stake distribution
+    pub fn total_value(&self) -> Value {
+        self.value.saturating_add(self.rewards)
+    }
+
+    /// The next spending counters expected on each lane of the account
+    pub fn spending(&self) -> &SpendingCounterIncreasing {
+        &self.spending
     }
 }
 
@@ -140,27 +182,52 @@ impl<Extra: Clone> AccountState<Extra> {
         Ok(st)
     }
 
-    /// Add Rewards to the account value but also as the last_reward
+    /// Add Rewards to the account rewards but also as the last_reward
     pub fn add_rewards(&self, e: Epoch, v: Value) -> Result<Self, ValueError> {
+        let new_rewards = (self.rewards + v)?;
+        let mut st = self.clone();
+        st.rewards = new_rewards;
+        st.last_rewards.add_for(e, v);
+        Ok(st)
+    }
+
+    /// Move some of the accrued rewards to the account value
+    ///
+    /// Only error if there is not enough rewards or if the value is overflowing
+    pub fn withdraw_rewards(&self, v: Value) -> Result<Self, LedgerError> {
+        let new_rewards = (self.rewards - v)?;
         let new_value = (self.value + v)?;
         let mut st = self.clone();
+        st.rewards = new_rewards;
         st.value = new_value;
-        st.last_rewards.add_for(e, v);
+        Ok(st)
+    }
+
+    /// Move the deposit of the account registration to the account value
+    pub fn refund_deposit(&self) -> Result<Self, LedgerError> {
+        let new_value = (self.value + self.deposit)?;
+        let mut st = self.clone();
+        st.value = new_value;
+        st.deposit = Value::zero();
         Ok(st)
     }
 
     /// Subtract a value from an account state, and return the new state.
     ///
-    /// Note that this *also* increment the counter, as this function would be usually call
-    /// for spending.
+    /// Note that this *also* check and increment the counter of the lane of
+    /// `counter`, as this function would be usually call for spending.
     ///
-    /// If the counter is also reaching the extremely rare of max, we only authorise
-    /// a total withdrawal of fund otherwise the fund would be stuck forever in limbo.
-    pub fn sub(&self, v: Value) -> Result<Option<Self>, LedgerError> {
+    /// If the counter is also reaching the extremely rare of max of its lane, we only
+    /// authorise a total withdrawal of fund otherwise the fund would be stuck forever in limbo.
+    pub fn sub(&self, counter: SpendingCounter, v: Value) -> Result<Option<Self>, LedgerError> {
         let new_value = (self.value - v)?;
-        match self.counter.increment() {
+        self.spending.verify(counter)?;
+        match counter.increment() {
             None => {
-                if new_value == Value::zero() {
+                if new_value == Value::zero()
+                    && self.rewards == Value::zero()
+                    && self.deposit == Value::zero()
+                {
                     Ok(None)
                 } else {
                     Err(LedgerError::NeedTotalWithdrawal)
@@ -168,7 +235,7 @@ impl<Extra: Clone> AccountState<Extra> {
             }
             Some(new_counter) => {
                 let mut r = self.clone();
-                r.counter = new_counter;
+                r.spending.set_next(new_counter);
                 r.value = new_value;
                 Ok(Some(r))
             }
@@ -189,17 +256,59 @@ impl<Extra: Clone> AccountState<Extra> {
 /// the counter is incremented. A matching counter
 /// needs to be used in the spending phase to make
 /// sure we have non-replayability of a transaction.
+///
+/// The `LANES_BITS` high bits of the counter select one of the `LANES`
+/// lanes of the account, each lane having its own counter in the remaining
+/// bits. The lanes are incremented independently, so transactions spending
+/// on different lanes can be applied in any order.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SpendingCounter(pub(crate) u32);
 
 impl SpendingCounter {
+    pub const LANES_BITS: usize = 3;
+    pub const LANES: usize = 1 << Self::LANES_BITS;
+    const UNLANED_BITS: usize = 32 - Self::LANES_BITS;
+    const UNLANED_MASK: u32 = (1 << Self::UNLANED_BITS) - 1;
+
     pub fn zero() -> Self {
         SpendingCounter(0)
     }
 
+    /// the spending counter `counter` of the lane `lane`
+    ///
+    /// panics if the lane is not below `LANES` or if the counter doesn't
+    /// fit in the bits of a lane
+    pub fn new(lane: usize, counter: u32) -> Self {
+        assert!(
+            lane < Self::LANES,
+            "spending counter lane {} is invalid",
+            lane
+        );
+        assert!(
+            counter <= Self::UNLANED_MASK,
+            "spending counter {} is too big for a lane",
+            counter
+        );
+        SpendingCounter(((lane as u32) << Self::UNLANED_BITS) | counter)
+    }
+
+    pub fn lane(self) -> usize {
+        (self.0 >> Self::UNLANED_BITS) as usize
+    }
+
+    /// the counter within the lane
+    pub fn unlaned_counter(self) -> u32 {
+        self.0 & Self::UNLANED_MASK
+    }
+
+    /// the next counter of the same lane, none if the lane is exhausted
     #[must_use = "this function does not modify the state"]
     pub fn increment(self) -> Option<Self> {
-        self.0.checked_add(1).map(SpendingCounter)
+        if self.unlaned_counter() == Self::UNLANED_MASK {
+            None
+        } else {
+            Some(SpendingCounter(self.0 + 1))
+        }
     }
 
     pub fn to_bytes(self) -> [u8; 4] {
@@ -219,6 +328,79 @@ impl From<SpendingCounter> for u32 {
     }
 }
 
+impl fmt::Display for SpendingCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.lane(), self.unlaned_counter())
+    }
+}
+
+/// The next spending counter expected on each lane of an account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendingCounterIncreasing {
+    nexts: [SpendingCounter; SpendingCounter::LANES],
+}
+
+impl Default for SpendingCounterIncreasing {
+    fn default() -> Self {
+        let mut nexts = [SpendingCounter::zero(); SpendingCounter::LANES];
+        for (lane, next) in nexts.iter_mut().enumerate() {
+            *next = SpendingCounter::new(lane, 0);
+        }
+        SpendingCounterIncreasing { nexts }
+    }
+}
+
+impl SpendingCounterIncreasing {
+    /// the lanes at their first counter, except the lane of `counter`
+    /// expecting `counter` next
+    pub fn new_from_counter(counter: SpendingCounter) -> Self {
+        let mut st = Self::default();
+        st.set_next(counter);
+        st
+    }
+
+    /// build the lanes from their next counters, none if a counter is not in
+    /// the lane of its position
+    pub fn from_nexts(nexts: [SpendingCounter; SpendingCounter::LANES]) -> Option<Self> {
+        if nexts
+            .iter()
+            .enumerate()
+            .all(|(lane, next)| next.lane() == lane)
+        {
+            Some(SpendingCounterIncreasing { nexts })
+        } else {
+            None
+        }
+    }
+
+    /// the next counter of the first lane
+    pub fn get_valid_counter(&self) -> SpendingCounter {
+        self.nexts[0]
+    }
+
+    /// the next counter of each lane
+    pub fn get_valid_counters(&self) -> &[SpendingCounter; SpendingCounter::LANES] {
+        &self.nexts
+    }
+
+    /// check that `counter` is the next counter of its lane
+    pub fn verify(&self, counter: SpendingCounter) -> Result<(), LedgerError> {
+        let expected = self.nexts[counter.lane()];
+        if counter == expected {
+            Ok(())
+        } else {
+            Err(LedgerError::SpendingCounterInvalid {
+                expected,
+                actual: counter,
+            })
+        }
+    }
+
+    fn set_next(&mut self, counter: SpendingCounter) {
+        self.nexts[counter.lane()] = counter;
+    }
+}
+
 pub struct Iter<'a, ID, Extra>(pub HamtIter<'a, ID, AccountState<Extra>>);
 
 impl<'a, ID, Extra> Iterator for Iter<'a, ID, Extra> {
@@ -233,7 +415,7 @@ impl<'a, ID, Extra> Iterator for Iter<'a, ID, Extra> {
 mod tests {
     use super::{
         AccountState, DelegationRatio, DelegationType, LastRewards, SpendingCounter,
-        DELEGATION_RATIO_MAX_DECLS,
+        SpendingCounterIncreasing, DELEGATION_RATIO_MAX_DECLS,
     };
     use crate::{
         accounting::account::LedgerError, certificate::PoolId, testing::builders::StakePoolBuilder,
@@ -249,26 +431,71 @@ mod tests {
         sub_value: Value,
         counter: u32,
     ) -> TestResult {
+        let counter = SpendingCounter::from(counter);
         let mut account_state = AccountState::new(init_value, ());
-        account_state.counter = counter.into();
+        account_state.spending = SpendingCounterIncreasing::new_from_counter(counter);
         TestResult::from_bool(
-            should_sub_fail(account_state.clone(), sub_value)
-                == account_state.sub(sub_value).is_err(),
+            should_sub_fail(account_state.clone(), counter, sub_value)
+                == account_state.sub(counter, sub_value).is_err(),
         )
     }
 
     #[test]
     pub fn max_counter() {
+        let counter = SpendingCounter(u32::MAX);
         let mut account_state = AccountState::new(Value(10), ());
-        account_state.counter = SpendingCounter(u32::MAX);
-        assert_eq!(account_state.get_counter(), u32::MAX);
-        assert!(account_state.sub(Value(10)).unwrap().is_none());
+        account_state.spending = SpendingCounterIncreasing::new_from_counter(counter);
         assert_eq!(
-            account_state.sub(Value(1)).err().unwrap(),
+            account_state.spending().get_valid_counters()[SpendingCounter::LANES - 1],
+            counter
+        );
+        assert!(account_state.sub(counter, Value(10)).unwrap().is_none());
+        assert_eq!(
+            account_state.sub(counter, Value(1)).err().unwrap(),
             LedgerError::NeedTotalWithdrawal
         );
     }
 
+    #[test]
+    pub fn spending_counter_lanes() {
+        let counter = SpendingCounter::new(5, 42);
+        assert_eq!(counter.lane(), 5);
+        assert_eq!(counter.unlaned_counter(), 42);
+        assert_eq!(counter.increment(), Some(SpendingCounter::new(5, 43)));
+        assert_eq!(SpendingCounter::from(42), SpendingCounter::new(0, 42));
+        assert_eq!(
+            SpendingCounter::new(2, SpendingCounter::UNLANED_MASK).increment(),
+            None
+        );
+    }
+
+    #[test]
+    pub fn lanes_are_spent_independently() {
+        let account_state = AccountState::new(Value(10), ());
+        let lane_1 = SpendingCounter::new(1, 0);
+        let lane_7 = SpendingCounter::new(7, 0);
+
+        let account_state = account_state.sub(lane_7, Value(1)).unwrap().unwrap();
+        let account_state = account_state.sub(lane_1, Value(1)).unwrap().unwrap();
+        assert_eq!(
+            account_state.sub(lane_7, Value(1)).err().unwrap(),
+            LedgerError::SpendingCounterInvalid {
+                expected: SpendingCounter::new(7, 1),
+                actual: lane_7,
+            }
+        );
+        let account_state = account_state
+            .sub(SpendingCounter::new(7, 1), Value(1))
+            .unwrap()
+            .unwrap();
+
+        let counters = account_state.spending().get_valid_counters();
+        assert_eq!(counters[0], SpendingCounter::new(0, 0));
+        assert_eq!(counters[1], SpendingCounter::new(1, 1));
+        assert_eq!(counters[7], SpendingCounter::new(7, 2));
+        assert_eq!(account_state.value(), Value(7));
+    }
+
     #[quickcheck]
     pub fn add_value(init_value: Value, value_to_add: Value) -> TestResult {
         let account_state = AccountState::new(init_value, ());
@@ -345,7 +572,10 @@ mod tests {
             operations: std::slice::Iter<ArbitraryAccountStateOp>,
             subs: u32,
         ) -> AccountState<()> {
-            let result_spending_counter = initial_account_state.counter.0 + subs;
+            let mut spending = initial_account_state.spending.clone();
+            spending.set_next(SpendingCounter(
+                initial_account_state.spending.get_valid_counter().0 + subs,
+            ));
             let mut delegation = initial_account_state.delegation().clone();
             let mut result_value = initial_account_state.get_value();
 
@@ -372,9 +602,11 @@ mod tests {
                 }
             }
             AccountState {
-                counter: SpendingCounter(result_spending_counter),
+                spending,
                 delegation,
                 value: result_value,
+                rewards: initial_account_state.rewards(),
+                deposit: initial_account_state.deposit(),
                 last_rewards: LastRewards::default(),
                 extra: (),
             }
@@ -411,8 +643,10 @@ mod tests {
                     }
                 }
                 ArbitraryAccountStateOp::Sub(value) => {
-                    let should_fail = should_sub_fail(account_state.clone(), value);
-                    match (should_fail, account_state.sub(value)) {
+                    let spending_counter = account_state.spending.get_valid_counter();
+                    let should_fail =
+                        should_sub_fail(account_state.clone(), spending_counter, value);
+                    match (should_fail, account_state.sub(spending_counter, value)) {
                         (false, Ok(account_state)) => {
                             successful_subs += 1;
                             // check if account has any funds left
@@ -466,12 +700,18 @@ mod tests {
         (value + account_state.get_value()).is_err()
     }
 
-    fn should_sub_fail(account_state: AccountState<()>, value: Value) -> bool {
+    fn should_sub_fail(
+        account_state: AccountState<()>,
+        counter: SpendingCounter,
+        value: Value,
+    ) -> bool {
         // should fail if we recieve negative result
         // or if we reached counter limit and it's now full withdrawal
         (account_state.get_value() - value).is_err()
-            || (account_state.counter.0.checked_add(1).is_none()
-                && account_state.get_value() != value)
+            || (counter.increment().is_none()
+                && (account_state.get_value() != value
+                    || account_state.rewards() != Value::zero()
+                    || account_state.deposit() != Value::zero()))
     }
 
     #[test]
@@ -566,11 +806,19 @@ mod tests {
         account_with_reward: AccountState<()>,
         initial_value: Value,
     ) -> TestResult {
-        if account_without_reward.value() != account_with_reward.value() {
+        if account_without_reward.total_value() != account_with_reward.total_value() {
             return TestResult::error(format!(
-                "value should be the same {} vs {}",
-                account_without_reward.value(),
-                account_with_reward.value()
+                "total value should be the same {} vs {}",
+                account_without_reward.total_value(),
+                account_with_reward.total_value()
+            ));
+        }
+
+        if account_with_reward.value() != initial_value {
+            return TestResult::error(format!(
+                "rewards should not be spendable {} vs {}",
+                account_with_reward.value(),
+                initial_value
             ));
         }
 
@@ -7,7 +7,7 @@
 pub mod account_state;
 pub mod last_rewards;
 use crate::{date::Epoch, value::*};
-use imhamt::{Hamt, InsertError, UpdateError};
+use imhamt::{Hamt, HamtIter, InsertError, UpdateError};
 use std::collections::hash_map::DefaultHasher;
 use std::fmt::{self, Debug};
 use std::hash::Hash;
@@ -27,6 +27,11 @@ pub enum LedgerError {
     AlreadyExists,
     #[error("Operation counter reached its maximum and next operation must be full withdrawal")]
     NeedTotalWithdrawal,
+    #[error("Spending counter {actual} doesn't match the next counter {expected} of its lane")]
+    SpendingCounterInvalid {
+        expected: SpendingCounter,
+        actual: SpendingCounter,
+    },
     #[error("Removed account is not empty")]
     NonZero,
     #[error("Value calculation failed")]
@@ -51,8 +56,15 @@ impl From<InsertError> for LedgerError {
 }
 
 /// The public ledger of all accounts associated with their current state
+///
+/// The spending counters of the deregistered accounts are kept, so that an
+/// account registered again continues its lanes and the transactions spent
+/// before its deregistration cannot be replayed.
 #[derive(Clone, PartialEq, Eq)]
-pub struct Ledger<ID: Hash + Eq, Extra>(Hamt<DefaultHasher, ID, AccountState<Extra>>);
+pub struct Ledger<ID: Hash + Eq, Extra> {
+    accounts: Hamt<DefaultHasher, ID, AccountState<Extra>>,
+    deregistered: Hamt<DefaultHasher, ID, SpendingCounterIncreasing>,
+}
 
 impl<ID: Clone + Eq + Hash, Extra: Clone> Default for Ledger<ID, Extra> {
     fn default() -> Self {
@@ -63,7 +75,48 @@ impl<ID: Clone + Eq + Hash, Extra: Clone> Default for Ledger<ID, Extra> {
 impl<ID: Clone + Eq + Hash, Extra: Clone> Ledger<ID, Extra> {
     /// Create a new empty account ledger
     pub fn new() -> Self {
-        Ledger(Hamt::new())
+        Ledger {
+            accounts: Hamt::new(),
+            deregistered: Hamt::new(),
+        }
+    }
+
+    /// Rebuild the ledger from its accounts and the spending counters of its
+    /// deregistered accounts
+    pub fn restore(
+        accounts: Vec<(ID, AccountState<Extra>)>,
+        deregistered: Vec<(ID, SpendingCounterIncreasing)>,
+    ) -> Self {
+        Ledger {
+            accounts: accounts.into_iter().collect(),
+            deregistered: deregistered.into_iter().collect(),
+        }
+    }
+
+    fn with_accounts(&self, accounts: Hamt<DefaultHasher, ID, AccountState<Extra>>) -> Self {
+        Ledger {
+            accounts,
+            deregistered: self.deregistered.clone(),
+        }
+    }
+
+    /// Insert the new state of an account that doesn't exist, continuing the
+    /// spending counters of the account if it was deregistered.
+    fn insert(&self, identifier: &ID, mut state: AccountState<Extra>) -> Result<Self, LedgerError> {
+        let deregistered = match self.deregistered.lookup(identifier) {
+            None => self.deregistered.clone(),
+            Some(spending) => {
+                state.spending = spending.clone();
+                self.deregistered
+                    .remove(identifier)
+                    .expect("the deregistered account was just found")
+            }
+        };
+        let accounts = self.accounts.insert(identifier.clone(), state)?;
+        Ok(Ledger {
+            accounts,
+            deregistered,
+        })
     }
 
     /// Add a new account into this ledger.
@@ -75,10 +128,7 @@ impl<ID: Clone + Eq + Hash, Extra: Clone> Ledger<ID, Extra> {
         initial_value: Value,
         extra: Extra,
     ) -> Result<Self, LedgerError> {
-        self.0
-            .insert(identifier.clone(), AccountState::new(initial_value, extra))
-            .map(Ledger)
-            .map_err(|e| e.into())
+        self.insert(identifier, AccountState::new(initial_value, extra))
     }
 
     /// Set the delegation of an account in this ledger
@@ -87,40 +137,87 @@ impl<ID: Clone + Eq + Hash, Extra: Clone> Ledger<ID, Extra> {
         identifier: &ID,
         delegation: &DelegationType,
     ) -> Result<Self, LedgerError> {
-        self.0
+        self.accounts
             .update(identifier, |st| {
                 Ok(Some(st.set_delegation(delegation.clone())))
             })
-            .map(Ledger)
+            .map(|accounts| self.with_accounts(accounts))
             .map_err(|e| e.into())
     }
 
     /// check if an account already exist
     #[inline]
     pub fn exists(&self, identifier: &ID) -> bool {
-        self.0.contains_key(identifier)
+        self.accounts.contains_key(identifier)
     }
 
     /// Get account state
     ///
     /// If the identifier does not match any account, error out
     pub fn get_state(&self, account: &ID) -> Result<&AccountState<Extra>, LedgerError> {
-        self.0.lookup(account).ok_or(LedgerError::NonExistent)
+        self.accounts
+            .lookup(account)
+            .ok_or(LedgerError::NonExistent)
     }
 
     /// Remove an account from this ledger
     ///
-    /// If the account still have value > 0, then error
+    /// If the account still have value > 0, rewards > 0 or holds a deposit, then error
     pub fn remove_account(&self, identifier: &ID) -> Result<Self, LedgerError> {
-        self.0
+        self.accounts
             .update(identifier, |st| {
-                if st.value == Value::zero() {
+                if st.value == Value::zero()
+                    && st.rewards == Value::zero()
+                    && st.deposit == Value::zero()
+                {
                     Ok(None)
                 } else {
                     Err(LedgerError::NonZero)
                 }
             })
-            .map(Ledger)
+            .map(|accounts| self.with_accounts(accounts))
+            .map_err(|e| e.into())
+    }
+
+    /// Remove an account from this ledger, keeping its spending counters for
+    /// the next time the account is created.
+    ///
+    /// If the account doesn't exist, still have value > 0, rewards > 0 or
+    /// holds a deposit, then error
+    pub fn deregister_account(&self, identifier: &ID) -> Result<Self, LedgerError> {
+        let spending = self.get_state(identifier)?.spending.clone();
+        let ledger = self.remove_account(identifier)?;
+        Ok(Ledger {
+            accounts: ledger.accounts,
+            deregistered: ledger.deregistered.insert_or_update_simple(
+                identifier.clone(),
+                spending.clone(),
+                |_| Some(spending.clone()),
+            ),
+        })
+    }
+
+    /// Create a new empty account holding the deposit of its registration.
+    ///
+    /// If the identifier is already present, error out.
+    pub fn register_account(
+        &self,
+        identifier: &ID,
+        deposit: Value,
+        extra: Extra,
+    ) -> Result<Self, LedgerError> {
+        self.insert(identifier, AccountState::new_registered(deposit, extra))
+    }
+
+    /// Move the deposit of an existing account to its value, returning the
+    /// refunded deposit.
+    ///
+    /// If the account doesn't exist, error out.
+    pub fn refund_deposit(&self, identifier: &ID) -> Result<(Self, Value), LedgerError> {
+        let deposit = self.get_state(identifier)?.deposit;
+        self.accounts
+            .update(identifier, |st| st.refund_deposit().map(Some))
+            .map(|accounts| (self.with_accounts(accounts), deposit))
             .map_err(|e| e.into())
     }
 
@@ -128,9 +225,9 @@ impl<ID: Clone + Eq + Hash, Extra: Clone> Ledger<ID, Extra> {
     ///
     /// If the account doesn't exist, error out.
     pub fn add_value(&self, identifier: &ID, value: Value) -> Result<Self, LedgerError> {
-        self.0
+        self.accounts
             .update(identifier, |st| st.add(value).map(Some))
-            .map(Ledger)
+            .map(|accounts| self.with_accounts(accounts))
             .map_err(|e| e.into())
     }
 
@@ -143,11 +240,16 @@ impl<ID: Clone + Eq + Hash, Extra: Clone> Ledger<ID, Extra> {
         value: Value,
         extra: Extra,
     ) -> Result<Self, ValueError> {
-        self.0
+        if self.deregistered.contains_key(identifier) {
+            return Ok(self
+                .insert(identifier, AccountState::new(value, extra))
+                .expect("a deregistered account doesn't exist"));
+        }
+        self.accounts
             .insert_or_update(identifier.clone(), AccountState::new(value, extra), |st| {
                 st.add_value(value).map(Some)
             })
-            .map(Ledger)
+            .map(|accounts| self.with_accounts(accounts))
     }
 
     /// Add rewards to an existing account.
@@ -160,44 +262,61 @@ impl<ID: Clone + Eq + Hash, Extra: Clone> Ledger<ID, Extra> {
         value: Value,
         extra: Extra,
     ) -> Result<Self, ValueError> {
-        self.0
+        if self.deregistered.contains_key(identifier) {
+            return Ok(self
+                .insert(identifier, AccountState::new_reward(epoch, value, extra))
+                .expect("a deregistered account doesn't exist"));
+        }
+        self.accounts
             .insert_or_update(
                 identifier.clone(),
                 AccountState::new_reward(epoch, value, extra),
                 |st| st.add_rewards(epoch, value).map(Some),
             )
-            .map(Ledger)
+            .map(|accounts| self.with_accounts(accounts))
     }
 
-    /// Subtract value to an existing account.
+    /// Move rewards of an existing account to its value.
     ///
-    /// If the account doesn't exist, or that the value would become negative, errors out.
+    /// If the account doesn't exist, or that there is not enough rewards, errors out.
+    pub fn withdraw_rewards(&self, identifier: &ID, value: Value) -> Result<Self, LedgerError> {
+        self.accounts
+            .update(identifier, |st| st.withdraw_rewards(value).map(Some))
+            .map(|accounts| self.with_accounts(accounts))
+            .map_err(|e| e.into())
+    }
+
+    /// Subtract value to an existing account, spending with `counter`.
+    ///
+    /// If the account doesn't exist, that the counter is not the next one of
+    /// its lane, or that the value would become negative, errors out.
     pub fn remove_value(
         &self,
         identifier: &ID,
+        counter: SpendingCounter,
         value: Value,
-    ) -> Result<(Self, SpendingCounter), LedgerError> {
-        // ideally we don't need 2 calls to do this
-        let counter = self
-            .0
-            .lookup(identifier)
-            .map_or(Err(LedgerError::NonExistent), |st| Ok(st.counter))?;
-        self.0
-            .update(identifier, |st| st.sub(value))
-            .map(|ledger| (Ledger(ledger), counter))
+    ) -> Result<Self, LedgerError> {
+        self.accounts
+            .update(identifier, |st| st.sub(counter, value))
+            .map(|accounts| self.with_accounts(accounts))
             .map_err(|e| e.into())
     }
 
     pub fn get_total_value(&self) -> Result<Value, ValueError> {
         let values = self
-            .0
+            .accounts
             .iter()
-            .map(|(_, account_state)| account_state.get_value());
+            .map(|(_, account_state)| account_state.total_value());
         Value::sum(values)
     }
 
     pub fn iter(&self) -> Iter<'_, ID, Extra> {
-        Iter(self.0.iter())
+        Iter(self.accounts.iter())
+    }
+
+    /// the spending counters of the deregistered accounts
+    pub fn iter_deregistered(&self) -> HamtIter<'_, ID, SpendingCounterIncreasing> {
+        self.deregistered.iter()
     }
 }
 
@@ -206,7 +325,7 @@ impl<ID: Clone + Eq + Hash + Debug, Extra: Clone + Debug> Debug for Ledger<ID, E
         write!(
             f,
             "{:?}",
-            self.0
+            self.accounts
                 .iter()
                 .map(|(id, account)| (id.clone(), account.clone()))
                 .collect::<Vec<(ID, AccountState<Extra>)>>()
@@ -218,7 +337,10 @@ impl<ID: Clone + Eq + Hash, Extra: Clone> std::iter::FromIterator<(ID, AccountSt
     for Ledger<ID, Extra>
 {
     fn from_iter<I: IntoIterator<Item = (ID, AccountState<Extra>)>>(iter: I) -> Self {
-        Ledger(Hamt::from_iter(iter))
+        Ledger {
+            accounts: Hamt::from_iter(iter),
+            deregistered: Hamt::new(),
+        }
     }
 }
 
@@ -393,13 +515,15 @@ mod tests {
         match ledger.get_state(&account_id) {
             Ok(account_state) => {
                 let expected_account_state = AccountState {
-                    counter: SpendingCounter::zero(),
+                    spending: SpendingCounterIncreasing::default(),
                     last_rewards: LastRewards {
                         epoch: 0,
                         reward: value,
                     },
                     delegation: DelegationType::Full(stake_pool_id),
-                    value: value_after_reward,
+                    value: Value(value.0 * 2),
+                    rewards: value,
+                    deposit: Value::zero(),
                     extra: (),
                 };
 
@@ -418,9 +542,27 @@ mod tests {
             }
         }
 
+        // rewards are moved to the account value
+        ledger = match ledger.withdraw_rewards(&account_id, value) {
+            Ok(ledger) => ledger,
+            Err(err) => {
+                return TestResult::error(format!(
+                    "Withdraw rewards operation for id {} should be successful: {:?}",
+                    account_id, err
+                ))
+            }
+        };
+        let account_state = ledger.get_state(&account_id).unwrap();
+        if account_state.value() != value_after_reward || account_state.rewards() != Value::zero() {
+            return TestResult::error(format!(
+                "Rewards of account {} should have been withdrawn: {:?}",
+                account_id, account_state
+            ));
+        }
+
         // remove value from account
-        ledger = match ledger.remove_value(&account_id, value) {
-            Ok((ledger, _spending_counter)) => ledger,
+        ledger = match ledger.remove_value(&account_id, SpendingCounter::zero(), value) {
+            Ok(ledger) => ledger,
             Err(err) => {
                 return TestResult::error(format!(
                     "Removew value operation for id {} should be successful: {:?}",
@@ -447,8 +589,9 @@ mod tests {
         }
 
         // removes all funds from account
-        ledger = match ledger.remove_value(&account_id, value_before_reward) {
-            Ok((ledger, _spending_counter)) => ledger,
+        let counter = SpendingCounter::zero().increment().unwrap();
+        ledger = match ledger.remove_value(&account_id, counter, value_before_reward) {
+            Ok(ledger) => ledger,
             Err(err) => {
                 return TestResult::error(format!(
                     "Remove all funds operation for id {} should be successful: {:?}",
@@ -505,13 +648,13 @@ mod tests {
         ledger = ledger
             .add_account(&id, account_state.get_value(), ())
             .unwrap();
-        let result = ledger.remove_value(&id, value_to_remove);
+        let result = ledger.remove_value(&id, SpendingCounter::zero(), value_to_remove);
         let expected_result = account_state.get_value() - value_to_remove;
         match (result, expected_result) {
             (Err(_), Err(_)) => verify_total_value(ledger, account_state.get_value()),
             (Ok(_), Err(_)) => TestResult::failed(),
             (Err(_), Ok(_)) => TestResult::failed(),
-            (Ok((ledger, _)), Ok(value)) => verify_total_value(ledger, value),
+            (Ok(ledger), Ok(value)) => verify_total_value(ledger, value),
         }
     }
 
@@ -565,6 +708,38 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn deregistered_account_continues_its_spending_counters() {
+        let id = TestGen::identifier();
+        let counter = SpendingCounter::new(1, 0);
+        let ledger = Ledger::new()
+            .register_account(&id, Value(100), ())
+            .unwrap()
+            .add_value(&id, Value(10))
+            .unwrap()
+            .remove_value(&id, counter, Value(10))
+            .unwrap()
+            .refund_deposit(&id)
+            .unwrap()
+            .0
+            .remove_value(&id, counter.increment().unwrap(), Value(100))
+            .unwrap();
+        let expected = ledger.get_state(&id).unwrap().spending().clone();
+
+        let ledger = ledger.deregister_account(&id).unwrap();
+        assert!(!ledger.exists(&id));
+        assert_eq!(ledger.iter_deregistered().count(), 1);
+
+        let ledger = ledger.register_account(&id, Value(100), ()).unwrap();
+        assert_eq!(ledger.get_state(&id).unwrap().spending(), &expected);
+        assert_eq!(ledger.iter_deregistered().count(), 0);
+        assert!(ledger
+            .add_value(&id, Value(10))
+            .unwrap()
+            .remove_value(&id, counter, Value(10))
+            .is_err());
+    }
+
     #[test]
     pub fn add_value_or_account_test() {
         let ledger = Ledger::new();
@@ -1,5 +1,7 @@
 #[warn(unused_imports)]
-use super::{AccountState, DelegationType, LastRewards, SpendingCounter};
+use super::{
+    AccountState, DelegationType, LastRewards, SpendingCounter, SpendingCounterIncreasing,
+};
 use quickcheck::{Arbitrary, Gen};
 
 impl Arbitrary for SpendingCounter {
@@ -8,12 +10,25 @@ impl Arbitrary for SpendingCounter {
     }
 }
 
+impl Arbitrary for SpendingCounterIncreasing {
+    fn arbitrary<G: Gen>(gen: &mut G) -> Self {
+        let mut nexts = [SpendingCounter::zero(); SpendingCounter::LANES];
+        for (lane, next) in nexts.iter_mut().enumerate() {
+            let counter = SpendingCounter::arbitrary(gen).unlaned_counter();
+            *next = SpendingCounter::new(lane, counter);
+        }
+        SpendingCounterIncreasing::from_nexts(nexts).unwrap()
+    }
+}
+
 impl Arbitrary for AccountState<()> {
     fn arbitrary<G: Gen>(gen: &mut G) -> Self {
         AccountState {
-            counter: Arbitrary::arbitrary(gen),
+            spending: Arbitrary::arbitrary(gen),
             delegation: DelegationType::Full(Arbitrary::arbitrary(gen)),
             value: Arbitrary::arbitrary(gen),
+            rewards: Arbitrary::arbitrary(gen),
+            deposit: Arbitrary::arbitrary(gen),
             last_rewards: LastRewards::default(),
             extra: (),
         }
@@ -118,8 +118,9 @@ impl Arbitrary for Block {
                     .set_signature(bft_proof.signature)
                     .generalize()
             }
-            BlockVersion::KesVrfproof => {
-                let gp_proof: GenesisPraosProof = Arbitrary::arbitrary(g);
+            BlockVersion::KesVrfproof | BlockVersion::KesEcVrfproof => {
+                let gp_proof =
+                    GenesisPraosProof::arbitrary_with_vrf(g, ver.to_vrf_algorithm().unwrap());
                 hdrbuilder
                     .into_genesis_praos_builder()
                     .unwrap()
@@ -0,0 +1,147 @@
+use crate::{
+    account::Identifier,
+    certificate::CertificateSlice,
+    transaction::{Payload, PayloadAuthData, PayloadData, PayloadSlice},
+};
+use chain_core::{
+    mempack::{ReadBuf, ReadError, Readable},
+    property,
+};
+use typed_bytes::{ByteArray, ByteBuilder};
+
+/// register a new account, holding the account registration deposit
+///
+/// The deposit is paid by the inputs of the transaction along the fees, so
+/// anyone can register an account on behalf of its owner. It is refunded to
+/// the account on its deregistration.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccountRegistration {
+    account: Identifier,
+}
+
+/// deregister an account, refunding the deposit of its registration
+///
+/// The transaction needs a single input spending from the deregistered
+/// account. The deposit is moved to the account value before the input is
+/// applied so it can be spent by the transaction, and the account must be
+/// empty afterward.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccountDeregistration {
+    account: Identifier,
+}
+
+impl AccountRegistration {
+    pub fn new(account: Identifier) -> Self {
+        Self { account }
+    }
+
+    pub fn account(&self) -> &Identifier {
+        &self.account
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.bytes(self.account.as_ref().as_ref())
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+}
+
+impl AccountDeregistration {
+    pub fn new(account: Identifier) -> Self {
+        Self { account }
+    }
+
+    pub fn account(&self) -> &Identifier {
+        &self.account
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.bytes(self.account.as_ref().as_ref())
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+}
+
+/* Auth/Payload ************************************************************* */
+
+impl Payload for AccountRegistration {
+    const HAS_DATA: bool = true;
+    const HAS_AUTH: bool = false;
+    type Auth = ();
+
+    fn payload_data(&self) -> PayloadData<Self> {
+        PayloadData(
+            self.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn payload_auth_data(_: &Self::Auth) -> PayloadAuthData<Self> {
+        PayloadAuthData(Vec::with_capacity(0).into(), std::marker::PhantomData)
+    }
+
+    fn payload_to_certificate_slice(p: PayloadSlice<'_, Self>) -> Option<CertificateSlice<'_>> {
+        Some(CertificateSlice::from(p))
+    }
+}
+
+impl Payload for AccountDeregistration {
+    const HAS_DATA: bool = true;
+    const HAS_AUTH: bool = false;
+    type Auth = ();
+
+    fn payload_data(&self) -> PayloadData<Self> {
+        PayloadData(
+            self.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn payload_auth_data(_: &Self::Auth) -> PayloadAuthData<Self> {
+        PayloadAuthData(Vec::with_capacity(0).into(), std::marker::PhantomData)
+    }
+
+    fn payload_to_certificate_slice(p: PayloadSlice<'_, Self>) -> Option<CertificateSlice<'_>> {
+        Some(CertificateSlice::from(p))
+    }
+}
+
+/* Ser/De ******************************************************************* */
+
+impl property::Serialize for AccountRegistration {
+    type Error = std::io::Error;
+    fn serialize<W: std::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        writer.write_all(self.serialize().as_slice())?;
+        Ok(())
+    }
+}
+
+impl Readable for AccountRegistration {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let account = Identifier::read(buf)?;
+        Ok(Self::new(account))
+    }
+}
+
+impl property::Serialize for AccountDeregistration {
+    type Error = std::io::Error;
+    fn serialize<W: std::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        writer.write_all(self.serialize().as_slice())?;
+        Ok(())
+    }
+}
+
+impl Readable for AccountDeregistration {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let account = Identifier::read(buf)?;
+        Ok(Self::new(account))
+    }
+}
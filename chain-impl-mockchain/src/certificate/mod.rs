@@ -1,7 +1,11 @@
+mod account_registration;
 mod delegation;
 mod encrypted_vote_tally;
 mod pool;
+mod pool_metadata;
+mod reward_withdrawal;
 mod vote_cast;
+mod vote_delegation;
 mod vote_plan;
 mod vote_tally;
 
@@ -10,8 +14,11 @@ mod test;
 
 use crate::transaction::{Payload, PayloadData, PayloadSlice};
 
+pub use self::account_registration::{AccountDeregistration, AccountRegistration};
 pub use self::encrypted_vote_tally::{EncryptedVoteTally, EncryptedVoteTallyProof};
+pub use self::reward_withdrawal::RewardWithdrawal;
 pub use self::vote_cast::VoteCast;
+pub use self::vote_delegation::{VoteDelegation, VoteRepresentative};
 pub use self::vote_plan::{
     ExternalProposalDocument, ExternalProposalId, Proposal, Proposals, PushProposal, VoteAction,
     VotePlan, VotePlanId, VotePlanProof,
@@ -25,6 +32,10 @@ pub use pool::{
     PoolPermissions, PoolRegistration, PoolRegistrationHash, PoolRetirement, PoolSignature,
     PoolUpdate,
 };
+pub use pool_metadata::{
+    PoolMetadata, PoolMetadataDocument, PoolMetadataHash, PoolRelay, POOL_MAX_RELAYS,
+    POOL_METADATA_URL_MAX_SIZE, POOL_RELAY_DNS_NAME_MAX_SIZE,
+};
 
 pub enum CertificateSlice<'a> {
     StakeDelegation(PayloadSlice<'a, StakeDelegation>),
@@ -36,6 +47,10 @@ pub enum CertificateSlice<'a> {
     VoteCast(PayloadSlice<'a, VoteCast>),
     VoteTally(PayloadSlice<'a, VoteTally>),
     EncryptedVoteTally(PayloadSlice<'a, EncryptedVoteTally>),
+    VoteDelegation(PayloadSlice<'a, VoteDelegation>),
+    RewardWithdrawal(PayloadSlice<'a, RewardWithdrawal>),
+    AccountRegistration(PayloadSlice<'a, AccountRegistration>),
+    AccountDeregistration(PayloadSlice<'a, AccountDeregistration>),
 }
 
 impl<'a> From<PayloadSlice<'a, StakeDelegation>> for CertificateSlice<'a> {
@@ -91,6 +106,30 @@ impl<'a> From<PayloadSlice<'a, EncryptedVoteTally>> for CertificateSlice<'a> {
     }
 }
 
+impl<'a> From<PayloadSlice<'a, VoteDelegation>> for CertificateSlice<'a> {
+    fn from(payload: PayloadSlice<'a, VoteDelegation>) -> CertificateSlice<'a> {
+        CertificateSlice::VoteDelegation(payload)
+    }
+}
+
+impl<'a> From<PayloadSlice<'a, RewardWithdrawal>> for CertificateSlice<'a> {
+    fn from(payload: PayloadSlice<'a, RewardWithdrawal>) -> CertificateSlice<'a> {
+        CertificateSlice::RewardWithdrawal(payload)
+    }
+}
+
+impl<'a> From<PayloadSlice<'a, AccountRegistration>> for CertificateSlice<'a> {
+    fn from(payload: PayloadSlice<'a, AccountRegistration>) -> CertificateSlice<'a> {
+        CertificateSlice::AccountRegistration(payload)
+    }
+}
+
+impl<'a> From<PayloadSlice<'a, AccountDeregistration>> for CertificateSlice<'a> {
+    fn from(payload: PayloadSlice<'a, AccountDeregistration>) -> CertificateSlice<'a> {
+        CertificateSlice::AccountDeregistration(payload)
+    }
+}
+
 impl<'a> CertificateSlice<'a> {
     pub fn into_owned(self) -> Certificate {
         match self {
@@ -109,6 +148,16 @@ impl<'a> CertificateSlice<'a> {
             CertificateSlice::EncryptedVoteTally(c) => {
                 Certificate::EncryptedVoteTally(c.into_payload())
             }
+            CertificateSlice::VoteDelegation(c) => Certificate::VoteDelegation(c.into_payload()),
+            CertificateSlice::RewardWithdrawal(c) => {
+                Certificate::RewardWithdrawal(c.into_payload())
+            }
+            CertificateSlice::AccountRegistration(c) => {
+                Certificate::AccountRegistration(c.into_payload())
+            }
+            CertificateSlice::AccountDeregistration(c) => {
+                Certificate::AccountDeregistration(c.into_payload())
+            }
         }
     }
 }
@@ -124,6 +173,10 @@ pub enum CertificatePayload {
     VoteCast(PayloadData<VoteCast>),
     VoteTally(PayloadData<VoteTally>),
     EncryptedVoteTally(PayloadData<EncryptedVoteTally>),
+    VoteDelegation(PayloadData<VoteDelegation>),
+    RewardWithdrawal(PayloadData<RewardWithdrawal>),
+    AccountRegistration(PayloadData<AccountRegistration>),
+    AccountDeregistration(PayloadData<AccountDeregistration>),
 }
 
 impl CertificatePayload {
@@ -138,6 +191,10 @@ impl CertificatePayload {
             CertificatePayload::VoteCast(payload) => payload.borrow().into(),
             CertificatePayload::VoteTally(payload) => payload.borrow().into(),
             CertificatePayload::EncryptedVoteTally(payload) => payload.borrow().into(),
+            CertificatePayload::VoteDelegation(payload) => payload.borrow().into(),
+            CertificatePayload::RewardWithdrawal(payload) => payload.borrow().into(),
+            CertificatePayload::AccountRegistration(payload) => payload.borrow().into(),
+            CertificatePayload::AccountDeregistration(payload) => payload.borrow().into(),
         }
     }
 }
@@ -168,6 +225,18 @@ impl<'a> From<&'a Certificate> for CertificatePayload {
             Certificate::EncryptedVoteTally(payload) => {
                 CertificatePayload::EncryptedVoteTally(payload.payload_data())
             }
+            Certificate::VoteDelegation(payload) => {
+                CertificatePayload::VoteDelegation(payload.payload_data())
+            }
+            Certificate::RewardWithdrawal(payload) => {
+                CertificatePayload::RewardWithdrawal(payload.payload_data())
+            }
+            Certificate::AccountRegistration(payload) => {
+                CertificatePayload::AccountRegistration(payload.payload_data())
+            }
+            Certificate::AccountDeregistration(payload) => {
+                CertificatePayload::AccountDeregistration(payload.payload_data())
+            }
         }
     }
 }
@@ -184,6 +253,10 @@ pub enum Certificate {
     VoteCast(VoteCast),
     VoteTally(VoteTally),
     EncryptedVoteTally(EncryptedVoteTally),
+    VoteDelegation(VoteDelegation),
+    RewardWithdrawal(RewardWithdrawal),
+    AccountRegistration(AccountRegistration),
+    AccountDeregistration(AccountDeregistration),
 }
 
 impl From<StakeDelegation> for Certificate {
@@ -240,6 +313,30 @@ impl From<EncryptedVoteTally> for Certificate {
     }
 }
 
+impl From<VoteDelegation> for Certificate {
+    fn from(vote_delegation: VoteDelegation) -> Self {
+        Self::VoteDelegation(vote_delegation)
+    }
+}
+
+impl From<RewardWithdrawal> for Certificate {
+    fn from(reward_withdrawal: RewardWithdrawal) -> Self {
+        Self::RewardWithdrawal(reward_withdrawal)
+    }
+}
+
+impl From<AccountRegistration> for Certificate {
+    fn from(account_registration: AccountRegistration) -> Self {
+        Self::AccountRegistration(account_registration)
+    }
+}
+
+impl From<AccountDeregistration> for Certificate {
+    fn from(account_deregistration: AccountDeregistration) -> Self {
+        Self::AccountDeregistration(account_deregistration)
+    }
+}
+
 impl Certificate {
     pub fn need_auth(&self) -> bool {
         match self {
@@ -252,6 +349,10 @@ impl Certificate {
             Certificate::VoteCast(_) => <VoteCast as Payload>::HAS_AUTH,
             Certificate::VoteTally(_) => <VoteTally as Payload>::HAS_AUTH,
             Certificate::EncryptedVoteTally(_) => <EncryptedVoteTally as Payload>::HAS_AUTH,
+            Certificate::VoteDelegation(_) => <VoteDelegation as Payload>::HAS_AUTH,
+            Certificate::RewardWithdrawal(_) => <RewardWithdrawal as Payload>::HAS_AUTH,
+            Certificate::AccountRegistration(_) => <AccountRegistration as Payload>::HAS_AUTH,
+            Certificate::AccountDeregistration(_) => <AccountDeregistration as Payload>::HAS_AUTH,
         }
     }
 }
@@ -290,6 +391,10 @@ mod tests {
             Certificate::VoteCast(_) => false,
             Certificate::VoteTally(_) => true,
             Certificate::EncryptedVoteTally(_) => true,
+            Certificate::VoteDelegation(_) => false,
+            Certificate::RewardWithdrawal(_) => false,
+            Certificate::AccountRegistration(_) => false,
+            Certificate::AccountDeregistration(_) => false,
         };
         TestResult::from_bool(certificate.need_auth() == expected_result)
     }
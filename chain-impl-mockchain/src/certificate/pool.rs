@@ -1,3 +1,4 @@
+use super::pool_metadata::{PoolMetadata, PoolRelay};
 use super::CertificateSlice;
 use crate::key::{deserialize_public_key, deserialize_signature, GenesisPraosLeader};
 use crate::rewards::TaxType;
@@ -48,6 +49,10 @@ pub struct PoolRegistration {
     pub reward_account: Option<AccountIdentifier>,
     /// Genesis Praos keys
     pub keys: GenesisPraosLeader,
+    /// Off-chain metadata of the pool
+    pub metadata: Option<PoolMetadata>,
+    /// Relays of the pool
+    pub relays: Vec<PoolRelay>,
 }
 
 /// Permission system related to the pool
@@ -56,6 +61,15 @@ pub struct PoolPermissions(u64);
 
 pub type ManagementThreshold = u8;
 
+/// flag set on the reward account tag of a registration followed by its
+/// versioned extension. Registrations without metadata nor relays don't have
+/// the extension and keep their original format, and pool identifier.
+const POOL_REGISTRATION_EXTENSION_FLAG: u8 = 0b1000_0000;
+
+/// version of the pool registration extension holding the metadata and the
+/// relays of the pool
+const POOL_REGISTRATION_EXTENSION_V1: u8 = 1;
+
 const MANAGEMENT_THRESHOLD_BITMASK: u64 = 0b11_1111; // only support 32, reserved one for later extension if needed
 
 #[allow(clippy::unusual_byte_groupings)]
@@ -122,11 +136,31 @@ impl PoolRegistration {
             .iter8(&mut self.operators.iter(), |bb, o| bb.bytes(o.as_ref()))
             .sub(|sbb| self.rewards.serialize_in(sbb));
 
-        match &self.reward_account {
-            None => bb.u8(0),
-            Some(AccountIdentifier::Single(pk)) => bb.u8(1).bytes(pk.as_ref().as_ref()),
-            Some(AccountIdentifier::Multi(pk)) => bb.u8(2).bytes(pk.as_ref()),
+        let has_extension = self.metadata.is_some() || !self.relays.is_empty();
+        let flag = if has_extension {
+            POOL_REGISTRATION_EXTENSION_FLAG
+        } else {
+            0
+        };
+
+        let bb = match &self.reward_account {
+            None => bb.u8(flag),
+            Some(AccountIdentifier::Single(pk)) => bb.u8(1 | flag).bytes(pk.as_ref().as_ref()),
+            Some(AccountIdentifier::Multi(pk)) => bb.u8(2 | flag).bytes(pk.as_ref()),
+        };
+
+        if !has_extension {
+            return bb;
         }
+
+        let bb = bb.u8(POOL_REGISTRATION_EXTENSION_V1);
+        let bb = match &self.metadata {
+            None => bb.u8(0),
+            Some(metadata) => bb.u8(1).sub(|sbb| metadata.serialize_in(sbb)),
+        };
+        bb.iter8(&mut self.relays.iter(), |bb, relay| {
+            bb.sub(|sbb| relay.serialize_in(sbb))
+        })
     }
 
     pub fn serialize(&self) -> ByteArray<Self> {
@@ -286,7 +320,9 @@ impl Readable for PoolRegistration {
         }
 
         let rewards = TaxType::read_frombuf(buf)?;
-        let reward_account = match buf.get_u8()? {
+        let reward_account_tag = buf.get_u8()?;
+        let has_extension = reward_account_tag & POOL_REGISTRATION_EXTENSION_FLAG != 0;
+        let reward_account = match reward_account_tag & !POOL_REGISTRATION_EXTENSION_FLAG {
             0 => None,
             1 => {
                 let pk = deserialize_public_key(buf)?;
@@ -300,6 +336,38 @@ impl Readable for PoolRegistration {
             n => return Err(ReadError::UnknownTag(n as u32)),
         };
 
+        let (metadata, relays) = if has_extension {
+            match buf.get_u8()? {
+                POOL_REGISTRATION_EXTENSION_V1 => {}
+                n => {
+                    return Err(ReadError::StructureInvalid(format!(
+                        "unknown pool registration extension version {}",
+                        n
+                    )))
+                }
+            }
+            let metadata = match buf.get_u8()? {
+                0 => None,
+                1 => Some(PoolMetadata::read(buf)?),
+                n => return Err(ReadError::UnknownTag(n as u32)),
+            };
+            let relays_nb = buf.get_u8()? as usize;
+            let mut relays = Vec::with_capacity(relays_nb);
+            for _ in 0..relays_nb {
+                relays.push(PoolRelay::read(buf)?);
+            }
+            if metadata.is_none() && relays.is_empty() {
+                // the extension is only present when it isn't empty, so the
+                // registration has a single encoding and identifier
+                return Err(ReadError::StructureInvalid(
+                    "empty pool registration extension".to_string(),
+                ));
+            }
+            (metadata, relays)
+        } else {
+            (None, Vec::new())
+        };
+
         let info = Self {
             serial,
             start_validity,
@@ -309,6 +377,8 @@ impl Readable for PoolRegistration {
             rewards,
             reward_account,
             keys,
+            metadata,
+            relays,
         };
         Ok(info)
     }
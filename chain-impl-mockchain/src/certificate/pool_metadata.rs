@@ -0,0 +1,123 @@
+use chain_core::mempack::{ReadBuf, ReadError, Readable};
+use chain_crypto::{
+    digest::{Digest, DigestOf},
+    Blake2b256,
+};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use typed_bytes::ByteBuilder;
+
+// the size limits are checked by the ledger on registration, like the
+// number of owners and operators
+
+/// maximum size in bytes of the URL of the pool metadata document
+pub const POOL_METADATA_URL_MAX_SIZE: usize = 64;
+/// maximum size in bytes of the DNS name of a pool relay
+pub const POOL_RELAY_DNS_NAME_MAX_SIZE: usize = 64;
+/// maximum number of relays declared by a pool
+pub const POOL_MAX_RELAYS: usize = 16;
+
+/// abstract tag type to represent the off-chain JSON document describing a
+/// pool (name, ticker, homepage...)
+pub struct PoolMetadataDocument;
+
+/// the Blake2b 256 bits hash of the pool metadata document, committing the
+/// pool to the content served at the metadata URL
+pub type PoolMetadataHash = DigestOf<Blake2b256, PoolMetadataDocument>;
+
+/// Off-chain metadata of a pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolMetadata {
+    /// where the metadata document can be retrieved
+    pub url: String,
+    /// hash of the metadata document
+    pub hash: PoolMetadataHash,
+}
+
+/// Network address at which a pool relay can be reached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolRelay {
+    Ipv4 { address: Ipv4Addr, port: u16 },
+    Ipv6 { address: Ipv6Addr, port: u16 },
+    DnsName { name: String, port: u16 },
+}
+
+impl PoolMetadata {
+    pub fn new(url: String, hash: PoolMetadataHash) -> Self {
+        PoolMetadata { url, hash }
+    }
+
+    /// the hash committing to the given metadata document
+    pub fn document_hash(document: &[u8]) -> PoolMetadataHash {
+        Digest::<Blake2b256>::digest(document).into()
+    }
+
+    /// check that a document retrieved from the metadata URL is the one the
+    /// pool committed to
+    pub fn matches_document(&self, document: &[u8]) -> bool {
+        self.hash == Self::document_hash(document)
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.iter8(self.url.as_bytes(), |bb, b| bb.u8(*b))
+            .bytes(self.hash.as_ref())
+    }
+}
+
+impl PoolRelay {
+    pub fn port(&self) -> u16 {
+        match self {
+            PoolRelay::Ipv4 { port, .. }
+            | PoolRelay::Ipv6 { port, .. }
+            | PoolRelay::DnsName { port, .. } => *port,
+        }
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        match self {
+            PoolRelay::Ipv4 { address, port } => bb.u8(0).bytes(&address.octets()).u16(*port),
+            PoolRelay::Ipv6 { address, port } => bb.u8(1).bytes(&address.octets()).u16(*port),
+            PoolRelay::DnsName { name, port } => bb
+                .u8(2)
+                .iter8(name.as_bytes(), |bb, b| bb.u8(*b))
+                .u16(*port),
+        }
+    }
+}
+
+fn read_string(buf: &mut ReadBuf, what: &str) -> Result<String, ReadError> {
+    let size = buf.get_u8()? as usize;
+    let bytes = buf.get_slice(size)?;
+    String::from_utf8(bytes.to_vec())
+        .map_err(|_| ReadError::StructureInvalid(format!("{} is not valid UTF-8", what)))
+}
+
+impl Readable for PoolMetadata {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let url = read_string(buf, "pool metadata URL")?;
+        let hash = <[u8; 32]>::read(buf)?.into();
+        Ok(PoolMetadata { url, hash })
+    }
+}
+
+impl Readable for PoolRelay {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        match buf.get_u8()? {
+            0 => {
+                let address = <[u8; 4]>::read(buf)?.into();
+                let port = buf.get_u16()?;
+                Ok(PoolRelay::Ipv4 { address, port })
+            }
+            1 => {
+                let address = <[u8; 16]>::read(buf)?.into();
+                let port = buf.get_u16()?;
+                Ok(PoolRelay::Ipv6 { address, port })
+            }
+            2 => {
+                let name = read_string(buf, "pool relay DNS name")?;
+                let port = buf.get_u16()?;
+                Ok(PoolRelay::DnsName { name, port })
+            }
+            n => Err(ReadError::UnknownTag(n as u32)),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    certificate::CertificateSlice,
+    transaction::{Payload, PayloadAuthData, PayloadData, PayloadSlice},
+    value::Value,
+};
+use chain_core::{
+    mempack::{ReadBuf, ReadError, Readable},
+    property,
+};
+use typed_bytes::{ByteArray, ByteBuilder};
+
+/// withdraw some of the rewards accrued by an account
+///
+/// Like the `VoteCast`, this structure is not sufficient to identify the
+/// account, and instead we rely on the transaction's single account input.
+///
+/// The withdrawn rewards are moved to the spendable value of the account
+/// before the inputs of the transaction are applied, so the fees of the
+/// withdrawal can be paid from the withdrawn rewards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RewardWithdrawal {
+    value: Value,
+}
+
+impl RewardWithdrawal {
+    pub fn new(value: Value) -> Self {
+        Self { value }
+    }
+
+    pub fn value(&self) -> Value {
+        self.value
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.u64(self.value.0)
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+}
+
+/* Auth/Payload ************************************************************* */
+
+impl Payload for RewardWithdrawal {
+    const HAS_DATA: bool = true;
+    const HAS_AUTH: bool = false;
+    type Auth = ();
+
+    fn payload_data(&self) -> PayloadData<Self> {
+        PayloadData(
+            self.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn payload_auth_data(_: &Self::Auth) -> PayloadAuthData<Self> {
+        PayloadAuthData(Vec::with_capacity(0).into(), std::marker::PhantomData)
+    }
+
+    fn payload_to_certificate_slice(p: PayloadSlice<'_, Self>) -> Option<CertificateSlice<'_>> {
+        Some(CertificateSlice::from(p))
+    }
+}
+
+/* Ser/De ******************************************************************* */
+
+impl property::Serialize for RewardWithdrawal {
+    type Error = std::io::Error;
+    fn serialize<W: std::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        writer.write_all(self.serialize().as_slice())?;
+        Ok(())
+    }
+}
+
+impl Readable for RewardWithdrawal {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let value = Value(buf.get_u64()?);
+        Ok(Self::new(value))
+    }
+}
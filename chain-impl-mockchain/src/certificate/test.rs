@@ -92,6 +92,42 @@ impl Arbitrary for OwnerStakeDelegation {
     }
 }
 
+fn arbitrary_ascii_string<G: Gen>(g: &mut G, max_size: usize) -> String {
+    let size = usize::arbitrary(g) % (max_size + 1);
+    (0..size)
+        .map(|_| char::from(b'a' + u8::arbitrary(g) % 26))
+        .collect()
+}
+
+impl Arbitrary for PoolMetadata {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        PoolMetadata::new(
+            arbitrary_ascii_string(g, POOL_METADATA_URL_MAX_SIZE),
+            Arbitrary::arbitrary(g),
+        )
+    }
+}
+
+impl Arbitrary for PoolRelay {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let port = Arbitrary::arbitrary(g);
+        match u8::arbitrary(g) % 3 {
+            0 => PoolRelay::Ipv4 {
+                address: Arbitrary::arbitrary(g),
+                port,
+            },
+            1 => PoolRelay::Ipv6 {
+                address: Arbitrary::arbitrary(g),
+                port,
+            },
+            _ => PoolRelay::DnsName {
+                name: arbitrary_ascii_string(g, POOL_RELAY_DNS_NAME_MAX_SIZE),
+                port,
+            },
+        }
+    }
+}
+
 impl Arbitrary for PoolRegistration {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         let start_validity: DurationSeconds = u64::arbitrary(g).into();
@@ -112,6 +148,9 @@ impl Arbitrary for PoolRegistration {
             operators.push(pk)
         }
 
+        let nb_relays = usize::arbitrary(g) % (POOL_MAX_RELAYS + 1);
+        let relays = (0..nb_relays).map(|_| Arbitrary::arbitrary(g)).collect();
+
         PoolRegistration {
             serial: Arbitrary::arbitrary(g),
             permissions: PoolPermissions::new(1),
@@ -121,6 +160,8 @@ impl Arbitrary for PoolRegistration {
             rewards: TaxType::zero(),
             reward_account: None,
             keys,
+            metadata: Arbitrary::arbitrary(g),
+            relays,
         }
     }
 }
@@ -200,6 +241,7 @@ impl Arbitrary for VotePlan {
             proposals,
             payload_type,
             keys,
+            vote::Weighting::arbitrary(g),
         )
     }
 }
@@ -223,6 +265,43 @@ impl Arbitrary for VoteCast {
     }
 }
 
+impl Arbitrary for VoteDelegation {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let vote_plan = Option::<VotePlanId>::arbitrary(g);
+        let representative = Option::<VoteRepresentative>::arbitrary(g);
+
+        VoteDelegation::new(vote_plan, representative)
+    }
+}
+
+impl Arbitrary for VoteRepresentative {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        if bool::arbitrary(g) {
+            VoteRepresentative::Myself
+        } else {
+            VoteRepresentative::Account(Arbitrary::arbitrary(g))
+        }
+    }
+}
+
+impl Arbitrary for RewardWithdrawal {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        RewardWithdrawal::new(Arbitrary::arbitrary(g))
+    }
+}
+
+impl Arbitrary for AccountRegistration {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        AccountRegistration::new(Arbitrary::arbitrary(g))
+    }
+}
+
+impl Arbitrary for AccountDeregistration {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        AccountDeregistration::new(Arbitrary::arbitrary(g))
+    }
+}
+
 impl Arbitrary for VoteTally {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         let vote_plan_id = VotePlanId::arbitrary(g);
@@ -257,7 +336,7 @@ impl Arbitrary for EncryptedVoteTallyProof {
 
 impl Arbitrary for Certificate {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let option = u8::arbitrary(g) % 9;
+        let option = u8::arbitrary(g) % 13;
         match option {
             0 => Certificate::StakeDelegation(Arbitrary::arbitrary(g)),
             1 => Certificate::OwnerStakeDelegation(Arbitrary::arbitrary(g)),
@@ -268,6 +347,10 @@ impl Arbitrary for Certificate {
             6 => Certificate::VoteCast(Arbitrary::arbitrary(g)),
             7 => Certificate::VoteTally(Arbitrary::arbitrary(g)),
             8 => Certificate::EncryptedVoteTally(Arbitrary::arbitrary(g)),
+            9 => Certificate::VoteDelegation(Arbitrary::arbitrary(g)),
+            10 => Certificate::RewardWithdrawal(Arbitrary::arbitrary(g)),
+            11 => Certificate::AccountRegistration(Arbitrary::arbitrary(g)),
+            12 => Certificate::AccountDeregistration(Arbitrary::arbitrary(g)),
             _ => panic!("unimplemented"),
         }
     }
@@ -283,3 +366,84 @@ fn pool_reg_serialization_bijection(b: PoolRegistration) -> TestResult {
     assert_eq!(buf.get_slice_end(), &[]);
     TestResult::from_bool(left == result)
 }
+
+#[quickcheck]
+fn pool_reg_extension_is_only_serialized_when_not_empty(b: PoolRegistration) -> TestResult {
+    let mut legacy = b.clone();
+    legacy.metadata = None;
+    legacy.relays = Vec::new();
+
+    let bytes = b.serialize();
+    let legacy_bytes = legacy.serialize();
+    let bytes = bytes.as_slice();
+    let legacy_bytes = legacy_bytes.as_slice();
+
+    // without reward account, the last byte of the legacy format is the
+    // reward account tag, flagged when the extension follows
+    let tag = legacy_bytes.len() - 1;
+    assert_eq!(legacy_bytes[tag], 0);
+    if b.metadata.is_none() && b.relays.is_empty() {
+        return TestResult::from_bool(bytes == legacy_bytes);
+    }
+    assert_eq!(bytes[..tag], legacy_bytes[..tag]);
+    assert_eq!(bytes[tag], 0b1000_0000);
+    TestResult::from_bool(bytes.len() > legacy_bytes.len())
+}
+
+#[quickcheck]
+fn pool_reg_with_empty_extension_is_rejected(b: PoolRegistration) -> TestResult {
+    let mut legacy = b;
+    legacy.metadata = None;
+    legacy.relays = Vec::new();
+
+    let mut bytes = legacy.serialize().as_slice().to_vec();
+    *bytes.last_mut().unwrap() |= 0b1000_0000;
+    // extension version 1, no metadata and no relay
+    bytes.extend_from_slice(&[1, 0, 0]);
+    let mut buf = ReadBuf::from(&bytes);
+    TestResult::from_bool(PoolRegistration::read(&mut buf).is_err())
+}
+
+#[quickcheck]
+fn vote_delegation_serialization_bijection(b: VoteDelegation) -> TestResult {
+    let b_got = b.serialize();
+    let mut buf = ReadBuf::from(b_got.as_ref());
+    let result = VoteDelegation::read(&mut buf);
+    let left = Ok(b);
+    assert_eq!(left, result);
+    assert_eq!(buf.get_slice_end(), &[]);
+    TestResult::from_bool(left == result)
+}
+
+#[quickcheck]
+fn reward_withdrawal_serialization_bijection(b: RewardWithdrawal) -> TestResult {
+    let b_got = b.serialize();
+    let mut buf = ReadBuf::from(b_got.as_ref());
+    let result = RewardWithdrawal::read(&mut buf);
+    let left = Ok(b);
+    assert_eq!(left, result);
+    assert_eq!(buf.get_slice_end(), &[]);
+    TestResult::from_bool(left == result)
+}
+
+#[quickcheck]
+fn account_registration_serialization_bijection(b: AccountRegistration) -> TestResult {
+    let b_got = b.serialize();
+    let mut buf = ReadBuf::from(b_got.as_ref());
+    let result = AccountRegistration::read(&mut buf);
+    let left = Ok(b);
+    assert_eq!(left, result);
+    assert_eq!(buf.get_slice_end(), &[]);
+    TestResult::from_bool(left == result)
+}
+
+#[quickcheck]
+fn account_deregistration_serialization_bijection(b: AccountDeregistration) -> TestResult {
+    let b_got = b.serialize();
+    let mut buf = ReadBuf::from(b_got.as_ref());
+    let result = AccountDeregistration::read(&mut buf);
+    let left = Ok(b);
+    assert_eq!(left, result);
+    assert_eq!(buf.get_slice_end(), &[]);
+    TestResult::from_bool(left == result)
+}
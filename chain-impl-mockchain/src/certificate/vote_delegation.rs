@@ -0,0 +1,140 @@
+use crate::{
+    certificate::{CertificateSlice, VotePlanId},
+    transaction::{
+        Payload, PayloadAuthData, PayloadData, PayloadSlice, UnspecifiedAccountIdentifier,
+    },
+};
+use chain_core::{
+    mempack::{ReadBuf, ReadError, Readable},
+    property,
+};
+use typed_bytes::{ByteArray, ByteBuilder};
+
+/// delegate the voting power of an account to a representative
+///
+/// Like the `VoteCast`, this structure is not sufficient to identify the
+/// delegator, and instead we rely on the transaction's single account input.
+///
+/// The delegation applies to a given vote plan, or to every vote plan if
+/// no vote plan is specified. A delegation specific to a vote plan takes
+/// precedence over the global delegation. Setting no representative
+/// removes the previous delegation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VoteDelegation {
+    vote_plan: Option<VotePlanId>,
+    representative: Option<VoteRepresentative>,
+}
+
+/// who holds the voting power of a delegating account
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VoteRepresentative {
+    /// the account keeps its voting power. For a vote plan, this overrides
+    /// the global delegation of the account.
+    Myself,
+    /// the voting power is delegated to the given account
+    Account(UnspecifiedAccountIdentifier),
+}
+
+const REPRESENTATIVE_NONE: u8 = 0;
+const REPRESENTATIVE_ACCOUNT: u8 = 1;
+const REPRESENTATIVE_MYSELF: u8 = 2;
+
+impl VoteRepresentative {
+    /// the account the voting power is delegated to, if any
+    pub fn account(&self) -> Option<&UnspecifiedAccountIdentifier> {
+        match self {
+            VoteRepresentative::Myself => None,
+            VoteRepresentative::Account(account) => Some(account),
+        }
+    }
+}
+
+impl VoteDelegation {
+    pub fn new(vote_plan: Option<VotePlanId>, representative: Option<VoteRepresentative>) -> Self {
+        Self {
+            vote_plan,
+            representative,
+        }
+    }
+
+    pub fn vote_plan(&self) -> Option<&VotePlanId> {
+        self.vote_plan.as_ref()
+    }
+
+    pub fn representative(&self) -> Option<&VoteRepresentative> {
+        self.representative.as_ref()
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        let bb = match &self.vote_plan {
+            None => bb.u8(0),
+            Some(vote_plan) => bb.u8(1).bytes(vote_plan.as_ref()),
+        };
+        match &self.representative {
+            None => bb.u8(REPRESENTATIVE_NONE),
+            Some(VoteRepresentative::Account(account)) => {
+                bb.u8(REPRESENTATIVE_ACCOUNT).bytes(account.as_ref())
+            }
+            Some(VoteRepresentative::Myself) => bb.u8(REPRESENTATIVE_MYSELF),
+        }
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+}
+
+/* Auth/Payload ************************************************************* */
+
+impl Payload for VoteDelegation {
+    const HAS_DATA: bool = true;
+    const HAS_AUTH: bool = false;
+    type Auth = ();
+
+    fn payload_data(&self) -> PayloadData<Self> {
+        PayloadData(
+            self.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn payload_auth_data(_: &Self::Auth) -> PayloadAuthData<Self> {
+        PayloadAuthData(Vec::with_capacity(0).into(), std::marker::PhantomData)
+    }
+
+    fn payload_to_certificate_slice(p: PayloadSlice<'_, Self>) -> Option<CertificateSlice<'_>> {
+        Some(CertificateSlice::from(p))
+    }
+}
+
+/* Ser/De ******************************************************************* */
+
+impl property::Serialize for VoteDelegation {
+    type Error = std::io::Error;
+    fn serialize<W: std::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        writer.write_all(self.serialize().as_slice())?;
+        Ok(())
+    }
+}
+
+impl Readable for VoteDelegation {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let vote_plan = match buf.get_u8()? {
+            0 => None,
+            1 => Some(<[u8; 32]>::read(buf)?.into()),
+            t => return Err(ReadError::UnknownTag(t as u32)),
+        };
+        let representative = match buf.get_u8()? {
+            REPRESENTATIVE_NONE => None,
+            REPRESENTATIVE_ACCOUNT => {
+                Some(VoteRepresentative::Account(<[u8; 32]>::read(buf)?.into()))
+            }
+            REPRESENTATIVE_MYSELF => Some(VoteRepresentative::Myself),
+            t => return Err(ReadError::UnknownTag(t as u32)),
+        };
+
+        Ok(Self::new(vote_plan, representative))
+    }
+}
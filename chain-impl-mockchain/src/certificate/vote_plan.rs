@@ -28,6 +28,11 @@ pub type ExternalProposalId = DigestOf<Blake2b256, ExternalProposalDocument>;
 /// the vote plan identifier on the blockchain
 pub type VotePlanId = DigestOf<Blake2b256, VotePlan>;
 
+/// flag set on the payload type byte of a vote plan when its weighting is
+/// serialized after the committee keys. Vote plans with the linear
+/// weighting don't have it and keep their original format, and identifier.
+const VOTE_PLAN_WEIGHTING_FLAG: u8 = 0b1000_0000;
+
 /// a vote plan for the voting system
 ///
 /// A vote plan defines what is being voted, for how long and how long
@@ -49,6 +54,8 @@ pub struct VotePlan {
     payload_type: vote::PayloadType,
     /// encrypting votes public keys
     committee_public_keys: Vec<chain_vote::MemberPublicKey>,
+    /// the rule converting the voters' stake into the weight of their votes
+    weighting: vote::Weighting,
 }
 
 #[derive(Debug, Clone)]
@@ -191,6 +198,7 @@ impl VotePlan {
         proposals: Proposals,
         payload_type: vote::PayloadType,
         committee_public_keys: Vec<chain_vote::MemberPublicKey>,
+        weighting: vote::Weighting,
     ) -> Self {
         Self {
             vote_start,
@@ -199,6 +207,7 @@ impl VotePlan {
             proposals,
             payload_type,
             committee_public_keys,
+            weighting,
         }
     }
 
@@ -250,6 +259,10 @@ impl VotePlan {
         &self.committee_public_keys
     }
 
+    pub fn weighting(&self) -> vote::Weighting {
+        self.weighting
+    }
+
     #[inline]
     pub fn vote_started(&self, date: BlockDate) -> bool {
         self.vote_start <= date
@@ -290,19 +303,31 @@ impl VotePlan {
     }
 
     pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
-        bb.u32(self.vote_start.epoch)
+        let has_weighting = self.weighting != vote::Weighting::Linear;
+        let flag = if has_weighting {
+            VOTE_PLAN_WEIGHTING_FLAG
+        } else {
+            0
+        };
+        let bb = bb
+            .u32(self.vote_start.epoch)
             .u32(self.vote_start.slot_id)
             .u32(self.vote_end.epoch)
             .u32(self.vote_end.slot_id)
             .u32(self.committee_end.epoch)
             .u32(self.committee_end.slot_id)
-            .u8(self.payload_type as u8)
+            .u8(self.payload_type as u8 | flag)
             .iter8(&mut self.proposals.iter(), |bb, proposal| {
                 proposal.serialize_in(bb)
             })
             .iter8(self.committee_public_keys.iter(), |bb, key| {
                 bb.bytes(key.to_bytes().as_ref())
-            })
+            });
+        if has_weighting {
+            self.weighting.serialize_in(bb)
+        } else {
+            bb
+        }
     }
 
     pub fn serialize(&self) -> ByteArray<Self> {
@@ -415,8 +440,9 @@ impl Readable for VotePlan {
             slot_id: buf.get_u32()?,
         };
 
-        let payload_type = buf
-            .get_u8()?
+        let payload_type_tag = buf.get_u8()?;
+        let has_weighting = payload_type_tag & VOTE_PLAN_WEIGHTING_FLAG != 0;
+        let payload_type = (payload_type_tag & !VOTE_PLAN_WEIGHTING_FLAG)
             .try_into()
             .map_err(|e: vote::TryFromIntError| ReadError::StructureInvalid(e.to_string()))?;
 
@@ -450,6 +476,20 @@ impl Readable for VotePlan {
             })?);
         }
 
+        let weighting = if has_weighting {
+            match vote::Weighting::read(buf)? {
+                // the linear weighting is encoded without the weighting
+                vote::Weighting::Linear => {
+                    return Err(ReadError::StructureInvalid(
+                        "vote plan has an explicit linear weighting".to_string(),
+                    ))
+                }
+                weighting => weighting,
+            }
+        } else {
+            vote::Weighting::Linear
+        };
+
         Ok(Self {
             vote_start,
             vote_end,
@@ -457,6 +497,7 @@ impl Readable for VotePlan {
             proposals,
             payload_type,
             committee_public_keys,
+            weighting,
         })
     }
 }
@@ -481,6 +522,42 @@ mod tests {
         decoded == vote_plan
     }
 
+    #[test]
+    pub fn linear_weighting_keeps_the_original_format() {
+        let linear = VotePlan::new(
+            BlockDate::first(),
+            BlockDate::first().next_epoch(),
+            BlockDate::first().next_epoch().next_epoch(),
+            VoteTestGen::proposals(1),
+            vote::PayloadType::Public,
+            Vec::new(),
+            vote::Weighting::Linear,
+        );
+        let square_root = VotePlan::new(
+            linear.vote_start(),
+            linear.vote_end(),
+            linear.committee_end(),
+            linear.proposals().clone(),
+            vote::PayloadType::Public,
+            Vec::new(),
+            vote::Weighting::SquareRoot,
+        );
+
+        let linear_bytes = linear.serialize();
+        let square_root_bytes = square_root.serialize();
+        // the weighting is only appended when it is not linear
+        assert_eq!(
+            linear_bytes.as_slice().len() + 1,
+            square_root_bytes.as_slice().len()
+        );
+        assert_eq!(linear_bytes.as_slice()[24], vote::PayloadType::Public as u8);
+        assert_ne!(linear.to_id(), square_root.to_id());
+
+        let mut explicit_linear = square_root_bytes.as_slice().to_vec();
+        *explicit_linear.last_mut().unwrap() = 0;
+        assert!(VotePlan::read(&mut ReadBuf::from(&explicit_linear)).is_err());
+    }
+
     #[test]
     pub fn proposals_are_full() {
         let mut proposals = VoteTestGen::proposals(Proposals::MAX_LEN);
@@ -504,6 +581,7 @@ mod tests {
             VoteTestGen::proposals(1),
             vote::PayloadType::Public,
             Vec::new(),
+            vote::Weighting::Linear,
         );
 
         assert!(vote_plan.vote_started(vote_start));
@@ -524,6 +602,7 @@ mod tests {
             VoteTestGen::proposals(1),
             vote::PayloadType::Public,
             Vec::new(),
+            vote::Weighting::Linear,
         );
 
         let before_voting = BlockDate::from_epoch_slot_id(0, 10);
@@ -556,6 +635,7 @@ mod tests {
             VoteTestGen::proposals(1),
             vote::PayloadType::Public,
             Vec::new(),
+            vote::Weighting::Linear,
         );
 
         let before_voting = BlockDate::from_epoch_slot_id(0, 10);
@@ -4,6 +4,9 @@
 //! ledger for either validation or some state tracking
 
 use crate::key::Hash;
+use chain_crypto::{
+    SumEd25519_12, SumEd25519_14, SumEd25519_6, SumEd25519_7, VerificationAlgorithm,
+};
 use strum_macros::{Display, EnumString, IntoStaticStr};
 
 pub type HeaderId = Hash; // TODO: change to DigestOf<Blake2b256, Header>
@@ -66,6 +69,111 @@ impl ConsensusType {
     }
 }
 
+/// Key evolving signature algorithm used by the Genesis-Praos leaders to sign
+/// block headers, the number identifying each of them is the depth of its
+/// tree of keys
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Display,
+    EnumString,
+    IntoStaticStr,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+pub enum KesAlgorithm {
+    #[strum(to_string = "sumed25519-6")]
+    SumEd25519_6 = 6,
+    #[strum(to_string = "sumed25519-7")]
+    SumEd25519_7 = 7,
+    #[default]
+    #[strum(to_string = "sumed25519-12")]
+    SumEd25519_12 = 12,
+    #[strum(to_string = "sumed25519-14")]
+    SumEd25519_14 = 14,
+}
+
+impl KesAlgorithm {
+    pub const ALL: [KesAlgorithm; 4] = [
+        KesAlgorithm::SumEd25519_6,
+        KesAlgorithm::SumEd25519_7,
+        KesAlgorithm::SumEd25519_12,
+        KesAlgorithm::SumEd25519_14,
+    ];
+
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            6 => Some(KesAlgorithm::SumEd25519_6),
+            7 => Some(KesAlgorithm::SumEd25519_7),
+            12 => Some(KesAlgorithm::SumEd25519_12),
+            14 => Some(KesAlgorithm::SumEd25519_14),
+            _ => None,
+        }
+    }
+
+    pub const fn signature_size(self) -> usize {
+        match self {
+            KesAlgorithm::SumEd25519_6 => SumEd25519_6::SIGNATURE_SIZE,
+            KesAlgorithm::SumEd25519_7 => SumEd25519_7::SIGNATURE_SIZE,
+            KesAlgorithm::SumEd25519_12 => SumEd25519_12::SIGNATURE_SIZE,
+            KesAlgorithm::SumEd25519_14 => SumEd25519_14::SIGNATURE_SIZE,
+        }
+    }
+
+    /// the algorithm of a signature, as the signature sizes of the algorithms
+    /// are all different
+    pub fn from_signature_size(size: usize) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|algorithm| algorithm.signature_size() == size)
+    }
+}
+
+/// Verifiable random function used by the Genesis-Praos leaders to prove
+/// their election, each of them has its own block version
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Display,
+    EnumString,
+    IntoStaticStr,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+pub enum VrfAlgorithm {
+    #[default]
+    #[strum(to_string = "ristretto-group2-hash-dh")]
+    RistrettoGroup2HashDh = 1,
+    #[strum(to_string = "ecvrf-edwards25519-sha512")]
+    EcVrfEdwards25519Sha512 = 2,
+}
+
+impl VrfAlgorithm {
+    pub const ALL: [VrfAlgorithm; 2] = [
+        VrfAlgorithm::RistrettoGroup2HashDh,
+        VrfAlgorithm::EcVrfEdwards25519Sha512,
+    ];
+
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(VrfAlgorithm::RistrettoGroup2HashDh),
+            2 => Some(VrfAlgorithm::EcVrfEdwards25519Sha512),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(any(test, feature = "property-test-api"))]
 mod tests {
     use super::*;
@@ -77,6 +185,18 @@ mod tests {
         }
     }
 
+    impl Arbitrary for KesAlgorithm {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            KesAlgorithm::ALL[usize::arbitrary(g) % KesAlgorithm::ALL.len()]
+        }
+    }
+
+    impl Arbitrary for VrfAlgorithm {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            VrfAlgorithm::ALL[usize::arbitrary(g) % VrfAlgorithm::ALL.len()]
+        }
+    }
+
     impl Arbitrary for ChainLength {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
             ChainLength(Arbitrary::arbitrary(g))
@@ -1,10 +1,10 @@
 use crate::date::Epoch;
 use crate::key::BftLeaderId;
 use crate::milli::Milli;
-use crate::rewards::{Ratio, TaxType};
+use crate::rewards::{CompoundingType, PoolSaturation, Ratio, TaxType};
 use crate::value::Value;
 use crate::{
-    chaintypes::ConsensusType,
+    chaintypes::{ConsensusType, KesAlgorithm, VrfAlgorithm},
     fee::{LinearFee, PerCertificateFee, PerVoteCertificateFee},
     vote::CommitteeId,
 };
@@ -83,6 +83,12 @@ pub enum ConfigParam {
     RemoveCommitteeId(CommitteeId),
     PerVoteCertificateFees(PerVoteCertificateFee),
     TransactionMaxExpiryEpochs(u8),
+    KesAlgorithm(KesAlgorithm),
+    VrfAlgorithm(VrfAlgorithm),
+    PoolRewardSaturation(PoolSaturation),
+    AccountRegistrationDeposit(Value),
+    PoolRegistrationDeposit(Value),
+    ResetDelegationOnPoolRetirement(bool),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -99,6 +105,16 @@ pub enum RewardParams {
         epoch_start: Epoch,
         epoch_rate: NonZeroU32,
     },
+    /// The pools are rewarded for their share of the stake, reduced when they
+    /// produce less blocks than expected from it, the difference going to the
+    /// treasury
+    PerformanceAware {
+        compounding_type: CompoundingType,
+        constant: u64,
+        ratio: Ratio,
+        epoch_start: Epoch,
+        epoch_rate: NonZeroU32,
+    },
 }
 
 // Discriminants can NEVER be 1024 or higher
@@ -156,6 +172,18 @@ pub enum Tag {
     PerVoteCertificateFees = 28,
     #[strum(to_string = "transaction-maximum-expiry-epochs")]
     TransactionMaxExpiryEpochs = 29,
+    #[strum(to_string = "kes-algorithm")]
+    KesAlgorithm = 30,
+    #[strum(to_string = "vrf-algorithm")]
+    VrfAlgorithm = 31,
+    #[strum(to_string = "pool-reward-saturation")]
+    PoolRewardSaturation = 32,
+    #[strum(to_string = "account-registration-deposit")]
+    AccountRegistrationDeposit = 33,
+    #[strum(to_string = "pool-registration-deposit")]
+    PoolRegistrationDeposit = 34,
+    #[strum(to_string = "reset-delegation-on-pool-retirement")]
+    ResetDelegationOnPoolRetirement = 35,
 }
 
 impl Tag {
@@ -187,6 +215,12 @@ impl Tag {
             27 => Some(Tag::RemoveCommitteeId),
             28 => Some(Tag::PerVoteCertificateFees),
             29 => Some(Tag::TransactionMaxExpiryEpochs),
+            30 => Some(Tag::KesAlgorithm),
+            31 => Some(Tag::VrfAlgorithm),
+            32 => Some(Tag::PoolRewardSaturation),
+            33 => Some(Tag::AccountRegistrationDeposit),
+            34 => Some(Tag::PoolRegistrationDeposit),
+            35 => Some(Tag::ResetDelegationOnPoolRetirement),
             _ => None,
         }
     }
@@ -223,6 +257,12 @@ impl<'a> From<&'a ConfigParam> for Tag {
             ConfigParam::RemoveCommitteeId(..) => Tag::RemoveCommitteeId,
             ConfigParam::PerVoteCertificateFees(..) => Tag::PerVoteCertificateFees,
             ConfigParam::TransactionMaxExpiryEpochs(..) => Tag::TransactionMaxExpiryEpochs,
+            ConfigParam::KesAlgorithm(..) => Tag::KesAlgorithm,
+            ConfigParam::VrfAlgorithm(..) => Tag::VrfAlgorithm,
+            ConfigParam::PoolRewardSaturation(..) => Tag::PoolRewardSaturation,
+            ConfigParam::AccountRegistrationDeposit(..) => Tag::AccountRegistrationDeposit,
+            ConfigParam::PoolRegistrationDeposit(..) => Tag::PoolRegistrationDeposit,
+            ConfigParam::ResetDelegationOnPoolRetirement(_) => Tag::ResetDelegationOnPoolRetirement,
         }
     }
 }
@@ -306,6 +346,23 @@ impl Readable for ConfigParam {
             Tag::TransactionMaxExpiryEpochs => {
                 ConfigParamVariant::from_payload(bytes).map(ConfigParam::TransactionMaxExpiryEpochs)
             }
+            Tag::KesAlgorithm => {
+                ConfigParamVariant::from_payload(bytes).map(ConfigParam::KesAlgorithm)
+            }
+            Tag::VrfAlgorithm => {
+                ConfigParamVariant::from_payload(bytes).map(ConfigParam::VrfAlgorithm)
+            }
+            Tag::PoolRewardSaturation => {
+                ConfigParamVariant::from_payload(bytes).map(ConfigParam::PoolRewardSaturation)
+            }
+            Tag::AccountRegistrationDeposit => {
+                ConfigParamVariant::from_payload(bytes).map(ConfigParam::AccountRegistrationDeposit)
+            }
+            Tag::PoolRegistrationDeposit => {
+                ConfigParamVariant::from_payload(bytes).map(ConfigParam::PoolRegistrationDeposit)
+            }
+            Tag::ResetDelegationOnPoolRetirement => ConfigParamVariant::from_payload(bytes)
+                .map(ConfigParam::ResetDelegationOnPoolRetirement),
         }
         .map_err(Into::into)
     }
@@ -343,6 +400,12 @@ impl property::Serialize for ConfigParam {
             ConfigParam::RemoveCommitteeId(data) => data.to_payload(),
             ConfigParam::PerVoteCertificateFees(data) => data.to_payload(),
             ConfigParam::TransactionMaxExpiryEpochs(data) => data.to_payload(),
+            ConfigParam::KesAlgorithm(data) => data.to_payload(),
+            ConfigParam::VrfAlgorithm(data) => data.to_payload(),
+            ConfigParam::PoolRewardSaturation(data) => data.to_payload(),
+            ConfigParam::AccountRegistrationDeposit(data) => data.to_payload(),
+            ConfigParam::PoolRegistrationDeposit(data) => data.to_payload(),
+            ConfigParam::ResetDelegationOnPoolRetirement(data) => data.to_payload(),
         };
         let taglen = TagLen::new(tag, bytes.len()).ok_or_else(|| {
             io::Error::new(
@@ -461,6 +524,23 @@ impl ConfigParamVariant for RewardParams {
                 .u64(ratio.denominator.get())
                 .u32(*epoch_start)
                 .u32(epoch_rate.get()),
+            RewardParams::PerformanceAware {
+                compounding_type,
+                constant,
+                ratio,
+                epoch_start,
+                epoch_rate,
+            } => ByteBuilder::new()
+                .u8(3)
+                .u8(match compounding_type {
+                    CompoundingType::Linear => 1,
+                    CompoundingType::Halvening => 2,
+                })
+                .u64(*constant)
+                .u64(ratio.numerator)
+                .u64(ratio.denominator.get())
+                .u32(*epoch_start)
+                .u32(epoch_rate.get()),
         };
         bb.finalize_as_vec()
     }
@@ -502,6 +582,29 @@ impl ConfigParamVariant for RewardParams {
                     epoch_rate: erate,
                 })
             }
+            3 => {
+                let compounding_type = match rb.get_u8()? {
+                    1 => CompoundingType::Linear,
+                    2 => CompoundingType::Halvening,
+                    _ => return Err(Error::InvalidTag),
+                };
+                let start = rb.get_u64()?;
+                let num = rb.get_u64()?;
+                let denom = rb.get_nz_u64()?;
+                let estart = rb.get_u32()?;
+                let erate = rb.get_nz_u32()?;
+                rb.expect_end()?;
+                Ok(RewardParams::PerformanceAware {
+                    compounding_type,
+                    constant: start,
+                    ratio: Ratio {
+                        numerator: num,
+                        denominator: denom,
+                    },
+                    epoch_start: estart,
+                    epoch_rate: erate,
+                })
+            }
             _ => Err(Error::InvalidTag),
         }
     }
@@ -556,6 +659,32 @@ impl ConfigParamVariant for ConsensusType {
     }
 }
 
+impl ConfigParamVariant for KesAlgorithm {
+    fn to_payload(&self) -> Vec<u8> {
+        vec![*self as u8]
+    }
+
+    fn from_payload(payload: &[u8]) -> Result<Self, Error> {
+        match payload {
+            [depth] => KesAlgorithm::from_u8(*depth).ok_or(Error::StructureInvalid),
+            _ => Err(Error::SizeInvalid),
+        }
+    }
+}
+
+impl ConfigParamVariant for VrfAlgorithm {
+    fn to_payload(&self) -> Vec<u8> {
+        vec![*self as u8]
+    }
+
+    fn from_payload(payload: &[u8]) -> Result<Self, Error> {
+        match payload {
+            [algorithm] => VrfAlgorithm::from_u8(*algorithm).ok_or(Error::StructureInvalid),
+            _ => Err(Error::SizeInvalid),
+        }
+    }
+}
+
 impl ConfigParamVariant for BftLeaderId {
     fn to_payload(&self) -> Vec<u8> {
         self.as_ref().to_vec()
@@ -642,6 +771,31 @@ impl ConfigParamVariant for (NonZeroU32, NonZeroU32) {
     }
 }
 
+impl ConfigParamVariant for PoolSaturation {
+    fn to_payload(&self) -> Vec<u8> {
+        let bb: ByteBuilder<PoolSaturation> = ByteBuilder::new();
+        bb.u32(self.target_nb_pools.get())
+            .u64(self.pledge_influence.numerator)
+            .u64(self.pledge_influence.denominator.get())
+            .finalize_as_vec()
+    }
+
+    fn from_payload(payload: &[u8]) -> Result<Self, Error> {
+        let mut rb = ReadBuf::from(payload);
+        let target_nb_pools = rb.get_nz_u32()?;
+        let num = rb.get_u64()?;
+        let denom = rb.get_nz_u64()?;
+        rb.expect_end()?;
+        Ok(PoolSaturation {
+            target_nb_pools,
+            pledge_influence: Ratio {
+                numerator: num,
+                denominator: denom,
+            },
+        })
+    }
+}
+
 impl ConfigParamVariant for Milli {
     fn to_payload(&self) -> Vec<u8> {
         self.to_millis().to_payload()
@@ -693,19 +847,27 @@ impl ConfigParamVariant for PerCertificateFee {
                 .unwrap_or(0)
                 .to_payload(),
         );
+        // only appended when set, so the payload of the fees defined
+        // before the reward withdrawal is unchanged
+        if let Some(certificate_reward_withdrawal) = self.certificate_reward_withdrawal {
+            v.extend(certificate_reward_withdrawal.get().to_payload());
+        }
         v
     }
 
     fn from_payload(payload: &[u8]) -> Result<Self, Error> {
-        if payload.len() != 3 * 8 {
-            return Err(Error::SizeInvalid);
-        }
+        let certificate_reward_withdrawal = match payload.len() {
+            24 => None,
+            32 => NonZeroU64::new(u64::from_payload(&payload[24..32])?),
+            _ => return Err(Error::SizeInvalid),
+        };
         Ok(PerCertificateFee {
             certificate_pool_registration: NonZeroU64::new(u64::from_payload(&payload[0..8])?),
             certificate_stake_delegation: NonZeroU64::new(u64::from_payload(&payload[8..16])?),
             certificate_owner_stake_delegation: NonZeroU64::new(u64::from_payload(
                 &payload[16..24],
             )?),
+            certificate_reward_withdrawal,
         })
     }
 }
@@ -847,27 +1009,46 @@ mod test {
 
     impl Arbitrary for RewardParams {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            if bool::arbitrary(g) {
-                RewardParams::Linear {
+            match u8::arbitrary(g) % 3 {
+                0 => RewardParams::Linear {
                     constant: Arbitrary::arbitrary(g),
                     ratio: Arbitrary::arbitrary(g),
                     epoch_start: Arbitrary::arbitrary(g),
                     epoch_rate: NonZeroU32::new(20).unwrap(),
-                }
-            } else {
-                RewardParams::Halving {
+                },
+                1 => RewardParams::Halving {
                     constant: Arbitrary::arbitrary(g),
                     ratio: Arbitrary::arbitrary(g),
                     epoch_start: Arbitrary::arbitrary(g),
                     epoch_rate: NonZeroU32::new(20).unwrap(),
-                }
+                },
+                _ => RewardParams::PerformanceAware {
+                    compounding_type: if bool::arbitrary(g) {
+                        CompoundingType::Linear
+                    } else {
+                        CompoundingType::Halvening
+                    },
+                    constant: Arbitrary::arbitrary(g),
+                    ratio: Arbitrary::arbitrary(g),
+                    epoch_start: Arbitrary::arbitrary(g),
+                    epoch_rate: NonZeroU32::new(20).unwrap(),
+                },
+            }
+        }
+    }
+
+    impl Arbitrary for PoolSaturation {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            PoolSaturation {
+                target_nb_pools: Arbitrary::arbitrary(g),
+                pledge_influence: Arbitrary::arbitrary(g),
             }
         }
     }
 
     impl Arbitrary for ConfigParam {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            match u8::arbitrary(g) % 30 {
+            match u8::arbitrary(g) % 36 {
                 0 => ConfigParam::Block0Date(Arbitrary::arbitrary(g)),
                 1 => ConfigParam::Discrimination(Arbitrary::arbitrary(g)),
                 2 => ConfigParam::ConsensusVersion(Arbitrary::arbitrary(g)),
@@ -898,6 +1079,12 @@ mod test {
                 27 => ConfigParam::RemoveCommitteeId(Arbitrary::arbitrary(g)),
                 28 => ConfigParam::PerCertificateFees(Arbitrary::arbitrary(g)),
                 29 => ConfigParam::TransactionMaxExpiryEpochs(Arbitrary::arbitrary(g)),
+                30 => ConfigParam::KesAlgorithm(Arbitrary::arbitrary(g)),
+                31 => ConfigParam::VrfAlgorithm(Arbitrary::arbitrary(g)),
+                32 => ConfigParam::PoolRewardSaturation(Arbitrary::arbitrary(g)),
+                33 => ConfigParam::AccountRegistrationDeposit(Arbitrary::arbitrary(g)),
+                34 => ConfigParam::PoolRegistrationDeposit(Arbitrary::arbitrary(g)),
+                35 => ConfigParam::ResetDelegationOnPoolRetirement(Arbitrary::arbitrary(g)),
                 _ => unreachable!(),
             }
         }
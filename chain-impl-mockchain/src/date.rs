@@ -42,6 +42,18 @@ impl BlockDate {
         }
     }
 
+    /// Get the date the given number of slots after this one.
+    #[must_use = "this returns the result of the operation, without modifying the original"]
+    pub fn shift_slots(self, slots: u32, era: &TimeEra) -> BlockDate {
+        let epoch_duration = era.slots_per_epoch() as u64;
+        let slot = self.slot_id as u64 + slots as u64;
+        let epoch = self.epoch as u64 + slot / epoch_duration;
+        BlockDate {
+            epoch: epoch.min(Epoch::MAX as u64) as Epoch,
+            slot_id: (slot % epoch_duration) as SlotId,
+        }
+    }
+
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub fn next_epoch(self) -> BlockDate {
         BlockDate {
@@ -162,6 +174,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn shift_slots() {
+        let era = TimeEra::new(0.into(), chain_time::Epoch(0), 10);
+        let date = BlockDate {
+            epoch: 1,
+            slot_id: 8,
+        };
+        assert_eq!(date.shift_slots(0, &era), date);
+        assert_eq!(date.shift_slots(1, &era), date.next(&era));
+        assert_eq!(
+            date.shift_slots(25, &era),
+            BlockDate {
+                epoch: 4,
+                slot_id: 3
+            }
+        );
+        assert_eq!(date.shift_slots(u32::MAX, &era).epoch, 429_496_731);
+    }
+
     impl Arbitrary for BlockDate {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
             BlockDate {
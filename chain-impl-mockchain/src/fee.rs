@@ -19,6 +19,7 @@ pub struct PerCertificateFee {
     pub certificate_pool_registration: Option<NonZeroU64>,
     pub certificate_stake_delegation: Option<NonZeroU64>,
     pub certificate_owner_stake_delegation: Option<NonZeroU64>,
+    pub certificate_reward_withdrawal: Option<NonZeroU64>,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Debug, Clone, Copy, Default)]
@@ -52,11 +53,13 @@ impl PerCertificateFee {
         certificate_pool_registration: Option<NonZeroU64>,
         certificate_stake_delegation: Option<NonZeroU64>,
         certificate_owner_stake_delegation: Option<NonZeroU64>,
+        certificate_reward_withdrawal: Option<NonZeroU64>,
     ) -> Self {
         Self {
             certificate_pool_registration,
             certificate_stake_delegation,
             certificate_owner_stake_delegation,
+            certificate_reward_withdrawal,
         }
     }
 
@@ -71,6 +74,9 @@ impl PerCertificateFee {
             CertificateSlice::OwnerStakeDelegation(_) => self
                 .certificate_owner_stake_delegation
                 .map(|v| Value(v.get())),
+            CertificateSlice::RewardWithdrawal(_) => {
+                self.certificate_reward_withdrawal.map(|v| Value(v.get()))
+            }
             _ => None,
         }
     }
@@ -153,6 +159,7 @@ mod test {
                 NonZeroU64::new(u64::arbitrary(g)),
                 NonZeroU64::new(u64::arbitrary(g)),
                 NonZeroU64::new(u64::arbitrary(g)),
+                NonZeroU64::new(u64::arbitrary(g)),
             )
         }
     }
@@ -172,7 +179,7 @@ mod test {
                 constant: Arbitrary::arbitrary(g),
                 coefficient: Arbitrary::arbitrary(g),
                 certificate: Arbitrary::arbitrary(g),
-                per_certificate_fees: PerCertificateFee::new(None, None, None),
+                per_certificate_fees: PerCertificateFee::new(None, None, None, None),
                 per_vote_certificate_fees: PerVoteCertificateFee::new(None, None),
             }
         }
@@ -195,6 +202,7 @@ mod test {
             || per_certificate_fees
                 .certificate_owner_stake_delegation
                 .is_none()
+            || per_certificate_fees.certificate_reward_withdrawal.is_none()
             || per_vote_certificate_fees.certificate_vote_plan.is_none()
             || per_vote_certificate_fees.certificate_vote_cast.is_none()
         {
@@ -231,6 +239,9 @@ mod test {
             Certificate::OwnerStakeDelegation { .. } => {
                 cert_fees.certificate_owner_stake_delegation.unwrap().into()
             }
+            Certificate::RewardWithdrawal { .. } => {
+                cert_fees.certificate_reward_withdrawal.unwrap().into()
+            }
             Certificate::VotePlan { .. } => vote_cert_fees.certificate_vote_plan.unwrap().into(),
             Certificate::VoteCast { .. } => vote_cert_fees.certificate_vote_cast.unwrap().into(),
             _ => fee.certificate,
@@ -43,6 +43,10 @@ pub enum Fragment {
     VoteCast(Transaction<certificate::VoteCast>),
     VoteTally(Transaction<certificate::VoteTally>),
     EncryptedVoteTally(Transaction<certificate::EncryptedVoteTally>),
+    VoteDelegation(Transaction<certificate::VoteDelegation>),
+    RewardWithdrawal(Transaction<certificate::RewardWithdrawal>),
+    AccountRegistration(Transaction<certificate::AccountRegistration>),
+    AccountDeregistration(Transaction<certificate::AccountDeregistration>),
 }
 
 impl PartialEq for Fragment {
@@ -69,6 +73,10 @@ pub(super) enum FragmentTag {
     VoteCast = 11,
     VoteTally = 12,
     EncryptedVoteTally = 13,
+    VoteDelegation = 14,
+    RewardWithdrawal = 15,
+    AccountRegistration = 16,
+    AccountDeregistration = 17,
 }
 
 impl FragmentTag {
@@ -88,6 +96,10 @@ impl FragmentTag {
             11 => Some(FragmentTag::VoteCast),
             12 => Some(FragmentTag::VoteTally),
             13 => Some(FragmentTag::EncryptedVoteTally),
+            14 => Some(FragmentTag::VoteDelegation),
+            15 => Some(FragmentTag::RewardWithdrawal),
+            16 => Some(FragmentTag::AccountRegistration),
+            17 => Some(FragmentTag::AccountDeregistration),
             _ => None,
         }
     }
@@ -111,6 +123,10 @@ impl Fragment {
             Fragment::VoteCast(_) => FragmentTag::VoteCast,
             Fragment::VoteTally(_) => FragmentTag::VoteTally,
             Fragment::EncryptedVoteTally(_) => FragmentTag::EncryptedVoteTally,
+            Fragment::VoteDelegation(_) => FragmentTag::VoteDelegation,
+            Fragment::RewardWithdrawal(_) => FragmentTag::RewardWithdrawal,
+            Fragment::AccountRegistration(_) => FragmentTag::AccountRegistration,
+            Fragment::AccountDeregistration(_) => FragmentTag::AccountDeregistration,
         }
     }
 
@@ -137,6 +153,14 @@ impl Fragment {
             Fragment::VoteCast(vote_plan) => vote_plan.serialize(&mut codec).unwrap(),
             Fragment::VoteTally(vote_tally) => vote_tally.serialize(&mut codec).unwrap(),
             Fragment::EncryptedVoteTally(vote_tally) => vote_tally.serialize(&mut codec).unwrap(),
+            Fragment::VoteDelegation(delegation) => delegation.serialize(&mut codec).unwrap(),
+            Fragment::RewardWithdrawal(withdrawal) => withdrawal.serialize(&mut codec).unwrap(),
+            Fragment::AccountRegistration(registration) => {
+                registration.serialize(&mut codec).unwrap()
+            }
+            Fragment::AccountDeregistration(deregistration) => {
+                deregistration.serialize(&mut codec).unwrap()
+            }
         }
         FragmentRaw(codec.into_inner())
     }
@@ -192,6 +216,18 @@ impl Readable for Fragment {
             Some(FragmentTag::EncryptedVoteTally) => {
                 Transaction::read(buf).map(Fragment::EncryptedVoteTally)
             }
+            Some(FragmentTag::VoteDelegation) => {
+                Transaction::read(buf).map(Fragment::VoteDelegation)
+            }
+            Some(FragmentTag::RewardWithdrawal) => {
+                Transaction::read(buf).map(Fragment::RewardWithdrawal)
+            }
+            Some(FragmentTag::AccountRegistration) => {
+                Transaction::read(buf).map(Fragment::AccountRegistration)
+            }
+            Some(FragmentTag::AccountDeregistration) => {
+                Transaction::read(buf).map(Fragment::AccountDeregistration)
+            }
             None => Err(ReadError::UnknownTag(tag as u32)),
         }
     }
@@ -9,7 +9,7 @@ use quickcheck_macros::quickcheck;
 
 impl Arbitrary for Fragment {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        match g.next_u32() % 14 {
+        match g.next_u32() % 18 {
             0 => Fragment::Initial(Arbitrary::arbitrary(g)),
             1 => Fragment::OldUtxoDeclaration(Arbitrary::arbitrary(g)),
             2 => Fragment::Transaction(Arbitrary::arbitrary(g)),
@@ -24,6 +24,10 @@ impl Arbitrary for Fragment {
             11 => Fragment::VoteCast(Arbitrary::arbitrary(g)),
             12 => Fragment::VoteTally(Arbitrary::arbitrary(g)),
             13 => Fragment::EncryptedVoteTally(Arbitrary::arbitrary(g)),
+            14 => Fragment::VoteDelegation(Arbitrary::arbitrary(g)),
+            15 => Fragment::RewardWithdrawal(Arbitrary::arbitrary(g)),
+            16 => Fragment::AccountRegistration(Arbitrary::arbitrary(g)),
+            17 => Fragment::AccountDeregistration(Arbitrary::arbitrary(g)),
             _ => unreachable!(),
         }
     }
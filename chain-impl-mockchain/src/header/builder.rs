@@ -1,4 +1,4 @@
-use super::components::VrfProof;
+use super::components::{HeaderAuth, VrfProof};
 use super::cstruct;
 use super::header::{HeaderBft, HeaderGenesisPraos, HeaderUnsigned};
 use super::version::BlockVersion;
@@ -12,7 +12,7 @@ use crate::{
     key::BftLeaderId,
 };
 
-use chain_crypto::{Ed25519, SecretKey, SumEd25519_12};
+use chain_crypto::{Ed25519, SecretKey, Signature, SigningAlgorithm, VerificationAlgorithm};
 use std::marker::PhantomData;
 
 /// Finalized BFT Header
@@ -138,7 +138,9 @@ impl HeaderBuilder<HeaderCommonDone> {
         self,
     ) -> Option<HeaderGenesisPraosBuilder<HeaderSetConsensusData>> {
         match self.0.version() {
-            cstruct::VERSION_GP => Some(HeaderGenesisPraosBuilder(self.0, PhantomData)),
+            cstruct::VERSION_GP | cstruct::VERSION_GP_ECVRF => {
+                Some(HeaderGenesisPraosBuilder(self.0, PhantomData))
+            }
             _ => None,
         }
     }
@@ -200,15 +202,20 @@ impl HeaderGenesisPraosBuilder<HeaderSetConsensusSignature> {
     /// Set the signature in the Genesis-Praos header and return a finalized Genesis-Praos Header
     pub fn set_signature(self, kes_signature: KesSignature) -> HeaderGenesisPraos {
         let mut hdr = self.0;
-        hdr.set_gp_kes_signature_slice(kes_signature.0.as_ref());
+        hdr.set_gp_kes_signature_slice(kes_signature.as_ref());
         HeaderGenesisPraos(hdr)
     }
 
     /// Just a helper to set the signature directly from what the secret key generate
-    pub fn sign_using(self, kes_signing_key: &SecretKey<SumEd25519_12>) -> HeaderGenesisPraos {
+    pub fn sign_using<A>(self, kes_signing_key: &SecretKey<A>) -> HeaderGenesisPraos
+    where
+        A: SigningAlgorithm,
+        A::PubAlg: VerificationAlgorithm,
+        KesSignature: From<Signature<HeaderAuth, A::PubAlg>>,
+    {
         let data = self.get_authenticated_data();
-        let signature = kes_signing_key.sign_slice(data);
-        self.set_signature(KesSignature(signature))
+        let signature: Signature<HeaderAuth, A::PubAlg> = kes_signing_key.sign_slice(data);
+        self.set_signature(signature.into())
     }
 }
 
@@ -216,12 +223,16 @@ impl HeaderGenesisPraosBuilder<HeaderSetConsensusSignature> {
 mod tests {
     use super::*;
     use crate::{
-        chaintypes::HeaderId,
+        chaintypes::{HeaderId, KesAlgorithm},
+        header::{Header, Proof},
         testing::{
             data::{LeaderPair, StakePool},
             TestGen,
         },
     };
+    use chain_crypto::{
+        PublicKey, SumEd25519_12, SumEd25519_14, SumEd25519_6, SumEd25519_7, Verification,
+    };
 
     fn block_date() -> BlockDate {
         BlockDate {
@@ -301,6 +312,50 @@ mod tests {
         assert_eq!(header.chain_length(), ChainLength(0), "wrong chain length");
     }
 
+    fn sign_with_kes_algorithm<A>(kes_algorithm: KesAlgorithm)
+    where
+        A: SigningAlgorithm<PubAlg = A> + VerificationAlgorithm,
+        KesSignature: From<Signature<HeaderAuth, A>>,
+    {
+        let kes_key = SecretKey::<A>::generate(rand_core::OsRng);
+        let kes_public_key =
+            PublicKey::<SumEd25519_12>::from_binary(kes_key.to_public().as_ref()).unwrap();
+        let header = HeaderBuilderNew::new(BlockVersion::KesVrfproof, &contents())
+            .set_parent(&parent_id(), chain_length())
+            .set_date(block_date())
+            .into_genesis_praos_builder()
+            .unwrap()
+            .set_consensus_data(&stake_pool().id(), &TestGen::vrf_proof(&stake_pool()))
+            .sign_using(&kes_key)
+            .generalize();
+
+        assert_eq!(
+            header.size().get(),
+            cstruct::HEADER_GP_AUTHED_SIZE + kes_algorithm.signature_size()
+        );
+        let header = Header::from_slice(header.as_slice()).unwrap();
+        match header.proof() {
+            Proof::GenesisPraos(proof) => {
+                assert_eq!(proof.kes_proof.algorithm(), kes_algorithm);
+                assert_eq!(
+                    proof
+                        .kes_proof
+                        .verify(&kes_public_key, header.as_auth_slice()),
+                    Verification::Success
+                );
+            }
+            _ => panic!("not a Genesis-Praos proof"),
+        }
+    }
+
+    #[test]
+    pub fn correct_header_of_every_kes_algorithm() {
+        sign_with_kes_algorithm::<SumEd25519_6>(KesAlgorithm::SumEd25519_6);
+        sign_with_kes_algorithm::<SumEd25519_7>(KesAlgorithm::SumEd25519_7);
+        sign_with_kes_algorithm::<SumEd25519_12>(KesAlgorithm::SumEd25519_12);
+        sign_with_kes_algorithm::<SumEd25519_14>(KesAlgorithm::SumEd25519_14);
+    }
+
     #[test]
     pub fn correct_bft_header() {
         let parent_id = parent_id();
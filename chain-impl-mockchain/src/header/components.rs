@@ -1,34 +1,79 @@
 use super::cstruct;
-use chain_crypto::algorithms::vrf::ProvenOutputSeed;
-use chain_crypto::{Ed25519, PublicKey, Signature, SumEd25519_12, Verification};
+use crate::chaintypes::{KesAlgorithm, VrfAlgorithm};
+use crate::leadership::genesis::Witness;
+use chain_crypto::algorithms::vrf::{ecvrf, ProvenOutputSeed};
+use chain_crypto::{
+    Ed25519, PublicKey, Signature, SumEd25519_12, SumEd25519_14, SumEd25519_6, SumEd25519_7,
+    Verification,
+};
 use std::fmt::{self, Debug};
 
 #[derive(Debug, Clone)]
 pub struct HeaderAuth;
 
-#[derive(Debug, Clone)]
-pub struct KesSignature(pub(crate) Signature<HeaderAuth, SumEd25519_12>);
+macro_rules! kes_signature {
+    ($($algorithm:ident),*) => {
+        /// KES signature of a Genesis-Praos header, with one of the algorithms
+        /// of `KesAlgorithm`
+        #[derive(Debug, Clone)]
+        pub enum KesSignature {
+            $($algorithm(Signature<HeaderAuth, $algorithm>),)*
+        }
 
-impl From<cstruct::GpKesSignature> for KesSignature {
-    fn from(b: cstruct::GpKesSignature) -> KesSignature {
-        KesSignature(
-            Signature::from_binary(&b[..]).expect("internal error: KES signature length invalid"),
-        )
-    }
-}
+        $(
+            impl From<Signature<HeaderAuth, $algorithm>> for KesSignature {
+                fn from(sig: Signature<HeaderAuth, $algorithm>) -> KesSignature {
+                    KesSignature::$algorithm(sig)
+                }
+            }
+        )*
 
-impl From<Signature<HeaderAuth, SumEd25519_12>> for KesSignature {
-    fn from(sig: Signature<HeaderAuth, SumEd25519_12>) -> KesSignature {
-        KesSignature(sig)
-    }
-}
+        impl KesSignature {
+            /// the size of the signature tells its algorithm
+            pub(super) fn from_slice(b: &[u8]) -> KesSignature {
+                match KesAlgorithm::from_signature_size(b.len())
+                    .expect("internal error: KES signature length invalid")
+                {
+                    $(KesAlgorithm::$algorithm => KesSignature::$algorithm(
+                        Signature::from_binary(b)
+                            .expect("internal error: KES signature length invalid"),
+                    ),)*
+                }
+            }
 
-impl KesSignature {
-    pub fn verify(&self, pk: &PublicKey<SumEd25519_12>, data: &[u8]) -> Verification {
-        self.0.verify_slice(pk, data)
-    }
+            pub fn algorithm(&self) -> KesAlgorithm {
+                match self {
+                    $(KesSignature::$algorithm(_) => KesAlgorithm::$algorithm,)*
+                }
+            }
+
+            /// The public key of every algorithm is the root of its tree of
+            /// keys, so the registered public key of a pool is used whatever
+            /// the depth of the tree.
+            pub fn verify(&self, pk: &PublicKey<SumEd25519_12>, data: &[u8]) -> Verification {
+                match self {
+                    $(KesSignature::$algorithm(sig) => {
+                        match PublicKey::<$algorithm>::from_binary(pk.as_ref()) {
+                            Ok(pk) => sig.verify_slice(&pk, data),
+                            Err(_) => Verification::Failed,
+                        }
+                    })*
+                }
+            }
+        }
+
+        impl AsRef<[u8]> for KesSignature {
+            fn as_ref(&self) -> &[u8] {
+                match self {
+                    $(KesSignature::$algorithm(sig) => sig.as_ref(),)*
+                }
+            }
+        }
+    };
 }
 
+kes_signature!(SumEd25519_6, SumEd25519_7, SumEd25519_12, SumEd25519_14);
+
 #[derive(Debug, Clone)]
 pub struct BftSignature(pub(crate) Signature<HeaderAuth, Ed25519>);
 
@@ -61,6 +106,28 @@ impl VrfProof {
     pub fn to_vrf_proof(&self) -> Option<ProvenOutputSeed> {
         ProvenOutputSeed::from_bytes_unverified(&self.0)
     }
+
+    /// the ECVRF proof, stored at the start of the proof slot and padded
+    /// with zeros
+    pub fn to_ecvrf_proof(&self) -> Option<ecvrf::Proof> {
+        let (proof, padding) = self.0.split_at(ecvrf::Proof::BYTES_LEN);
+        if padding.iter().any(|b| *b != 0) {
+            return None;
+        }
+        ecvrf::Proof::from_bytes_unverified(proof)
+    }
+
+    /// the witness of the VRF `vrf_algorithm`
+    pub fn to_witness(&self, vrf_algorithm: VrfAlgorithm) -> Option<Witness> {
+        match vrf_algorithm {
+            VrfAlgorithm::RistrettoGroup2HashDh => {
+                self.to_vrf_proof().map(Witness::RistrettoGroup2HashDh)
+            }
+            VrfAlgorithm::EcVrfEdwards25519Sha512 => {
+                self.to_ecvrf_proof().map(Witness::EcVrfEdwards25519Sha512)
+            }
+        }
+    }
 }
 
 impl From<ProvenOutputSeed> for VrfProof {
@@ -68,3 +135,20 @@ impl From<ProvenOutputSeed> for VrfProof {
         VrfProof(v.bytes())
     }
 }
+
+impl From<ecvrf::Proof> for VrfProof {
+    fn from(v: ecvrf::Proof) -> VrfProof {
+        let mut proof: cstruct::GpVrfProof = [0; 96];
+        proof[..ecvrf::Proof::BYTES_LEN].copy_from_slice(&v.to_bytes());
+        VrfProof(proof)
+    }
+}
+
+impl From<Witness> for VrfProof {
+    fn from(witness: Witness) -> VrfProof {
+        match witness {
+            Witness::RistrettoGroup2HashDh(proof) => proof.into(),
+            Witness::EcVrfEdwards25519Sha512(proof) => proof.into(),
+        }
+    }
+}
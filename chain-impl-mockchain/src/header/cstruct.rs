@@ -1,6 +1,7 @@
 // lowlevel header binary accessors, use module qualified and fundamentally allow to do invalid construction
 #![allow(dead_code)]
 
+use crate::chaintypes::KesAlgorithm;
 use std::mem::size_of;
 
 // ************************************************************************
@@ -20,7 +21,11 @@ pub(super) type BftSignature = [u8; 64];
 
 pub(super) type GpNodeId = [u8; 32];
 pub(super) type GpVrfProof = [u8; 96];
-pub(super) type GpKesSignature = [u8; 484];
+
+// the KES signature of a Genesis-Praos header has the size of the signatures
+// of the KES algorithm in use, which are all different
+const GP_KES_SIGNATURE_DEFAULT_SIZE: usize = KesAlgorithm::SumEd25519_12.signature_size();
+const GP_KES_SIGNATURE_MAX_SIZE: usize = KesAlgorithm::SumEd25519_14.signature_size();
 
 // common parts
 const HEADER_OFFSET_VERSION: usize = 0;
@@ -46,12 +51,14 @@ const HEADER_OFFSET_GP_ID: usize = HEADER_COMMON_SIZE;
 const HEADER_OFFSET_GP_VRF_PROOF: usize = HEADER_OFFSET_GP_ID + size_of::<GpNodeId>();
 const HEADER_OFFSET_GP_KES_SIG: usize = HEADER_OFFSET_GP_VRF_PROOF + size_of::<GpVrfProof>();
 
-pub const HEADER_GP_SIZE: usize = HEADER_OFFSET_GP_KES_SIG + size_of::<GpKesSignature>();
+/// size of a Genesis-Praos header signed with the default KES algorithm
+pub const HEADER_GP_SIZE: usize = HEADER_OFFSET_GP_KES_SIG + GP_KES_SIGNATURE_DEFAULT_SIZE;
+pub const HEADER_GP_MAX_SIZE: usize = HEADER_OFFSET_GP_KES_SIG + GP_KES_SIGNATURE_MAX_SIZE;
 
 pub const HEADER_GP_AUTHED_SIZE: usize = HEADER_OFFSET_GP_KES_SIG;
 
 pub const HEADER_MIN_KNOWN_SIZE: usize = HEADER_COMMON_SIZE;
-pub const HEADER_MAX_KNOWN_SIZE: usize = HEADER_GP_SIZE;
+pub const HEADER_MAX_KNOWN_SIZE: usize = HEADER_GP_MAX_SIZE;
 
 // ************************************************************************
 // Header union construction & accessors
@@ -59,25 +66,34 @@ pub const HEADER_MAX_KNOWN_SIZE: usize = HEADER_GP_SIZE;
 
 pub(super) type HeaderUnsigned = [u8; HEADER_COMMON_SIZE];
 pub(super) type HeaderBft = [u8; HEADER_BFT_SIZE];
-pub(super) type HeaderGp = [u8; HEADER_GP_SIZE];
+pub(super) type HeaderGp = [u8; HEADER_GP_MAX_SIZE];
 
-pub(super) union Header {
+union HeaderData {
     unsigned: HeaderUnsigned,
     bft: HeaderBft,
     gp: HeaderGp,
 }
 
+pub(super) struct Header {
+    data: HeaderData,
+    // only meaningful for the Genesis-Praos headers
+    gp_kes_signature_size: usize,
+}
+
 impl Clone for Header {
     fn clone(&self) -> Self {
-        let mut gp = [0u8; HEADER_GP_SIZE];
-        gp[..].copy_from_slice(unsafe { &self.gp[..] });
-        Header { gp }
+        let mut gp = [0u8; HEADER_GP_MAX_SIZE];
+        gp[..].copy_from_slice(unsafe { &self.data.gp[..] });
+        Header {
+            data: HeaderData { gp },
+            gp_kes_signature_size: self.gp_kes_signature_size,
+        }
     }
 }
 
 impl PartialEq for Header {
     fn eq(&self, other: &Self) -> bool {
-        unsafe { self.gp[..] == other.gp[..] }
+        self.as_slice().as_slice() == other.as_slice().as_slice()
     }
 }
 impl Eq for Header {}
@@ -85,38 +101,57 @@ impl Eq for Header {}
 pub(super) const VERSION_UNSIGNED: Version = 0;
 pub(super) const VERSION_BFT: Version = 1;
 pub(super) const VERSION_GP: Version = 2;
+// same layout as VERSION_GP, the VRF proof being an ECVRF proof followed by
+// zeros up to the size of the VRF proof
+pub(super) const VERSION_GP_ECVRF: Version = 3;
+
+fn is_gp(version: Version) -> bool {
+    version == VERSION_GP || version == VERSION_GP_ECVRF
+}
 
 pub struct HeaderSlice<'a>(&'a [u8]);
 
 impl Header {
     pub fn version(&self) -> Version {
         let mut buf = [0u8; size_of::<Version>()];
-        let s = unsafe { &self.unsigned[HEADER_OFFSET_VERSION..HEADER_OFFSET_CONTENT_SIZE] };
+        let s = unsafe { &self.data.unsigned[HEADER_OFFSET_VERSION..HEADER_OFFSET_CONTENT_SIZE] };
         buf.copy_from_slice(s);
         Version::from_be_bytes(buf)
     }
 
     pub fn as_slice(&self) -> HeaderSlice<'_> {
         match self.version() {
-            VERSION_UNSIGNED => unsafe { HeaderSlice(&self.unsigned[..]) },
-            VERSION_BFT => unsafe { HeaderSlice(&self.bft[..]) },
-            VERSION_GP => unsafe { HeaderSlice(&self.gp[..]) },
+            VERSION_UNSIGNED => unsafe { HeaderSlice(&self.data.unsigned[..]) },
+            VERSION_BFT => unsafe { HeaderSlice(&self.data.bft[..]) },
+            VERSION_GP | VERSION_GP_ECVRF => unsafe {
+                HeaderSlice(&self.data.gp[..self.gp_size()])
+            },
             _ => panic!("Header: cstruct: as slice with undefined version"),
         }
     }
 
     pub(self) fn as_slice_mut(&mut self) -> &mut [u8] {
         match self.version() {
-            VERSION_UNSIGNED => unsafe { &mut self.unsigned[..] },
-            VERSION_BFT => unsafe { &mut self.bft[..] },
-            VERSION_GP => unsafe { &mut self.gp[..] },
+            VERSION_UNSIGNED => unsafe { &mut self.data.unsigned[..] },
+            VERSION_BFT => unsafe { &mut self.data.bft[..] },
+            VERSION_GP | VERSION_GP_ECVRF => {
+                let size = self.gp_size();
+                unsafe { &mut self.data.gp[..size] }
+            }
             _ => panic!("Header: cstruct: as slice mut with undefined version"),
         }
     }
 
+    fn gp_size(&self) -> usize {
+        HEADER_OFFSET_GP_KES_SIG + self.gp_kes_signature_size
+    }
+
     pub fn new(version: Version) -> Header {
-        let gp = [0u8; HEADER_GP_SIZE];
-        let mut hdr = Header { gp };
+        let gp = [0u8; HEADER_GP_MAX_SIZE];
+        let mut hdr = Header {
+            data: HeaderData { gp },
+            gp_kes_signature_size: GP_KES_SIGNATURE_DEFAULT_SIZE,
+        };
         hdr.set_version(version);
         hdr
     }
@@ -124,7 +159,7 @@ impl Header {
     pub fn set_version(&mut self, s: Version) {
         let sbuf = s.to_be_bytes();
         unsafe {
-            self.unsigned[HEADER_OFFSET_VERSION..HEADER_OFFSET_CONTENT_SIZE]
+            self.data.unsigned[HEADER_OFFSET_VERSION..HEADER_OFFSET_CONTENT_SIZE]
                 .copy_from_slice(&sbuf[..])
         }
     }
@@ -132,7 +167,7 @@ impl Header {
     pub fn set_content_size(&mut self, s: ContentSize) {
         let sbuf = s.to_be_bytes();
         unsafe {
-            self.unsigned[HEADER_OFFSET_CONTENT_SIZE..HEADER_OFFSET_DATE_EPOCH]
+            self.data.unsigned[HEADER_OFFSET_CONTENT_SIZE..HEADER_OFFSET_DATE_EPOCH]
                 .copy_from_slice(&sbuf[..])
         }
     }
@@ -140,7 +175,7 @@ impl Header {
     pub fn set_date_epoch(&mut self, s: DateEpoch) {
         let sbuf = s.to_be_bytes();
         unsafe {
-            self.unsigned[HEADER_OFFSET_DATE_EPOCH..HEADER_OFFSET_DATE_SLOTID]
+            self.data.unsigned[HEADER_OFFSET_DATE_EPOCH..HEADER_OFFSET_DATE_SLOTID]
                 .copy_from_slice(&sbuf[..])
         }
     }
@@ -148,7 +183,7 @@ impl Header {
     pub fn set_date_slotid(&mut self, s: DateSlotid) {
         let sbuf = s.to_be_bytes();
         unsafe {
-            self.unsigned[HEADER_OFFSET_DATE_SLOTID..HEADER_OFFSET_HEIGHT]
+            self.data.unsigned[HEADER_OFFSET_DATE_SLOTID..HEADER_OFFSET_HEIGHT]
                 .copy_from_slice(&sbuf[..])
         }
     }
@@ -156,21 +191,22 @@ impl Header {
     pub fn set_height(&mut self, s: Height) {
         let sbuf = s.to_be_bytes();
         unsafe {
-            self.unsigned[HEADER_OFFSET_HEIGHT..HEADER_OFFSET_CONTENT_HASH]
+            self.data.unsigned[HEADER_OFFSET_HEIGHT..HEADER_OFFSET_CONTENT_HASH]
                 .copy_from_slice(&sbuf[..])
         }
     }
 
     pub fn set_content_hash(&mut self, s: &ContentHash) {
         unsafe {
-            self.unsigned[HEADER_OFFSET_CONTENT_HASH..HEADER_OFFSET_PARENT_HASH]
+            self.data.unsigned[HEADER_OFFSET_CONTENT_HASH..HEADER_OFFSET_PARENT_HASH]
                 .copy_from_slice(&s[..])
         }
     }
 
     pub fn set_parent_hash(&mut self, s: &ParentHash) {
         unsafe {
-            self.unsigned[HEADER_OFFSET_PARENT_HASH..HEADER_COMMON_SIZE].copy_from_slice(&s[..])
+            self.data.unsigned[HEADER_OFFSET_PARENT_HASH..HEADER_COMMON_SIZE]
+                .copy_from_slice(&s[..])
         }
     }
 
@@ -178,7 +214,7 @@ impl Header {
     pub fn set_bft_leader_id(&mut self, s: &BftLeaderId) {
         assert_eq!(self.version(), VERSION_BFT);
         unsafe {
-            self.bft[HEADER_OFFSET_BFT_LEADER_ID..HEADER_OFFSET_BFT_SIGNATURE]
+            self.data.bft[HEADER_OFFSET_BFT_LEADER_ID..HEADER_OFFSET_BFT_SIGNATURE]
                 .copy_from_slice(&s[..])
         }
     }
@@ -187,58 +223,62 @@ impl Header {
         assert_eq!(self.version(), VERSION_BFT);
         assert_eq!(s.len(), size_of::<BftLeaderId>());
         unsafe {
-            self.bft[HEADER_OFFSET_BFT_LEADER_ID..HEADER_OFFSET_BFT_SIGNATURE].copy_from_slice(s)
+            self.data.bft[HEADER_OFFSET_BFT_LEADER_ID..HEADER_OFFSET_BFT_SIGNATURE]
+                .copy_from_slice(s)
         }
     }
 
     #[allow(dead_code)]
     pub fn set_bft_signature(&mut self, s: &BftSignature) {
         assert_eq!(self.version(), VERSION_BFT);
-        unsafe { self.bft[HEADER_OFFSET_BFT_SIGNATURE..HEADER_BFT_SIZE].copy_from_slice(&s[..]) }
+        unsafe {
+            self.data.bft[HEADER_OFFSET_BFT_SIGNATURE..HEADER_BFT_SIZE].copy_from_slice(&s[..])
+        }
     }
 
     pub fn set_bft_signature_slice(&mut self, s: &[u8]) {
         assert_eq!(self.version(), VERSION_BFT);
         assert_eq!(s.len(), size_of::<BftSignature>());
-        unsafe { self.bft[HEADER_OFFSET_BFT_SIGNATURE..HEADER_BFT_SIZE].copy_from_slice(s) }
+        unsafe { self.data.bft[HEADER_OFFSET_BFT_SIGNATURE..HEADER_BFT_SIZE].copy_from_slice(s) }
     }
 
     pub fn set_gp_node_id(&mut self, s: &GpNodeId) {
-        assert_eq!(self.version(), VERSION_GP);
-        unsafe { self.gp[HEADER_OFFSET_GP_ID..HEADER_OFFSET_GP_VRF_PROOF].copy_from_slice(&s[..]) }
+        assert!(is_gp(self.version()));
+        unsafe {
+            self.data.gp[HEADER_OFFSET_GP_ID..HEADER_OFFSET_GP_VRF_PROOF].copy_from_slice(&s[..])
+        }
     }
 
     #[allow(dead_code)]
     pub fn set_gp_node_id_slice(&mut self, s: &[u8]) {
-        assert_eq!(self.version(), VERSION_GP);
+        assert!(is_gp(self.version()));
         assert_eq!(s.len(), size_of::<GpNodeId>());
-        unsafe { self.gp[HEADER_OFFSET_GP_ID..HEADER_OFFSET_GP_VRF_PROOF].copy_from_slice(s) }
+        unsafe { self.data.gp[HEADER_OFFSET_GP_ID..HEADER_OFFSET_GP_VRF_PROOF].copy_from_slice(s) }
     }
 
     pub fn set_gp_vrf_proof(&mut self, s: &GpVrfProof) {
-        assert_eq!(self.version(), VERSION_GP);
+        assert!(is_gp(self.version()));
         unsafe {
-            self.gp[HEADER_OFFSET_GP_VRF_PROOF..HEADER_OFFSET_GP_KES_SIG].copy_from_slice(&s[..])
+            self.data.gp[HEADER_OFFSET_GP_VRF_PROOF..HEADER_OFFSET_GP_KES_SIG]
+                .copy_from_slice(&s[..])
         }
     }
 
     #[allow(dead_code)]
     pub fn set_gp_vrf_proof_slice(&mut self, s: &[u8]) {
-        assert_eq!(self.version(), VERSION_GP);
+        assert!(is_gp(self.version()));
         assert_eq!(s.len(), size_of::<GpVrfProof>());
-        unsafe { self.gp[HEADER_OFFSET_GP_VRF_PROOF..HEADER_OFFSET_GP_KES_SIG].copy_from_slice(s) }
-    }
-
-    #[allow(dead_code)]
-    pub fn set_gp_kes_signature(&mut self, s: &GpKesSignature) {
-        assert_eq!(self.version(), VERSION_GP);
-        unsafe { self.gp[HEADER_OFFSET_GP_KES_SIG..HEADER_GP_SIZE].copy_from_slice(&s[..]) }
+        unsafe {
+            self.data.gp[HEADER_OFFSET_GP_VRF_PROOF..HEADER_OFFSET_GP_KES_SIG].copy_from_slice(s)
+        }
     }
 
     pub fn set_gp_kes_signature_slice(&mut self, s: &[u8]) {
-        assert_eq!(self.version(), VERSION_GP);
-        assert_eq!(s.len(), size_of::<GpKesSignature>());
-        unsafe { self.gp[HEADER_OFFSET_GP_KES_SIG..HEADER_GP_SIZE].copy_from_slice(s) }
+        assert!(is_gp(self.version()));
+        assert!(KesAlgorithm::from_signature_size(s.len()).is_some());
+        self.gp_kes_signature_size = s.len();
+        let end = self.gp_size();
+        unsafe { self.data.gp[HEADER_OFFSET_GP_KES_SIG..end].copy_from_slice(s) }
     }
 }
 
@@ -250,6 +290,8 @@ pub enum HeaderError {
     UnknownVersion,
     #[error("block header payload size {got} does not match the expected {expected} bytes")]
     SizeMismatch { expected: usize, got: usize },
+    #[error("block header KES signature size {0} does not match any KES algorithm")]
+    KesSignatureSizeInvalid(usize),
 }
 
 impl<'a> HeaderSlice<'a> {
@@ -282,12 +324,10 @@ impl<'a> HeaderSlice<'a> {
                 }
                 Ok(hdr)
             }
-            VERSION_GP => {
-                if len != HEADER_GP_SIZE {
-                    return Err(HeaderError::SizeMismatch {
-                        expected: HEADER_GP_SIZE,
-                        got: len,
-                    });
+            VERSION_GP | VERSION_GP_ECVRF => {
+                let kes_signature_size = len.saturating_sub(HEADER_GP_AUTHED_SIZE);
+                if KesAlgorithm::from_signature_size(kes_signature_size).is_none() {
+                    return Err(HeaderError::KesSignatureSizeInvalid(kes_signature_size));
                 }
                 Ok(hdr)
             }
@@ -301,6 +341,9 @@ impl<'a> HeaderSlice<'a> {
 
     pub(super) fn to_owned(&self) -> Header {
         let mut new = Header::new(self.version());
+        if is_gp(self.version()) {
+            new.gp_kes_signature_size = self.0.len() - HEADER_GP_AUTHED_SIZE;
+        }
         new.as_slice_mut().copy_from_slice(self.0);
         new
     }
@@ -378,7 +421,7 @@ impl<'a> HeaderSlice<'a> {
     }
 
     pub fn gp_node_id_ref(&self) -> &[u8] {
-        assert_eq!(self.version(), VERSION_GP);
+        assert!(is_gp(self.version()));
         &self.0[HEADER_OFFSET_GP_ID..HEADER_OFFSET_GP_VRF_PROOF]
     }
 
@@ -389,7 +432,7 @@ impl<'a> HeaderSlice<'a> {
     }
 
     pub fn gp_vrf_proof_ref(&self) -> &[u8] {
-        assert_eq!(self.version(), VERSION_GP);
+        assert!(is_gp(self.version()));
         &self.0[HEADER_OFFSET_GP_VRF_PROOF..HEADER_OFFSET_GP_KES_SIG]
     }
 
@@ -400,14 +443,8 @@ impl<'a> HeaderSlice<'a> {
     }
 
     pub fn gp_kes_signature_ref(&self) -> &[u8] {
-        assert_eq!(self.version(), VERSION_GP);
-        &self.0[HEADER_OFFSET_GP_KES_SIG..HEADER_GP_SIZE]
-    }
-
-    pub fn gp_kes_signature(&self) -> GpKesSignature {
-        let mut buf = [0u8; size_of::<GpKesSignature>()];
-        buf.copy_from_slice(self.gp_kes_signature_ref());
-        buf
+        assert!(is_gp(self.version()));
+        &self.0[HEADER_OFFSET_GP_KES_SIG..]
     }
 
     pub fn slice_bft_auth(self) -> &'a [u8] {
@@ -416,7 +453,7 @@ impl<'a> HeaderSlice<'a> {
     }
 
     pub fn slice_gp_auth(self) -> &'a [u8] {
-        assert_eq!(self.version(), VERSION_GP);
+        assert!(is_gp(self.version()));
         &self.0[0..HEADER_GP_AUTHED_SIZE]
     }
 }
@@ -443,7 +480,51 @@ mod tests {
         let gp_vrf_proof_slice = [0; 96];
         header.set_gp_vrf_proof_slice(&gp_vrf_proof_slice);
         let gp_kes_signature = [0; 484];
-        header.set_gp_kes_signature(&gp_kes_signature);
+        header.set_gp_kes_signature_slice(&gp_kes_signature);
+    }
+
+    #[test]
+    pub fn header_gp_ecvrf_version_set() {
+        let mut header = Header::new(VERSION_GP_ECVRF);
+        header.set_gp_node_id_slice(&[0; 32]);
+        header.set_gp_vrf_proof_slice(&[0; 96]);
+        header.set_gp_kes_signature_slice(&[0; 484]);
+
+        let slice = header.as_slice().as_slice();
+        assert_eq!(slice.len(), HEADER_GP_SIZE);
+        let header_slice = HeaderSlice::from_slice(slice).unwrap();
+        assert_eq!(header_slice.version(), VERSION_GP_ECVRF);
+        assert!(header_slice.to_owned() == header);
+    }
+
+    #[test]
+    pub fn header_gp_size_follows_kes_algorithm() {
+        for algorithm in KesAlgorithm::ALL.iter() {
+            let mut header = Header::new(VERSION_GP);
+            header.set_gp_kes_signature_slice(&vec![0; algorithm.signature_size()]);
+            let slice = header.as_slice().as_slice();
+            assert_eq!(
+                slice.len(),
+                HEADER_GP_AUTHED_SIZE + algorithm.signature_size()
+            );
+
+            let header_slice = HeaderSlice::from_slice(slice).unwrap();
+            assert_eq!(
+                header_slice.gp_kes_signature_ref().len(),
+                algorithm.signature_size()
+            );
+            assert!(header_slice.to_owned() == header);
+        }
+    }
+
+    #[test]
+    pub fn header_slice_from_slice_unknown_kes_signature_size() {
+        let mut slice = [0; HEADER_GP_SIZE + 32];
+        slice[1] = VERSION_GP as u8;
+        assert_eq!(
+            HeaderSlice::from_slice(&slice).err().unwrap(),
+            HeaderError::KesSignatureSizeInvalid(484 + 32)
+        );
     }
 
     #[test]
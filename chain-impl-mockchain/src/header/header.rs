@@ -1,4 +1,4 @@
-use super::components::VrfProof;
+use super::components::{KesSignature, VrfProof};
 use super::cstruct;
 use super::deconstruct::{BftProof, Common, GenesisPraosProof, Proof};
 use super::version::BlockVersion;
@@ -105,7 +105,7 @@ impl Header {
     }
 
     pub fn size(&self) -> NonZeroUsize {
-        self.block_version().get_size()
+        NonZeroUsize::new(self.as_slice().len()).unwrap()
     }
 
     // deprecated: use .id()
@@ -146,7 +146,8 @@ impl Header {
     pub fn block_version(&self) -> BlockVersion {
         match self {
             Header::Unsigned(_) => BlockVersion::Genesis,
-            Header::GenesisPraos(_) => BlockVersion::KesVrfproof,
+            Header::GenesisPraos(_) => BlockVersion::from_u16(self.get_cstruct().version())
+                .expect("genesis praos header has a known version"),
             Header::Bft(_) => BlockVersion::Ed25519Signed,
         }
     }
@@ -185,7 +186,9 @@ impl Header {
         match BlockVersion::from_u16(hdr.version()).expect("header slice only know version") {
             BlockVersion::Genesis => Ok(Header::Unsigned(HeaderUnsigned(hdr))),
             BlockVersion::Ed25519Signed => Ok(Header::Bft(HeaderBft(hdr))),
-            BlockVersion::KesVrfproof => Ok(Header::GenesisPraos(HeaderGenesisPraos(hdr))),
+            BlockVersion::KesVrfproof | BlockVersion::KesEcVrfproof => {
+                Ok(Header::GenesisPraos(HeaderGenesisPraos(hdr)))
+            }
         }
     }
 
@@ -213,18 +216,22 @@ impl Header {
                 leader_id: self.get_cstruct().bft_leader_id().into(),
                 signature: self.get_cstruct().bft_signature().into(),
             }),
-            BlockVersion::KesVrfproof => Proof::GenesisPraos(GenesisPraosProof {
-                node_id: self.get_cstruct().gp_node_id().into(),
-                vrf_proof: VrfProof(self.get_cstruct().gp_vrf_proof()),
-                kes_proof: self.get_cstruct().gp_kes_signature().into(),
-            }),
+            BlockVersion::KesVrfproof | BlockVersion::KesEcVrfproof => {
+                Proof::GenesisPraos(GenesisPraosProof {
+                    node_id: self.get_cstruct().gp_node_id().into(),
+                    vrf_proof: VrfProof(self.get_cstruct().gp_vrf_proof()),
+                    kes_proof: KesSignature::from_slice(self.get_cstruct().gp_kes_signature_ref()),
+                })
+            }
         }
     }
 
     #[inline]
     pub fn get_stakepool_id(&self) -> Option<PoolId> {
         match self.block_version() {
-            BlockVersion::KesVrfproof => Some(self.get_cstruct().gp_node_id().into()),
+            BlockVersion::KesVrfproof | BlockVersion::KesEcVrfproof => {
+                Some(self.get_cstruct().gp_node_id().into())
+            }
             _ => None,
         }
     }
@@ -238,10 +245,14 @@ impl Header {
     }
 
     pub fn get_consensus_eval_context(&self) -> ConsensusEvalContext {
-        match self.block_version() {
-            BlockVersion::KesVrfproof => {
+        let block_version = self.block_version();
+        match block_version {
+            BlockVersion::KesVrfproof | BlockVersion::KesEcVrfproof => {
+                let vrf_algorithm = block_version
+                    .to_vrf_algorithm()
+                    .expect("genesis praos block version has a VRF algorithm");
                 let nonce = VrfProof(self.get_cstruct().gp_vrf_proof())
-                    .to_vrf_proof()
+                    .to_witness(vrf_algorithm)
                     .map(|p| leadership::genesis::witness_to_nonce(&p))
                     .expect("internal-error: content_eval_context: vrf proof invalid: shouldn't be trying get an header content application context");
                 let node_id = self.get_cstruct().gp_node_id();
@@ -309,6 +320,7 @@ impl Readable for Header {
             HeaderError::InvalidSize => ReadError::NotEnoughBytes(0, 0),
             HeaderError::UnknownVersion => ReadError::UnknownTag(0),
             HeaderError::SizeMismatch { expected, got } => ReadError::SizeTooBig(expected, got),
+            HeaderError::KesSignatureSizeInvalid(_) => ReadError::StructureInvalid(e.to_string()),
         })
     }
 }
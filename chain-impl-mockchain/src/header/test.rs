@@ -1,12 +1,12 @@
 use super::*;
-use crate::chaintypes::ChainLength;
-use crate::header::{BftProof, BftSignature, Common, GenesisPraosProof, KesSignature};
+use crate::chaintypes::{ChainLength, VrfAlgorithm};
+use crate::header::{BftProof, BftSignature, Common, GenesisPraosProof, KesSignature, VrfProof};
 use crate::key::BftLeaderId;
 #[cfg(test)]
 use crate::testing::serialization::serialization_bijection_r;
 use chain_crypto::{
-    self, AsymmetricKey, Ed25519, RistrettoGroup2HashDh, SecretKey, SumEd25519_12,
-    VerifiableRandomFunction,
+    self, AsymmetricKey, EcVrfEdwards25519Sha512, Ed25519, RistrettoGroup2HashDh, SecretKey,
+    SumEd25519_12, VerifiableRandomFunction,
 };
 use lazy_static::lazy_static;
 #[cfg(test)]
@@ -21,7 +21,7 @@ quickcheck! {
 
 impl Arbitrary for BlockVersion {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        BlockVersion::from_u16(u16::arbitrary(g) % 3).unwrap()
+        BlockVersion::from_u16(u16::arbitrary(g) % 4).unwrap()
     }
 }
 
@@ -57,14 +57,27 @@ impl Arbitrary for BftProof {
 }
 impl Arbitrary for GenesisPraosProof {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        GenesisPraosProof::arbitrary_with_vrf(g, VrfAlgorithm::RistrettoGroup2HashDh)
+    }
+}
+
+impl GenesisPraosProof {
+    pub(crate) fn arbitrary_with_vrf<G: Gen>(g: &mut G, vrf_algorithm: VrfAlgorithm) -> Self {
         use chain_crypto::testing;
         let tcg = testing::TestCryptoGen::arbitrary(g);
 
         let node_id = Arbitrary::arbitrary(g);
 
-        let vrf_proof = {
-            let sk = RistrettoGroup2HashDh::generate(&mut tcg.get_rng(0));
-            RistrettoGroup2HashDh::evaluate_and_prove(&sk, &[0, 1, 2, 3], &mut tcg.get_rng(1))
+        let vrf_proof: VrfProof = match vrf_algorithm {
+            VrfAlgorithm::RistrettoGroup2HashDh => {
+                let sk = RistrettoGroup2HashDh::generate(tcg.get_rng(0));
+                RistrettoGroup2HashDh::evaluate_and_prove(&sk, &[0, 1, 2, 3], tcg.get_rng(1)).into()
+            }
+            VrfAlgorithm::EcVrfEdwards25519Sha512 => {
+                let sk = EcVrfEdwards25519Sha512::generate(tcg.get_rng(0));
+                EcVrfEdwards25519Sha512::evaluate_and_prove(&sk, &[0, 1, 2, 3], tcg.get_rng(1))
+                    .into()
+            }
         };
 
         let kes_proof = {
@@ -73,11 +86,11 @@ impl Arbitrary for GenesisPraosProof {
             }
             let sk = SK_FIRST.clone();
             let signature = sk.sign(&[0u8, 1, 2, 3]);
-            KesSignature(signature.coerce())
+            KesSignature::SumEd25519_12(signature.coerce())
         };
         GenesisPraosProof {
             node_id,
-            vrf_proof: vrf_proof.into(),
+            vrf_proof,
             kes_proof,
         }
     }
@@ -104,8 +117,11 @@ impl Arbitrary for Header {
                     .set_signature(bft_proof.signature)
                     .generalize()
             }
-            BlockVersion::KesVrfproof => {
-                let gp_proof: GenesisPraosProof = Arbitrary::arbitrary(g);
+            BlockVersion::KesVrfproof | BlockVersion::KesEcVrfproof => {
+                let gp_proof = GenesisPraosProof::arbitrary_with_vrf(
+                    g,
+                    common.block_version.to_vrf_algorithm().unwrap(),
+                );
                 hdrbuilder
                     .into_genesis_praos_builder()
                     .unwrap()
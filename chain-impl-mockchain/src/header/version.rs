@@ -1,5 +1,5 @@
 use super::cstruct;
-use crate::chaintypes::ConsensusType;
+use crate::chaintypes::{ConsensusType, VrfAlgorithm};
 use std::num::NonZeroUsize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -55,6 +55,7 @@ pub enum BlockVersion {
     Genesis,
     Ed25519Signed,
     KesVrfproof,
+    KesEcVrfproof,
 }
 
 impl BlockVersion {
@@ -63,6 +64,7 @@ impl BlockVersion {
             cstruct::VERSION_UNSIGNED => Some(BlockVersion::Genesis),
             cstruct::VERSION_BFT => Some(BlockVersion::Ed25519Signed),
             cstruct::VERSION_GP => Some(BlockVersion::KesVrfproof),
+            cstruct::VERSION_GP_ECVRF => Some(BlockVersion::KesEcVrfproof),
             _ => None,
         }
     }
@@ -72,23 +74,28 @@ impl BlockVersion {
             BlockVersion::Genesis => cstruct::VERSION_UNSIGNED,
             BlockVersion::Ed25519Signed => cstruct::VERSION_BFT,
             BlockVersion::KesVrfproof => cstruct::VERSION_GP,
+            BlockVersion::KesEcVrfproof => cstruct::VERSION_GP_ECVRF,
         }
     }
 
+    /// size of the headers of this version; Genesis-Praos headers are only of
+    /// this size when signed with the default KES algorithm
     pub const fn get_size(self) -> NonZeroUsize {
-        const SIZE: [NonZeroUsize; 3] = [
+        const SIZE: [NonZeroUsize; 4] = [
             unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_COMMON_SIZE) },
             unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_BFT_SIZE) },
             unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_GP_SIZE) },
+            unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_GP_SIZE) },
         ];
         SIZE[self as usize]
     }
 
     pub const fn get_auth_size(self) -> NonZeroUsize {
-        const SIZE: [NonZeroUsize; 3] = [
+        const SIZE: [NonZeroUsize; 4] = [
             unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_COMMON_SIZE) },
             unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_BFT_AUTHED_SIZE) },
             unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_GP_AUTHED_SIZE) },
+            unsafe { NonZeroUsize::new_unchecked(cstruct::HEADER_GP_AUTHED_SIZE) },
         ];
         SIZE[self as usize]
     }
@@ -98,6 +105,24 @@ impl BlockVersion {
             BlockVersion::Genesis => None,
             BlockVersion::Ed25519Signed => Some(ConsensusType::Bft),
             BlockVersion::KesVrfproof => Some(ConsensusType::GenesisPraos),
+            BlockVersion::KesEcVrfproof => Some(ConsensusType::GenesisPraos),
+        }
+    }
+
+    /// the Genesis-Praos block version whose headers carry a proof of
+    /// the VRF `vrf_algorithm`
+    pub fn genesis_praos(vrf_algorithm: VrfAlgorithm) -> Self {
+        match vrf_algorithm {
+            VrfAlgorithm::RistrettoGroup2HashDh => BlockVersion::KesVrfproof,
+            VrfAlgorithm::EcVrfEdwards25519Sha512 => BlockVersion::KesEcVrfproof,
+        }
+    }
+
+    pub fn to_vrf_algorithm(self) -> Option<VrfAlgorithm> {
+        match self {
+            BlockVersion::Genesis | BlockVersion::Ed25519Signed => None,
+            BlockVersion::KesVrfproof => Some(VrfAlgorithm::RistrettoGroup2HashDh),
+            BlockVersion::KesEcVrfproof => Some(VrfAlgorithm::EcVrfEdwards25519Sha512),
         }
     }
 }
@@ -105,7 +130,7 @@ impl BlockVersion {
 #[cfg(test)]
 mod tests {
 
-    use crate::chaintypes::ConsensusType;
+    use crate::chaintypes::{ConsensusType, VrfAlgorithm};
     use crate::header::{AnyBlockVersion, BlockVersion};
     use quickcheck::TestResult;
     use quickcheck_macros::quickcheck;
@@ -174,5 +199,20 @@ mod tests {
             BlockVersion::KesVrfproof.to_consensus_type(),
             Some(ConsensusType::GenesisPraos)
         );
+        assert_eq!(
+            BlockVersion::KesEcVrfproof.to_consensus_type(),
+            Some(ConsensusType::GenesisPraos)
+        );
+    }
+
+    #[test]
+    pub fn genesis_praos_vrf_algorithm() {
+        for vrf_algorithm in VrfAlgorithm::ALL.iter() {
+            assert_eq!(
+                BlockVersion::genesis_praos(*vrf_algorithm).to_vrf_algorithm(),
+                Some(*vrf_algorithm)
+            );
+        }
+        assert_eq!(BlockVersion::Ed25519Signed.to_vrf_algorithm(), None);
     }
 }
@@ -1,12 +1,14 @@
 //! Module provides cryptographic utilities and types related to
 //! the user keys.
 //!
+use crate::chaintypes::VrfAlgorithm;
 use chain_core::mempack::{read_mut_slice, ReadBuf, ReadError, Readable};
 use chain_core::property;
 use chain_crypto as crypto;
 use chain_crypto::{
-    digest::DigestOf, AsymmetricKey, AsymmetricPublicKey, Blake2b256, Ed25519, PublicKey,
-    RistrettoGroup2HashDh, SecretKey, SigningAlgorithm, SumEd25519_12, VerificationAlgorithm,
+    digest::DigestOf, AsymmetricKey, AsymmetricPublicKey, Blake2b256, EcVrfEdwards25519Sha512,
+    Ed25519, PublicKey, RistrettoGroup2HashDh, SecretKey, SigningAlgorithm, SumEd25519_12,
+    VerificationAlgorithm,
 };
 use rand_core::{CryptoRng, RngCore};
 use typed_bytes::ByteBuilder;
@@ -371,11 +373,70 @@ impl From<PublicKey<BftVerificationAlg>> for BftLeaderId {
     }
 }
 
+/// VRF public key of a stake pool, to be interpreted as a key of the
+/// `VrfAlgorithm` of the chain
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VrfPublicKey([u8; VrfPublicKey::BYTES_LEN]);
+
+impl VrfPublicKey {
+    pub const BYTES_LEN: usize = 32;
+
+    pub fn from_bytes(bytes: [u8; Self::BYTES_LEN]) -> Self {
+        VrfPublicKey(bytes)
+    }
+
+    pub fn to_ristretto(&self) -> Option<PublicKey<RistrettoGroup2HashDh>> {
+        PublicKey::from_binary(&self.0).ok()
+    }
+
+    pub fn to_ecvrf(&self) -> Option<PublicKey<EcVrfEdwards25519Sha512>> {
+        PublicKey::from_binary(&self.0).ok()
+    }
+
+    /// check that the key is a valid public key of `vrf_algorithm`
+    pub fn is_valid_for(&self, vrf_algorithm: VrfAlgorithm) -> bool {
+        match vrf_algorithm {
+            VrfAlgorithm::RistrettoGroup2HashDh => self.to_ristretto().is_some(),
+            VrfAlgorithm::EcVrfEdwards25519Sha512 => self.to_ecvrf().is_some(),
+        }
+    }
+}
+
+impl AsRef<[u8]> for VrfPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<PublicKey<RistrettoGroup2HashDh>> for VrfPublicKey {
+    fn from(key: PublicKey<RistrettoGroup2HashDh>) -> Self {
+        let mut bytes = [0; Self::BYTES_LEN];
+        bytes.copy_from_slice(key.as_ref());
+        VrfPublicKey(bytes)
+    }
+}
+
+impl From<PublicKey<EcVrfEdwards25519Sha512>> for VrfPublicKey {
+    fn from(key: PublicKey<EcVrfEdwards25519Sha512>) -> Self {
+        let mut bytes = [0; Self::BYTES_LEN];
+        bytes.copy_from_slice(key.as_ref());
+        VrfPublicKey(bytes)
+    }
+}
+
+impl Readable for VrfPublicKey {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let mut bytes = [0; Self::BYTES_LEN];
+        read_mut_slice(buf, &mut bytes)?;
+        Ok(VrfPublicKey(bytes))
+    }
+}
+
 /// Praos Leader consisting of the KES public key and VRF public key
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GenesisPraosLeader {
     pub kes_public_key: PublicKey<SumEd25519_12>,
-    pub vrf_public_key: PublicKey<RistrettoGroup2HashDh>,
+    pub vrf_public_key: VrfPublicKey,
 }
 
 impl GenesisPraosLeader {
@@ -392,7 +453,7 @@ impl GenesisPraosLeader {
 
 impl Readable for GenesisPraosLeader {
     fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
-        let vrf_public_key = deserialize_public_key(buf)?;
+        let vrf_public_key = VrfPublicKey::read(buf)?;
         let kes_public_key = deserialize_public_key(buf)?;
         Ok(GenesisPraosLeader {
             kes_public_key,
@@ -437,7 +498,7 @@ mod tests {
             let mut rng = tcg.get_rng(0);
             let vrf_sk: SecretKey<RistrettoGroup2HashDh> = SecretKey::generate(&mut rng);
             GenesisPraosLeader {
-                vrf_public_key: vrf_sk.to_public(),
+                vrf_public_key: vrf_sk.to_public().into(),
                 kes_public_key: PK_KES.clone(),
             }
         }
@@ -448,4 +509,16 @@ mod tests {
             serialization_bijection(leader_id)
         }
     }
+
+    #[test]
+    fn vrf_public_key_validity() {
+        let vrf_sk: SecretKey<EcVrfEdwards25519Sha512> = SecretKey::generate(rand_core::OsRng);
+        let vrf_public_key = VrfPublicKey::from(vrf_sk.to_public());
+        assert!(vrf_public_key.is_valid_for(VrfAlgorithm::EcVrfEdwards25519Sha512));
+        assert_eq!(vrf_public_key.to_ecvrf(), Some(vrf_sk.to_public()));
+
+        // the encoding of a point of order 4
+        let small_order = VrfPublicKey::from_bytes([0; VrfPublicKey::BYTES_LEN]);
+        assert!(!small_order.is_valid_for(VrfAlgorithm::EcVrfEdwards25519Sha512));
+    }
 }
@@ -3,6 +3,7 @@ mod vrfeval;
 use crate::{
     certificate::PoolId,
     chaineval::PraosNonce,
+    chaintypes::{KesAlgorithm, VrfAlgorithm},
     date::{BlockDate, Epoch},
     header::{Header, HeaderDesc, Proof},
     leadership::{Error, ErrorKind, Verification},
@@ -11,11 +12,10 @@ use crate::{
     stake::{PercentStake, PoolsState, Stake, StakeDistribution},
 };
 use chain_crypto::Verification as SigningVerification;
-use chain_crypto::{RistrettoGroup2HashDh, SecretKey};
 use thiserror::Error;
 pub(crate) use vrfeval::witness_to_nonce;
 use vrfeval::VrfEvaluator;
-pub use vrfeval::{Threshold, VrfEvalFailure, Witness, WitnessOutput};
+pub use vrfeval::{Threshold, VrfEvalFailure, VrfSecretKey, Witness, WitnessOutput};
 
 /// Genesis Praos leadership data for a specific epoch
 pub struct LeadershipData {
@@ -25,12 +25,35 @@ pub struct LeadershipData {
     // the epoch this leader selection is valid for
     epoch: Epoch,
     active_slots_coeff: ActiveSlotsCoeff,
+    kes_algorithm: KesAlgorithm,
+    vrf_algorithm: VrfAlgorithm,
 }
 
 #[derive(Debug, Error)]
 enum GenesisError {
     #[error("Wrong epoch, expected epoch {expected} but received block at epoch {actual}")]
     InvalidEpoch { expected: Epoch, actual: Epoch },
+    #[error(
+        "Wrong KES algorithm in {bdesc}, expected {expected} but the block is signed with {actual}"
+    )]
+    InvalidKesAlgorithm {
+        bdesc: HeaderDesc,
+        expected: KesAlgorithm,
+        actual: KesAlgorithm,
+    },
+    #[error(
+        "Wrong VRF algorithm in {bdesc}, expected {expected} but the block is proven with {actual}"
+    )]
+    InvalidVrfAlgorithm {
+        bdesc: HeaderDesc,
+        expected: VrfAlgorithm,
+        actual: VrfAlgorithm,
+    },
+    #[error("Wrong VRF key, expected a {expected} key but received a {actual} key")]
+    InvalidVrfKey {
+        expected: VrfAlgorithm,
+        actual: VrfAlgorithm,
+    },
     #[error("Total stake is null")]
     TotalStakeIsZero,
 }
@@ -57,9 +80,11 @@ impl LeadershipData {
         LeadershipData {
             epoch_nonce: ledger.settings.consensus_nonce.clone(),
             nodes: ledger.delegation.clone(),
-            distribution: ledger.get_stake_distribution(),
+            distribution: ledger.active_stake_distribution(epoch).clone(),
             epoch,
             active_slots_coeff: ledger.settings.active_slots_coeff,
+            kes_algorithm: ledger.settings.kes_algorithm,
+            vrf_algorithm: ledger.settings.vrf_algorithm,
         }
     }
 
@@ -74,7 +99,7 @@ impl LeadershipData {
     pub fn leader(
         &self,
         pool_id: &PoolId,
-        vrf_key: &SecretKey<RistrettoGroup2HashDh>,
+        vrf_key: &VrfSecretKey,
         date: BlockDate,
     ) -> Result<Option<Witness>, Error> {
         if date.epoch != self.epoch {
@@ -87,6 +112,16 @@ impl LeadershipData {
             ));
         }
 
+        if vrf_key.algorithm() != self.vrf_algorithm {
+            return Err(Error::new_(
+                ErrorKind::Failure,
+                GenesisError::InvalidVrfKey {
+                    expected: self.vrf_algorithm,
+                    actual: vrf_key.algorithm(),
+                },
+            ));
+        }
+
         let stake_snapshot = &self.distribution;
 
         match stake_snapshot.get_stake_for(pool_id) {
@@ -133,6 +168,33 @@ impl LeadershipData {
 
         match block_header.proof() {
             Proof::GenesisPraos(ref genesis_praos_proof) => {
+                let kes_algorithm = genesis_praos_proof.kes_proof.algorithm();
+                if kes_algorithm != self.kes_algorithm {
+                    return Verification::Failure(Error::new_(
+                        ErrorKind::InvalidLeaderSignature,
+                        GenesisError::InvalidKesAlgorithm {
+                            bdesc: block_header.description(),
+                            expected: self.kes_algorithm,
+                            actual: kes_algorithm,
+                        },
+                    ));
+                }
+
+                let vrf_algorithm = block_header
+                    .block_version()
+                    .to_vrf_algorithm()
+                    .expect("genesis praos block version has a VRF algorithm");
+                if vrf_algorithm != self.vrf_algorithm {
+                    return Verification::Failure(Error::new_(
+                        ErrorKind::InvalidLeaderProof,
+                        GenesisError::InvalidVrfAlgorithm {
+                            bdesc: block_header.description(),
+                            expected: self.vrf_algorithm,
+                            actual: vrf_algorithm,
+                        },
+                    ));
+                }
+
                 let node_id = &genesis_praos_proof.node_id;
                 match (
                     stake_snapshot.get_stake_for(node_id),
@@ -144,7 +206,7 @@ impl LeadershipData {
 
                         let percent_stake = PercentStake::new(stake, total_stake);
 
-                        let proof = match genesis_praos_proof.vrf_proof.to_vrf_proof() {
+                        let proof = match genesis_praos_proof.vrf_proof.to_witness(vrf_algorithm) {
                             None => {
                                 return Verification::Failure(Error::new_(
                                     ErrorKind::InvalidLeaderProof,
@@ -229,17 +291,20 @@ mod tests {
     };
     use crate::value::Value;
     use chain_core::property::ChainLength;
-    use chain_crypto::{RistrettoGroup2HashDh, SecretKey};
+    use chain_crypto::{EcVrfEdwards25519Sha512, SecretKey};
 
     use std::collections::HashMap;
 
-    fn make_pool(ledger: &mut Ledger) -> (PoolId, SecretKey<RistrettoGroup2HashDh>) {
+    fn make_pool(ledger: &mut Ledger) -> (PoolId, VrfSecretKey) {
         let stake_pool = StakePoolBuilder::new().build();
         ledger
             .delegation()
             .register_stake_pool(stake_pool.info())
             .expect("cannot add stake pool to ledger");
-        (stake_pool.id(), stake_pool.vrf().private_key().clone())
+        (
+            stake_pool.id(),
+            stake_pool.vrf().private_key().clone().into(),
+        )
     }
 
     #[derive(Clone, Debug)]
@@ -270,7 +335,7 @@ mod tests {
         }
     }
 
-    type Pools = HashMap<PoolId, (SecretKey<RistrettoGroup2HashDh>, u64, Stake)>;
+    type Pools = HashMap<PoolId, (VrfSecretKey, u64, Stake)>;
 
     fn make_leadership_with_pools(ledger: &Ledger, pools: &Pools) -> LeadershipData {
         let mut selection = LeadershipData::new(0, &ledger);
@@ -308,7 +373,7 @@ mod tests {
             .expect("cannot build test ledger")
             .ledger;
 
-        let mut pools = HashMap::<PoolId, (SecretKey<RistrettoGroup2HashDh>, u64, Stake)>::new();
+        let mut pools = HashMap::<PoolId, (VrfSecretKey, u64, Stake)>::new();
 
         for _i in 0..leader_election_parameters.pools_count {
             let (pool_id, pool_vrf_private_key) = make_pool(&mut ledger);
@@ -488,7 +553,11 @@ mod tests {
         );
 
         assert!(selection
-            .leader(&stake_pool.id(), &stake_pool.vrf().private_key(), date)
+            .leader(
+                &stake_pool.id(),
+                &stake_pool.vrf().private_key().clone().into(),
+                date
+            )
             .is_err());
     }
 
@@ -509,7 +578,11 @@ mod tests {
 
         assert_eq!(
             selection
-                .leader(&stake_pool.id(), &stake_pool.vrf().private_key(), date)
+                .leader(
+                    &stake_pool.id(),
+                    &stake_pool.vrf().private_key().clone().into(),
+                    date
+                )
                 .unwrap(),
             None
         );
@@ -532,7 +605,11 @@ mod tests {
         update_stake_pool_total_value(&mut selection, &stake_pool.id(), Stake::zero());
 
         assert!(selection
-            .leader(&stake_pool.id(), &stake_pool.vrf().private_key(), date)
+            .leader(
+                &stake_pool.id(),
+                &stake_pool.vrf().private_key().clone().into(),
+                date
+            )
             .is_err());
     }
 
@@ -596,6 +673,120 @@ mod tests {
         assert!(selection.verify(&header).failure());
     }
 
+    #[test]
+    pub fn leadership_verify_different_kes_algorithm() {
+        let date = BlockDate::first();
+        let testledger = LedgerBuilder::from_config(
+            ConfigBuilder::new().with_kes_algorithm(KesAlgorithm::SumEd25519_6),
+        )
+        .build()
+        .expect("cannot build test ledger");
+        let ledger = testledger.ledger;
+
+        let stake_pool = StakePoolBuilder::new().build();
+        ledger
+            .delegation()
+            .register_stake_pool(stake_pool.info())
+            .expect("cannot register stake pool");
+        let mut selection = LeadershipData::new(date.epoch, &ledger);
+        update_stake_pool_total_value(
+            &mut selection,
+            &stake_pool.id(),
+            Stake::from_value(Value(100)),
+        );
+
+        // the pools of the test builders sign with SumEd25519_12
+        let block = GenesisPraosBlockBuilder::new()
+            .with_date(date)
+            .with_chain_length(ledger.chain_length())
+            .with_parent_id(testledger.block0_hash)
+            .build(&stake_pool, ledger.era());
+
+        match selection.verify(&block.header) {
+            Verification::Failure(error) => {
+                assert_eq!(error.kind, ErrorKind::InvalidLeaderSignature);
+                assert!(matches!(
+                    error.cause.unwrap().downcast_ref::<GenesisError>(),
+                    Some(GenesisError::InvalidKesAlgorithm {
+                        expected: KesAlgorithm::SumEd25519_6,
+                        actual: KesAlgorithm::SumEd25519_12,
+                        ..
+                    })
+                ));
+            }
+            Verification::Success => panic!("block signed with the wrong KES algorithm"),
+        }
+    }
+
+    #[test]
+    pub fn leadership_ecvrf() {
+        let testledger = LedgerBuilder::from_config(
+            ConfigBuilder::new().with_vrf_algorithm(VrfAlgorithm::EcVrfEdwards25519Sha512),
+        )
+        .build()
+        .expect("cannot build test ledger");
+        let ledger = testledger.ledger;
+
+        let mut stake_pool = StakePoolBuilder::new().build();
+        let vrf_key: SecretKey<EcVrfEdwards25519Sha512> = SecretKey::generate(rand_core::OsRng);
+        stake_pool.info_mut().keys.vrf_public_key = vrf_key.to_public().into();
+        let pool_id = stake_pool.info().to_id();
+        let mut selection = LeadershipData::new(0, &ledger);
+        selection.nodes = ledger
+            .delegation()
+            .register_stake_pool(stake_pool.info())
+            .expect("cannot register stake pool");
+        update_stake_pool_total_value(&mut selection, &pool_id, Stake::from_value(Value(100)));
+
+        let ristretto_key = stake_pool.vrf().private_key().clone().into();
+        assert!(selection
+            .leader(&pool_id, &ristretto_key, BlockDate::first())
+            .is_err());
+
+        let vrf_key = vrf_key.into();
+        let (date, witness) = std::iter::successors(Some(BlockDate::first()), |date| {
+            Some(date.next(ledger.era()))
+        })
+        .find_map(|date| {
+            selection
+                .leader(&pool_id, &vrf_key, date)
+                .unwrap()
+                .map(|witness| (date, witness))
+        })
+        .expect("the only pool is elected in some slot");
+        assert_eq!(witness.algorithm(), VrfAlgorithm::EcVrfEdwards25519Sha512);
+
+        let block0_hash = testledger.block0_hash;
+        let header = |block_version| {
+            HeaderBuilderNew::new(block_version, &Contents::empty())
+                .set_parent(&block0_hash, ledger.chain_length().increase())
+                .set_date(date)
+                .into_genesis_praos_builder()
+                .unwrap()
+                .set_consensus_data(&pool_id, &witness.clone().into())
+                .sign_using(stake_pool.kes().private_key())
+                .generalize()
+        };
+        assert!(selection
+            .verify(&header(BlockVersion::KesEcVrfproof))
+            .success());
+
+        match selection.verify(&header(BlockVersion::KesVrfproof)) {
+            Verification::Failure(error) => {
+                assert_eq!(error.kind, ErrorKind::InvalidLeaderProof);
+                assert!(matches!(
+                    error.cause.unwrap().downcast_ref::<GenesisError>(),
+                    Some(GenesisError::InvalidVrfAlgorithm {
+                        expected: VrfAlgorithm::EcVrfEdwards25519Sha512,
+                        actual: VrfAlgorithm::RistrettoGroup2HashDh,
+                        ..
+                    })
+                ));
+            }
+            Verification::Success => panic!("block proven with the wrong VRF algorithm"),
+        }
+    }
+
     #[test]
     pub fn leadership_verify_no_stake() {
         let date = BlockDate::first();
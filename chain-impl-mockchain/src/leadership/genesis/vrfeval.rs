@@ -1,12 +1,15 @@
 /// This contains the current evaluation methods for the VRF and its link to
 /// the stake distribution
 use crate::chaineval::PraosNonce;
+use crate::chaintypes::VrfAlgorithm;
 use crate::date::SlotId;
+use crate::key::VrfPublicKey;
 use crate::setting::ActiveSlotsCoeff;
 use crate::stake::PercentStake;
 use chain_crypto::{
-    vrf_evaluate_and_prove, vrf_verified_get_output, vrf_verify, PublicKey, RistrettoGroup2HashDh,
-    SecretKey, VerifiableRandomFunction, VrfVerification,
+    vrf_evaluate_and_prove, vrf_verified_get_output, vrf_verify, Blake2b256,
+    EcVrfEdwards25519Sha512, RistrettoGroup2HashDh, SecretKey, VerifiableRandomFunction,
+    VrfVerification,
 };
 use rand_core::OsRng;
 
@@ -43,9 +46,96 @@ impl Input {
     }
 }
 
+/// VRF secret key of a stake pool, for one of the `VrfAlgorithm`
+#[derive(Clone)]
+pub enum VrfSecretKey {
+    RistrettoGroup2HashDh(SecretKey<RistrettoGroup2HashDh>),
+    EcVrfEdwards25519Sha512(SecretKey<EcVrfEdwards25519Sha512>),
+}
+
+impl VrfSecretKey {
+    pub fn algorithm(&self) -> VrfAlgorithm {
+        match self {
+            VrfSecretKey::RistrettoGroup2HashDh(_) => VrfAlgorithm::RistrettoGroup2HashDh,
+            VrfSecretKey::EcVrfEdwards25519Sha512(_) => VrfAlgorithm::EcVrfEdwards25519Sha512,
+        }
+    }
+}
+
+impl From<SecretKey<RistrettoGroup2HashDh>> for VrfSecretKey {
+    fn from(key: SecretKey<RistrettoGroup2HashDh>) -> Self {
+        VrfSecretKey::RistrettoGroup2HashDh(key)
+    }
+}
+
+impl From<SecretKey<EcVrfEdwards25519Sha512>> for VrfSecretKey {
+    fn from(key: SecretKey<EcVrfEdwards25519Sha512>) -> Self {
+        VrfSecretKey::EcVrfEdwards25519Sha512(key)
+    }
+}
+
 /// Witness
-pub type Witness = <RistrettoGroup2HashDh as VerifiableRandomFunction>::VerifiedRandomOutput;
-pub type WitnessOutput = <RistrettoGroup2HashDh as VerifiableRandomFunction>::RandomOutput;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Witness {
+    RistrettoGroup2HashDh(
+        <RistrettoGroup2HashDh as VerifiableRandomFunction>::VerifiedRandomOutput,
+    ),
+    EcVrfEdwards25519Sha512(
+        <EcVrfEdwards25519Sha512 as VerifiableRandomFunction>::VerifiedRandomOutput,
+    ),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitnessOutput {
+    RistrettoGroup2HashDh(<RistrettoGroup2HashDh as VerifiableRandomFunction>::RandomOutput),
+    EcVrfEdwards25519Sha512(<EcVrfEdwards25519Sha512 as VerifiableRandomFunction>::RandomOutput),
+}
+
+impl Witness {
+    pub fn algorithm(&self) -> VrfAlgorithm {
+        match self {
+            Witness::RistrettoGroup2HashDh(_) => VrfAlgorithm::RistrettoGroup2HashDh,
+            Witness::EcVrfEdwards25519Sha512(_) => VrfAlgorithm::EcVrfEdwards25519Sha512,
+        }
+    }
+
+    /// the random output of the witness, only meaningful once the witness
+    /// is verified
+    pub fn to_output(&self) -> WitnessOutput {
+        match self {
+            Witness::RistrettoGroup2HashDh(witness) => WitnessOutput::RistrettoGroup2HashDh(
+                vrf_verified_get_output::<RistrettoGroup2HashDh>(witness),
+            ),
+            Witness::EcVrfEdwards25519Sha512(witness) => {
+                WitnessOutput::EcVrfEdwards25519Sha512(vrf_verified_get_output::<
+                    EcVrfEdwards25519Sha512,
+                >(witness))
+            }
+        }
+    }
+
+    fn verify(&self, key: &VrfPublicKey, input: &Input) -> bool {
+        let verification = match self {
+            Witness::RistrettoGroup2HashDh(witness) => key
+                .to_ristretto()
+                .map(|key| vrf_verify(&key, &input.0, witness)),
+            Witness::EcVrfEdwards25519Sha512(witness) => key
+                .to_ecvrf()
+                .map(|key| vrf_verify(&key, &input.0, witness)),
+        };
+        verification == Some(VrfVerification::Success)
+    }
+}
+
+impl WitnessOutput {
+    /// Get the output for this input and a known suffix
+    pub fn to_output(&self, input: &[u8], suffix: &[u8]) -> Blake2b256 {
+        match self {
+            WitnessOutput::RistrettoGroup2HashDh(output) => output.to_output(input, suffix),
+            WitnessOutput::EcVrfEdwards25519Sha512(output) => output.to_output(input, suffix),
+        }
+    }
+}
 
 pub struct VrfEvaluator<'a> {
     pub stake: PercentStake,
@@ -55,8 +145,7 @@ pub struct VrfEvaluator<'a> {
 }
 
 pub(crate) fn witness_to_nonce(witness: &Witness) -> PraosNonce {
-    let r = vrf_verified_get_output::<RistrettoGroup2HashDh>(witness);
-    get_nonce(&r)
+    get_nonce(&witness.to_output())
 }
 
 #[derive(Clone, Debug)]
@@ -72,11 +161,18 @@ impl<'a> VrfEvaluator<'a> {
     /// Evaluate if the threshold is above for a given input for the key and the associated stake
     ///
     /// On threshold success, the witness is returned, otherwise None is returned
-    pub fn evaluate(&self, key: &SecretKey<RistrettoGroup2HashDh>) -> Option<Witness> {
+    pub fn evaluate(&self, key: &VrfSecretKey) -> Option<Witness> {
         let input = Input::create(self.nonce, self.slot_id);
         let csprng = OsRng;
-        let vr = vrf_evaluate_and_prove(key, &input.0, csprng);
-        let r = vrf_verified_get_output::<RistrettoGroup2HashDh>(&vr);
+        let vr = match key {
+            VrfSecretKey::RistrettoGroup2HashDh(key) => {
+                Witness::RistrettoGroup2HashDh(vrf_evaluate_and_prove(key, &input.0, csprng))
+            }
+            VrfSecretKey::EcVrfEdwards25519Sha512(key) => {
+                Witness::EcVrfEdwards25519Sha512(vrf_evaluate_and_prove(key, &input.0, csprng))
+            }
+        };
+        let r = vr.to_output();
         let t = get_threshold(&input, &r);
         if above_stake_threshold(t, &self.stake, self.active_slots_coeff) {
             Some(vr)
@@ -91,12 +187,12 @@ impl<'a> VrfEvaluator<'a> {
     /// On success, the nonce is returned, otherwise None is returned
     pub fn verify(
         &self,
-        key: &PublicKey<RistrettoGroup2HashDh>,
+        key: &VrfPublicKey,
         witness: &'a Witness,
     ) -> Result<PraosNonce, VrfEvalFailure> {
         let input = Input::create(self.nonce, self.slot_id);
-        if vrf_verify(key, &input.0, witness) == VrfVerification::Success {
-            let r = vrf_verified_get_output::<RistrettoGroup2HashDh>(witness);
+        if witness.verify(key, &input) {
+            let r = witness.to_output();
             // compare threshold against phi-adjusted-stake
             let threshold = get_threshold(&input, &r);
             let phi_stake = phi(self.active_slots_coeff, &self.stake);
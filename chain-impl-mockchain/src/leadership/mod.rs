@@ -7,7 +7,7 @@ use crate::{
     ledger::{Ledger, LedgerParameters},
     stake::StakeDistribution,
 };
-use chain_crypto::{Ed25519, RistrettoGroup2HashDh, SecretKey, SumEd25519_12};
+use chain_crypto::{Ed25519, SecretKey, SumEd25519_12};
 use chain_time::era::TimeEra;
 
 pub mod bft;
@@ -55,7 +55,7 @@ pub struct BftLeader {
 pub struct GenesisLeader {
     pub node_id: PoolId,
     pub sig_key: SecretKey<SumEd25519_12>,
-    pub vrf_key: SecretKey<RistrettoGroup2HashDh>,
+    pub vrf_key: genesis::VrfSecretKey,
 }
 
 pub struct Leader {
@@ -94,7 +94,12 @@ impl LeadershipConsensus {
             LeadershipConsensus::Bft(_) if block_version == BlockVersion::Ed25519Signed => {
                 Verification::Success
             }
-            LeadershipConsensus::GenesisPraos(_) if block_version == BlockVersion::KesVrfproof => {
+            LeadershipConsensus::GenesisPraos(_)
+                if matches!(
+                    block_version,
+                    BlockVersion::KesVrfproof | BlockVersion::KesEcVrfproof
+                ) =>
+            {
                 Verification::Success
             }
             _ => Verification::Failure(Error::new(ErrorKind::IncompatibleBlockVersion)),
@@ -308,6 +313,9 @@ mod tests {
         assert!(bft_leadership_consensus
             .verify_version(BlockVersion::KesVrfproof)
             .failure());
+        assert!(bft_leadership_consensus
+            .verify_version(BlockVersion::KesEcVrfproof)
+            .failure());
         assert!(bft_leadership_consensus
             .verify_version(BlockVersion::Genesis)
             .failure());
@@ -324,6 +332,9 @@ mod tests {
         assert!(gen_leadership_consensus
             .verify_version(BlockVersion::KesVrfproof)
             .success());
+        assert!(gen_leadership_consensus
+            .verify_version(BlockVersion::KesEcVrfproof)
+            .success());
         assert!(gen_leadership_consensus
             .verify_version(BlockVersion::Genesis)
             .failure());
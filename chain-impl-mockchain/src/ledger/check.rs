@@ -61,6 +61,21 @@ pub(super) fn valid_output_value(output: &Output<Address>) -> LedgerCheck {
     )
 }
 
+/// Check that the UTxO being spent is not locked at the given date
+pub(super) fn valid_utxo_lock(
+    utxo: &UtxoPointer,
+    output: &Output<Address>,
+    cur_date: BlockDate,
+) -> LedgerCheck {
+    match output.lock {
+        Some(OutputLock::Until(until)) => {
+            if_cond_fail_with!(cur_date < until, Error::UtxoLocked { utxo: *utxo, until })
+        }
+        // relative locks are resolved when the output is added to the ledger
+        Some(OutputLock::Slots(_)) | None => Ok(()),
+    }
+}
+
 /// check that the transaction input/outputs/witnesses is valid for stake_owner_delegation
 ///
 /// * Only 1 input (subsequently 1 witness), no output
@@ -87,10 +102,49 @@ pub(super) fn valid_vote_cast(tx: &TransactionSlice<certificate::VoteCast>) -> L
     )
 }
 
+/// check that the transaction input/outputs/witnesses is valid for the vote delegation
+///
+/// * Only 1 input (subsequently 1 witness), no output
+pub(super) fn valid_vote_delegation(
+    tx: &TransactionSlice<certificate::VoteDelegation>,
+) -> LedgerCheck {
+    if_cond_fail_with!(
+        tx.inputs().nb_inputs() != 1
+            || tx.witnesses().nb_witnesses() != 1
+            || tx.outputs().nb_outputs() != 0,
+        Error::VoteDelegationInvalidTransaction
+    )
+}
+
+/// check that the transaction input/outputs/witnesses is valid for the reward withdrawal
+///
+/// * Only 1 input (subsequently 1 witness), any number of outputs
+pub(super) fn valid_reward_withdrawal(
+    tx: &TransactionSlice<certificate::RewardWithdrawal>,
+) -> LedgerCheck {
+    if_cond_fail_with!(
+        tx.inputs().nb_inputs() != 1 || tx.witnesses().nb_witnesses() != 1,
+        Error::RewardWithdrawalInvalidTransaction
+    )
+}
+
+/// check that the transaction input/outputs/witnesses is valid for the account deregistration
+///
+/// * Only 1 input (subsequently 1 witness), any number of outputs
+pub(super) fn valid_account_deregistration(
+    tx: &TransactionSlice<certificate::AccountDeregistration>,
+) -> LedgerCheck {
+    if_cond_fail_with!(
+        tx.inputs().nb_inputs() != 1 || tx.witnesses().nb_witnesses() != 1,
+        Error::AccountDeregistrationInvalidTransaction
+    )
+}
+
 /// check that the pool registration certificate is valid
 ///
 /// * management threshold T is valid: 0 < T <= #owners
 /// * there is no more than MAXIMUM_OWNERS
+/// * the metadata URL, the relays and their DNS names are within the size limits
 pub(super) fn valid_pool_registration_certificate(
     auth_cert: &certificate::PoolRegistration,
 ) -> LedgerCheck {
@@ -114,6 +168,23 @@ pub(super) fn valid_pool_registration_certificate(
         auth_cert.operators.len() > CHECK_POOL_REG_MAXIMUM_OPERATORS,
         Error::PoolRegistrationHasTooManyOperators
     )?;
+    if_cond_fail_with!(
+        matches!(&auth_cert.metadata, Some(metadata)
+            if metadata.url.len() > certificate::POOL_METADATA_URL_MAX_SIZE),
+        Error::PoolRegistrationMetadataUrlTooLong
+    )?;
+    if_cond_fail_with!(
+        auth_cert.relays.len() > certificate::POOL_MAX_RELAYS,
+        Error::PoolRegistrationHasTooManyRelays
+    )?;
+    if_cond_fail_with!(
+        auth_cert.relays.iter().any(|relay| matches!(
+            relay,
+            certificate::PoolRelay::DnsName { name, .. }
+                if name.len() > certificate::POOL_RELAY_DNS_NAME_MAX_SIZE
+        )),
+        Error::PoolRegistrationRelayDnsNameTooLong
+    )?;
     Ok(())
 }
 
@@ -238,7 +309,16 @@ mod tests {
             && (pool_registration.management_threshold() as usize)
                 <= pool_registration.owners.len()
             && pool_registration.owners.len() <= CHECK_POOL_REG_MAXIMUM_OWNERS
-            && pool_registration.operators.len() <= CHECK_POOL_REG_MAXIMUM_OPERATORS;
+            && pool_registration.operators.len() <= CHECK_POOL_REG_MAXIMUM_OPERATORS
+            && !matches!(&pool_registration.metadata, Some(metadata)
+                if metadata.url.len() > certificate::POOL_METADATA_URL_MAX_SIZE)
+            && pool_registration.relays.len() <= certificate::POOL_MAX_RELAYS
+            && pool_registration.relays.iter().all(|relay| match relay {
+                certificate::PoolRelay::DnsName { name, .. } => {
+                    name.len() <= certificate::POOL_RELAY_DNS_NAME_MAX_SIZE
+                }
+                _ => true,
+            });
         let result = valid_pool_registration_certificate(&pool_registration);
         to_quickchek_result(result, is_valid)
     }
@@ -17,6 +17,7 @@ impl Ledger {
             era: _,
             pots: _,
             leaders_log: _,
+            stake_snapshots: _,
             votes: _,
             governance: _,
             evm,
@@ -65,6 +66,7 @@ impl Ledger {
             era: era1,
             pots: pots1,
             leaders_log: leaders_log1,
+            stake_snapshots: stake_snapshots1,
             votes: votes1,
             governance: governance1,
             evm: evm1,
@@ -84,6 +86,7 @@ impl Ledger {
             era: era2,
             pots: pots2,
             leaders_log: leaders_log2,
+            stake_snapshots: stake_snapshots2,
             votes: votes2,
             governance: governance2,
             evm: evm2,
@@ -103,6 +106,10 @@ impl Ledger {
             format!("era-same: {}", era1 == era2),
             format!("pots-same: {}", pots1 == pots2),
             format!("leaders-log-same: {}", leaders_log1 == leaders_log2),
+            format!(
+                "stake-snapshots-same: {}",
+                stake_snapshots1 == stake_snapshots2
+            ),
             format!("vote-plans: {}", votes1 == votes2),
             format!("governance: {}", governance1 == governance2),
         ];
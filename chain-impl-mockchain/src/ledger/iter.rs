@@ -8,8 +8,8 @@ use crate::chaintypes::ChainLength;
 use crate::config::ConfigParam;
 use crate::date::BlockDate;
 use crate::key::Hash;
-use crate::stake::PoolsState;
-use crate::vote::{VotePlanLedger, VotePlanManager};
+use crate::stake::{PoolsState, StakeDistribution, StakeSnapshot, StakeSnapshots};
+use crate::vote::{Delegations, VotePlanLedger, VotePlanManager};
 use crate::{account, legacy, multisig, setting, update, utxo};
 use chain_addr::Address;
 use chain_time::TimeEra;
@@ -26,6 +26,12 @@ pub enum Entry<'a> {
             &'a crate::accounting::account::AccountState<()>,
         ),
     ),
+    DeregisteredAccount(
+        (
+            &'a account::Identifier,
+            &'a crate::accounting::account::SpendingCounterIncreasing,
+        ),
+    ),
     ConfigParam(ConfigParam),
     UpdateProposal(
         (
@@ -48,6 +54,8 @@ pub enum Entry<'a> {
     StakePool((&'a crate::certificate::PoolId, &'a crate::stake::PoolState)),
     LeaderParticipation((&'a crate::certificate::PoolId, &'a u32)),
     VotePlan(&'a VotePlan),
+    StakeSnapshots(&'a StakeSnapshots),
+    VoteDelegations(&'a Delegations),
 }
 
 #[derive(Clone)]
@@ -62,6 +70,12 @@ pub enum EntryOwned {
             crate::accounting::account::AccountState<()>,
         ),
     ),
+    DeregisteredAccount(
+        (
+            account::Identifier,
+            crate::accounting::account::SpendingCounterIncreasing,
+        ),
+    ),
     ConfigParam(ConfigParam),
     UpdateProposal(
         (
@@ -79,6 +93,8 @@ pub enum EntryOwned {
     StakePool((crate::certificate::PoolId, crate::stake::PoolState)),
     LeaderParticipation((crate::certificate::PoolId, u32)),
     VotePlan(VotePlan),
+    StakeSnapshots(StakeSnapshots),
+    VoteDelegations(Delegations),
     StopEntry,
 }
 
@@ -106,6 +122,9 @@ impl EntryOwned {
             EntryOwned::Account((identifier, account_state)) => {
                 Some(Entry::Account((identifier, account_state)))
             }
+            EntryOwned::DeregisteredAccount((identifier, spending)) => {
+                Some(Entry::DeregisteredAccount((identifier, spending)))
+            }
             EntryOwned::ConfigParam(config_param) => Some(Entry::ConfigParam(config_param.clone())),
             EntryOwned::UpdateProposal((proposal_id, proposal_state)) => {
                 Some(Entry::UpdateProposal((proposal_id, proposal_state)))
@@ -123,6 +142,8 @@ impl EntryOwned {
                 Some(Entry::LeaderParticipation((pool_id, participation)))
             }
             EntryOwned::VotePlan(vote_plan) => Some(Entry::VotePlan(vote_plan)),
+            EntryOwned::StakeSnapshots(snapshots) => Some(Entry::StakeSnapshots(snapshots)),
+            EntryOwned::VoteDelegations(delegations) => Some(Entry::VoteDelegations(delegations)),
             EntryOwned::StopEntry => None,
         }
     }
@@ -141,6 +162,13 @@ enum IterState<'a> {
     Utxo(utxo::Iter<'a, Address>),
     OldUtxo(utxo::Iter<'a, legacy::OldAddress>),
     Accounts(crate::accounting::account::Iter<'a, account::Identifier, ()>),
+    DeregisteredAccounts(
+        imhamt::HamtIter<
+            'a,
+            account::Identifier,
+            crate::accounting::account::SpendingCounterIncreasing,
+        >,
+    ),
     ConfigParams(Vec<ConfigParam>),
     UpdateProposals(
         std::collections::btree_map::Iter<
@@ -157,6 +185,8 @@ enum IterState<'a> {
     Pots(pots::Entries<'a>),
     LeaderParticipations(imhamt::HamtIter<'a, crate::certificate::PoolId, u32>),
     VotePlan(imhamt::HamtIter<'a, VotePlanId, VotePlanManager>),
+    StakeSnapshots,
+    VoteDelegations,
     Done,
 }
 
@@ -195,11 +225,19 @@ impl<'a> Iterator for LedgerIterator<'a> {
             },
             IterState::Accounts(iter) => match iter.next() {
                 None => {
-                    self.state = IterState::ConfigParams(self.ledger.settings.to_config_params().0);
+                    self.state =
+                        IterState::DeregisteredAccounts(self.ledger.accounts.iter_deregistered());
                     self.next()
                 }
                 Some(x) => Some(Entry::Account(x)),
             },
+            IterState::DeregisteredAccounts(iter) => match iter.next() {
+                None => {
+                    self.state = IterState::ConfigParams(self.ledger.settings.to_config_params().0);
+                    self.next()
+                }
+                Some(x) => Some(Entry::DeregisteredAccount(x)),
+            },
             IterState::ConfigParams(params) => {
                 if let Some(param) = params.pop() {
                     Some(Entry::ConfigParam(param))
@@ -253,11 +291,19 @@ impl<'a> Iterator for LedgerIterator<'a> {
             },
             IterState::VotePlan(iter) => match iter.next() {
                 None => {
-                    self.state = IterState::Done;
+                    self.state = IterState::StakeSnapshots;
                     self.next()
                 }
                 Some((_, plan_manager)) => Some(Entry::VotePlan(plan_manager.plan())),
             },
+            IterState::StakeSnapshots => {
+                self.state = IterState::VoteDelegations;
+                Some(Entry::StakeSnapshots(&self.ledger.stake_snapshots))
+            }
+            IterState::VoteDelegations => {
+                self.state = IterState::Done;
+                Some(Entry::VoteDelegations(&self.ledger.votes.delegations))
+            }
             IterState::Done => None,
         }
     }
@@ -278,14 +324,16 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
         let mut utxos: HashMap<Hash, Vec<_>> = HashMap::new();
         let mut oldutxos: HashMap<Hash, Vec<_>> = HashMap::new();
         let mut accounts = vec![];
+        let mut deregistered_accounts = vec![];
         let mut config_params = crate::fragment::ConfigParams::new();
         let mut updates = update::UpdateState::new();
         let mut multisig_accounts = vec![];
         let mut multisig_declarations = vec![];
-        let delegation = PoolsState::new();
+        let mut delegation = PoolsState::new();
         let mut globals = None;
         let mut pots = Pots::zero();
         let mut leaders_log = LeadersParticipationRecord::new();
+        let mut stake_snapshots = None;
         // TODO: votes don't have their entry
         let mut votes = VotePlanLedger::new();
         let governance = Governance::default();
@@ -312,6 +360,9 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
                 Entry::Account((account_id, account_state)) => {
                     accounts.push((account_id.clone(), account_state.clone()));
                 }
+                Entry::DeregisteredAccount((account_id, spending)) => {
+                    deregistered_accounts.push((account_id.clone(), spending.clone()));
+                }
                 Entry::ConfigParam(param) => {
                     config_params.push(param.clone());
                 }
@@ -327,7 +378,7 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
                     multisig_declarations.push((id.clone(), decl.clone()));
                 }
                 Entry::StakePool((pool_id, pool_state)) => {
-                    delegation
+                    delegation.stake_pools = delegation
                         .stake_pools
                         .insert(pool_id.clone(), pool_state.clone())
                         .unwrap();
@@ -346,15 +397,21 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
                         )
                         .unwrap();
                 }
+                Entry::StakeSnapshots(snapshots) => {
+                    stake_snapshots = Some(snapshots.clone());
+                }
+                Entry::VoteDelegations(delegations) => {
+                    votes.delegations = delegations.clone();
+                }
             }
         }
 
         let globals = globals.ok_or(Error::IncompleteLedger)?;
 
-        Ok(Ledger {
+        let mut ledger = Ledger {
             utxos: utxos.into_iter().collect(),
             oldutxos: oldutxos.into_iter().collect(),
-            accounts: accounts.into_iter().collect(),
+            accounts: account::Ledger::restore(accounts, deregistered_accounts),
             settings: setting::Settings::new().apply(&config_params)?,
             updates,
             multisig: multisig::Ledger::restore(multisig_accounts, multisig_declarations),
@@ -365,10 +422,23 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
             era: globals.era,
             pots,
             leaders_log,
+            stake_snapshots: StakeSnapshots::new(StakeSnapshot::new(
+                globals.date.epoch,
+                StakeDistribution::empty(),
+            )),
             votes,
             governance,
             evm,
-        })
+        };
+        // without any snapshot, start over from the current distribution
+        ledger.stake_snapshots = match stake_snapshots {
+            Some(snapshots) => snapshots,
+            None => StakeSnapshots::new(StakeSnapshot::new(
+                globals.date.epoch,
+                ledger.get_stake_distribution(),
+            )),
+        };
+        Ok(ledger)
     }
 }
 
@@ -376,8 +446,10 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
 mod tests {
     use super::*;
     use crate::{
+        certificate::VoteRepresentative,
         ledger::{Entry, Ledger},
-        testing::{ConfigBuilder, LedgerBuilder},
+        testing::{ConfigBuilder, LedgerBuilder, TestGen},
+        transaction::UnspecifiedAccountIdentifier,
         value::Value,
     };
 
@@ -422,13 +494,13 @@ mod tests {
                 }
                 Entry::Account((id, state)) => {
                     println!(
-                        "Account {} {} {:?} {}",
-                        id,
-                        u32::from(state.counter),
-                        state.delegation,
-                        state.value,
+                        "Account {} {:?} {:?} {}",
+                        id, state.spending, state.delegation, state.value,
                     );
                 }
+                Entry::DeregisteredAccount((id, spending)) => {
+                    println!("DeregisteredAccount {} {:?}", id, spending);
+                }
                 Entry::ConfigParam(param) => {
                     println!(
                         "ConfigParam {:?} {:?}",
@@ -444,11 +516,8 @@ mod tests {
                 }
                 Entry::MultisigAccount((id, state)) => {
                     println!(
-                        "MultisigAccount {} {} {:?} {}",
-                        id,
-                        u32::from(state.counter),
-                        state.delegation,
-                        state.value,
+                        "MultisigAccount {} {:?} {:?} {}",
+                        id, state.spending, state.delegation, state.value,
                     );
                 }
                 Entry::MultisigDeclaration((id, decl)) => {
@@ -462,8 +531,8 @@ mod tests {
                 Entry::StakePool((id, state)) => {
                     let info = state.registration.as_ref();
                     println!(
-                        "StakePool {} {} {:?} {:?}",
-                        id, info.serial, info.owners, info.keys,
+                        "StakePool {} {} {:?} {:?} {:?} {:?}",
+                        id, info.serial, info.owners, info.keys, info.metadata, info.relays,
                     );
                 }
                 Entry::Pot(entry) => {
@@ -475,6 +544,17 @@ mod tests {
                 Entry::VotePlan(plan) => {
                     println!("VotePlan {}", plan.to_id());
                 }
+                Entry::StakeSnapshots(snapshots) => {
+                    println!(
+                        "StakeSnapshots {} {} {}",
+                        snapshots.mark().epoch(),
+                        snapshots.set().epoch(),
+                        snapshots.go().epoch(),
+                    );
+                }
+                Entry::VoteDelegations(delegations) => {
+                    println!("VoteDelegations {}", delegations.iter().count());
+                }
             }
         }
     }
@@ -495,4 +575,33 @@ mod tests {
 
         assert!(ledger == ledger2)
     }
+
+    #[test]
+    pub fn iterate_with_vote_delegations() {
+        let testledger = LedgerBuilder::from_config(ConfigBuilder::new())
+            .faucet_value(Value(42000))
+            .build()
+            .expect("cannot build test ledger");
+
+        let mut ledger = testledger.ledger;
+        let alice = UnspecifiedAccountIdentifier::from_single_account(TestGen::identifier());
+        let bob = UnspecifiedAccountIdentifier::from_single_account(TestGen::identifier());
+        ledger.votes.delegations = ledger
+            .votes
+            .delegations
+            .delegate(
+                alice.clone(),
+                Some(VoteRepresentative::Account(bob.clone())),
+            )
+            .delegate(bob, Some(VoteRepresentative::Myself));
+
+        let ledger2: Result<Ledger, _> = ledger.iter().collect();
+        let ledger2 = ledger2.unwrap();
+
+        assert_eq!(
+            ledger2.votes.delegations.representative(&alice),
+            ledger.votes.delegations.representative(&alice)
+        );
+        assert!(ledger == ledger2)
+    }
 }
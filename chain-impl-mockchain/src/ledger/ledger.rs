@@ -6,10 +6,13 @@ use super::evm;
 use super::governance::{Governance, ParametersGovernanceAction, TreasuryGovernanceAction};
 use super::leaderlog::LeadersParticipationRecord;
 use super::pots::Pots;
+use super::retirement_info::{PoolRetirementsInfo, RetiredPoolInfo};
 use super::reward_info::{EpochRewardsInfo, RewardsInfoParameters};
+use super::signatures::VerifiedSignatures;
 
+use crate::accounting::account::DelegationType;
 use crate::chaineval::HeaderContentEvalContext;
-use crate::chaintypes::{ChainLength, ConsensusType, HeaderId};
+use crate::chaintypes::{ChainLength, ConsensusType, HeaderId, VrfAlgorithm};
 use crate::config::{self, ConfigParam};
 use crate::date::{BlockDate, Epoch};
 use crate::fee::{FeeAlgorithm, LinearFee};
@@ -18,19 +21,27 @@ use crate::rewards;
 use crate::setting::ActiveSlotsCoeffError;
 use crate::stake::{
     PercentStake, PoolError, PoolStakeInformation, PoolsState, StakeControl, StakeDistribution,
+    StakeSnapshot, StakeSnapshots,
 };
 use crate::transaction::*;
 use crate::treasury::Treasury;
 use crate::value::*;
-use crate::vote::{CommitteeId, VotePlanLedger, VotePlanLedgerError, VotePlanStatus};
-use crate::{account, certificate, legacy, multisig, setting, stake, update, utxo};
+use crate::vote::{
+    CommitteeId, VerifiedBallots, VotePlanLedger, VotePlanLedgerError, VotePlanStatus,
+};
+use crate::{account, certificate, legacy, multisig, script, setting, stake, update, utxo};
 use crate::{
-    certificate::{OwnerStakeDelegation, PoolId, VoteAction, VoteCast, VotePlan},
+    certificate::{
+        AccountRegistration, OwnerStakeDelegation, PoolId, RewardWithdrawal, VoteAction, VoteCast,
+        VoteDelegation, VotePlan,
+    },
     chaineval::ConsensusEvalContext,
 };
 use chain_addr::{Address, Discrimination, Kind};
 use chain_crypto::Verification;
-use chain_time::{Epoch as TimeEpoch, SlotDuration, TimeEra, TimeFrame, Timeline};
+use chain_time::{
+    Epoch as TimeEpoch, Slot, SlotDuration, TimeEra, TimeFrame, TimeOffsetSeconds, Timeline,
+};
 
 use std::collections::HashSet;
 use std::convert::TryInto;
@@ -92,16 +103,27 @@ pub struct Ledger {
     pub(crate) era: TimeEra,
     pub(crate) pots: Pots,
     pub(crate) leaders_log: LeadersParticipationRecord,
+    pub(crate) stake_snapshots: StakeSnapshots,
     pub(crate) votes: VotePlanLedger,
     pub(crate) governance: Governance,
     pub(crate) evm: evm::Ledger,
 }
 
+/// The verifications done at once for all the fragments of a block with
+/// `Ledger::verify_fragments`, that are not done again when applying the
+/// fragments one by one.
+#[derive(Default)]
+pub struct VerifiedFragments {
+    pub ballots: VerifiedBallots,
+    pub signatures: VerifiedSignatures,
+}
+
 #[derive(Debug, Clone)]
 pub struct ApplyBlockLedger {
     ledger: Ledger,
     ledger_params: LedgerParameters,
     block_date: BlockDate,
+    pool_retirements: Option<PoolRetirementsInfo>,
 }
 
 // Dummy implementation of Debug for Ledger
@@ -167,6 +189,12 @@ pub enum Block0Error {
     HasVoteCast,
     #[error("Vote tallying are not valid in the block0")]
     HasVoteTally,
+    #[error("Vote delegations are not valid in the block0")]
+    HasVoteDelegation,
+    #[error("Reward withdrawals are not valid in the block0")]
+    HasRewardWithdrawal,
+    #[error("Account registrations are not valid in the block0")]
+    HasAccountRegistration,
 }
 
 pub type OutputOldAddress = Output<legacy::OldAddress>;
@@ -253,6 +281,18 @@ pub enum Error {
     OwnerStakeDelegationInvalidTransaction,
     #[error("Transaction for VoteCast is invalid. expecting 1 input, 1 witness and 0 output")]
     VoteCastInvalidTransaction,
+    #[error(
+        "Transaction for VoteDelegation is invalid. expecting 1 input, 1 witness and 0 output"
+    )]
+    VoteDelegationInvalidTransaction,
+    #[error(
+        "Transaction for RewardWithdrawal is invalid. expecting 1 account input and 1 witness"
+    )]
+    RewardWithdrawalInvalidTransaction,
+    #[error("Transaction for AccountDeregistration is invalid. expecting 1 input from the deregistered account and 1 witness")]
+    AccountDeregistrationInvalidTransaction,
+    #[error("Account {account} is not registered and cannot receive any value")]
+    AccountNotRegistered { account: account::Identifier },
     #[error("Wrong chain length, expected {expected} but received {actual}")]
     WrongChainLength {
         actual: ChainLength,
@@ -280,10 +320,20 @@ pub enum Error {
     PoolRegistrationHasTooManyOwners,
     #[error("Pool registration with too many operators")]
     PoolRegistrationHasTooManyOperators,
+    #[error("Pool registration metadata URL is too long")]
+    PoolRegistrationMetadataUrlTooLong,
+    #[error("Pool registration with too many relays")]
+    PoolRegistrationHasTooManyRelays,
+    #[error("Pool registration relay DNS name is too long")]
+    PoolRegistrationRelayDnsNameTooLong,
     #[error("Pool registration management threshold is zero")]
     PoolRegistrationManagementThresholdZero,
     #[error("Pool registration management threshold above owners")]
     PoolRegistrationManagementThresholdAbove,
+    #[error("Pool registration VRF public key is not a valid {vrf_algorithm} key")]
+    PoolRegistrationVrfKeyInvalid { vrf_algorithm: VrfAlgorithm },
+    #[error("Pool registration reward account is an undeclared multisig account {account}")]
+    PoolRegistrationMultisigRewardAccountUnknown { account: multisig::Identifier },
     #[error("Pool Update not allowed yet")]
     PoolUpdateNotAllowedYet,
     #[error("Stake Delegation payload signature failed")]
@@ -310,8 +360,25 @@ pub enum Error {
     UpdateNotAllowedYet,
     #[error("Voting error")]
     VotePlan(#[from] VotePlanLedgerError),
-    #[error("Scripts addresses are not yet supported by the system")]
-    ScriptsAddressNotAllowedYet,
+    #[error("UTxO {utxo} cannot be spent before {until}")]
+    UtxoLocked { utxo: UtxoPointer, until: BlockDate },
+    #[error("Outputs to accounts cannot be locked")]
+    AccountOutputLocked { output: Output<Address> },
+    #[error("Expected a script witness")]
+    ExpectingScriptWitness,
+    #[error("Invalid script")]
+    Script(#[from] script::ScriptError),
+    #[error("Revealed script does not match the script address")]
+    ScriptIdentifierMismatch {
+        utxo: UtxoPointer,
+        output: OutputAddress,
+    },
+    #[error("Script not satisfied by the transaction")]
+    ScriptNotSatisfied {
+        utxo: UtxoPointer,
+        output: OutputAddress,
+        witness: Witness,
+    },
 }
 
 impl LedgerParameters {
@@ -341,6 +408,7 @@ impl Ledger {
             era,
             pots,
             leaders_log: LeadersParticipationRecord::new(),
+            stake_snapshots: StakeSnapshots::new(StakeSnapshot::new(0, StakeDistribution::empty())),
             votes: VotePlanLedger::new(),
             governance: Governance::default(),
             evm: evm::Ledger::new(),
@@ -446,7 +514,8 @@ impl Ledger {
                     let tx = tx.as_slice();
                     check::valid_block0_transaction_no_inputs(&tx)?;
 
-                    ledger = ledger.apply_tx_outputs(fragment_id, tx.outputs())?;
+                    ledger =
+                        ledger.apply_tx_outputs(fragment_id, tx.outputs(), BlockDate::first())?;
                 }
                 Fragment::UpdateProposal(_) => {
                     return Err(Error::Block0(Block0Error::HasUpdateProposal));
@@ -496,10 +565,23 @@ impl Ledger {
                 Fragment::EncryptedVoteTally(_) => {
                     return Err(Error::Block0(Block0Error::HasVoteTally));
                 }
+                Fragment::VoteDelegation(_) => {
+                    return Err(Error::Block0(Block0Error::HasVoteDelegation));
+                }
+                Fragment::RewardWithdrawal(_) => {
+                    return Err(Error::Block0(Block0Error::HasRewardWithdrawal));
+                }
+                Fragment::AccountRegistration(_) | Fragment::AccountDeregistration(_) => {
+                    return Err(Error::Block0(Block0Error::HasAccountRegistration));
+                }
             }
         }
 
         ledger.validate_utxo_total_value()?;
+        ledger.stake_snapshots = StakeSnapshots::new(StakeSnapshot::new(
+            ledger.date.epoch,
+            ledger.get_stake_distribution(),
+        ));
         Ok(ledger)
     }
 
@@ -507,6 +589,10 @@ impl Ledger {
         self.leaders_log.total() != 0
     }
 
+    pub fn leaders_log(&self) -> &LeadersParticipationRecord {
+        &self.leaders_log
+    }
+
     pub fn apply_protocol_changes(&self) -> Result<Self, Error> {
         let mut new = self.clone();
 
@@ -527,6 +613,10 @@ impl Ledger {
     ///
     /// * Reset the leaders log
     /// * Distribute the contribution (rewards + fees) to pools and their delegatees
+    ///
+    /// `distribution` is not checked: callers must pass the one the blocks
+    /// of the ending epoch were elected with, `active_stake_distribution`
+    /// of that epoch.
     pub fn distribute_rewards<'a>(
         &'a self,
         distribution: &StakeDistribution,
@@ -607,11 +697,33 @@ impl Ledger {
 
             let total_blocks = leaders_log.total();
             let reward_unit = total_reward.split_in(total_blocks);
+            let total_stake = distribution.total_stake();
+            // when adjusted to their performance, the pools are rewarded for
+            // their share of the stake rather than of the produced blocks
+            let performance_adjusted =
+                ledger_params.reward_params.pool_performance_adjusted && total_stake.0 > 0;
+            let mut undistributed = total_reward;
 
             for (pool_id, pool_blocks) in leaders_log.iter() {
+                let pool_total_reward_uncapped = if performance_adjusted {
+                    distribution
+                        .to_pools
+                        .get(pool_id)
+                        .map(|pool_distribution| {
+                            rewards::stake_share(
+                                total_reward,
+                                pool_distribution.stake.total,
+                                total_stake,
+                            )
+                        })
+                        .unwrap_or_else(Value::zero)
+                } else {
+                    reward_unit.parts.scale(*pool_blocks).unwrap()
+                };
+                undistributed = (undistributed - pool_total_reward_uncapped).unwrap();
+
                 // possibly cap the reward for a given pool.
                 // if this is capped, then the overflow amount is send to treasury
-                let pool_total_reward_uncapped = reward_unit.parts.scale(*pool_blocks).unwrap();
                 let pool_total_reward = match pool_capper {
                     None => pool_total_reward_uncapped,
                     Some(pool_cap) => {
@@ -622,8 +734,46 @@ impl Ledger {
                     }
                 };
 
-                match distribution.to_pools.get(pool_id) {
+                // the pool may have retired since the stake distribution was taken
+                match distribution
+                    .to_pools
+                    .get(pool_id)
+                    .filter(|_| new_ledger.delegation.stake_pool_exists(pool_id))
+                {
                     Some(pool_distribution) => {
+                        // reduce the reward of a pool producing less blocks than
+                        // expected from its stake, the difference going to treasury
+                        let pool_total_reward = if performance_adjusted {
+                            let expected = rewards::expected_blocks(
+                                new_ledger.era.slots_per_epoch(),
+                                new_ledger.settings.active_slots_coeff,
+                                &PercentStake::new(pool_distribution.stake.total, total_stake),
+                            );
+                            let performance_distr =
+                                rewards::performance_cut(pool_total_reward, *pool_blocks, expected);
+                            new_ledger.pots.treasury_add(performance_distr.taxed)?;
+                            performance_distr.after_tax
+                        } else {
+                            pool_total_reward
+                        };
+                        // possibly reduce the reward of a saturated pool or of a pool
+                        // with little pledge, the difference going to treasury
+                        let pool_total_reward = match ledger_params.reward_params.pool_saturation {
+                            Some(saturation) if total_stake.0 > 0 => {
+                                let saturation_distr = rewards::saturation_cut(
+                                    pool_total_reward,
+                                    pool_distribution.stake.total,
+                                    pool_distribution.pledge(),
+                                    total_stake,
+                                    &saturation,
+                                );
+                                new_ledger.pots.treasury_add(saturation_distr.taxed)?;
+                                rewards_info
+                                    .set_stake_pool_saturation(pool_id, saturation_distr.taxed);
+                                saturation_distr.after_tax
+                            }
+                            _ => pool_total_reward,
+                        };
                         new_ledger.distribute_poolid_rewards(
                             &mut rewards_info,
                             epoch,
@@ -639,9 +789,9 @@ impl Ledger {
                 }
             }
 
-            if reward_unit.remaining > Value::zero() {
+            if undistributed > Value::zero() {
                 // if anything remaining, put it in treasury
-                new_ledger.pots.treasury_add(reward_unit.remaining)?;
+                new_ledger.pots.treasury_add(undistributed)?;
             }
         }
 
@@ -652,6 +802,52 @@ impl Ledger {
         Ok((new_ledger, rewards_info))
     }
 
+    /// Project the rewards that would be distributed for the current epoch,
+    /// without modifying the ledger.
+    ///
+    /// The projection uses the given stake `distribution`, normally the
+    /// `active_stake_distribution` of the current epoch, and either the
+    /// blocks recorded so far, or the hypothetical `leaders_log` if any.
+    /// All stake pools and accounts are reported in the returned information.
+    pub fn forecast_rewards(
+        &self,
+        distribution: &StakeDistribution,
+        leaders_log: Option<&LeadersParticipationRecord>,
+    ) -> Result<EpochRewardsInfo, Error> {
+        let ledger_params = self.get_ledger_parameters();
+        let forecast = match leaders_log {
+            None => self.distribute_rewards(
+                distribution,
+                &ledger_params,
+                RewardsInfoParameters::report_all(),
+            ),
+            Some(leaders_log) => {
+                let mut ledger = self.clone();
+                ledger.leaders_log = leaders_log.clone();
+                ledger.distribute_rewards(
+                    distribution,
+                    &ledger_params,
+                    RewardsInfoParameters::report_all(),
+                )
+            }
+        };
+        forecast.map(|(_, rewards_info)| rewards_info)
+    }
+
+    /// The stake distribution as if `account` had its delegation set to `delegation`
+    pub fn forecast_stake_distribution(
+        &self,
+        account: &account::Identifier,
+        delegation: &account::DelegationType,
+    ) -> Result<StakeDistribution, Error> {
+        let accounts = self.accounts.set_delegation(account, delegation)?;
+        Ok(stake::get_distribution(
+            &accounts,
+            &self.delegation,
+            &self.utxos,
+        ))
+    }
+
     fn distribute_poolid_rewards(
         &mut self,
         reward_info: &mut EpochRewardsInfo,
@@ -678,43 +874,30 @@ impl Ledger {
         match &reg.reward_account {
             Some(reward_account) => match reward_account {
                 AccountIdentifier::Single(single_account) => {
-                    self.accounts = self.accounts.add_rewards_to_account(
-                        single_account,
-                        epoch,
-                        distr.taxed,
-                        (),
-                    )?;
-                    reward_info.add_to_account(single_account, distr.taxed);
+                    self.reward_account(reward_info, epoch, single_account, distr.taxed)?;
+                }
+                AccountIdentifier::Multi(multi_account) => {
+                    self.multisig =
+                        self.multisig
+                            .add_rewards_to_account(multi_account, epoch, distr.taxed)?;
+                    reward_info.add_to_multisig_account(multi_account, distr.taxed);
                 }
-                AccountIdentifier::Multi(_multi_account) => unimplemented!(),
             },
             None => {
                 if reg.owners.len() > 1 {
                     let splitted = distr.taxed.split_in(reg.owners.len() as u32);
                     for owner in &reg.owners {
                         let id = owner.clone().into();
-                        self.accounts =
-                            self.accounts
-                                .add_rewards_to_account(&id, epoch, splitted.parts, ())?;
-                        reward_info.add_to_account(&id, splitted.parts);
+                        self.reward_account(reward_info, epoch, &id, splitted.parts)?;
                     }
                     // pool owners 0 get potentially an extra sweetener of value 1 to #owners - 1
                     if splitted.remaining > Value::zero() {
                         let id = reg.owners[0].clone().into();
-                        self.accounts = self.accounts.add_rewards_to_account(
-                            &id,
-                            epoch,
-                            splitted.remaining,
-                            (),
-                        )?;
-                        reward_info.add_to_account(&id, splitted.remaining);
+                        self.reward_account(reward_info, epoch, &id, splitted.remaining)?;
                     }
                 } else {
                     let id = reg.owners[0].clone().into();
-                    self.accounts =
-                        self.accounts
-                            .add_rewards_to_account(&id, epoch, distr.taxed, ())?;
-                    reward_info.add_to_account(&id, distr.taxed);
+                    self.reward_account(reward_info, epoch, &id, distr.taxed)?;
                 }
             }
         }
@@ -726,10 +909,7 @@ impl Ledger {
                 let ps = PercentStake::new(*stake, distribution.stake.total);
                 let r = ps.scale_value(distr.after_tax);
                 leftover_reward = (leftover_reward - r).unwrap();
-                self.accounts = self
-                    .accounts
-                    .add_rewards_to_account(account, epoch, r, ())?;
-                reward_info.add_to_account(account, r);
+                self.reward_account(reward_info, epoch, account, r)?;
             }
         }
 
@@ -770,6 +950,12 @@ impl Ledger {
             panic!("internal error: apply_block called after epoch transition, but distribute_rewards has not been called")
         }
 
+        let pool_retirements = if block_date.epoch > new_ledger.date.epoch {
+            Some(new_ledger.apply_epoch_transition(block_date.epoch)?)
+        } else {
+            None
+        };
+
         // Process Update proposals if needed
         let (updates, settings) = new_ledger.updates.process_proposals(
             new_ledger.settings,
@@ -783,9 +969,102 @@ impl Ledger {
             ledger: new_ledger,
             ledger_params,
             block_date,
+            pool_retirements,
         })
     }
 
+    /// Process the transition to the start of `epoch`: the pools whose
+    /// retirement is due are retired, then the stake snapshot of the epoch
+    /// that just ended is taken.
+    pub(crate) fn apply_epoch_transition(
+        &mut self,
+        epoch: Epoch,
+    ) -> Result<PoolRetirementsInfo, Error> {
+        let pool_retirements = self.apply_pool_retirements(epoch)?;
+        self.stake_snapshots = self.stake_snapshots.rotate(StakeSnapshot::new(
+            self.date.epoch,
+            self.get_stake_distribution(),
+        ));
+        Ok(pool_retirements)
+    }
+
+    /// Retire the pools whose retirement is due at the start of `epoch`,
+    /// refunding their deposit and reporting their delegators. Following
+    /// the settings, the delegations to the retired pools are reset.
+    fn apply_pool_retirements(&mut self, epoch: Epoch) -> Result<PoolRetirementsInfo, Error> {
+        let reset = self.settings.reset_delegation_on_pool_retirement;
+        let mut info = PoolRetirementsInfo::new(epoch, reset);
+
+        for pool_id in self.delegation.stake_pools_retiring_at(epoch) {
+            let state = self.delegation.stake_pool_get_state(&pool_id)?.clone();
+            self.delegation = self.delegation.deregister_stake_pool(&pool_id)?;
+            self.refund_pool_deposit(&state.registration, state.deposit)?;
+            info.stake_pools.insert(
+                pool_id,
+                RetiredPoolInfo {
+                    deposit: state.deposit,
+                    ..RetiredPoolInfo::default()
+                },
+            );
+        }
+        if info.is_empty() {
+            return Ok(info);
+        }
+
+        let mut accounts = Vec::new();
+        for (account, state) in self.accounts.iter() {
+            for (pool_id, retired) in info.stake_pools.iter_mut() {
+                if state.delegation().delegates_to(pool_id) {
+                    retired.accounts.push(account.clone());
+                    accounts.push(account.clone());
+                }
+            }
+        }
+        let mut multisig_accounts = Vec::new();
+        for (account, state) in self.multisig.iter_accounts() {
+            for (pool_id, retired) in info.stake_pools.iter_mut() {
+                if state.delegation().delegates_to(pool_id) {
+                    retired.multisig_accounts.push(account.clone());
+                    multisig_accounts.push(account.clone());
+                }
+            }
+        }
+
+        if reset {
+            for account in accounts {
+                self.accounts = self
+                    .accounts
+                    .set_delegation(&account, &DelegationType::NonDelegated)?;
+            }
+            for account in multisig_accounts {
+                self.multisig = self
+                    .multisig
+                    .set_delegation(&account, &DelegationType::NonDelegated)?;
+            }
+        }
+
+        Ok(info)
+    }
+
+    /// The epoch at the start of which a pool retiring at `retirement_time`
+    /// is retired: the first epoch starting at or after the retirement time,
+    /// and at the earliest the epoch following `cur_date`.
+    fn pool_retirement_epoch(
+        &self,
+        retirement_time: TimeOffsetSeconds,
+        cur_date: BlockDate,
+    ) -> Epoch {
+        let seconds: u64 = retirement_time.into();
+        let slot_duration = u64::from(self.settings.slot_duration).max(1);
+        let slot = seconds.saturating_add(slot_duration - 1) / slot_duration;
+        let epoch = match self.era.from_slot_to_era(Slot::from(slot)) {
+            Some(position) if position.slot.0 == 0 => position.epoch.0,
+            Some(position) => position.epoch.0.saturating_add(1),
+            None => 0,
+        };
+        epoch.max(cur_date.epoch + 1)
+    }
+
     /// Try to apply messages to a State, and return the new State if successful
     pub fn apply_block(
         &self,
@@ -809,16 +1088,49 @@ impl Ledger {
             });
         }
 
+        let verified = self.verify_fragments(contents.iter());
+
         let new_block_ledger =
             self.begin_block(ledger_params, metadata.chain_length, metadata.block_date)?;
-        let new_block_ledger = contents
-            .iter()
-            .try_fold(new_block_ledger, |new_block_ledger, fragment| {
-                new_block_ledger.apply_fragment(fragment)
-            })?;
+        let new_block_ledger =
+            contents
+                .iter()
+                .try_fold(new_block_ledger, |new_block_ledger, fragment| {
+                    new_block_ledger.apply_fragment_verified(fragment, &verified)
+                })?;
         Ok(new_block_ledger.finish(&metadata.consensus_eval_context))
     }
 
+    /// verify at once the private ballots and the witness signatures of the
+    /// given fragments, to apply them with `apply_fragment_verified`
+    pub fn verify_fragments<'a, I>(&self, fragments: I) -> VerifiedFragments
+    where
+        I: IntoIterator<Item = &'a Fragment>,
+    {
+        let fragments: Vec<_> = fragments.into_iter().collect();
+        VerifiedFragments {
+            ballots: self.verify_private_ballots(fragments.iter().copied()),
+            signatures: self.verify_signatures(fragments),
+        }
+    }
+
+    /// verify at once the proofs of the private ballots cast in the given
+    /// fragments, rather than one proof at a time when applying the
+    /// vote casts with `apply_fragment_verified`
+    pub fn verify_private_ballots<'a, I>(&self, fragments: I) -> VerifiedBallots
+    where
+        I: IntoIterator<Item = &'a Fragment>,
+    {
+        let vote_casts = fragments
+            .into_iter()
+            .filter_map(|fragment| match fragment {
+                Fragment::VoteCast(tx) => Some(tx.as_slice().payload().into_payload()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        self.votes.verify_private_ballots(&vote_casts)
+    }
+
     /// Try to apply a message to the State, and return the new State if successful
     ///
     /// this does not _advance_ the state to the new _state_ but apply a simple fragment
@@ -829,6 +1141,23 @@ impl Ledger {
         ledger_params: &LedgerParameters,
         content: &Fragment,
         block_date: BlockDate,
+    ) -> Result<Self, Error> {
+        self.apply_fragment_verified(
+            ledger_params,
+            content,
+            block_date,
+            &VerifiedFragments::default(),
+        )
+    }
+
+    /// same as `apply_fragment`, but the proofs of the private vote casts
+    /// and the signatures already `verified` are not verified again.
+    pub fn apply_fragment_verified(
+        &self,
+        ledger_params: &LedgerParameters,
+        content: &Fragment,
+        block_date: BlockDate,
+        verified: &VerifiedFragments,
     ) -> Result<Self, Error> {
         let mut new_ledger = self.clone();
 
@@ -838,16 +1167,26 @@ impl Ledger {
             Fragment::OldUtxoDeclaration(_) => return Err(Error::Block0OnlyFragmentReceived),
             Fragment::Transaction(tx) => {
                 let tx = tx.as_slice();
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
                 new_ledger = new_ledger_;
             }
             Fragment::OwnerStakeDelegation(tx) => {
                 let tx = tx.as_slice();
                 // this is a lightweight check, do this early to avoid doing any unnecessary computation
                 check::valid_stake_owner_delegation_transaction(&tx)?;
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
 
                 // we've just verified that this is a valid transaction (i.e. contains 1 input and 1 witness)
                 let (account_id, witness) = match tx.inputs().iter().next().unwrap().to_enum() {
@@ -869,7 +1208,7 @@ impl Ledger {
                 let tx = tx.as_slice();
                 let payload = tx.payload().into_payload();
                 let payload_auth = tx.payload_auth().into_payload_auth();
-                let verified = match payload_auth {
+                let verification = match payload_auth {
                     AccountBindingSignature::Single(signature) => {
                         let account_pk = payload
                             .account_id
@@ -884,40 +1223,64 @@ impl Ledger {
                     }
                 };
 
-                if verified == Verification::Failed {
+                if verification == Verification::Failed {
                     return Err(Error::StakeDelegationSignatureFailed);
                 }
 
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
                 new_ledger = new_ledger_.apply_stake_delegation(&payload)?;
             }
             Fragment::PoolRegistration(tx) => {
                 let tx = tx.as_slice();
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let deposit = new_ledger.settings.pool_registration_deposit;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_with_deposit(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                    deposit,
+                )?;
                 new_ledger = new_ledger_.apply_pool_registration_signcheck(
                     &tx.payload().into_payload(),
                     &tx.transaction_binding_auth_data(),
                     tx.payload_auth().into_payload_auth(),
+                    deposit,
                 )?;
             }
             Fragment::PoolRetirement(tx) => {
                 let tx = tx.as_slice();
 
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
                 new_ledger = new_ledger_.apply_pool_retirement(
                     &tx.payload().into_payload(),
                     &tx.transaction_binding_auth_data(),
                     tx.payload_auth().into_payload_auth(),
+                    block_date,
                 )?;
             }
             Fragment::PoolUpdate(tx) => {
                 let tx = tx.as_slice();
 
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
                 new_ledger = new_ledger_.apply_pool_update(
                     &tx.payload().into_payload(),
                     &tx.transaction_binding_auth_data(),
@@ -939,8 +1302,13 @@ impl Ledger {
             }
             Fragment::VotePlan(tx) => {
                 let tx = tx.as_slice();
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
                 new_ledger = new_ledger_.apply_vote_plan(
                     &tx,
                     block_date,
@@ -953,8 +1321,13 @@ impl Ledger {
                 let tx = tx.as_slice();
                 // this is a lightweight check, do this early to avoid doing any unnecessary computation
                 check::valid_vote_cast(&tx)?;
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
 
                 // we've just verified that this is a valid transaction (i.e. contains 1 input and 1 witness)
                 let account_id = match tx.inputs().iter().next().unwrap().to_enum() {
@@ -964,14 +1337,22 @@ impl Ledger {
                     InputEnum::AccountInput(account_id, _) => account_id,
                 };
 
-                new_ledger =
-                    new_ledger_.apply_vote_cast(account_id, tx.payload().into_payload())?;
+                new_ledger = new_ledger_.apply_vote_cast(
+                    account_id,
+                    tx.payload().into_payload(),
+                    &verified.ballots,
+                )?;
             }
             Fragment::VoteTally(tx) => {
                 let tx = tx.as_slice();
 
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
 
                 new_ledger = new_ledger_.apply_vote_tally(
                     &tx.payload().into_payload(),
@@ -982,8 +1363,13 @@ impl Ledger {
             Fragment::EncryptedVoteTally(tx) => {
                 let tx = tx.as_slice();
 
-                let (new_ledger_, _fee) =
-                    new_ledger.apply_transaction(&fragment_id, &tx, block_date, ledger_params)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
 
                 new_ledger = new_ledger_.apply_encrypted_vote_tally(
                     &tx.payload().into_payload(),
@@ -991,17 +1377,167 @@ impl Ledger {
                     tx.payload_auth().into_payload_auth(),
                 )?;
             }
+            Fragment::VoteDelegation(tx) => {
+                let tx = tx.as_slice();
+                check::valid_vote_delegation(&tx)?;
+                let (new_ledger_, _fee) = new_ledger.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
+
+                // we've just verified that this is a valid transaction (i.e. contains 1 input and 1 witness)
+                let account_id = match tx.inputs().iter().next().unwrap().to_enum() {
+                    InputEnum::UtxoInput(_) => {
+                        return Err(Error::VoteDelegationInvalidTransaction);
+                    }
+                    InputEnum::AccountInput(account_id, _) => account_id,
+                };
+
+                new_ledger =
+                    new_ledger_.apply_vote_delegation(account_id, &tx.payload().into_payload())?;
+            }
+            Fragment::RewardWithdrawal(tx) => {
+                let tx = tx.as_slice();
+                check::valid_reward_withdrawal(&tx)?;
+
+                // we've just verified that this is a valid transaction (i.e. contains 1 input and 1 witness)
+                let (input, witness) = tx.inputs_and_witnesses().iter().next().unwrap();
+                let account_id = match input.to_enum() {
+                    InputEnum::UtxoInput(_) => {
+                        return Err(Error::RewardWithdrawalInvalidTransaction);
+                    }
+                    InputEnum::AccountInput(account_id, _) => account_id,
+                };
+
+                // the rewards are moved before spending the input so the fees
+                // can be paid from them. The input's witness is checked while
+                // applying the transaction.
+                let new_ledger_ = new_ledger.apply_reward_withdrawal(
+                    &account_id,
+                    &witness,
+                    &tx.payload().into_payload(),
+                )?;
+                let (new_ledger_, _fee) = new_ledger_.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
+                new_ledger = new_ledger_;
+            }
+            Fragment::AccountRegistration(tx) => {
+                let tx = tx.as_slice();
+                let deposit = new_ledger.settings.account_registration_deposit;
+
+                // the account is registered first so the transaction can
+                // already send some value to it
+                let new_ledger_ =
+                    new_ledger.apply_account_registration(&tx.payload().into_payload(), deposit)?;
+                let (new_ledger_, _fee) = new_ledger_.apply_transaction_with_deposit(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                    deposit,
+                )?;
+                new_ledger = new_ledger_;
+            }
+            Fragment::AccountDeregistration(tx) => {
+                let tx = tx.as_slice();
+                check::valid_account_deregistration(&tx)?;
+
+                // we've just verified that this is a valid transaction (i.e. contains 1 input and 1 witness)
+                let (input, witness) = tx.inputs_and_witnesses().iter().next().unwrap();
+                let account_id = match input.to_enum() {
+                    InputEnum::UtxoInput(_) => {
+                        return Err(Error::AccountDeregistrationInvalidTransaction);
+                    }
+                    InputEnum::AccountInput(account_id, _) => account_id,
+                };
+                let deregistration = tx.payload().into_payload();
+                match match_identifier_witness(&account_id, &witness)? {
+                    MatchingIdentifierWitness::Single(account_id, ..)
+                        if &account_id == deregistration.account() => {}
+                    _ => return Err(Error::AccountDeregistrationInvalidTransaction),
+                }
+
+                // like the reward withdrawal, the deposit is refunded before
+                // spending the input so the transaction can spend it
+                let new_ledger_ = new_ledger.refund_account_deposit(deregistration.account())?;
+                let (mut new_ledger_, _fee) = new_ledger_.apply_transaction_verified(
+                    &fragment_id,
+                    &tx,
+                    block_date,
+                    ledger_params,
+                    &verified.signatures,
+                )?;
+                new_ledger_.accounts = new_ledger_
+                    .accounts
+                    .deregister_account(deregistration.account())?;
+                new_ledger = new_ledger_;
+            }
         }
 
         Ok(new_ledger)
     }
 
     pub fn apply_transaction<'a, Extra>(
+        self,
+        fragment_id: &FragmentId,
+        tx: &TransactionSlice<'a, Extra>,
+        cur_date: BlockDate,
+        dyn_params: &LedgerParameters,
+    ) -> Result<(Self, Value), Error>
+    where
+        Extra: Payload,
+        LinearFee: FeeAlgorithm,
+    {
+        self.apply_transaction_verified(
+            fragment_id,
+            tx,
+            cur_date,
+            dyn_params,
+            &VerifiedSignatures::new(),
+        )
+    }
+
+    fn apply_transaction_verified<'a, Extra>(
+        self,
+        fragment_id: &FragmentId,
+        tx: &TransactionSlice<'a, Extra>,
+        cur_date: BlockDate,
+        dyn_params: &LedgerParameters,
+        signatures: &VerifiedSignatures,
+    ) -> Result<(Self, Value), Error>
+    where
+        Extra: Payload,
+        LinearFee: FeeAlgorithm,
+    {
+        self.apply_transaction_with_deposit(
+            fragment_id,
+            tx,
+            cur_date,
+            dyn_params,
+            signatures,
+            Value::zero(),
+        )
+    }
+
+    /// apply a transaction whose inputs pay a registration deposit on top of
+    /// the fees, the deposit is held in the deposits pot
+    fn apply_transaction_with_deposit<'a, Extra>(
         mut self,
         fragment_id: &FragmentId,
         tx: &TransactionSlice<'a, Extra>,
         cur_date: BlockDate,
         dyn_params: &LedgerParameters,
+        signatures: &VerifiedSignatures,
+        deposit: Value,
     ) -> Result<(Self, Value), Error>
     where
         Extra: Payload,
@@ -1010,10 +1546,12 @@ impl Ledger {
         check::valid_transaction_ios_number(tx)?;
         check::valid_transaction_date(&self.settings, tx.valid_until(), cur_date)?;
         let fee = calculate_fee(tx, dyn_params);
-        tx.verify_strictly_balanced(fee)?;
-        self = self.apply_tx_inputs(tx)?;
-        self = self.apply_tx_outputs(*fragment_id, tx.outputs())?;
+        tx.verify_strictly_balanced((fee + deposit)?)?;
+        self = self.apply_tx_inputs(tx, cur_date, signatures)?;
+        self.check_tx_outputs_registered(tx.outputs())?;
+        self = self.apply_tx_outputs(*fragment_id, tx.outputs(), cur_date)?;
         self = self.apply_tx_fee(fee)?;
+        self.pots.deposits_add(deposit)?;
         Ok((self, fee))
     }
 
@@ -1082,8 +1620,61 @@ impl Ledger {
         mut self,
         account_id: UnspecifiedAccountIdentifier,
         vote: VoteCast,
+        verified: &VerifiedBallots,
+    ) -> Result<Self, Error> {
+        self.votes = self
+            .votes
+            .apply_vote(self.date(), account_id, vote, verified)?;
+        Ok(self)
+    }
+
+    pub fn apply_vote_delegation(
+        mut self,
+        account_id: UnspecifiedAccountIdentifier,
+        delegation: &VoteDelegation,
     ) -> Result<Self, Error> {
-        self.votes = self.votes.apply_vote(self.date(), account_id, vote)?;
+        self.votes = self
+            .votes
+            .apply_vote_delegation(self.date(), account_id, delegation)?;
+        Ok(self)
+    }
+
+    pub fn apply_reward_withdrawal(
+        mut self,
+        account_id: &UnspecifiedAccountIdentifier,
+        witness: &Witness,
+        withdrawal: &RewardWithdrawal,
+    ) -> Result<Self, Error> {
+        match match_identifier_witness(account_id, witness)? {
+            MatchingIdentifierWitness::Single(account_id, ..) => {
+                self.accounts = self
+                    .accounts
+                    .withdraw_rewards(&account_id, withdrawal.value())?;
+            }
+            MatchingIdentifierWitness::Multi(account_id, ..) => {
+                self.multisig = self
+                    .multisig
+                    .withdraw_rewards(&account_id, withdrawal.value())?;
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn apply_account_registration(
+        mut self,
+        registration: &AccountRegistration,
+        deposit: Value,
+    ) -> Result<Self, Error> {
+        self.accounts = self
+            .accounts
+            .register_account(registration.account(), deposit, ())?;
+        Ok(self)
+    }
+
+    fn refund_account_deposit(mut self, account: &account::Identifier) -> Result<Self, Error> {
+        let (accounts, deposit) = self.accounts.refund_deposit(account)?;
+        self.accounts = accounts;
+        self.pots.deposits_refund(deposit)?;
         Ok(self)
     }
 
@@ -1163,6 +1754,7 @@ impl Ledger {
         cert: &certificate::PoolRegistration,
         bad: &TransactionBindingAuthData<'a>,
         sig: certificate::PoolSignature,
+        deposit: Value,
     ) -> Result<Self, Error> {
         check::valid_pool_registration_certificate(cert)?;
         check::valid_pool_signature(&sig)?;
@@ -1171,24 +1763,56 @@ impl Ledger {
             return Err(Error::PoolRetirementSignatureFailed);
         }
 
-        self.apply_pool_registration(cert)
+        self.apply_pool_registration_with_deposit(cert, deposit)
     }
 
     pub fn apply_pool_registration(
+        self,
+        cert: &certificate::PoolRegistration,
+    ) -> Result<Self, Error> {
+        self.apply_pool_registration_with_deposit(cert, Value::zero())
+    }
+
+    /// register the pool, recording the deposit paid by the registration
+    /// transaction so it can be refunded on retirement
+    fn apply_pool_registration_with_deposit(
         mut self,
         cert: &certificate::PoolRegistration,
+        deposit: Value,
     ) -> Result<Self, Error> {
         check::valid_pool_registration_certificate(cert)?;
+        self.check_pool_registration(cert)?;
 
-        self.delegation = self.delegation.register_stake_pool(cert.clone())?;
+        self.delegation = self
+            .delegation
+            .register_stake_pool_with_deposit(cert.clone(), deposit)?;
         Ok(self)
     }
 
+    /// the checks of a pool registration which depend on the ledger state
+    fn check_pool_registration(&self, cert: &certificate::PoolRegistration) -> Result<(), Error> {
+        let vrf_algorithm = self.settings.vrf_algorithm;
+        if !cert.keys.vrf_public_key.is_valid_for(vrf_algorithm) {
+            return Err(Error::PoolRegistrationVrfKeyInvalid { vrf_algorithm });
+        }
+        // rewards can only be credited to declared multisig accounts, while
+        // single accounts are created on their first reward
+        if let Some(AccountIdentifier::Multi(account)) = &cert.reward_account {
+            if !self.multisig.exists(account) {
+                return Err(Error::PoolRegistrationMultisigRewardAccountUnknown {
+                    account: account.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub fn apply_pool_retirement<'a>(
         mut self,
         auth_cert: &certificate::PoolRetirement,
         bad: &TransactionBindingAuthData<'a>,
         sig: certificate::PoolSignature,
+        cur_date: BlockDate,
     ) -> Result<Self, Error> {
         check::valid_pool_signature(&sig)?;
 
@@ -1197,10 +1821,39 @@ impl Ledger {
             return Err(Error::PoolRetirementSignatureFailed);
         }
 
-        self.delegation = self.delegation.deregister_stake_pool(&auth_cert.pool_id)?;
+        // the pool keeps running until the epoch transition retiring it
+        let epoch = self.pool_retirement_epoch(auth_cert.retirement_time, cur_date);
+        self.delegation
+            .schedule_stake_pool_retirement(&auth_cert.pool_id, epoch)?;
         Ok(self)
     }
 
+    /// refund the deposit of a retired pool to its reward account, or to its
+    /// first owner if it has no reward account
+    fn refund_pool_deposit(
+        &mut self,
+        reg: &certificate::PoolRegistration,
+        deposit: Value,
+    ) -> Result<(), Error> {
+        if deposit == Value::zero() {
+            return Ok(());
+        }
+        self.pots.deposits_refund(deposit)?;
+        match &reg.reward_account {
+            Some(AccountIdentifier::Single(account)) => {
+                self.refund_to_account(account, deposit)?;
+            }
+            Some(AccountIdentifier::Multi(account)) => {
+                self.multisig = self.multisig.add_value(account, deposit)?;
+            }
+            None => {
+                let account = reg.owners[0].clone().into();
+                self.refund_to_account(&account, deposit)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn apply_pool_update<'a>(
         mut self,
         auth_cert: &certificate::PoolUpdate,
@@ -1226,6 +1879,7 @@ impl Ledger {
         if sig.verify(&state.registration, bad) == Verification::Failed {
             return Err(Error::PoolUpdateSignatureFailed);
         }
+        self.check_pool_registration(new)?;
 
         let new = new.clone();
 
@@ -1260,10 +1914,10 @@ impl Ledger {
     ) -> Result<Self, Error> {
         let delegation_type = delegation.get_delegation_type();
         match match_identifier_witness(account_id, witness)? {
-            MatchingIdentifierWitness::Single(account_id, _witness) => {
+            MatchingIdentifierWitness::Single(account_id, ..) => {
                 self.accounts = self.accounts.set_delegation(&account_id, delegation_type)?;
             }
-            MatchingIdentifierWitness::Multi(account_id, _witness) => {
+            MatchingIdentifierWitness::Multi(account_id, ..) => {
                 self.multisig = self.multisig.set_delegation(&account_id, delegation_type)?;
             }
         };
@@ -1275,6 +1929,17 @@ impl Ledger {
         stake::get_distribution(&self.accounts, &self.delegation, &self.utxos)
     }
 
+    /// The rotating stake snapshots, taken at each epoch transition
+    pub fn stake_snapshots(&self) -> &StakeSnapshots {
+        &self.stake_snapshots
+    }
+
+    /// The stake distribution to use for the leader election of `epoch`
+    /// and for the rewards of the blocks created during `epoch`
+    pub fn active_stake_distribution(&self, epoch: Epoch) -> &StakeDistribution {
+        self.stake_snapshots.active_at(epoch).distribution()
+    }
+
     /// access the ledger static parameters
     pub fn get_static_parameters(&self) -> &LedgerStaticParameters {
         &self.static_params
@@ -1368,33 +2033,49 @@ impl Ledger {
     fn apply_tx_inputs<Extra: Payload>(
         mut self,
         tx: &TransactionSlice<Extra>,
+        cur_date: BlockDate,
+        signatures: &VerifiedSignatures,
     ) -> Result<Self, Error> {
         let sign_data_hash = tx.transaction_sign_data_hash();
         for (input, witness) in tx.inputs_and_witnesses().iter() {
             match input.to_enum() {
                 InputEnum::UtxoInput(utxo) => {
-                    self = self.apply_input_to_utxo(&sign_data_hash, &utxo, &witness)?
+                    self = self.apply_input_to_utxo(
+                        &sign_data_hash,
+                        &utxo,
+                        &witness,
+                        cur_date,
+                        signatures,
+                    )?
                 }
                 InputEnum::AccountInput(account_id, value) => {
                     match match_identifier_witness(&account_id, &witness)? {
-                        MatchingIdentifierWitness::Single(account_id, witness) => {
+                        MatchingIdentifierWitness::Single(
+                            account_id,
+                            spending_counter,
+                            witness,
+                        ) => {
                             self.accounts = input_single_account_verify(
                                 self.accounts,
                                 &self.static_params.block0_initial_hash,
                                 &sign_data_hash,
                                 &account_id,
+                                spending_counter,
                                 witness,
                                 value,
+                                signatures,
                             )?
                         }
-                        MatchingIdentifierWitness::Multi(account_id, witness) => {
+                        MatchingIdentifierWitness::Multi(account_id, spending_counter, witness) => {
                             self.multisig = input_multi_account_verify(
                                 self.multisig,
                                 &self.static_params.block0_initial_hash,
                                 &sign_data_hash,
                                 &account_id,
+                                spending_counter,
                                 witness,
                                 value,
+                                signatures,
                             )?
                         }
                     }
@@ -1408,6 +2089,7 @@ impl Ledger {
         mut self,
         fragment_id: FragmentId,
         outputs: OutputsSlice<'_>,
+        cur_date: BlockDate,
     ) -> Result<Self, Error> {
         let mut new_utxos = Vec::new();
         for (index, output) in outputs.iter().enumerate() {
@@ -1416,8 +2098,15 @@ impl Ledger {
             if output.address.discrimination() != self.static_params.discrimination {
                 return Err(Error::InvalidDiscrimination);
             }
+            let output = match output.lock {
+                Some(OutputLock::Slots(slots)) => {
+                    let until = cur_date.shift_slots(slots, &self.era);
+                    output.with_lock(OutputLock::Until(until))
+                }
+                _ => output,
+            };
             match output.address.kind() {
-                Kind::Single(_) => {
+                Kind::Single(_) | Kind::Script(_) => {
                     new_utxos.push((index as u8, output.clone()));
                 }
                 Kind::Group(_, account_id) => {
@@ -1429,6 +2118,9 @@ impl Ledger {
                     }
                     new_utxos.push((index as u8, output.clone()));
                 }
+                Kind::Account(_) | Kind::Multisig(_) if output.lock.is_some() => {
+                    return Err(Error::AccountOutputLocked { output });
+                }
                 Kind::Account(identifier) => {
                     // don't have a way to make a newtype ref from the ref so .clone()
                     let account = identifier.clone().into();
@@ -1438,10 +2130,6 @@ impl Ledger {
                     let identifier = multisig::Identifier::from(*identifier);
                     self.multisig = self.multisig.add_value(&identifier, output.value)?;
                 }
-                Kind::Script(_identifier) => {
-                    // TODO: scripts address kinds are not yet supported
-                    return Err(Error::ScriptsAddressNotAllowedYet);
-                }
             }
         }
         if !new_utxos.is_empty() {
@@ -1450,6 +2138,27 @@ impl Ledger {
         Ok(self)
     }
 
+    /// when the account registration takes a deposit, the account outputs of
+    /// a transaction cannot create their account anymore. The group outputs
+    /// still create theirs, as the stake of a group utxo is always assigned
+    /// through its account
+    fn check_tx_outputs_registered(&self, outputs: OutputsSlice<'_>) -> Result<(), Error> {
+        if !self.requires_registration() {
+            return Ok(());
+        }
+        for output in outputs.iter() {
+            let account = match output.address.kind() {
+                Kind::Account(account) => account,
+                _ => continue,
+            };
+            let account = account::Identifier::from(account.clone());
+            if !self.accounts.exists(&account) {
+                return Err(Error::AccountNotRegistered { account });
+            }
+        }
+        Ok(())
+    }
+
     fn add_value_or_create_account(
         &mut self,
         account: &account::Identifier,
@@ -1465,6 +2174,46 @@ impl Ledger {
         Ok(())
     }
 
+    /// when the account registration takes a deposit, only a registration
+    /// creates an account
+    fn requires_registration(&self) -> bool {
+        self.settings.account_registration_deposit != Value::zero()
+    }
+
+    /// refund some value to an account, or to the treasury if the account
+    /// would have to be created without its registration
+    fn refund_to_account(
+        &mut self,
+        account: &account::Identifier,
+        value: Value,
+    ) -> Result<(), Error> {
+        if self.requires_registration() && !self.accounts.exists(account) {
+            self.pots.treasury_add(value)?;
+            return Ok(());
+        }
+        self.add_value_or_create_account(account, value)
+    }
+
+    /// distribute some rewards to an account, or to the treasury if the
+    /// account would have to be created without its registration
+    fn reward_account(
+        &mut self,
+        reward_info: &mut EpochRewardsInfo,
+        epoch: Epoch,
+        account: &account::Identifier,
+        value: Value,
+    ) -> Result<(), Error> {
+        if self.requires_registration() && !self.accounts.exists(account) {
+            self.pots.treasury_add(value)?;
+            return Ok(());
+        }
+        self.accounts = self
+            .accounts
+            .add_rewards_to_account(account, epoch, value, ())?;
+        reward_info.add_to_account(account, value);
+        Ok(())
+    }
+
     fn apply_tx_fee(mut self, fee: Value) -> Result<Self, Error> {
         self.pots.append_fees(fee)?;
         Ok(self)
@@ -1475,10 +2224,12 @@ impl Ledger {
         sign_data_hash: &TransactionSignDataHash,
         utxo: &UtxoPointer,
         witness: &Witness,
+        cur_date: BlockDate,
+        signatures: &VerifiedSignatures,
     ) -> Result<Self, Error> {
         match witness {
-            Witness::Account(_) => Err(Error::ExpectingUtxoWitness),
-            Witness::Multisig(_) => Err(Error::ExpectingUtxoWitness),
+            Witness::Account(..) => Err(Error::ExpectingUtxoWitness),
+            Witness::Multisig(..) => Err(Error::ExpectingUtxoWitness),
             Witness::OldUtxo(pk, cc, signature) => {
                 let (old_utxos, associated_output) = self
                     .oldutxos
@@ -1528,16 +2279,18 @@ impl Ledger {
                         value: associated_output.value,
                     });
                 }
+                check::valid_utxo_lock(utxo, &associated_output, cur_date)?;
 
                 let data_to_verify = WitnessUtxoData::new(
                     &self.static_params.block0_initial_hash,
                     sign_data_hash,
                     WitnessUtxoVersion::Normal,
                 );
-                let verified = signature.verify(
-                    associated_output.address.public_key().unwrap(),
-                    &data_to_verify,
-                );
+                let public_key = associated_output
+                    .address
+                    .public_key()
+                    .ok_or(Error::ExpectingScriptWitness)?;
+                let verified = signatures.verify(public_key, signature, &data_to_verify);
                 if verified == chain_crypto::Verification::Failed {
                     return Err(Error::UtxoInvalidSignature {
                         utxo: *utxo,
@@ -1547,6 +2300,41 @@ impl Ledger {
                 };
                 Ok(self)
             }
+            Witness::Script(script, script_witness) => {
+                let (new_utxos, associated_output) =
+                    self.utxos.remove(&utxo.transaction_id, utxo.output_index)?;
+                self.utxos = new_utxos;
+                if utxo.value != associated_output.value {
+                    return Err(Error::UtxoValueNotMatching {
+                        expected: utxo.value,
+                        value: associated_output.value,
+                    });
+                }
+                check::valid_utxo_lock(utxo, &associated_output, cur_date)?;
+
+                let identifier = match associated_output.address.kind() {
+                    Kind::Script(identifier) => script::Identifier::from(*identifier),
+                    _ => return Err(Error::ExpectingUtxoWitness),
+                };
+                script.is_valid()?;
+                if script.to_identifier() != identifier {
+                    return Err(Error::ScriptIdentifierMismatch {
+                        utxo: *utxo,
+                        output: associated_output,
+                    });
+                }
+
+                let data_to_verify =
+                    WitnessScriptData::new(&self.static_params.block0_initial_hash, sign_data_hash);
+                if !script_witness.verify(script, cur_date, &data_to_verify) {
+                    return Err(Error::ScriptNotSatisfied {
+                        utxo: *utxo,
+                        output: associated_output,
+                        witness: witness.clone(),
+                    });
+                }
+                Ok(self)
+            }
         }
     }
 
@@ -1564,10 +2352,27 @@ impl ApplyBlockLedger {
         self.block_date
     }
 
+    /// The pools retired by the epoch transition this block started with,
+    /// if the block is the first of its epoch
+    pub fn pool_retirements(&self) -> Option<&PoolRetirementsInfo> {
+        self.pool_retirements.as_ref()
+    }
+
     pub fn apply_fragment(&self, fragment: &Fragment) -> Result<Self, Error> {
-        let ledger = self
-            .ledger
-            .apply_fragment(&self.ledger_params, fragment, self.block_date)?;
+        self.apply_fragment_verified(fragment, &VerifiedFragments::default())
+    }
+
+    pub fn apply_fragment_verified(
+        &self,
+        fragment: &Fragment,
+        verified: &VerifiedFragments,
+    ) -> Result<Self, Error> {
+        let ledger = self.ledger.apply_fragment_verified(
+            &self.ledger_params,
+            fragment,
+            self.block_date,
+            verified,
+        )?;
         Ok(ApplyBlockLedger {
             ledger,
             ..self.clone()
@@ -1605,6 +2410,7 @@ fn apply_old_declaration(
         let output = Output {
             address: d.0.clone(),
             value: d.1,
+            lock: None,
         };
         outputs.push((i as u8, output))
     }
@@ -1620,8 +2426,16 @@ fn calculate_fee<'a, Extra: Payload>(
 }
 
 pub enum MatchingIdentifierWitness<'a> {
-    Single(account::Identifier, &'a account::Witness),
-    Multi(multisig::Identifier, &'a multisig::Witness),
+    Single(
+        account::Identifier,
+        account::SpendingCounter,
+        &'a account::Witness,
+    ),
+    Multi(
+        multisig::Identifier,
+        account::SpendingCounter,
+        &'a multisig::Witness,
+    ),
 }
 
 fn match_identifier_witness<'a>(
@@ -1631,17 +2445,26 @@ fn match_identifier_witness<'a>(
     match witness {
         Witness::OldUtxo(..) => Err(Error::ExpectingAccountWitness),
         Witness::Utxo(_) => Err(Error::ExpectingAccountWitness),
-        Witness::Account(sig) => {
+        Witness::Script(..) => Err(Error::ExpectingAccountWitness),
+        Witness::Account(spending_counter, sig) => {
             // refine account to a single account identifier
             let account = account
                 .to_single_account()
                 .ok_or(Error::AccountIdentifierInvalid)?;
-            Ok(MatchingIdentifierWitness::Single(account, sig))
+            Ok(MatchingIdentifierWitness::Single(
+                account,
+                *spending_counter,
+                sig,
+            ))
         }
-        Witness::Multisig(msignature) => {
+        Witness::Multisig(spending_counter, msignature) => {
             // refine account to a multisig account identifier
             let account = account.to_multi_account();
-            Ok(MatchingIdentifierWitness::Multi(account, msignature))
+            Ok(MatchingIdentifierWitness::Multi(
+                account,
+                *spending_counter,
+                msignature,
+            ))
         }
     }
 }
@@ -1651,19 +2474,21 @@ fn input_single_account_verify<'a>(
     block0_hash: &HeaderId,
     sign_data_hash: &TransactionSignDataHash,
     account: &account::Identifier,
+    spending_counter: account::SpendingCounter,
     witness: &'a account::Witness,
     value: Value,
+    signatures: &VerifiedSignatures,
 ) -> Result<account::Ledger, Error> {
-    // .remove_value() check if there's enough value and if not, returns a Err.
-    let (new_ledger, spending_counter) = ledger.remove_value(account, value)?;
-    ledger = new_ledger;
+    // .remove_value() check if there's enough value and if the spending
+    // counter is the next one of its lane, and if not, returns a Err.
+    ledger = ledger.remove_value(account, spending_counter, value)?;
 
     let tidsc = WitnessAccountData::new(block0_hash, sign_data_hash, spending_counter);
-    let verified = witness.verify(account.as_ref(), &tidsc);
+    let verified = signatures.verify(account.as_ref(), witness, &tidsc);
     if verified == chain_crypto::Verification::Failed {
         return Err(Error::AccountInvalidSignature {
             account: account.clone(),
-            witness: Witness::Account(witness.clone()),
+            witness: Witness::Account(spending_counter, witness.clone()),
         });
     };
     Ok(ledger)
@@ -1674,17 +2499,23 @@ fn input_multi_account_verify<'a>(
     block0_hash: &HeaderId,
     sign_data_hash: &TransactionSignDataHash,
     account: &multisig::Identifier,
+    spending_counter: account::SpendingCounter,
     witness: &'a multisig::Witness,
     value: Value,
+    signatures: &VerifiedSignatures,
 ) -> Result<multisig::Ledger, Error> {
-    // .remove_value() check if there's enough value and if not, returns a Err.
-    let (new_ledger, declaration, spending_counter) = ledger.remove_value(account, value)?;
+    // .remove_value() check if there's enough value and if the spending
+    // counter is the next one of its lane, and if not, returns a Err.
+    let (new_ledger, declaration) = ledger.remove_value(account, spending_counter, value)?;
 
     let data_to_verify = WitnessMultisigData::new(block0_hash, sign_data_hash, spending_counter);
-    if !witness.verify(declaration, &data_to_verify) {
+    let verified = witness.verify_with(declaration, |pk, sig| {
+        signatures.verify(pk, sig, &data_to_verify) == Verification::Success
+    });
+    if !verified {
         return Err(Error::MultisigInvalidSignature {
             multisig: account.clone(),
-            witness: Witness::Multisig(witness.clone()),
+            witness: Witness::Multisig(spending_counter, witness.clone()),
         });
     }
     ledger = new_ledger;
@@ -1857,9 +2688,9 @@ mod tests {
             (Witness::OldUtxo(..), Err(_)) => TestResult::passed(),
             (Witness::Utxo(_), Ok(_)) => TestResult::error("expecting error, but got success"),
             (Witness::Utxo(_), Err(_)) => TestResult::passed(),
-            (Witness::Account(_), Ok(_)) => TestResult::passed(),
-            (Witness::Account(_), Err(_)) => TestResult::error("unexpected error"),
-            (Witness::Multisig(_), _) => TestResult::discard(),
+            (Witness::Account(..), Ok(_)) => TestResult::passed(),
+            (Witness::Account(..), Err(_)) => TestResult::error("unexpected error"),
+            (Witness::Multisig(..), _) | (Witness::Script(..), _) => TestResult::discard(),
         }
     }
 
@@ -1881,8 +2712,10 @@ mod tests {
             &block0_hash,
             &sign_data_hash,
             &id,
+            SpendingCounter::zero(),
             &witness,
             value_to_sub,
+            &VerifiedSignatures::new(),
         );
 
         TestResult::from_bool(result.is_err())
@@ -1903,14 +2736,18 @@ mod tests {
             &account,
         );
         let sign_data_hash = signed_tx.hash();
+        let (spending_counter, witness) =
+            to_account_witness(signed_tx.witnesses().iter().next().unwrap());
 
         let result = super::input_single_account_verify(
             account_ledger,
             &block0_hash,
             &sign_data_hash,
             &id,
-            &to_account_witness(&signed_tx.witnesses().iter().next().unwrap()),
+            spending_counter,
+            &witness,
             value_to_sub,
+            &VerifiedSignatures::new(),
         );
         assert!(result.is_ok())
     }
@@ -1939,21 +2776,27 @@ mod tests {
             &account,
         );
         let sign_data_hash = signed_tx.hash();
+        let (spending_counter, witness) =
+            to_account_witness(signed_tx.witnesses().iter().next().unwrap());
 
         let result = super::input_single_account_verify(
             account_ledger,
             &wrong_block0_hash,
             &sign_data_hash,
             &id,
-            &to_account_witness(&signed_tx.witnesses().iter().next().unwrap()),
+            spending_counter,
+            &witness,
             value_to_sub,
+            &VerifiedSignatures::new(),
         );
         assert!(result.is_err())
     }
 
-    fn to_account_witness(witness: &Witness) -> &account::Witness {
+    fn to_account_witness(witness: Witness) -> (SpendingCounter, account::Witness) {
         match witness {
-            Witness::Account(account_witness) => account_witness,
+            Witness::Account(spending_counter, account_witness) => {
+                (spending_counter, account_witness)
+            }
             _ => panic!("wrong type of witness"),
         }
     }
@@ -1974,14 +2817,18 @@ mod tests {
             &account,
         );
         let sign_data_hash = signed_tx.hash();
+        let (spending_counter, witness) =
+            to_account_witness(signed_tx.witnesses().iter().next().unwrap());
 
         let result = super::input_single_account_verify(
             account_ledger,
             &wrong_block0_hash,
             &sign_data_hash,
             &id,
-            &to_account_witness(&signed_tx.witnesses().iter().next().unwrap()),
+            spending_counter,
+            &witness,
             value_to_sub,
+            &VerifiedSignatures::new(),
         );
         assert!(result.is_err())
     }
@@ -2003,14 +2850,18 @@ mod tests {
             &account,
         );
         let sign_data_hash = signed_tx.hash();
+        let (spending_counter, witness) =
+            to_account_witness(signed_tx.witnesses().iter().next().unwrap());
 
         let result = super::input_single_account_verify(
             account_ledger,
             &wrong_block0_hash,
             &sign_data_hash,
             &non_existing_account.public_key().into(),
-            &to_account_witness(&signed_tx.witnesses().iter().next().unwrap()),
+            spending_counter,
+            &witness,
             value_to_sub,
+            &VerifiedSignatures::new(),
         );
         assert!(result.is_err())
     }
@@ -2028,15 +2879,21 @@ mod tests {
             .unwrap();
 
         let inner_ledger: Ledger = test_ledger.into();
-        let result = inner_ledger.apply_input_to_utxo(&sign_data_hash, &utxo_pointer, &witness);
+        let result = inner_ledger.apply_input_to_utxo(
+            &sign_data_hash,
+            &utxo_pointer,
+            &witness,
+            BlockDate::first(),
+            &VerifiedSignatures::new(),
+        );
         match (witness, result) {
             (Witness::OldUtxo(..), Ok(_)) => TestResult::error("expecting error, but got success"),
             (Witness::OldUtxo(..), Err(_)) => TestResult::passed(),
             (Witness::Utxo(_), Ok(_)) => TestResult::error("expecting error, but got success"),
             (Witness::Utxo(_), Err(_)) => TestResult::passed(),
-            (Witness::Account(_), Ok(_)) => TestResult::error("expecting error, but got success"),
-            (Witness::Account(_), Err(_)) => TestResult::passed(),
-            (Witness::Multisig(_), _) => TestResult::discard(),
+            (Witness::Account(..), Ok(_)) => TestResult::error("expecting error, but got success"),
+            (Witness::Account(..), Err(_)) => TestResult::passed(),
+            (Witness::Multisig(..), _) | (Witness::Script(..), _) => TestResult::discard(),
         }
     }
 
@@ -2061,6 +2918,8 @@ mod tests {
             &sign_data_hash,
             &utxo_pointer,
             &signed_tx.witnesses().iter().next().unwrap(),
+            BlockDate::first(),
+            &VerifiedSignatures::new(),
         );
         assert!(result.is_ok())
     }
@@ -2085,10 +2944,167 @@ mod tests {
             &sign_data_hash,
             &utxo_pointer,
             &signed_tx.witnesses().iter().next().unwrap(),
+            BlockDate::first(),
+            &VerifiedSignatures::new(),
         );
         assert!(result.is_err())
     }
 
+    #[test]
+    pub fn test_input_utxo_verify_script() {
+        let sk = chain_crypto::SecretKey::<account::AccountAlg>::generate(rand_core::OsRng);
+        let static_params = InternalApplyTransactionTestParams::new().static_params;
+        let block0_hash = static_params.block0_initial_hash;
+        let ledger = build_ledger(
+            utxo::Ledger::new(),
+            account::Ledger::new(),
+            multisig::Ledger::new(),
+            static_params,
+        );
+
+        // vesting: locked until epoch 1 for the owner of the key
+        let script = script::Script::All(vec![
+            script::Script::Signature(Hash::hash_bytes(sk.to_public().as_ref())),
+            script::Script::After(BlockDate {
+                epoch: 1,
+                slot_id: 0,
+            }),
+        ]);
+        let address = Address(
+            Discrimination::Test,
+            Kind::Script(script.to_identifier().into()),
+        );
+        let fragment_id = TestGen::hash();
+        let outputs = [Output::from_address(address, Value(100))];
+        let ledger = ledger
+            .apply_tx_outputs(
+                fragment_id,
+                transaction_from_ios_only(&[], &outputs).get_tx_outputs(),
+                BlockDate::first(),
+            )
+            .unwrap();
+        let utxo_pointer = UtxoPointer::new(fragment_id, 0, Value(100));
+
+        let sign_data_hash = TransactionSignDataHash::digest(&vec![1, 2, 3].into());
+        let script_witness = |script: &script::Script| {
+            Witness::new_script(&block0_hash, &sign_data_hash, script.clone(), |data| {
+                let mut builder = script::WitnessBuilder::new();
+                builder
+                    .append_signature(sk.to_public(), sk.sign(data))
+                    .unwrap();
+                builder.finalize()
+            })
+        };
+        let apply = |witness: &Witness, epoch| {
+            ledger.clone().apply_input_to_utxo(
+                &sign_data_hash,
+                &utxo_pointer,
+                witness,
+                BlockDate { epoch, slot_id: 0 },
+                &VerifiedSignatures::new(),
+            )
+        };
+
+        assert!(matches!(
+            apply(&script_witness(&script), 0),
+            Err(Error::ScriptNotSatisfied { .. })
+        ));
+        assert!(apply(&script_witness(&script), 1).is_ok());
+
+        let other_script = script::Script::Any(vec![script]);
+        assert!(matches!(
+            apply(&script_witness(&other_script), 1),
+            Err(Error::ScriptIdentifierMismatch { .. })
+        ));
+
+        let utxo_witness = Witness::new_utxo(&block0_hash, &sign_data_hash, |data| sk.sign(data));
+        assert_eq!(
+            apply(&utxo_witness, 1).unwrap_err(),
+            Error::ExpectingScriptWitness
+        );
+    }
+
+    #[test]
+    pub fn test_input_utxo_verify_lock() {
+        let faucet = AddressData::utxo(Discrimination::Test);
+        let static_params = InternalApplyTransactionTestParams::new().static_params;
+        let block0_hash = static_params.block0_initial_hash;
+        let ledger = build_ledger(
+            utxo::Ledger::new(),
+            account::Ledger::new(),
+            multisig::Ledger::new(),
+            static_params,
+        );
+        let date = |epoch, slot_id| BlockDate { epoch, slot_id };
+
+        // the time era of the test ledger has 4 slots per epoch
+        let fragment_id = TestGen::hash();
+        let outputs = [
+            Output::from_address(faucet.address.clone(), Value(100))
+                .with_lock(OutputLock::Until(date(1, 2))),
+            Output::from_address(faucet.address.clone(), Value(100))
+                .with_lock(OutputLock::Slots(3)),
+        ];
+        let ledger = ledger
+            .apply_tx_outputs(
+                fragment_id,
+                transaction_from_ios_only(&[], &outputs).get_tx_outputs(),
+                date(0, 2),
+            )
+            .unwrap();
+
+        let sign_data_hash = TransactionSignDataHash::digest(&vec![1, 2, 3].into());
+        let witness = make_witness(&block0_hash, &faucet, &sign_data_hash);
+        let apply = |index, cur_date| {
+            let utxo_pointer = UtxoPointer::new(fragment_id, index, Value(100));
+            ledger.clone().apply_input_to_utxo(
+                &sign_data_hash,
+                &utxo_pointer,
+                &witness,
+                cur_date,
+                &VerifiedSignatures::new(),
+            )
+        };
+
+        assert!(matches!(
+            apply(0, date(1, 1)),
+            Err(Error::UtxoLocked { until, .. }) if until == date(1, 2)
+        ));
+        assert!(apply(0, date(1, 2)).is_ok());
+        assert!(apply(0, date(2, 0)).is_ok());
+
+        // the relative lock is resolved against the date the output was added
+        assert!(matches!(
+            apply(1, date(1, 0)),
+            Err(Error::UtxoLocked { until, .. }) if until == date(1, 1)
+        ));
+        assert!(apply(1, date(1, 1)).is_ok());
+    }
+
+    #[test]
+    pub fn test_locked_account_output_is_rejected() {
+        let account = AddressData::account(Discrimination::Test);
+        let static_params = InternalApplyTransactionTestParams::new().static_params;
+        let ledger = build_ledger(
+            utxo::Ledger::new(),
+            account::Ledger::new(),
+            multisig::Ledger::new(),
+            static_params,
+        );
+
+        let output =
+            Output::from_address(account.address, Value(100)).with_lock(OutputLock::Slots(1));
+        let result = ledger.apply_tx_outputs(
+            TestGen::hash(),
+            transaction_from_ios_only(&[], std::slice::from_ref(&output)).get_tx_outputs(),
+            BlockDate::first(),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::AccountOutputLocked { output: o }) if o.address == output.address
+        ));
+    }
+
     #[quickcheck]
     pub fn test_internal_apply_transaction_output_property(
         utxos: utxo::Ledger<Address>,
@@ -2106,7 +3122,8 @@ mod tests {
 
         let ledger = build_ledger(utxos, accounts, multisig_ledger, static_params.clone());
         let auth_tx = transaction_from_ios_only(&[], &outputs);
-        let result = ledger.apply_tx_outputs(transaction_id, auth_tx.get_tx_outputs());
+        let result =
+            ledger.apply_tx_outputs(transaction_id, auth_tx.get_tx_outputs(), BlockDate::first());
 
         match (
             should_expect_success(arbitrary_outputs, &static_params),
@@ -2244,7 +3261,11 @@ mod tests {
         );
 
         let ledger = ledger
-            .apply_tx_outputs(params.transaction_id(), auth_tx.get_tx_outputs())
+            .apply_tx_outputs(
+                params.transaction_id(),
+                auth_tx.get_tx_outputs(),
+                BlockDate::first(),
+            )
             .expect("Unexpected error while applying transaction output");
 
         LedgerStateVerifier::new(ledger)
@@ -2277,7 +3298,11 @@ mod tests {
 
         let auth_tx = transaction_from_ios_only(&[], &[delegation_output.clone()]);
         let ledger = ledger
-            .apply_tx_outputs(params.transaction_id(), auth_tx.get_tx_outputs())
+            .apply_tx_outputs(
+                params.transaction_id(),
+                auth_tx.get_tx_outputs(),
+                BlockDate::first(),
+            )
             .expect("Unexpected error while applying transaction output");
 
         LedgerStateVerifier::new(ledger)
@@ -2312,7 +3337,11 @@ mod tests {
 
         let auth_tx = transaction_from_ios_only(&[], &[account.make_output(Value(200))]);
         let ledger = ledger
-            .apply_tx_outputs(params.transaction_id(), auth_tx.get_tx_outputs())
+            .apply_tx_outputs(
+                params.transaction_id(),
+                auth_tx.get_tx_outputs(),
+                BlockDate::first(),
+            )
             .expect("Unexpected error while applying transaction output");
 
         LedgerStateVerifier::new(ledger)
@@ -2340,7 +3369,11 @@ mod tests {
         let ledger = build_ledger(utxos, accounts, multisig_ledger, params.static_params());
         let auth_tx = transaction_from_ios_only(&[], &[account.make_output(Value(200))]);
         let ledger = ledger
-            .apply_tx_outputs(params.transaction_id(), auth_tx.get_tx_outputs())
+            .apply_tx_outputs(
+                params.transaction_id(),
+                auth_tx.get_tx_outputs(),
+                BlockDate::first(),
+            )
             .expect("Unexpected error while applying transaction output");
 
         LedgerStateVerifier::new(ledger)
@@ -2369,7 +3402,11 @@ mod tests {
         let auth_tx = empty_transaction();
 
         let ledger = ledger
-            .apply_tx_outputs(params.transaction_id(), auth_tx.get_tx_outputs())
+            .apply_tx_outputs(
+                params.transaction_id(),
+                auth_tx.get_tx_outputs(),
+                BlockDate::first(),
+            )
             .expect("Unexpected error while applying transaction output");
 
         LedgerStateVerifier::new(ledger)
@@ -8,13 +8,17 @@ mod leaderlog;
 pub mod ledger;
 mod pots;
 pub mod recovery;
+mod retirement_info;
 mod reward_info;
+mod signatures;
 
 pub use iter::*;
 pub use leaderlog::LeadersParticipationRecord;
 pub use ledger::*;
 pub use pots::Pots;
+pub use retirement_info::{PoolRetirementsInfo, RetiredPoolInfo};
 pub use reward_info::{EpochRewardsInfo, RewardsInfoParameters};
+pub use signatures::VerifiedSignatures;
 
 #[cfg(test)]
 pub mod tests;
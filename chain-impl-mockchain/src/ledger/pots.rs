@@ -10,6 +10,7 @@ pub struct Pots {
     pub(crate) fees: Value,
     pub(crate) treasury: Treasury,
     pub(crate) rewards: Value,
+    pub(crate) deposits: Value,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -17,6 +18,7 @@ pub enum Entry {
     Fees(Value),
     Treasury(Value),
     Rewards(Value),
+    Deposits(Value),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -24,6 +26,7 @@ pub enum EntryType {
     Fees,
     Treasury,
     Rewards,
+    Deposits,
 }
 
 impl Entry {
@@ -32,6 +35,7 @@ impl Entry {
             Entry::Fees(v) => *v,
             Entry::Treasury(v) => *v,
             Entry::Rewards(v) => *v,
+            Entry::Deposits(v) => *v,
         }
     }
 
@@ -40,6 +44,7 @@ impl Entry {
             Entry::Fees(_) => EntryType::Fees,
             Entry::Treasury(_) => EntryType::Treasury,
             Entry::Rewards(_) => EntryType::Rewards,
+            Entry::Deposits(_) => EntryType::Deposits,
         }
     }
 }
@@ -48,6 +53,7 @@ pub enum IterState {
     Fees,
     Treasury,
     Rewards,
+    Deposits,
     Done,
 }
 
@@ -72,9 +78,13 @@ impl<'a> Iterator for Entries<'a> {
                 Some(Entry::Treasury(self.pots.treasury.value()))
             }
             IterState::Rewards => {
-                self.it = IterState::Done;
+                self.it = IterState::Deposits;
                 Some(Entry::Rewards(self.pots.rewards))
             }
+            IterState::Deposits => {
+                self.it = IterState::Done;
+                Some(Entry::Deposits(self.pots.deposits))
+            }
             IterState::Done => None,
         }
     }
@@ -95,6 +105,7 @@ impl Pots {
             fees: Value::zero(),
             treasury: Treasury::initial(Value::zero()),
             rewards: Value::zero(),
+            deposits: Value::zero(),
         }
     }
 
@@ -156,6 +167,24 @@ impl Pots {
         Ok(())
     }
 
+    /// Hold the deposit of a registration
+    pub fn deposits_add(&mut self, value: Value) -> Result<(), Error> {
+        self.deposits = self
+            .deposits
+            .checked_add(value)
+            .map_err(|error| Error::PotValueInvalid { error })?;
+        Ok(())
+    }
+
+    /// Release the deposit of a registration to refund it
+    pub fn deposits_refund(&mut self, value: Value) -> Result<(), Error> {
+        self.deposits = self
+            .deposits
+            .checked_sub(value)
+            .map_err(|error| Error::PotValueInvalid { error })?;
+        Ok(())
+    }
+
     /// Get the value in the treasury
     pub fn fees_value(&self) -> Value {
         self.fees
@@ -171,6 +200,7 @@ impl Pots {
             Entry::Fees(v) => self.fees = *v,
             Entry::Treasury(v) => self.treasury = Treasury::initial(*v),
             Entry::Rewards(v) => self.rewards = *v,
+            Entry::Deposits(v) => self.deposits = *v,
         }
     }
 }
@@ -188,6 +218,7 @@ mod tests {
                 fees: Arbitrary::arbitrary(g),
                 treasury: Arbitrary::arbitrary(g),
                 rewards: Arbitrary::arbitrary(g),
+                deposits: Arbitrary::arbitrary(g),
             }
         }
     }
@@ -198,6 +229,7 @@ mod tests {
         assert_eq!(pots.fees, Value::zero());
         assert_eq!(pots.treasury, Treasury::initial(Value::zero()));
         assert_eq!(pots.rewards, Value::zero());
+        assert_eq!(pots.deposits, Value::zero());
     }
 
     #[quickcheck]
@@ -213,6 +245,9 @@ mod tests {
                 Entry::Rewards(rewards) => {
                     assert_eq!(pots.rewards, rewards);
                 }
+                Entry::Deposits(deposits) => {
+                    assert_eq!(pots.deposits, deposits);
+                }
             }
         }
         TestResult::passed()
@@ -40,8 +40,11 @@ use super::{Entry, EntryOwned};
 use crate::account::AccountAlg;
 use crate::accounting::account::{
     AccountState, DelegationRatio, DelegationType, LastRewards, SpendingCounter,
+    SpendingCounterIncreasing,
+};
+use crate::certificate::{
+    PoolId, PoolRegistration, Proposal, Proposals, VoteAction, VotePlan, VoteRepresentative,
 };
-use crate::certificate::{PoolId, PoolRegistration, Proposal, Proposals, VoteAction, VotePlan};
 use crate::config::ConfigParam;
 use crate::date::BlockDate;
 use crate::fragment::FragmentId;
@@ -50,8 +53,11 @@ use crate::key::serialize_public_key;
 use crate::ledger::{Globals, Ledger, LedgerStaticParameters};
 use crate::legacy;
 use crate::multisig::{DeclElement, Declaration};
-use crate::stake::{PoolLastRewards, PoolState};
-use crate::transaction::Output;
+use crate::stake::{
+    PoolLastRewards, PoolStakeDistribution, PoolStakeInformation, PoolState, Stake,
+    StakeDistribution, StakeSnapshot, StakeSnapshots,
+};
+use crate::transaction::{Output, OutputLock, UnspecifiedAccountIdentifier, INPUT_PTR_SIZE};
 use crate::update::{UpdateProposal, UpdateProposalId, UpdateProposalState, UpdateVoterId};
 use crate::value::Value;
 use crate::vote;
@@ -164,9 +170,11 @@ fn pack_account_state<W: std::io::Write>(
     account_state: &AccountState<()>,
     codec: &mut Codec<W>,
 ) -> Result<(), std::io::Error> {
-    codec.put_u32(account_state.counter.0)?;
+    pack_spending_counters(&account_state.spending, codec)?;
     pack_delegation_type(&account_state.delegation, codec)?;
     codec.put_u64(account_state.value.0)?;
+    codec.put_u64(account_state.rewards.0)?;
+    codec.put_u64(account_state.deposit.0)?;
     pack_last_rewards(&account_state.last_rewards, codec)?;
     Ok(())
 }
@@ -174,19 +182,61 @@ fn pack_account_state<W: std::io::Write>(
 fn unpack_account_state<R: std::io::BufRead>(
     codec: &mut Codec<R>,
 ) -> Result<AccountState<()>, std::io::Error> {
-    let counter = codec.get_u32()?;
+    let spending = unpack_spending_counters(codec)?;
     let delegation = unpack_delegation_type(codec)?;
     let value = codec.get_u64()?;
+    let rewards = codec.get_u64()?;
+    let deposit = codec.get_u64()?;
     let last_rewards = unpack_last_rewards(codec)?;
     Ok(AccountState {
-        counter: SpendingCounter(counter),
+        spending,
         delegation,
         value: Value(value),
+        rewards: Value(rewards),
+        deposit: Value(deposit),
         last_rewards,
         extra: (),
     })
 }
 
+fn pack_spending_counters<W: std::io::Write>(
+    spending: &SpendingCounterIncreasing,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    let counters = spending.get_valid_counters();
+    codec.put_u8(counters.len() as u8)?;
+    for counter in counters.iter() {
+        codec.put_u32(counter.0)?;
+    }
+    Ok(())
+}
+
+fn unpack_spending_counters<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<SpendingCounterIncreasing, std::io::Error> {
+    let lanes = codec.get_u8()? as usize;
+    if lanes != SpendingCounter::LANES {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "Expected {} spending counter lanes, got {}",
+                SpendingCounter::LANES,
+                lanes
+            ),
+        ));
+    }
+    let mut nexts = [SpendingCounter::zero(); SpendingCounter::LANES];
+    for next in nexts.iter_mut() {
+        *next = SpendingCounter(codec.get_u32()?);
+    }
+    SpendingCounterIncreasing::from_nexts(nexts).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Spending counter in the wrong lane",
+        )
+    })
+}
+
 fn pack_delegation_ratio<W: std::io::Write>(
     delegation_ratio: &DelegationRatio,
     codec: &mut Codec<W>,
@@ -419,6 +469,12 @@ fn pack_per_certificate_fee<W: std::io::Write>(
             .map(|v| v.get())
             .unwrap_or(0),
     )?;
+    codec.put_u64(
+        per_certificate_fee
+            .certificate_reward_withdrawal
+            .map(|v| v.get())
+            .unwrap_or(0),
+    )?;
     Ok(())
 }
 
@@ -449,11 +505,13 @@ fn unpack_per_certificate_fee<R: std::io::BufRead>(
     let certificate_pool_registration = std::num::NonZeroU64::new(codec.get_u64()?);
     let certificate_stake_delegation = std::num::NonZeroU64::new(codec.get_u64()?);
     let certificate_owner_stake_delegation = std::num::NonZeroU64::new(codec.get_u64()?);
+    let certificate_reward_withdrawal = std::num::NonZeroU64::new(codec.get_u64()?);
 
     Ok(PerCertificateFee {
         certificate_pool_registration,
         certificate_stake_delegation,
         certificate_owner_stake_delegation,
+        certificate_reward_withdrawal,
     })
 }
 
@@ -580,6 +638,10 @@ fn pack_pot_entry<W: std::io::Write>(
             codec.put_u8(2)?;
             codec.put_u64(value.0)?;
         }
+        pots::Entry::Deposits(value) => {
+            codec.put_u8(3)?;
+            codec.put_u64(value.0)?;
+        }
     }
     Ok(())
 }
@@ -591,6 +653,7 @@ fn unpack_pot_entry<R: std::io::BufRead>(
         0 => Ok(pots::Entry::Fees(Value(codec.get_u64()?))),
         1 => Ok(pots::Entry::Treasury(Value(codec.get_u64()?))),
         2 => Ok(pots::Entry::Rewards(Value(codec.get_u64()?))),
+        3 => Ok(pots::Entry::Deposits(Value(codec.get_u64()?))),
         code => Err(std::io::Error::new(
             std::io::ErrorKind::InvalidData,
             format!("Invalid Entry type code {}", code),
@@ -696,6 +759,14 @@ fn pack_pool_state<W: std::io::Write>(
 ) -> Result<(), std::io::Error> {
     pack_pool_last_rewards(&pool_state.last_rewards, codec)?;
     pack_pool_registration(&pool_state.registration, codec)?;
+    codec.put_u64(pool_state.deposit.0)?;
+    match pool_state.retirement_epoch {
+        None => codec.put_u8(0)?,
+        Some(epoch) => {
+            codec.put_u8(1)?;
+            codec.put_u32(epoch)?;
+        }
+    }
     Ok(())
 }
 
@@ -704,13 +775,130 @@ fn unpack_pool_state<R: std::io::BufRead>(
 ) -> Result<PoolState, std::io::Error> {
     let last_rewards = unpack_pool_last_rewards(codec)?;
     let registration = Arc::new(unpack_pool_registration(codec)?);
+    let deposit = Value(codec.get_u64()?);
+    let retirement_epoch = match codec.get_u8()? {
+        0 => None,
+        1 => Some(codec.get_u32()?),
+        code => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Invalid pool retirement epoch code {}", code),
+            ))
+        }
+    };
 
     Ok(PoolState {
         last_rewards,
         registration,
+        deposit,
+        retirement_epoch,
     })
 }
 
+fn pack_stake_distribution<W: std::io::Write>(
+    distribution: &StakeDistribution,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    codec.put_u64(distribution.unassigned.0)?;
+    codec.put_u64(distribution.dangling.0)?;
+    // sorted, so the same distribution is always packed the same way
+    let mut pools: Vec<_> = distribution.to_pools.iter().collect();
+    pools.sort_by_key(|(pool_id, _)| *pool_id);
+    codec.put_u64(pools.len() as u64)?;
+    for (pool_id, pool_info) in pools {
+        pack_pool_id(pool_id, codec)?;
+        match &pool_info.registration {
+            None => codec.put_u8(0)?,
+            Some(registration) => {
+                codec.put_u8(1)?;
+                pack_pool_registration(registration, codec)?;
+            }
+        }
+        codec.put_u64(pool_info.stake.total.0)?;
+        let mut accounts: Vec<_> = pool_info.stake.accounts.iter().collect();
+        accounts.sort_by_key(|(identifier, _)| *identifier);
+        codec.put_u64(accounts.len() as u64)?;
+        for (identifier, stake) in accounts {
+            pack_account_identifier(identifier, codec)?;
+            codec.put_u64(stake.0)?;
+        }
+    }
+    Ok(())
+}
+
+fn unpack_stake_distribution<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<StakeDistribution, std::io::Error> {
+    let mut distribution = StakeDistribution::empty();
+    distribution.unassigned = Stake(codec.get_u64()?);
+    distribution.dangling = Stake(codec.get_u64()?);
+    let pools_size = codec.get_u64()?;
+    for _ in 0..pools_size {
+        let pool_id = unpack_pool_id(codec)?;
+        let registration = match codec.get_u8()? {
+            0 => None,
+            1 => Some(Arc::new(unpack_pool_registration(codec)?)),
+            code => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Invalid pool registration presence code {}", code),
+                ))
+            }
+        };
+        let mut stake = PoolStakeDistribution::new();
+        stake.total = Stake(codec.get_u64()?);
+        let accounts_size = codec.get_u64()?;
+        for _ in 0..accounts_size {
+            let identifier = unpack_account_identifier(codec)?;
+            let account_stake = Stake(codec.get_u64()?);
+            stake.accounts.insert(identifier, account_stake);
+        }
+        distribution.to_pools.insert(
+            pool_id,
+            PoolStakeInformation {
+                registration,
+                stake,
+            },
+        );
+    }
+    Ok(distribution)
+}
+
+fn pack_stake_snapshot<W: std::io::Write>(
+    snapshot: &StakeSnapshot,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    codec.put_u32(snapshot.epoch())?;
+    pack_stake_distribution(snapshot.distribution(), codec)
+}
+
+fn unpack_stake_snapshot<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<StakeSnapshot, std::io::Error> {
+    let epoch = codec.get_u32()?;
+    let distribution = unpack_stake_distribution(codec)?;
+    Ok(StakeSnapshot::new(epoch, distribution))
+}
+
+fn pack_stake_snapshots<W: std::io::Write>(
+    snapshots: &StakeSnapshots,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    pack_stake_snapshot(snapshots.mark(), codec)?;
+    pack_stake_snapshot(snapshots.set(), codec)?;
+    pack_stake_snapshot(snapshots.go(), codec)?;
+    Ok(())
+}
+
+fn unpack_stake_snapshots<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<StakeSnapshots, std::io::Error> {
+    let mark = unpack_stake_snapshot(codec)?;
+    let set = unpack_stake_snapshot(codec)?;
+    let go = unpack_stake_snapshot(codec)?;
+    Ok(StakeSnapshots::from_parts(mark, set, go))
+}
+
 fn pack_update_proposal_state<W: std::io::Write>(
     update_proposal_state: &UpdateProposalState,
     codec: &mut Codec<W>,
@@ -818,6 +1006,7 @@ where
 {
     address_packer(&output.address, codec)?;
     codec.put_u64(output.value.0)?;
+    pack_output_lock(&output.lock, codec)?;
     Ok(())
 }
 
@@ -830,7 +1019,44 @@ where
 {
     let address = address_unpacker(codec)?;
     let value = Value(codec.get_u64()?);
-    Ok(Output { address, value })
+    let lock = unpack_output_lock(codec)?;
+    Ok(Output {
+        address,
+        value,
+        lock,
+    })
+}
+
+fn pack_output_lock<W: std::io::Write>(
+    lock: &Option<OutputLock>,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    match lock {
+        None => codec.put_u8(0)?,
+        Some(OutputLock::Until(date)) => {
+            codec.put_u8(1)?;
+            pack_block_date(*date, codec)?;
+        }
+        Some(OutputLock::Slots(slots)) => {
+            codec.put_u8(2)?;
+            codec.put_u32(*slots)?;
+        }
+    }
+    Ok(())
+}
+
+fn unpack_output_lock<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<Option<OutputLock>, std::io::Error> {
+    match codec.get_u8()? {
+        0 => Ok(None),
+        1 => Ok(Some(OutputLock::Until(unpack_block_date(codec)?))),
+        2 => Ok(Some(OutputLock::Slots(codec.get_u32()?))),
+        code => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid OutputLock type code {}", code),
+        )),
+    }
 }
 
 fn pack_old_addr<W: std::io::Write>(
@@ -965,6 +1191,38 @@ fn unpack_committee_public_keys<R: BufRead>(
     Ok(result)
 }
 
+fn pack_weighting<W: std::io::Write>(
+    weighting: vote::Weighting,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    match weighting {
+        vote::Weighting::Linear => codec.put_u8(vote::Weighting::TAG_LINEAR),
+        vote::Weighting::SquareRoot => codec.put_u8(vote::Weighting::TAG_SQUARE_ROOT),
+        vote::Weighting::Capped { max } => {
+            codec.put_u8(vote::Weighting::TAG_CAPPED)?;
+            codec.put_u64(max.0)
+        }
+        vote::Weighting::OneAccountOneVote => {
+            codec.put_u8(vote::Weighting::TAG_ONE_ACCOUNT_ONE_VOTE)
+        }
+    }
+}
+
+fn unpack_weighting<R: BufRead>(codec: &mut Codec<R>) -> Result<vote::Weighting, io::Error> {
+    match codec.get_u8()? {
+        vote::Weighting::TAG_LINEAR => Ok(vote::Weighting::Linear),
+        vote::Weighting::TAG_SQUARE_ROOT => Ok(vote::Weighting::SquareRoot),
+        vote::Weighting::TAG_CAPPED => Ok(vote::Weighting::Capped {
+            max: Stake(codec.get_u64()?),
+        }),
+        vote::Weighting::TAG_ONE_ACCOUNT_ONE_VOTE => Ok(vote::Weighting::OneAccountOneVote),
+        code => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown vote weighting code {}", code),
+        )),
+    }
+}
+
 fn pack_vote_plan<W: std::io::Write>(
     vote_plan: &VotePlan,
     codec: &mut Codec<W>,
@@ -975,6 +1233,7 @@ fn pack_vote_plan<W: std::io::Write>(
     pack_payload_type(vote_plan.payload_type(), codec)?;
     pack_vote_proposals(vote_plan.proposals(), codec)?;
     pack_committee_public_keys(vote_plan.committee_public_keys(), codec)?;
+    pack_weighting(vote_plan.weighting(), codec)?;
     Ok(())
 }
 
@@ -985,6 +1244,7 @@ fn unpack_vote_plan<R: std::io::BufRead>(codec: &mut Codec<R>) -> Result<VotePla
     let payload_type = unpack_payload_type(codec)?;
     let proposals = unpack_proposals(codec)?;
     let keys = unpack_committee_public_keys(codec)?;
+    let weighting = unpack_weighting(codec)?;
     Ok(VotePlan::new(
         vote_start,
         vote_end,
@@ -992,9 +1252,61 @@ fn unpack_vote_plan<R: std::io::BufRead>(codec: &mut Codec<R>) -> Result<VotePla
         proposals,
         payload_type,
         keys,
+        weighting,
     ))
 }
 
+fn pack_vote_delegations<W: std::io::Write>(
+    delegations: &vote::Delegations,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    // sorted, so the same delegations are always packed the same way
+    let mut delegations: Vec<_> = delegations.iter().collect();
+    delegations.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+    codec.put_u64(delegations.len() as u64)?;
+    for (delegator, representative) in delegations {
+        codec.put_bytes(delegator.as_ref())?;
+        match representative {
+            VoteRepresentative::Myself => codec.put_u8(0)?,
+            VoteRepresentative::Account(account) => {
+                codec.put_u8(1)?;
+                codec.put_bytes(account.as_ref())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn unpack_unspecified_account_identifier<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<UnspecifiedAccountIdentifier, std::io::Error> {
+    let mut bytes = [0; INPUT_PTR_SIZE];
+    bytes.copy_from_slice(&codec.get_bytes(INPUT_PTR_SIZE)?);
+    Ok(bytes.into())
+}
+
+fn unpack_vote_delegations<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<vote::Delegations, std::io::Error> {
+    let size = codec.get_u64()?;
+    let mut delegations = Vec::new();
+    for _ in 0..size {
+        let delegator = unpack_unspecified_account_identifier(codec)?;
+        let representative = match codec.get_u8()? {
+            0 => VoteRepresentative::Myself,
+            1 => VoteRepresentative::Account(unpack_unspecified_account_identifier(codec)?),
+            code => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Invalid vote representative type code {}", code),
+                ))
+            }
+        };
+        delegations.push((delegator, representative));
+    }
+    Ok(delegations.into_iter().collect())
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum EntrySerializeCode {
     Globals = 0,
@@ -1009,6 +1321,9 @@ enum EntrySerializeCode {
     StakePool = 9,
     LeaderParticipation = 10,
     VotePlan = 11,
+    StakeSnapshots = 12,
+    DeregisteredAccount = 13,
+    VoteDelegations = 14,
     SerializationEnd = 99,
 }
 
@@ -1027,6 +1342,9 @@ impl EntrySerializeCode {
             9 => Some(EntrySerializeCode::StakePool),
             10 => Some(EntrySerializeCode::LeaderParticipation),
             11 => Some(EntrySerializeCode::VotePlan),
+            12 => Some(EntrySerializeCode::StakeSnapshots),
+            13 => Some(EntrySerializeCode::DeregisteredAccount),
+            14 => Some(EntrySerializeCode::VoteDelegations),
             99 => Some(EntrySerializeCode::SerializationEnd),
             _ => None,
         }
@@ -1059,6 +1377,11 @@ fn pack_entry<W: std::io::Write>(
             pack_account_identifier(identifier, codec)?;
             pack_account_state(account_state, codec)?;
         }
+        Entry::DeregisteredAccount((identifier, spending)) => {
+            codec.put_u8(EntrySerializeCode::DeregisteredAccount as u8)?;
+            pack_account_identifier(identifier, codec)?;
+            pack_spending_counters(spending, codec)?;
+        }
         Entry::ConfigParam(config_param) => {
             codec.put_u8(EntrySerializeCode::ConfigParam as u8)?;
             pack_config_param(config_param, codec)?;
@@ -1092,6 +1415,14 @@ fn pack_entry<W: std::io::Write>(
             codec.put_u8(EntrySerializeCode::VotePlan as u8)?;
             pack_vote_plan(vote_plan, codec)?;
         }
+        Entry::StakeSnapshots(snapshots) => {
+            codec.put_u8(EntrySerializeCode::StakeSnapshots as u8)?;
+            pack_stake_snapshots(snapshots, codec)?;
+        }
+        Entry::VoteDelegations(delegations) => {
+            codec.put_u8(EntrySerializeCode::VoteDelegations as u8)?;
+            pack_vote_delegations(delegations, codec)?;
+        }
     }
     Ok(())
 }
@@ -1122,6 +1453,11 @@ fn unpack_entry_owned<R: std::io::BufRead>(
             let account = unpack_account_state(codec)?;
             Ok(EntryOwned::Account((identifier, account)))
         }
+        EntrySerializeCode::DeregisteredAccount => {
+            let identifier = unpack_account_identifier(codec)?;
+            let spending = unpack_spending_counters(codec)?;
+            Ok(EntryOwned::DeregisteredAccount((identifier, spending)))
+        }
         EntrySerializeCode::ConfigParam => Ok(EntryOwned::ConfigParam(unpack_config_param(codec)?)),
         EntrySerializeCode::UpdateProposal => {
             let proposal_id = unpack_update_proposal_id(codec)?;
@@ -1152,6 +1488,12 @@ fn unpack_entry_owned<R: std::io::BufRead>(
             let vote_plan = unpack_vote_plan(codec)?;
             Ok(EntryOwned::VotePlan(vote_plan))
         }
+        EntrySerializeCode::StakeSnapshots => {
+            Ok(EntryOwned::StakeSnapshots(unpack_stake_snapshots(codec)?))
+        }
+        EntrySerializeCode::VoteDelegations => {
+            Ok(EntryOwned::VoteDelegations(unpack_vote_delegations(codec)?))
+        }
         EntrySerializeCode::SerializationEnd => Ok(EntryOwned::StopEntry),
     }
 }
@@ -1208,7 +1550,7 @@ impl Deserialize for Ledger {
 #[cfg(test)]
 pub mod test {
     use super::*;
-    use crate::testing::{ConfigBuilder, LedgerBuilder, StakePoolBuilder};
+    use crate::testing::{ConfigBuilder, LedgerBuilder, StakePoolBuilder, TestGen};
     use cardano_legacy_address::Addr;
     use chain_crypto::Blake2b256;
     use quickcheck::{quickcheck, TestResult};
@@ -1360,6 +1702,7 @@ pub mod test {
             pots::Entry::Fees(Value(10)),
             pots::Entry::Rewards(Value(10)),
             pots::Entry::Treasury(Value(10)),
+            pots::Entry::Deposits(Value(10)),
         ]
         .iter()
         {
@@ -1403,6 +1746,13 @@ pub mod test {
                 owners: Vec::new(),
                 threshold: 10,
             }),
+            DeclElement::Sub(Declaration {
+                owners: vec![
+                    DeclElement::Owner(key::Hash::from_bytes(id_bytes)),
+                    DeclElement::Owner(key::Hash::from_bytes([0x2; 32])),
+                ],
+                threshold: 1,
+            }),
             DeclElement::Owner(key::Hash::from_bytes(id_bytes)),
         ]
         .iter()
@@ -1443,16 +1793,31 @@ pub mod test {
         let output: Output<()> = Output {
             address: (),
             value: Value(1000),
+            lock: None,
         };
+        let locks = [
+            None,
+            Some(OutputLock::Until(BlockDate {
+                epoch: 3,
+                slot_id: 7,
+            })),
+            Some(OutputLock::Slots(100)),
+        ];
 
-        let mut c = std::io::Cursor::new(Vec::new());
-        let mut codec = Codec::new(c);
-        pack_output(&output, &mut |_, _| Ok(()), &mut codec)?;
-        c = codec.into_inner();
-        c.set_position(0);
-        codec = Codec::new(c);
-        let other_output = unpack_output(&mut |_| Ok(()), &mut codec)?;
-        assert_eq!(output, other_output);
+        for lock in locks.iter() {
+            let output = Output {
+                lock: *lock,
+                ..output.clone()
+            };
+            let mut c = std::io::Cursor::new(Vec::new());
+            let mut codec = Codec::new(c);
+            pack_output(&output, &mut |_, _| Ok(()), &mut codec)?;
+            c = codec.into_inner();
+            c.set_position(0);
+            codec = Codec::new(c);
+            let other_output = unpack_output(&mut |_| Ok(()), &mut codec)?;
+            assert_eq!(output, other_output);
+        }
         Ok(())
     }
 
@@ -1472,6 +1837,81 @@ pub mod test {
         Ok(())
     }
 
+    #[test]
+    pub fn ledger_with_deregistered_account_serialize_deserialize_bijection(
+    ) -> Result<(), std::io::Error> {
+        let test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+            .faucet_value(Value(42000))
+            .build()
+            .expect("cannot build test ledger");
+
+        let mut ledger: Ledger = test_ledger.into();
+        let id = TestGen::identifier();
+        ledger.accounts = ledger
+            .accounts
+            .register_account(&id, Value::zero(), ())
+            .and_then(|accounts| accounts.deregister_account(&id))
+            .expect("cannot deregister the account");
+
+        let mut c = std::io::Cursor::new(Vec::new());
+        ledger.serialize(&mut c)?;
+        c.set_position(0);
+        let other_ledger = Ledger::deserialize(&mut c)?;
+        assert_eq!(other_ledger.accounts.iter_deregistered().count(), 1);
+        assert_eq!(ledger, other_ledger);
+        Ok(())
+    }
+
+    #[test]
+    pub fn ledger_with_vote_delegations_serialize_deserialize_bijection(
+    ) -> Result<(), std::io::Error> {
+        let test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+            .faucet_value(Value(42000))
+            .build()
+            .expect("cannot build test ledger");
+
+        let mut ledger: Ledger = test_ledger.into();
+        let alice = UnspecifiedAccountIdentifier::from_single_account(TestGen::identifier());
+        let bob = UnspecifiedAccountIdentifier::from_single_account(TestGen::identifier());
+        ledger.votes.delegations = ledger
+            .votes
+            .delegations
+            .delegate(
+                alice.clone(),
+                Some(VoteRepresentative::Account(bob.clone())),
+            )
+            .delegate(bob, Some(VoteRepresentative::Myself));
+
+        let mut c = std::io::Cursor::new(Vec::new());
+        ledger.serialize(&mut c)?;
+        c.set_position(0);
+        let other_ledger = Ledger::deserialize(&mut c)?;
+        assert_eq!(other_ledger.votes.delegations.iter().count(), 2);
+        assert_eq!(ledger, other_ledger);
+        Ok(())
+    }
+
+    #[test]
+    pub fn stake_snapshots_pack_unpack_bijection() -> Result<(), std::io::Error> {
+        let stake_pool = StakePoolBuilder::new().build();
+        let mut distribution = StakeDistribution::empty();
+        distribution.unassigned = Stake(10);
+        distribution.dangling = Stake(20);
+        let mut pool_info = PoolStakeInformation {
+            registration: Some(Arc::new(stake_pool.info())),
+            stake: PoolStakeDistribution::new(),
+        };
+        pool_info.add_value(&TestGen::public_key().into(), Stake(30));
+        distribution.to_pools.insert(stake_pool.id(), pool_info);
+
+        let snapshots = StakeSnapshots::new(StakeSnapshot::new(0, StakeDistribution::empty()))
+            .rotate(StakeSnapshot::new(1, distribution));
+        let result =
+            pack_unpack_bijection(&pack_stake_snapshots, &unpack_stake_snapshots, snapshots);
+        assert!(!result.is_failure());
+        Ok(())
+    }
+
     #[cfg(test)]
     fn pack_unpack_bijection<T, Pack, Unpack>(
         pack_method: &Pack,
@@ -0,0 +1,45 @@
+use crate::account;
+use crate::certificate::PoolId;
+use crate::date::Epoch;
+use crate::multisig;
+use crate::value::Value;
+use std::collections::BTreeMap;
+
+/// The stake pools retired at an epoch transition.
+///
+/// The delegators of a retired pool don't earn any rewards until they
+/// delegate to another pool, they are listed here so they can be warned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolRetirementsInfo {
+    /// The epoch starting with the retirements
+    pub epoch: Epoch,
+    /// Whether the delegations to the retired pools have been reset
+    pub delegations_reset: bool,
+    /// The retired pools, empty when no retirement was due
+    pub stake_pools: BTreeMap<PoolId, RetiredPoolInfo>,
+}
+
+/// The deposit refund and the delegators of a retired stake pool
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetiredPoolInfo {
+    /// Deposit of the pool registration refunded to the pool
+    pub deposit: Value,
+    /// Accounts delegating, even partially, to the pool
+    pub accounts: Vec<account::Identifier>,
+    /// Multisig accounts delegating, even partially, to the pool
+    pub multisig_accounts: Vec<multisig::Identifier>,
+}
+
+impl PoolRetirementsInfo {
+    pub fn new(epoch: Epoch, delegations_reset: bool) -> Self {
+        PoolRetirementsInfo {
+            epoch,
+            delegations_reset,
+            stake_pools: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stake_pools.is_empty()
+    }
+}
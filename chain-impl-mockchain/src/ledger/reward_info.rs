@@ -1,5 +1,6 @@
 use crate::account;
 use crate::certificate::PoolId;
+use crate::multisig;
 use crate::value::Value;
 use std::collections::BTreeMap;
 use std::default::Default;
@@ -41,8 +42,12 @@ pub struct EpochRewardsInfo {
     pub treasury: Value,
     /// Amount added to each pool id. structure can be empty.
     pub stake_pools: BTreeMap<PoolId, (Value, Value)>,
+    /// Amount cut from each pool reward by the saturation. structure can be empty.
+    pub stake_pools_saturation: BTreeMap<PoolId, Value>,
     /// Amount added to each account. structure can be empty.
     pub accounts: BTreeMap<account::Identifier, Value>,
+    /// Amount added to each multisig account. structure can be empty.
+    pub multisig_accounts: BTreeMap<multisig::Identifier, Value>,
 }
 
 impl EpochRewardsInfo {
@@ -53,7 +58,9 @@ impl EpochRewardsInfo {
             fees: Value::zero(),
             treasury: Value::zero(),
             stake_pools: BTreeMap::new(),
+            stake_pools_saturation: BTreeMap::new(),
             accounts: BTreeMap::new(),
+            multisig_accounts: BTreeMap::new(),
         }
     }
 
@@ -69,6 +76,12 @@ impl EpochRewardsInfo {
         }
     }
 
+    pub fn set_stake_pool_saturation(&mut self, pool: &PoolId, cut: Value) {
+        if self.params.report_stake_pools {
+            self.stake_pools_saturation.insert(pool.clone(), cut);
+        }
+    }
+
     pub fn add_to_account(&mut self, account: &account::Identifier, value: Value) {
         if self.params.report_accounts {
             let ent = self.accounts.entry(account.clone()).or_default();
@@ -76,6 +89,13 @@ impl EpochRewardsInfo {
         }
     }
 
+    pub fn add_to_multisig_account(&mut self, account: &multisig::Identifier, value: Value) {
+        if self.params.report_accounts {
+            let ent = self.multisig_accounts.entry(account.clone()).or_default();
+            *ent = (*ent + value).unwrap()
+        }
+    }
+
     pub fn total(&self) -> Value {
         (self.drawn + self.fees).unwrap()
     }
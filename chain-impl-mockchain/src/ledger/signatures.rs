@@ -0,0 +1,347 @@
+//! Verification in batch of the witness signatures of the fragments of a block
+
+use super::ledger::Ledger;
+use crate::fragment::{Fragment, FragmentId};
+use crate::multisig::WitnessMultisigData;
+use crate::transaction::*;
+use chain_crypto::{
+    Ed25519, Ed25519BatchVerification, Ed25519BatchVerifier, PublicKey, Signature, Verification,
+};
+use std::collections::{HashMap, HashSet};
+
+type SignatureKey = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+fn signature_key<A, T>(
+    publickey: &PublicKey<A>,
+    signature: &Signature<T, A>,
+    slice: &[u8],
+) -> SignatureKey
+where
+    A: Ed25519BatchVerification,
+    T: ?Sized,
+{
+    (
+        publickey.as_ref().to_vec(),
+        signature.as_ref().to_vec(),
+        slice.to_vec(),
+    )
+}
+
+/// The witness signatures of a block verified all at once with
+/// `Ledger::verify_signatures`. When applying the fragments of the block,
+/// only the signatures that are not part of it are verified.
+#[derive(Default)]
+pub struct VerifiedSignatures {
+    signatures: HashSet<SignatureKey>,
+}
+
+impl VerifiedSignatures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// verify the signature of `object`, unless it is already verified
+    pub fn verify<A, T>(
+        &self,
+        publickey: &PublicKey<A>,
+        signature: &Signature<T, A>,
+        object: &T,
+    ) -> Verification
+    where
+        A: Ed25519BatchVerification,
+        T: ?Sized + AsRef<[u8]>,
+    {
+        if self
+            .signatures
+            .contains(&signature_key(publickey, signature, object.as_ref()))
+        {
+            Verification::Success
+        } else {
+            signature.verify(publickey, object)
+        }
+    }
+}
+
+/// Collect the witness signatures of the fragments of a block. The public
+/// keys of the utxo inputs are guessed from the state of the ledger before
+/// the block, a wrong guess only means the signature is verified again when
+/// applying its fragment.
+struct SignatureBatch<'a> {
+    ledger: &'a Ledger,
+    batch: Ed25519BatchVerifier,
+    signatures: HashSet<SignatureKey>,
+    /// the utxos created by the previous fragments of the block
+    utxos: HashMap<(FragmentId, u8), PublicKey<Ed25519>>,
+}
+
+impl<'a> SignatureBatch<'a> {
+    fn new(ledger: &'a Ledger) -> Self {
+        SignatureBatch {
+            ledger,
+            batch: Ed25519BatchVerifier::new(),
+            signatures: HashSet::new(),
+            utxos: HashMap::new(),
+        }
+    }
+
+    fn queue<A, T>(&mut self, publickey: &PublicKey<A>, signature: &Signature<T, A>, object: &T)
+    where
+        A: Ed25519BatchVerification,
+        T: ?Sized + AsRef<[u8]>,
+    {
+        // signatures left out of the batch are verified when applying
+        // their fragment
+        if self.batch.queue(publickey, signature, object) {
+            self.signatures
+                .insert(signature_key(publickey, signature, object.as_ref()));
+        }
+    }
+
+    fn utxo_public_key(&self, utxo: &UtxoPointer) -> Option<PublicKey<Ed25519>> {
+        match self
+            .ledger
+            .utxos
+            .get(&utxo.transaction_id, utxo.output_index)
+        {
+            Some(entry) => entry.output.address.public_key().cloned(),
+            None => self
+                .utxos
+                .get(&(utxo.transaction_id, utxo.output_index))
+                .cloned(),
+        }
+    }
+
+    fn queue_fragment(&mut self, fragment: &Fragment) {
+        let fragment_id = fragment.hash();
+        match fragment {
+            Fragment::Initial(_)
+            | Fragment::OldUtxoDeclaration(_)
+            | Fragment::UpdateProposal(_)
+            | Fragment::UpdateVote(_) => {}
+            Fragment::Transaction(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::OwnerStakeDelegation(tx) => {
+                self.queue_transaction(&fragment_id, &tx.as_slice())
+            }
+            Fragment::StakeDelegation(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::PoolRegistration(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::PoolRetirement(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::PoolUpdate(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::VotePlan(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::VoteCast(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::VoteTally(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::EncryptedVoteTally(tx) => {
+                self.queue_transaction(&fragment_id, &tx.as_slice())
+            }
+            Fragment::VoteDelegation(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::RewardWithdrawal(tx) => self.queue_transaction(&fragment_id, &tx.as_slice()),
+            Fragment::AccountRegistration(tx) => {
+                self.queue_transaction(&fragment_id, &tx.as_slice())
+            }
+            Fragment::AccountDeregistration(tx) => {
+                self.queue_transaction(&fragment_id, &tx.as_slice())
+            }
+        }
+    }
+
+    fn queue_transaction<P: Payload>(
+        &mut self,
+        fragment_id: &FragmentId,
+        tx: &TransactionSlice<P>,
+    ) {
+        let block0_hash = self.ledger.static_params.block0_initial_hash;
+        let sign_data_hash = tx.transaction_sign_data_hash();
+        for (input, witness) in tx.inputs_and_witnesses().iter() {
+            match (input.to_enum(), &witness) {
+                (InputEnum::UtxoInput(utxo), Witness::Utxo(signature)) => {
+                    if let Some(publickey) = self.utxo_public_key(&utxo) {
+                        let data = WitnessUtxoData::new(
+                            &block0_hash,
+                            &sign_data_hash,
+                            WitnessUtxoVersion::Normal,
+                        );
+                        self.queue(&publickey, signature, &data);
+                    }
+                }
+                (InputEnum::AccountInput(account_id, _), Witness::Account(counter, signature)) => {
+                    let account = match account_id.to_single_account() {
+                        Some(account) => account,
+                        None => continue,
+                    };
+                    let data = WitnessAccountData::new(&block0_hash, &sign_data_hash, *counter);
+                    self.queue(account.as_ref(), signature, &data);
+                }
+                (InputEnum::AccountInput(_, _), Witness::Multisig(counter, witness)) => {
+                    let data = WitnessMultisigData::new(&block0_hash, &sign_data_hash, *counter);
+                    for (publickey, signature) in witness.signatures() {
+                        self.queue(publickey, signature, &data);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (index, output) in tx.outputs().iter().enumerate() {
+            if let Some(publickey) = output.address.public_key() {
+                self.utxos
+                    .insert((*fragment_id, index as u8), publickey.clone());
+            }
+        }
+    }
+
+    fn verify(self) -> VerifiedSignatures {
+        match self.batch.verify(rand_core::OsRng) {
+            Verification::Success => VerifiedSignatures {
+                signatures: self.signatures,
+            },
+            // the signatures are verified one by one when applying the
+            // fragments, to find the failing one
+            Verification::Failed => VerifiedSignatures::new(),
+        }
+    }
+}
+
+impl Ledger {
+    /// verify at once the witness signatures of the transactions of the
+    /// given fragments, rather than one signature at a time when applying
+    /// the fragments with `apply_fragment_verified`
+    pub fn verify_signatures<'a, I>(&self, fragments: I) -> VerifiedSignatures
+    where
+        I: IntoIterator<Item = &'a Fragment>,
+    {
+        let mut batch = SignatureBatch::new(self);
+        for fragment in fragments {
+            batch.queue_fragment(fragment);
+        }
+        batch.verify()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        date::BlockDate,
+        ledger::Error,
+        testing::{
+            data::AddressData,
+            scenario::{prepare_scenario, wallet, FragmentFactory},
+            ConfigBuilder, LedgerBuilder, TestGen, TestTxBuilder,
+        },
+        value::Value,
+    };
+    use chain_addr::Discrimination;
+
+    #[test]
+    fn verify_utxo_signatures() {
+        let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+            .faucet_value(Value(1000))
+            .build()
+            .unwrap();
+        let receiver = AddressData::utxo(Discrimination::Test);
+        let fragment = TestTxBuilder::new(test_ledger.block0_hash)
+            .move_from_faucet(&mut test_ledger, &receiver.address, Value(100))
+            .get_fragment();
+
+        let verified = test_ledger.ledger.verify_fragments(vec![&fragment]);
+        assert_eq!(verified.signatures.len(), 1);
+        assert!(test_ledger
+            .ledger
+            .apply_fragment_verified(
+                &test_ledger.parameters,
+                &fragment,
+                BlockDate::first(),
+                &verified
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn verify_account_signatures() {
+        let (mut test_ledger, controller) = prepare_scenario()
+            .with_initials(vec![wallet("Alice").with(1_000), wallet("Bob").with(1_000)])
+            .build()
+            .unwrap();
+        let mut alice = controller.wallet("Alice").unwrap();
+        let bob = controller.wallet("Bob").unwrap();
+        let fragment_factory = FragmentFactory::from_ledger(&test_ledger);
+
+        let mut fragments = Vec::new();
+        for _ in 0..3 {
+            fragments.push(fragment_factory.transaction(&alice, &bob, &mut test_ledger, 10));
+            alice.confirm_transaction();
+        }
+
+        let verified = test_ledger.ledger.verify_fragments(fragments.iter());
+        assert_eq!(verified.signatures.len(), 3);
+        let ledger = fragments
+            .iter()
+            .try_fold(test_ledger.ledger.clone(), |ledger, fragment| {
+                ledger.apply_fragment_verified(
+                    &test_ledger.parameters,
+                    fragment,
+                    BlockDate::first(),
+                    &verified,
+                )
+            });
+        assert!(ledger.is_ok());
+    }
+
+    #[test]
+    fn invalid_signature_is_reported_by_its_fragment() {
+        let (mut test_ledger, controller) = prepare_scenario()
+            .with_initials(vec![wallet("Alice").with(1_000), wallet("Bob").with(1_000)])
+            .build()
+            .unwrap();
+        let mut alice = controller.wallet("Alice").unwrap();
+        let bob = controller.wallet("Bob").unwrap();
+        let fragment_factory = FragmentFactory::from_ledger(&test_ledger);
+
+        let mut fragments = vec![
+            fragment_factory.transaction(&alice, &bob, &mut test_ledger, 10),
+            fragment_factory.transaction(&bob, &alice, &mut test_ledger, 10),
+        ];
+        alice.confirm_transaction();
+        // the last transaction is signed for another block0
+        fragments.push(
+            TestTxBuilder::new(TestGen::hash())
+                .move_funds(
+                    &mut test_ledger,
+                    &alice.as_account(),
+                    &bob.as_account(),
+                    Value(10),
+                )
+                .get_fragment(),
+        );
+
+        let verified = test_ledger.ledger.verify_fragments(fragments.iter());
+        assert!(verified.signatures.is_empty());
+
+        let ledger = fragments[..2]
+            .iter()
+            .try_fold(test_ledger.ledger.clone(), |ledger, fragment| {
+                ledger.apply_fragment_verified(
+                    &test_ledger.parameters,
+                    fragment,
+                    BlockDate::first(),
+                    &verified,
+                )
+            })
+            .unwrap();
+        assert!(matches!(
+            ledger.apply_fragment_verified(
+                &test_ledger.parameters,
+                &fragments[2],
+                BlockDate::first(),
+                &verified,
+            ),
+            Err(Error::AccountInvalidSignature { .. })
+        ));
+    }
+}
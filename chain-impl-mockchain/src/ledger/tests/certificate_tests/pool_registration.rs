@@ -1,21 +1,27 @@
 #![cfg(test)]
 
 use crate::{
-    certificate::PoolPermissions,
+    certificate::{
+        PoolMetadata, PoolPermissions, PoolRelay, POOL_MAX_RELAYS, POOL_METADATA_URL_MAX_SIZE,
+        POOL_RELAY_DNS_NAME_MAX_SIZE,
+    },
     date::BlockDate,
     ledger::{
         check::{CHECK_POOL_REG_MAXIMUM_OPERATORS, CHECK_POOL_REG_MAXIMUM_OWNERS},
-        Error,
+        Entry, Error, Ledger,
     },
+    multisig::{DeclElement, Declaration},
     testing::{
         builders::{build_stake_pool_registration_cert, StakePoolBuilder, TestTxCertBuilder},
         data::Wallet,
         ConfigBuilder, LedgerBuilder, TestGen,
     },
+    transaction::AccountIdentifier,
     value::*,
 };
 use chain_crypto::{Ed25519, PublicKey};
 use std::iter;
+use std::net::Ipv4Addr;
 
 #[test]
 pub fn pool_registration_is_accepted() {
@@ -158,6 +164,156 @@ pub fn pool_registration_too_many_operators() {
     );
 }
 
+const POOL_METADATA_DOCUMENT: &[u8] = br#"{"name":"pool","ticker":"POOL"}"#;
+
+fn pool_metadata() -> PoolMetadata {
+    PoolMetadata::new(
+        "https://pool.example.com/metadata.json".to_owned(),
+        PoolMetadata::document_hash(POOL_METADATA_DOCUMENT),
+    )
+}
+
+#[test]
+pub fn pool_registration_with_metadata_is_accepted() {
+    let alice = Wallet::from_value(Value(100));
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .faucets_wallets(vec![&alice])
+        .build()
+        .expect("cannot build test ledger");
+
+    let relays = vec![
+        PoolRelay::Ipv4 {
+            address: Ipv4Addr::new(192, 0, 2, 1),
+            port: 3000,
+        },
+        PoolRelay::DnsName {
+            name: "relay.pool.example.com".to_owned(),
+            port: 3000,
+        },
+    ];
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .with_pool_permissions(PoolPermissions::new(1))
+        .with_metadata(pool_metadata())
+        .with_relays(relays.clone())
+        .build();
+
+    let certificate = build_stake_pool_registration_cert(&stake_pool.info());
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(test_ledger.date(), &[alice], &certificate);
+    assert!(test_ledger
+        .apply_fragment(&fragment, test_ledger.date())
+        .is_ok());
+
+    let registration = test_ledger
+        .delegation()
+        .lookup_reg(&stake_pool.id())
+        .cloned()
+        .unwrap();
+    assert_eq!(registration.metadata, Some(pool_metadata()));
+    assert!(registration
+        .metadata
+        .as_ref()
+        .unwrap()
+        .matches_document(POOL_METADATA_DOCUMENT));
+    assert_eq!(registration.relays, relays);
+
+    let pool_entry = test_ledger.ledger.iter().find_map(|entry| match entry {
+        Entry::StakePool((id, state)) if *id == stake_pool.id() => {
+            Some(state.registration.as_ref().clone())
+        }
+        _ => None,
+    });
+    assert_eq!(pool_entry, Some(registration));
+
+    let restored: Result<Ledger, _> = test_ledger.ledger.iter().collect();
+    assert!(restored.unwrap() == test_ledger.ledger);
+}
+
+#[test]
+pub fn pool_registration_metadata_url_too_long() {
+    let alice = Wallet::from_value(Value(100));
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .faucets_wallets(vec![&alice])
+        .build()
+        .expect("cannot build test ledger");
+
+    let mut metadata = pool_metadata();
+    metadata.url = "a".repeat(POOL_METADATA_URL_MAX_SIZE + 1);
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .with_pool_permissions(PoolPermissions::new(1))
+        .with_metadata(metadata)
+        .build();
+
+    let certificate = build_stake_pool_registration_cert(&stake_pool.info());
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(test_ledger.date(), &[alice], &certificate);
+    assert_err!(
+        Error::PoolRegistrationMetadataUrlTooLong,
+        test_ledger.apply_fragment(&fragment, BlockDate::first())
+    );
+}
+
+#[test]
+pub fn pool_registration_too_many_relays() {
+    let alice = Wallet::from_value(Value(100));
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .faucets_wallets(vec![&alice])
+        .build()
+        .expect("cannot build test ledger");
+
+    let relays = (0..=POOL_MAX_RELAYS)
+        .map(|i| PoolRelay::Ipv4 {
+            address: Ipv4Addr::new(192, 0, 2, i as u8),
+            port: 3000,
+        })
+        .collect();
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .with_pool_permissions(PoolPermissions::new(1))
+        .with_relays(relays)
+        .build();
+
+    let certificate = build_stake_pool_registration_cert(&stake_pool.info());
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(test_ledger.date(), &[alice], &certificate);
+    assert_err!(
+        Error::PoolRegistrationHasTooManyRelays,
+        test_ledger.apply_fragment(&fragment, BlockDate::first())
+    );
+}
+
+#[test]
+pub fn pool_registration_relay_dns_name_too_long() {
+    let alice = Wallet::from_value(Value(100));
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .faucets_wallets(vec![&alice])
+        .build()
+        .expect("cannot build test ledger");
+
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .with_pool_permissions(PoolPermissions::new(1))
+        .with_relays(vec![PoolRelay::DnsName {
+            name: "a".repeat(POOL_RELAY_DNS_NAME_MAX_SIZE + 1),
+            port: 3000,
+        }])
+        .build();
+
+    let certificate = build_stake_pool_registration_cert(&stake_pool.info());
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(test_ledger.date(), &[alice], &certificate);
+    assert_err!(
+        Error::PoolRegistrationRelayDnsNameTooLong,
+        test_ledger.apply_fragment(&fragment, BlockDate::first())
+    );
+}
+
 #[test]
 #[should_panic]
 pub fn pool_registration_zero_signatures() {
@@ -207,3 +363,47 @@ pub fn pool_registration_too_many_signatures() {
         test_ledger.apply_fragment(&fragment, test_ledger.date())
     );
 }
+
+#[test]
+pub fn pool_registration_multisig_reward_account() {
+    let alice = Wallet::from_value(Value(100));
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .faucets_wallets(vec![&alice])
+        .build()
+        .expect("cannot build test ledger");
+
+    let declaration = Declaration {
+        threshold: 1,
+        owners: vec![
+            DeclElement::from_publickey(&alice.public_key()),
+            DeclElement::from_publickey(&TestGen::public_key()),
+        ],
+    };
+    let account = declaration.to_identifier();
+
+    let mut stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .with_pool_permissions(PoolPermissions::new(1))
+        .build();
+    stake_pool.info_mut().reward_account = Some(AccountIdentifier::Multi(account.clone()));
+
+    let certificate = build_stake_pool_registration_cert(&stake_pool.info());
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(test_ledger.date(), &[alice], &certificate);
+    assert_err!(
+        Error::PoolRegistrationMultisigRewardAccountUnknown {
+            account: account.clone()
+        },
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    );
+
+    test_ledger.ledger.multisig = test_ledger
+        .ledger
+        .multisig
+        .add_account(&declaration)
+        .unwrap();
+    test_ledger
+        .apply_fragment(&fragment, test_ledger.date())
+        .unwrap();
+}
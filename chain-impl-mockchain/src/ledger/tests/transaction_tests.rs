@@ -1,10 +1,12 @@
 #![cfg(test)]
 
 use crate::{
-    accounting::account::LedgerError::NonExistent,
+    accounting::account::{
+        LedgerError::{NonExistent, SpendingCounterInvalid},
+        SpendingCounter,
+    },
     date::BlockDate,
     ledger::{
-        self,
         check::{TxValidityError, TxVerifyError},
         Error::{Account, InvalidTransactionValidity, TransactionMalformed},
     },
@@ -29,6 +31,7 @@ pub fn transaction_fail_when_255_outputs() {
     let output = Output {
         address: receiver.address,
         value: Value(1),
+        lock: None,
     };
     let outputs: Vec<_> = std::iter::repeat(output).take(255).collect();
 
@@ -56,6 +59,7 @@ pub fn transaction_fail_when_validity_out_of_range() {
     let output = Output {
         address: receiver.address,
         value: Value(1),
+        lock: None,
     };
     let outputs = [output];
 
@@ -95,6 +99,7 @@ pub fn transaction_fail_when_validity_too_far() {
     let output = Output {
         address: receiver.address,
         value: Value(1),
+        lock: None,
     };
 
     let valid_until = BlockDate {
@@ -131,7 +136,7 @@ pub fn duplicated_account_transaction() {
         Err(err) => panic!("first transaction should be succesful but {}", err),
         Ok(_) => {
             assert_err_match!(
-                ledger::Error::AccountInvalidSignature { .. },
+                Account(SpendingCounterInvalid { .. }),
                 test_ledger.apply_transaction(fragment2, BlockDate::first())
             );
         }
@@ -172,12 +177,63 @@ pub fn transaction_with_incorrect_account_spending_counter() {
     let fragment = TestTxBuilder::new(test_ledger.block0_hash)
         .move_from_faucet(&mut test_ledger, &receiver.into(), Value(1000))
         .get_fragment();
-    assert!(
-        test_ledger
-            .apply_transaction(fragment, BlockDate::first())
-            .is_err(),
-        "first transaction should be successful"
+    assert_err!(
+        Account(SpendingCounterInvalid {
+            expected: SpendingCounter::zero(),
+            actual: SpendingCounter::from(1),
+        }),
+        test_ledger.apply_transaction(fragment, BlockDate::first())
+    );
+}
+
+#[test]
+pub fn transactions_in_different_lanes_can_be_applied_in_any_order() {
+    let faucet = AddressDataValue::account(Discrimination::Test, Value(1000));
+    let receiver = AddressDataValue::utxo(Discrimination::Test, Value(0));
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .faucet(&faucet)
+        .build()
+        .expect("cannot build test ledger");
+
+    let lane_0 = faucet.with_spending_counter(SpendingCounter::new(0, 0));
+    let lane_1 = faucet.with_spending_counter(SpendingCounter::new(1, 0));
+    let lane_1_next = faucet.with_spending_counter(SpendingCounter::new(1, 1));
+
+    let fragments: Vec<_> = [&lane_1_next, &lane_0, &lane_1]
+        .iter()
+        .map(|source| {
+            TestTxBuilder::new(test_ledger.block0_hash)
+                .move_funds(&mut test_ledger, source, &receiver, Value(100))
+                .get_fragment()
+        })
+        .collect();
+
+    // the second spending of lane 1 can't be applied before the first one
+    assert_err!(
+        Account(SpendingCounterInvalid {
+            expected: SpendingCounter::new(1, 0),
+            actual: SpendingCounter::new(1, 1),
+        }),
+        test_ledger.apply_transaction(fragments[0].clone(), BlockDate::first())
     );
+
+    // lane 1 is spent before lane 0
+    for index in &[2, 1, 0] {
+        assert!(test_ledger
+            .apply_transaction(fragments[*index].clone(), BlockDate::first())
+            .is_ok());
+    }
+
+    let state = test_ledger
+        .ledger
+        .accounts()
+        .get_state(&faucet.to_id())
+        .unwrap();
+    assert_eq!(state.value(), Value(700));
+    let counters = state.spending().get_valid_counters();
+    assert_eq!(counters[0], SpendingCounter::new(0, 1));
+    assert_eq!(counters[1], SpendingCounter::new(1, 2));
 }
 
 #[test]
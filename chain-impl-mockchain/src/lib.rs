@@ -24,6 +24,7 @@ pub mod milli;
 pub mod multisig;
 pub mod multiverse;
 pub mod rewards;
+pub mod script;
 pub mod setting;
 pub mod stake;
 pub mod transaction;
@@ -35,8 +35,8 @@ pub enum DeclarationError {
     HasNotEnoughOwners,
     #[error("Too many owners")]
     HasTooManyOwners,
-    #[error("Sub not implemented")]
-    SubNotImplemented,
+    #[error("Sub declaration nested too deeply")]
+    SubTooDeep,
 }
 
 impl std::fmt::Display for Identifier {
@@ -49,6 +49,9 @@ impl std::fmt::Display for Identifier {
 ///
 /// * a threshold that need to be between 1 and the size of owners
 /// * a bunch of owners which is either a hash of a key, or a sub declaration
+///
+/// Sub declarations cannot have sub declarations of their own, as
+/// witnesses are indexed with at most 2 levels of `TreeIndex`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Declaration {
     pub(crate) threshold: u8, // between 1 and len(owners)
@@ -102,6 +105,19 @@ impl Declaration {
     }
 
     pub fn is_valid(&self) -> Result<(), DeclarationError> {
+        self.is_valid_level()?;
+        for owner in self.owners.iter() {
+            if let DeclElement::Sub(sub) = owner {
+                sub.is_valid_level()?;
+                if sub.owners.iter().any(|o| matches!(o, DeclElement::Sub(_))) {
+                    return Err(DeclarationError::SubTooDeep);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_valid_level(&self) -> Result<(), DeclarationError> {
         if self.threshold < 1 || self.threshold as usize > self.owners.len() {
             return Err(DeclarationError::ThresholdInvalid);
         }
@@ -3,7 +3,8 @@ use std::collections::hash_map::DefaultHasher;
 use thiserror::Error;
 
 use super::declaration::{Declaration, DeclarationError, Identifier};
-use crate::accounting::account::{self, DelegationType, Iter, SpendingCounter};
+use crate::accounting::account::{self, AccountState, DelegationType, Iter, SpendingCounter};
+use crate::date::Epoch;
 use crate::value::{Value, ValueError};
 
 #[derive(Clone, PartialEq, Eq, Default)]
@@ -99,6 +100,50 @@ impl Ledger {
         })
     }
 
+    /// Add rewards to a declared account.
+    ///
+    /// If the account is not declared, errors out.
+    pub fn add_rewards_to_account(
+        &self,
+        identifier: &Identifier,
+        epoch: Epoch,
+        value: Value,
+    ) -> Result<Self, LedgerError> {
+        if !self.exists(identifier) {
+            return Err(LedgerError::DoesntExist);
+        }
+        let new_accounts = self
+            .accounts
+            .add_rewards_to_account(identifier, epoch, value, ())
+            .map_err(account::LedgerError::from)?;
+        Ok(Self {
+            accounts: new_accounts,
+            declarations: self.declarations.clone(),
+        })
+    }
+
+    /// Move rewards of a declared account to its value.
+    pub fn withdraw_rewards(
+        &self,
+        identifier: &Identifier,
+        value: Value,
+    ) -> Result<Self, LedgerError> {
+        let new_accounts = self.accounts.withdraw_rewards(identifier, value)?;
+        Ok(Self {
+            accounts: new_accounts,
+            declarations: self.declarations.clone(),
+        })
+    }
+
+    /// Check if the account is declared in this ledger
+    pub fn exists(&self, identifier: &Identifier) -> bool {
+        self.declarations.lookup(identifier).is_some()
+    }
+
+    pub fn get_state(&self, identifier: &Identifier) -> Result<&AccountState<()>, LedgerError> {
+        Ok(self.accounts.get_state(identifier)?)
+    }
+
     pub fn iter_accounts(&self) -> Iter<'_, Identifier, ()> {
         self.accounts.iter()
     }
@@ -107,24 +152,25 @@ impl Ledger {
         self.declarations.iter()
     }
 
-    /// If the account doesn't exist, or that the value would become negative, errors out.
+    /// If the account doesn't exist, that the counter is not the next one of
+    /// its lane, or that the value would become negative, errors out.
     pub fn remove_value(
         &self,
         identifier: &Identifier,
+        counter: SpendingCounter,
         value: Value,
-    ) -> Result<(Self, &Declaration, SpendingCounter), LedgerError> {
+    ) -> Result<(Self, &Declaration), LedgerError> {
         let decl = self
             .declarations
             .lookup(identifier)
             .ok_or(LedgerError::DoesntExist)?;
-        let (new_accts, spending_counter) = self.accounts.remove_value(identifier, value)?;
+        let new_accts = self.accounts.remove_value(identifier, counter, value)?;
         Ok((
             Self {
                 accounts: new_accts,
                 declarations: self.declarations.clone(),
             },
             decl,
-            spending_counter,
         ))
     }
 
@@ -141,6 +141,160 @@ mod test {
         }
     }
 
+    #[test]
+    fn multisig_works_depth2() {
+        use chain_core::mempack::{ReadBuf, Readable};
+        use chain_core::property::Serialize;
+
+        let mut rng = rand_core::OsRng;
+        // 2 of (board 2/3, ops 1/2, cfo)
+        let board = (0..3)
+            .map(|i| make_participant(&mut rng, i))
+            .collect::<Vec<_>>();
+        let ops = (0..2)
+            .map(|i| make_participant(&mut rng, i))
+            .collect::<Vec<_>>();
+        let (sk_cfo, pk_cfo, o_cfo, _) = make_participant(&mut rng, 2);
+        let sub = |threshold, participants: &[(_, _, key::Hash, _)]| {
+            DeclElement::Sub(Declaration {
+                threshold,
+                owners: participants
+                    .iter()
+                    .map(|(_, _, o, _)| DeclElement::Owner(*o))
+                    .collect(),
+            })
+        };
+        let decl = Declaration {
+            threshold: 2,
+            owners: vec![sub(2, &board), sub(1, &ops), DeclElement::Owner(o_cfo)],
+        };
+        assert_eq!(decl.is_valid(), Ok(()));
+
+        let fake_sign_data: TransactionSignData = vec![1, 2, 3].into();
+        let fake_sign_data_hash = TransactionSignDataHash::digest(&fake_sign_data);
+        let fake_block0_hash = key::Hash::hash_bytes(&[1, 2, 3, 4, 5, 6, 7]);
+        let msg = WitnessMultisigData::new(
+            &fake_block0_hash,
+            &fake_sign_data_hash,
+            SpendingCounter::zero(),
+        );
+        let board_index = Index::from_u8(0).unwrap();
+        let ops_index = Index::from_u8(1).unwrap();
+        let cfo_index = Index::from_u8(2).unwrap();
+
+        // board (2 of 3) and cfo
+        {
+            let mut witness_builder = WitnessBuilder::new();
+            for (sk, pk, _, i) in board.iter().skip(1) {
+                witness_builder.append(
+                    TreeIndex::D2(board_index, *i),
+                    pk.clone(),
+                    sk.sign(&msg).coerce(),
+                );
+            }
+            witness_builder.append(
+                TreeIndex::D1(cfo_index),
+                pk_cfo.clone(),
+                sk_cfo.sign(&msg).coerce(),
+            );
+            let witness = witness_builder.finalize();
+            assert!(
+                witness.verify(&decl, &msg),
+                "multisignature [board+cfo] failed"
+            );
+
+            let bytes = witness.serialize_as_vec().unwrap();
+            let witness = Witness::read(&mut ReadBuf::from(&bytes)).unwrap();
+            assert!(
+                witness.verify(&decl, &msg),
+                "deserialized multisignature failed"
+            );
+        }
+
+        // ops (1 of 2) and board (1 of 3) do not meet the board threshold
+        {
+            let mut witness_builder = WitnessBuilder::new();
+            let (sk, pk, _, i) = &board[0];
+            witness_builder.append(
+                TreeIndex::D2(board_index, *i),
+                pk.clone(),
+                sk.sign(&msg).coerce(),
+            );
+            let (sk, pk, _, i) = &ops[1];
+            witness_builder.append(
+                TreeIndex::D2(ops_index, *i),
+                pk.clone(),
+                sk.sign(&msg).coerce(),
+            );
+            let witness = witness_builder.finalize();
+            assert!(
+                !witness.verify(&decl, &msg),
+                "multisignature [ops+1 board] succeeded"
+            );
+        }
+
+        // a sub declaration witness cannot be used for a key owner
+        {
+            let mut witness_builder = WitnessBuilder::new();
+            let (sk, pk, _, i) = &ops[0];
+            witness_builder.append(
+                TreeIndex::D2(ops_index, *i),
+                pk.clone(),
+                sk.sign(&msg).coerce(),
+            );
+            witness_builder.append(
+                TreeIndex::D2(cfo_index, Index::from_u8(0).unwrap()),
+                pk_cfo,
+                sk_cfo.sign(&msg).coerce(),
+            );
+            let witness = witness_builder.finalize();
+            assert!(
+                !witness.verify(&decl, &msg),
+                "multisignature [ops+cfo as sub] succeeded"
+            );
+        }
+    }
+
+    #[test]
+    fn nested_declaration_validity() {
+        let owner = |n: u8| DeclElement::Owner(key::Hash::hash_bytes(&[n]));
+        let flat = Declaration {
+            threshold: 1,
+            owners: vec![owner(0), owner(1)],
+        };
+        let nested = Declaration {
+            threshold: 1,
+            owners: vec![owner(2), DeclElement::Sub(flat.clone())],
+        };
+        assert_eq!(nested.is_valid(), Ok(()));
+        assert!(Ledger::new().add_account(&nested).is_ok());
+
+        let invalid_sub = Declaration {
+            threshold: 1,
+            owners: vec![
+                owner(2),
+                DeclElement::Sub(Declaration {
+                    threshold: 3,
+                    owners: flat.owners.clone(),
+                }),
+            ],
+        };
+        assert_eq!(
+            invalid_sub.is_valid(),
+            Err(DeclarationError::ThresholdInvalid)
+        );
+
+        let too_deep = Declaration {
+            threshold: 1,
+            owners: vec![owner(3), DeclElement::Sub(nested)],
+        };
+        assert_eq!(too_deep.is_valid(), Err(DeclarationError::SubTooDeep));
+        assert!(matches!(
+            Ledger::new().add_account(&too_deep),
+            Err(LedgerError::DeclarationError(DeclarationError::SubTooDeep))
+        ));
+    }
+
     impl Arbitrary for Identifier {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
             let mut b = [0u8; 32];
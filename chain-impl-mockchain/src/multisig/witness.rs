@@ -33,27 +33,34 @@ impl std::fmt::Display for Witness {
 }
 
 impl Witness {
+    /// the public keys and signatures of the witness
+    pub fn signatures(&self) -> impl Iterator<Item = (&Pk, &Sig)> {
+        self.0.iter().map(|(_, pk, sig)| (pk, sig))
+    }
+
     pub fn verify(&self, declaration: &Declaration, msg: &WitnessMultisigData) -> bool {
+        self.verify_with(declaration, |pk, sig| {
+            sig.verify(pk, msg) == Verification::Success
+        })
+    }
+
+    /// same as `verify`, but the signatures are checked with `verify_signature`
+    pub fn verify_with<F>(&self, declaration: &Declaration, verify_signature: F) -> bool
+    where
+        F: Fn(&Pk, &Sig) -> bool,
+    {
         let mut v = Vec::new();
+        let mut subs: BTreeMap<Index, Vec<(Index, Pk)>> = BTreeMap::new();
         for (ti, pk, sig) in self.0.iter() {
+            if !verify_signature(pk, sig) {
+                return false;
+            };
             match ti {
-                TreeIndex::D2(_, _) => {
-                    // Code doesn't support multi level verification yet
-                    return false;
-                }
-                TreeIndex::D1(i) => {
-                    //let sig: Signature<[u8], account::AccountAlg> = sig.clone().coerce();
-                    if sig.verify(pk, msg) == Verification::Failed {
-                        return false;
-                    };
-                    v.push((*i, pk.clone()))
-                }
+                TreeIndex::D1(i) => v.push((*i, pk.clone())),
+                TreeIndex::D2(r, i) => subs.entry(*r).or_default().push((*i, pk.clone())),
             }
         }
-        if verify_identifier_threshold(declaration, &v[..]).is_err() {
-            return false;
-        };
-        true
+        verify_nested_identifier_threshold(declaration, &v[..], &subs).is_ok()
     }
 }
 
@@ -99,7 +106,7 @@ impl Readable for Witness {
         let mut v = vec![(first_index, first_key, first_sig)];
 
         let mut prev_index = first_index;
-        for _ in 0..len {
+        for _ in 1..len {
             let ti = deserialize_index(buf)?;
             if ti <= prev_index {
                 return Err(ReadError::StructureInvalid(
@@ -146,7 +153,37 @@ pub fn verify_identifier_threshold(
     declaration: &Declaration,
     witnesses: &[(Index, PublicKey<Ed25519>)],
 ) -> Result<(), LedgerError> {
-    if witnesses.len() < declaration.threshold() {
+    verify_level(declaration, witnesses, &[])
+}
+
+/// Same as `verify_identifier_threshold` for a declaration with sub
+/// declarations: the witnesses of each sub declaration, indexed by the
+/// position of the sub declaration in the owners, need to fulfill the
+/// requirements of the sub declaration, which then counts as one witness
+/// toward the threshold of the declaration.
+pub fn verify_nested_identifier_threshold(
+    declaration: &Declaration,
+    witnesses: &[(Index, PublicKey<Ed25519>)],
+    sub_witnesses: &BTreeMap<Index, Vec<(Index, PublicKey<Ed25519>)>>,
+) -> Result<(), LedgerError> {
+    let mut satisfied_subs = Vec::new();
+    for (r, witnesses) in sub_witnesses.iter() {
+        match declaration.owners.get(r.to_usize()) {
+            None => return Err(LedgerError::ParticipantOutOfBound),
+            Some(DeclElement::Owner(_)) => return Err(LedgerError::IdentifierMismatch),
+            Some(DeclElement::Sub(sub)) => verify_identifier_threshold(sub, witnesses)?,
+        }
+        satisfied_subs.push(*r);
+    }
+    verify_level(declaration, witnesses, &satisfied_subs)
+}
+
+fn verify_level(
+    declaration: &Declaration,
+    witnesses: &[(Index, PublicKey<Ed25519>)],
+    satisfied_subs: &[Index],
+) -> Result<(), LedgerError> {
+    if witnesses.len() + satisfied_subs.len() < declaration.threshold() {
         return Err(LedgerError::ThresholdNotMet);
     }
 
@@ -157,6 +194,9 @@ pub fn verify_identifier_threshold(
         if idx >= opt.len() {
             return Err(LedgerError::ParticipantOutOfBound);
         }
+        if satisfied_subs.contains(i) {
+            return Err(LedgerError::IdentifierMismatch);
+        }
         opt[idx] = Some(w.clone())
     }
     let mut r = Vec::new();
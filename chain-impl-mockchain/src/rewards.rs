@@ -1,5 +1,6 @@
 use crate::date::Epoch;
-use crate::stake::Stake;
+use crate::setting::ActiveSlotsCoeff;
+use crate::stake::{PercentStake, Stake};
 use crate::value::{Value, ValueError};
 use chain_core::mempack::{ReadBuf, ReadError};
 use std::num::{NonZeroU32, NonZeroU64};
@@ -99,6 +100,20 @@ impl TaxType {
     }
 }
 
+/// Saturation of the stake pools rewards
+///
+/// A pool is saturated once it holds more than `1 / target_nb_pools` of the
+/// total stake, the rewards of the stake above the saturation point are cut.
+/// The owners' pledge increases the rewards of the pool up to the saturation
+/// point, by at most the `pledge_influence` factor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolSaturation {
+    /// Number of stake pools the system is aiming for
+    pub target_nb_pools: NonZeroU32,
+    /// Influence of the pledge on the rewards
+    pub pledge_influence: Ratio,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Limit {
     /// the drawn value will not be limited
@@ -133,6 +148,13 @@ pub struct Parameters {
     /// Pool Capping
     /// This doesn't really make sense
     pub pool_participation_capping: Option<(NonZeroU32, NonZeroU32)>,
+    /// Reward the pools for their share of the stake rather than of the
+    /// blocks, reduced for the pools producing less blocks than expected
+    /// from their stake, the difference going to the treasury
+    pub pool_performance_adjusted: bool,
+    /// Cut the rewards of the saturated pools and of the pools with
+    /// little pledge, the difference going to the treasury
+    pub pool_saturation: Option<PoolSaturation>,
 }
 
 impl Parameters {
@@ -145,6 +167,8 @@ impl Parameters {
             epoch_start: 0,
             reward_drawing_limit_max: Limit::None,
             pool_participation_capping: None,
+            pool_performance_adjusted: false,
+            pool_saturation: None,
         }
     }
 }
@@ -267,13 +291,109 @@ pub fn tax_cut(v: Value, tax_type: &TaxType) -> Result<TaxDistribution, ValueErr
     })
 }
 
+/// The number of blocks a pool holding `stake` is expected to produce in an
+/// epoch of `slots_per_epoch` slots, each slot electing the pool with the
+/// probability `1 - (1 - f)^stake`
+pub fn expected_blocks(
+    slots_per_epoch: u32,
+    active_slots_coeff: ActiveSlotsCoeff,
+    stake: &PercentStake,
+) -> f64 {
+    let f: f64 = active_slots_coeff.into();
+    slots_per_epoch as f64 * (1.0 - (1.0 - f).powf(stake.as_float()))
+}
+
+/// The part of `reward` matching the share of `total_stake` held by a pool
+/// with `pool_stake`, the reward of the pool when it produces all its
+/// expected blocks
+pub fn stake_share(reward: Value, pool_stake: Stake, total_stake: Stake) -> Value {
+    if total_stake.0 == 0 {
+        return Value::zero();
+    }
+    Value((reward.0 as u128 * pool_stake.0 as u128 / total_stake.0 as u128) as u64)
+}
+
+/// Cut the reward of a pool which produced `produced` blocks out of the
+/// `expected` ones, the pool keeping `reward * produced / expected`.
+///
+/// The expected number of blocks is rounded to a thousandth of block so
+/// that the cut itself is computed with integers.
+pub fn performance_cut(reward: Value, produced: u32, expected: f64) -> TaxDistribution {
+    const SCALE: u128 = 1_000;
+    let expected = (expected * SCALE as f64).round() as u128;
+    let produced = produced as u128 * SCALE;
+    if produced >= expected {
+        return TaxDistribution {
+            taxed: Value::zero(),
+            after_tax: reward,
+        };
+    }
+    let after_tax = Value(((reward.0 as u128 * produced) / expected) as u64);
+    TaxDistribution {
+        taxed: (reward - after_tax).unwrap(),
+        after_tax,
+    }
+}
+
+/// Cut the reward of a pool holding `pool_stake` (out of which `pledge` is
+/// delegated by its owners) out of `total_stake`.
+///
+/// With `z = 1 / target_nb_pools`, `σ` the relative stake of the pool, `s` the
+/// relative pledge and `a` the pledge influence, the pool keeps:
+///
+/// ```text
+/// reward * min(σ, z) / σ * (1 + a * min(s, z) / z) / (1 + a)
+/// ```
+///
+/// so that only a saturated pool fully pledged by its owners keeps all its reward.
+pub fn saturation_cut(
+    reward: Value,
+    pool_stake: Stake,
+    pledge: Stake,
+    total_stake: Stake,
+    saturation: &PoolSaturation,
+) -> TaxDistribution {
+    const SCALE: u128 = 1_000_000;
+    if pool_stake.0 == 0 {
+        return TaxDistribution {
+            taxed: Value::zero(),
+            after_tax: reward,
+        };
+    }
+
+    let saturation_stake = std::cmp::max(
+        1,
+        total_stake.0 as u128 / saturation.target_nb_pools.get() as u128,
+    );
+    let pool_stake = pool_stake.0 as u128;
+    let capped_stake = std::cmp::min(pool_stake, saturation_stake);
+    let capped_pledge = std::cmp::min(pledge.0 as u128, saturation_stake);
+
+    // pledge factor, scaled by SCALE
+    let num = saturation.pledge_influence.numerator as u128;
+    let den = saturation.pledge_influence.denominator.get() as u128;
+    let pledge_scaled = capped_pledge * SCALE / saturation_stake;
+    let pledge_factor = (den * SCALE + num * pledge_scaled) / (den + num);
+
+    let after_saturation = reward.0 as u128 * capped_stake / pool_stake;
+    let after_tax = Value((after_saturation * pledge_factor / SCALE) as u64);
+    TaxDistribution {
+        taxed: (reward - after_tax).unwrap(),
+        after_tax,
+    }
+}
+
 #[cfg(any(test, feature = "property-test-api"))]
 mod tests {
     use super::*;
     #[cfg(test)]
+    use crate::milli::Milli;
+    #[cfg(test)]
     use quickcheck::TestResult;
     use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
+    #[cfg(test)]
+    use std::convert::TryFrom;
 
     #[quickcheck]
     fn tax_cut_fully_accounted(v: Value, treasury_tax: TaxType) -> TestResult {
@@ -341,6 +461,8 @@ mod tests {
             epoch_start: 0,
             reward_drawing_limit_max: Limit::None,
             pool_participation_capping: None,
+            pool_performance_adjusted: false,
+            pool_saturation: None,
         };
         let epoch = 1;
         let system_info = SystemInformation {
@@ -352,6 +474,143 @@ mod tests {
         );
     }
 
+    #[quickcheck]
+    fn performance_cut_fully_accounted(reward: Value, produced: u32, expected: u32) -> bool {
+        let td = performance_cut(reward, produced, expected as f64);
+        (td.taxed + td.after_tax).unwrap() == reward && td.after_tax <= reward
+    }
+
+    #[test]
+    fn performance_cut_proportional_to_missed_blocks() {
+        let td = performance_cut(Value(1000), 3, 4.0);
+        assert_eq!(td.after_tax, Value(750));
+        assert_eq!(td.taxed, Value(250));
+
+        // producing more blocks than expected is not rewarded more
+        let td = performance_cut(Value(1000), 5, 4.0);
+        assert_eq!(td.after_tax, Value(1000));
+        assert_eq!(td.taxed, Value::zero());
+
+        let td = performance_cut(Value(1000), 0, 0.0);
+        assert_eq!(td.after_tax, Value(1000));
+    }
+
+    #[quickcheck]
+    fn stake_shares_do_not_exceed_the_reward(reward: Value, stakes: Vec<u32>) -> bool {
+        let total_stake = Stake(stakes.iter().map(|stake| *stake as u64).sum());
+        let shares = stakes
+            .iter()
+            .map(|stake| stake_share(reward, Stake(*stake as u64), total_stake));
+        Value::sum(shares).unwrap() <= reward
+    }
+
+    #[test]
+    fn expected_blocks_follow_the_election_probability() {
+        let active_slots_coeff = ActiveSlotsCoeff::try_from(Milli::HALF).unwrap();
+        let all = PercentStake::new(Stake(100), Stake(100));
+        let half = PercentStake::new(Stake(50), Stake(100));
+        let none = PercentStake::new(Stake(0), Stake(100));
+        assert!((expected_blocks(1000, active_slots_coeff, &all) - 500.0).abs() < 1e-9);
+        assert!(expected_blocks(1000, active_slots_coeff, &half) > 250.0);
+        assert!(expected_blocks(1000, active_slots_coeff, &none).abs() < 1e-9);
+    }
+
+    fn saturation(target_nb_pools: u32, numerator: u64, denominator: u64) -> PoolSaturation {
+        PoolSaturation {
+            target_nb_pools: NonZeroU32::new(target_nb_pools).unwrap(),
+            pledge_influence: Ratio {
+                numerator,
+                denominator: NonZeroU64::new(denominator).unwrap(),
+            },
+        }
+    }
+
+    #[quickcheck]
+    fn saturation_cut_fully_accounted(
+        reward: Value,
+        pool_stake: Stake,
+        pledge: Stake,
+        target_nb_pools: NonZeroU32,
+        pledge_influence: Ratio,
+    ) -> TestResult {
+        if pledge > pool_stake {
+            return TestResult::discard();
+        }
+        let total_stake = Stake(pool_stake.0.saturating_mul(2));
+        let saturation = PoolSaturation {
+            target_nb_pools,
+            pledge_influence,
+        };
+        let td = saturation_cut(reward, pool_stake, pledge, total_stake, &saturation);
+        TestResult::from_bool(
+            (td.taxed + td.after_tax).unwrap() == reward && td.after_tax <= reward,
+        )
+    }
+
+    #[test]
+    fn saturation_cut_above_saturation() {
+        // 4 pools expected, the pool holds half of the stake with full pledge
+        let td = saturation_cut(
+            Value(1000),
+            Stake(500),
+            Stake(500),
+            Stake(1000),
+            &saturation(4, 0, 1),
+        );
+        assert_eq!(td.after_tax, Value(500));
+        assert_eq!(td.taxed, Value(500));
+
+        // under saturation, nothing is cut without pledge influence
+        let td = saturation_cut(
+            Value(1000),
+            Stake(100),
+            Stake(0),
+            Stake(1000),
+            &saturation(4, 0, 1),
+        );
+        assert_eq!(td.after_tax, Value(1000));
+    }
+
+    #[test]
+    fn saturation_cut_rewards_pledge() {
+        // saturated pool fully pledged keeps everything
+        let td = saturation_cut(
+            Value(1000),
+            Stake(250),
+            Stake(250),
+            Stake(1000),
+            &saturation(4, 1, 1),
+        );
+        assert_eq!(td.after_tax, Value(1000));
+
+        // zero pledge halves the reward with an influence of 1
+        let td = saturation_cut(
+            Value(1000),
+            Stake(250),
+            Stake(0),
+            Stake(1000),
+            &saturation(4, 1, 1),
+        );
+        assert_eq!(td.after_tax, Value(500));
+
+        // splitting the pledge in two pools reduces the rewards
+        let single = saturation_cut(
+            Value(1000),
+            Stake(200),
+            Stake(100),
+            Stake(1000),
+            &saturation(4, 1, 1),
+        );
+        let half = saturation_cut(
+            Value(500),
+            Stake(100),
+            Stake(50),
+            Stake(1000),
+            &saturation(4, 1, 1),
+        );
+        assert!(single.after_tax > (half.after_tax + half.after_tax).unwrap());
+    }
+
     impl Arbitrary for TaxType {
         fn arbitrary<G: Gen>(gen: &mut G) -> Self {
             let fixed = Arbitrary::arbitrary(gen);
@@ -398,6 +657,8 @@ mod tests {
                 epoch_start: Arbitrary::arbitrary(g),
                 reward_drawing_limit_max: Limit::arbitrary(g),
                 pool_participation_capping: None,
+                pool_performance_adjusted: Arbitrary::arbitrary(g),
+                pool_saturation: None,
             }
         }
     }
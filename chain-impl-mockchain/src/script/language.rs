@@ -0,0 +1,359 @@
+use crate::date::BlockDate;
+use crate::key;
+use chain_core::mempack::{ReadBuf, ReadError, Readable};
+use chain_core::property;
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+/// Maximum nesting of combinators in a script
+pub const MAX_SCRIPT_DEPTH: usize = 8;
+/// Maximum number of nodes (leaves and combinators) in a script
+pub const MAX_SCRIPT_NODES: usize = 64;
+/// Maximum size in bytes of a hash preimage revealed in a witness
+pub const MAX_PREIMAGE_SIZE: usize = 64;
+
+const SCRIPT_TAG_SIGNATURE: u8 = 0;
+const SCRIPT_TAG_AFTER: u8 = 1;
+const SCRIPT_TAG_BEFORE: u8 = 2;
+const SCRIPT_TAG_HASH_PREIMAGE: u8 = 3;
+const SCRIPT_TAG_ALL: u8 = 4;
+const SCRIPT_TAG_ANY: u8 = 5;
+const SCRIPT_TAG_AT_LEAST: u8 = 6;
+
+/// Script Identifier, the hash of the serialized script
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Identifier(key::Hash);
+
+impl AsRef<[u8]> for Identifier {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl From<[u8; 32]> for Identifier {
+    fn from(a: [u8; 32]) -> Self {
+        Identifier(a.into())
+    }
+}
+
+impl From<Identifier> for [u8; 32] {
+    fn from(a: Identifier) -> Self {
+        a.0.into()
+    }
+}
+
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    #[error("Script combinator without sub scripts")]
+    Empty,
+    #[error("Too many sub scripts in a combinator")]
+    TooManySubScripts,
+    #[error("Invalid threshold")]
+    ThresholdInvalid,
+    #[error("Script nested too deeply")]
+    TooDeep,
+    #[error("Script has too many nodes")]
+    TooManyNodes,
+    #[error("Hash preimage of {size} bytes is bigger than {max} bytes")]
+    PreimageTooBig { size: usize, max: usize },
+    #[error("Script witness has too many hash preimages")]
+    TooManyPreimages,
+    #[error("Script witness has too many signatures")]
+    TooManySignatures,
+}
+
+/// Spending condition of a script address.
+///
+/// * `Signature`: a signature by the key whose hash is given
+/// * `After`: the spending block is at or after the given date
+/// * `Before`: the spending block is strictly before the given date
+/// * `HashPreimage`: data hashing to the given hash is revealed
+/// * `All`, `Any` and `AtLeast`: all, one or a threshold of the sub scripts
+///   are satisfied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Script {
+    Signature(key::Hash),
+    After(BlockDate),
+    Before(BlockDate),
+    HashPreimage(key::Hash),
+    All(Vec<Script>),
+    Any(Vec<Script>),
+    AtLeast(u8, Vec<Script>),
+}
+
+impl Script {
+    /// Get the identifier associated with a script, the one used in
+    /// `Kind::Script` addresses
+    pub fn to_identifier(&self) -> Identifier {
+        Identifier(key::Hash::hash_bytes(&self.to_bytes()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_script(self, &mut bytes);
+        bytes
+    }
+
+    pub fn is_valid(&self) -> Result<(), ScriptError> {
+        let mut nodes = 0;
+        self.check(0, &mut nodes)
+    }
+
+    fn check(&self, depth: usize, nodes: &mut usize) -> Result<(), ScriptError> {
+        if depth >= MAX_SCRIPT_DEPTH {
+            return Err(ScriptError::TooDeep);
+        }
+        *nodes += 1;
+        if *nodes > MAX_SCRIPT_NODES {
+            return Err(ScriptError::TooManyNodes);
+        }
+        let scripts = match self {
+            Script::Signature(_)
+            | Script::After(_)
+            | Script::Before(_)
+            | Script::HashPreimage(_) => return Ok(()),
+            Script::All(scripts) | Script::Any(scripts) => scripts,
+            Script::AtLeast(threshold, scripts) => {
+                if *threshold < 1 || *threshold as usize > scripts.len() {
+                    return Err(ScriptError::ThresholdInvalid);
+                }
+                scripts
+            }
+        };
+        if scripts.is_empty() {
+            return Err(ScriptError::Empty);
+        }
+        if scripts.len() > u8::MAX as usize {
+            return Err(ScriptError::TooManySubScripts);
+        }
+        scripts
+            .iter()
+            .try_for_each(|script| script.check(depth + 1, nodes))
+    }
+
+    /// Evaluate the script at the given date, given the hashes of the keys
+    /// that validly signed the transaction and the hashes of the revealed
+    /// preimages.
+    pub(super) fn is_satisfied(
+        &self,
+        date: BlockDate,
+        signers: &BTreeSet<key::Hash>,
+        preimages: &BTreeSet<key::Hash>,
+    ) -> bool {
+        let satisfied = |scripts: &[Script]| {
+            scripts
+                .iter()
+                .filter(|script| script.is_satisfied(date, signers, preimages))
+                .count()
+        };
+        match self {
+            Script::Signature(hash) => signers.contains(hash),
+            Script::After(after) => date >= *after,
+            Script::Before(before) => date < *before,
+            Script::HashPreimage(hash) => preimages.contains(hash),
+            Script::All(scripts) => satisfied(scripts) == scripts.len(),
+            Script::Any(scripts) => satisfied(scripts) >= 1,
+            Script::AtLeast(threshold, scripts) => satisfied(scripts) >= *threshold as usize,
+        }
+    }
+}
+
+impl property::Serialize for Script {
+    type Error = std::io::Error;
+
+    fn serialize<W: std::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        let mut bytes = Vec::new();
+        write_script(self, &mut bytes);
+        writer.write_all(&bytes)
+    }
+}
+
+// not generic over the writer, as recursing on a generic writer would not
+// be bounded at compile time
+fn write_script(script: &Script, bytes: &mut Vec<u8>) {
+    let put_scripts = |bytes: &mut Vec<u8>, scripts: &[Script]| {
+        bytes.push(scripts.len() as u8);
+        for script in scripts {
+            write_script(script, bytes);
+        }
+    };
+    let put_date = |bytes: &mut Vec<u8>, date: &BlockDate| {
+        bytes.extend_from_slice(&date.epoch.to_be_bytes());
+        bytes.extend_from_slice(&date.slot_id.to_be_bytes());
+    };
+
+    match script {
+        Script::Signature(hash) => {
+            bytes.push(SCRIPT_TAG_SIGNATURE);
+            bytes.extend_from_slice(hash.as_ref());
+        }
+        Script::After(date) => {
+            bytes.push(SCRIPT_TAG_AFTER);
+            put_date(bytes, date);
+        }
+        Script::Before(date) => {
+            bytes.push(SCRIPT_TAG_BEFORE);
+            put_date(bytes, date);
+        }
+        Script::HashPreimage(hash) => {
+            bytes.push(SCRIPT_TAG_HASH_PREIMAGE);
+            bytes.extend_from_slice(hash.as_ref());
+        }
+        Script::All(scripts) => {
+            bytes.push(SCRIPT_TAG_ALL);
+            put_scripts(bytes, scripts);
+        }
+        Script::Any(scripts) => {
+            bytes.push(SCRIPT_TAG_ANY);
+            put_scripts(bytes, scripts);
+        }
+        Script::AtLeast(threshold, scripts) => {
+            bytes.push(SCRIPT_TAG_AT_LEAST);
+            bytes.push(*threshold);
+            put_scripts(bytes, scripts);
+        }
+    }
+}
+
+// depth and number of nodes are checked while reading, so a malicious
+// script cannot make the reader recurse or allocate without bounds
+fn read_script(buf: &mut ReadBuf, depth: usize, nodes: &mut usize) -> Result<Script, ReadError> {
+    if depth >= MAX_SCRIPT_DEPTH {
+        return Err(ReadError::StructureInvalid(
+            ScriptError::TooDeep.to_string(),
+        ));
+    }
+    *nodes += 1;
+    if *nodes > MAX_SCRIPT_NODES {
+        return Err(ReadError::StructureInvalid(
+            ScriptError::TooManyNodes.to_string(),
+        ));
+    }
+
+    let mut read_scripts = |buf: &mut ReadBuf| {
+        let len = buf.get_u8()?;
+        (0..len)
+            .map(|_| read_script(buf, depth + 1, nodes))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let read_date = |buf: &mut ReadBuf| -> Result<BlockDate, ReadError> {
+        let epoch = buf.get_u32()?;
+        let slot_id = buf.get_u32()?;
+        Ok(BlockDate { epoch, slot_id })
+    };
+
+    match buf.get_u8()? {
+        SCRIPT_TAG_SIGNATURE => key::Hash::read(buf).map(Script::Signature),
+        SCRIPT_TAG_AFTER => read_date(buf).map(Script::After),
+        SCRIPT_TAG_BEFORE => read_date(buf).map(Script::Before),
+        SCRIPT_TAG_HASH_PREIMAGE => key::Hash::read(buf).map(Script::HashPreimage),
+        SCRIPT_TAG_ALL => read_scripts(buf).map(Script::All),
+        SCRIPT_TAG_ANY => read_scripts(buf).map(Script::Any),
+        SCRIPT_TAG_AT_LEAST => {
+            let threshold = buf.get_u8()?;
+            read_scripts(buf).map(|scripts| Script::AtLeast(threshold, scripts))
+        }
+        i => Err(ReadError::UnknownTag(i as u32)),
+    }
+}
+
+impl Readable for Script {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let mut nodes = 0;
+        let script = read_script(buf, 0, &mut nodes)?;
+        script
+            .is_valid()
+            .map_err(|e| ReadError::StructureInvalid(e.to_string()))?;
+        Ok(script)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::serialization::serialization_bijection_r;
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+
+    fn signature(n: u8) -> Script {
+        Script::Signature(key::Hash::hash_bytes(&[n]))
+    }
+
+    #[quickcheck]
+    fn script_serialization_bijection(script: Script) -> TestResult {
+        serialization_bijection_r(script)
+    }
+
+    #[quickcheck]
+    fn arbitrary_script_is_valid(script: Script) -> bool {
+        script.is_valid().is_ok()
+    }
+
+    #[test]
+    fn invalid_scripts() {
+        assert_eq!(Script::All(vec![]).is_valid(), Err(ScriptError::Empty));
+        assert_eq!(
+            Script::AtLeast(0, vec![signature(0)]).is_valid(),
+            Err(ScriptError::ThresholdInvalid)
+        );
+        assert_eq!(
+            Script::AtLeast(2, vec![signature(0)]).is_valid(),
+            Err(ScriptError::ThresholdInvalid)
+        );
+
+        let deep = (0..MAX_SCRIPT_DEPTH).fold(signature(0), |script, _| Script::All(vec![script]));
+        assert_eq!(deep.is_valid(), Err(ScriptError::TooDeep));
+        assert!(Script::read(&mut ReadBuf::from(&deep.to_bytes())).is_err());
+
+        let wide = Script::Any((0..MAX_SCRIPT_NODES as u8).map(signature).collect());
+        assert_eq!(wide.is_valid(), Err(ScriptError::TooManyNodes));
+        assert!(Script::read(&mut ReadBuf::from(&wide.to_bytes())).is_err());
+    }
+
+    #[test]
+    fn identifier_commits_to_script() {
+        let script = Script::Any(vec![signature(0), signature(1)]);
+        assert_eq!(script.to_identifier(), script.clone().to_identifier());
+        assert_ne!(
+            script.to_identifier(),
+            Script::All(vec![signature(0), signature(1)]).to_identifier()
+        );
+    }
+
+    #[test]
+    fn evaluation() {
+        let date = |epoch| BlockDate { epoch, slot_id: 0 };
+        let hash = |n: u8| key::Hash::hash_bytes(&[n]);
+        let set = |hashes: &[key::Hash]| hashes.iter().cloned().collect::<BTreeSet<_>>();
+        let none = BTreeSet::new();
+
+        // vesting: the beneficiary after epoch 10, or the owner at any time
+        let vesting = Script::Any(vec![
+            Script::All(vec![signature(0), Script::After(date(10))]),
+            signature(1),
+        ]);
+        assert!(!vesting.is_satisfied(date(9), &set(&[hash(0)]), &none));
+        assert!(vesting.is_satisfied(date(10), &set(&[hash(0)]), &none));
+        assert!(vesting.is_satisfied(date(0), &set(&[hash(1)]), &none));
+        assert!(!vesting.is_satisfied(date(20), &none, &none));
+
+        // escrow: 2 of 3 signatures, or a refund before epoch 5 with a secret
+        let escrow = Script::Any(vec![
+            Script::AtLeast(2, vec![signature(0), signature(1), signature(2)]),
+            Script::All(vec![
+                Script::Before(date(5)),
+                Script::HashPreimage(hash(42)),
+            ]),
+        ]);
+        assert!(!escrow.is_satisfied(date(0), &set(&[hash(2)]), &none));
+        assert!(escrow.is_satisfied(date(0), &set(&[hash(0), hash(2)]), &none));
+        assert!(escrow.is_satisfied(date(4), &none, &set(&[hash(42)])));
+        assert!(!escrow.is_satisfied(date(5), &none, &set(&[hash(42)])));
+    }
+}
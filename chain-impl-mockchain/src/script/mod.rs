@@ -0,0 +1,79 @@
+//! Scripts locking the funds sent to `Kind::Script` addresses.
+//!
+//! A script is a small predicate built from signature checks, hash
+//! preimages and timelocks combined with AND/OR/threshold nodes. There is
+//! no loop and no arithmetic: the evaluation of a script is bounded by its
+//! size, which is itself bounded by `MAX_SCRIPT_NODES` and `MAX_SCRIPT_DEPTH`.
+//!
+//! Outputs only commit to the identifier (hash) of the script; the script
+//! itself is revealed along with a `Witness` when the output is spent.
+
+mod language;
+mod witness;
+
+pub use language::{
+    Identifier, Script, ScriptError, MAX_PREIMAGE_SIZE, MAX_SCRIPT_DEPTH, MAX_SCRIPT_NODES,
+};
+pub use witness::{Witness, WitnessBuilder, WitnessScriptData};
+
+#[cfg(any(test, feature = "property-test-api"))]
+mod test {
+    use super::*;
+    use crate::{account, date::BlockDate, key};
+    use chain_crypto::{testing::arbitrary_secret_key, SecretKey, Signature};
+    use quickcheck::{Arbitrary, Gen};
+
+    // at most 3 levels of combinators of at most 3 sub scripts, to stay
+    // below MAX_SCRIPT_NODES
+    fn arbitrary_script<G: Gen>(g: &mut G, depth: usize) -> Script {
+        let leaf = depth >= 3 || bool::arbitrary(g);
+        let option = if leaf {
+            u8::arbitrary(g) % 4
+        } else {
+            4 + u8::arbitrary(g) % 3
+        };
+        let sub_scripts = |g: &mut G| {
+            let len = 1 + usize::arbitrary(g) % 3;
+            (0..len)
+                .map(|_| arbitrary_script(g, depth + 1))
+                .collect::<Vec<_>>()
+        };
+        match option {
+            0 => Script::Signature(key::Hash::arbitrary(g)),
+            1 => Script::After(BlockDate::arbitrary(g)),
+            2 => Script::Before(BlockDate::arbitrary(g)),
+            3 => Script::HashPreimage(key::Hash::arbitrary(g)),
+            4 => Script::All(sub_scripts(g)),
+            5 => Script::Any(sub_scripts(g)),
+            _ => {
+                let scripts = sub_scripts(g);
+                let threshold = 1 + u8::arbitrary(g) % scripts.len() as u8;
+                Script::AtLeast(threshold, scripts)
+            }
+        }
+    }
+
+    impl Arbitrary for Script {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            arbitrary_script(g, 0)
+        }
+    }
+
+    impl Arbitrary for Witness {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let mut builder = WitnessBuilder::new();
+            for _ in 0..usize::arbitrary(g) % 3 {
+                let sk: SecretKey<account::AccountAlg> = arbitrary_secret_key(g);
+                builder
+                    .append_signature(sk.to_public(), Signature::arbitrary(g))
+                    .unwrap();
+            }
+            for _ in 0..usize::arbitrary(g) % 3 {
+                let len = usize::arbitrary(g) % MAX_PREIMAGE_SIZE;
+                let preimage = (0..len).map(|_| u8::arbitrary(g)).collect();
+                builder.append_preimage(preimage).unwrap();
+            }
+            builder.finalize()
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::account;
+use crate::date::BlockDate;
+use crate::key::{
+    self, deserialize_public_key, deserialize_signature, serialize_public_key, serialize_signature,
+};
+use chain_core::mempack::{ReadBuf, ReadError, Readable};
+use chain_core::property;
+use chain_crypto::{PublicKey, Signature, Verification};
+use std::collections::BTreeSet;
+
+use super::language::{Script, ScriptError, MAX_PREIMAGE_SIZE};
+pub use crate::transaction::WitnessScriptData;
+
+pub type Pk = PublicKey<account::AccountAlg>;
+pub type Sig = Signature<WitnessScriptData, account::AccountAlg>;
+
+/// Witness for script: the signatures of the transaction and the hash
+/// preimages needed to satisfy the script being spent
+#[derive(Debug, Clone)]
+pub struct Witness {
+    signatures: Vec<(Pk, Sig)>,
+    preimages: Vec<Vec<u8>>,
+}
+
+impl PartialEq for Witness {
+    fn eq(&self, other: &Self) -> bool {
+        self.preimages == other.preimages
+            && self.signatures.len() == other.signatures.len()
+            && self
+                .signatures
+                .iter()
+                .zip(other.signatures.iter())
+                .all(|((pk1, s1), (pk2, s2))| pk1 == pk2 && s1.as_ref() == s2.as_ref())
+    }
+}
+impl Eq for Witness {}
+
+impl std::fmt::Display for Witness {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Script Witness {} signatures {} preimages",
+            self.signatures.len(),
+            self.preimages.len()
+        )
+    }
+}
+
+impl Witness {
+    pub fn signatures(&self) -> &[(Pk, Sig)] {
+        &self.signatures
+    }
+
+    pub fn preimages(&self) -> &[Vec<u8>] {
+        &self.preimages
+    }
+
+    /// Check that the script is satisfied at the given date. Every
+    /// signature of the witness needs to be valid, even the ones that
+    /// are not needed by the script.
+    pub fn verify(&self, script: &Script, date: BlockDate, msg: &WitnessScriptData) -> bool {
+        let mut signers = BTreeSet::new();
+        for (pk, sig) in self.signatures.iter() {
+            if sig.verify(pk, msg) == Verification::Failed {
+                return false;
+            }
+            signers.insert(key::Hash::hash_bytes(pk.as_ref()));
+        }
+        let preimages = self
+            .preimages
+            .iter()
+            .map(|preimage| key::Hash::hash_bytes(preimage))
+            .collect();
+        script.is_satisfied(date, &signers, &preimages)
+    }
+}
+
+impl property::Serialize for Witness {
+    type Error = std::io::Error;
+
+    fn serialize<W: std::io::Write>(&self, writer: W) -> Result<(), Self::Error> {
+        use chain_core::packer::*;
+
+        let mut codec = Codec::new(writer);
+        codec.put_u8(self.signatures.len() as u8)?;
+        for (pk, sig) in self.signatures.iter() {
+            serialize_public_key(pk, &mut codec)?;
+            serialize_signature(sig, &mut codec)?;
+        }
+        codec.put_u8(self.preimages.len() as u8)?;
+        for preimage in self.preimages.iter() {
+            codec.put_u8(preimage.len() as u8)?;
+            codec.put_bytes(preimage)?;
+        }
+        Ok(())
+    }
+}
+
+impl Readable for Witness {
+    fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let signatures = (0..buf.get_u8()?)
+            .map(|_| {
+                let pk = deserialize_public_key(buf)?;
+                let sig = deserialize_signature(buf)?;
+                Ok((pk, sig))
+            })
+            .collect::<Result<Vec<_>, ReadError>>()?;
+        let preimages = (0..buf.get_u8()?)
+            .map(|_| {
+                let len = buf.get_u8()? as usize;
+                if len > MAX_PREIMAGE_SIZE {
+                    return Err(ReadError::SizeTooBig(len, MAX_PREIMAGE_SIZE));
+                }
+                Ok(buf.get_slice(len)?.to_vec())
+            })
+            .collect::<Result<Vec<_>, ReadError>>()?;
+        Ok(Witness {
+            signatures,
+            preimages,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct WitnessBuilder {
+    signatures: Vec<(Pk, Sig)>,
+    preimages: Vec<Vec<u8>>,
+}
+
+impl WitnessBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append_signature(&mut self, publickey: Pk, sig: Sig) -> Result<(), ScriptError> {
+        if self.signatures.len() >= u8::MAX as usize {
+            return Err(ScriptError::TooManySignatures);
+        }
+        self.signatures.push((publickey, sig));
+        Ok(())
+    }
+
+    pub fn append_preimage(&mut self, preimage: Vec<u8>) -> Result<(), ScriptError> {
+        if preimage.len() > MAX_PREIMAGE_SIZE {
+            return Err(ScriptError::PreimageTooBig {
+                size: preimage.len(),
+                max: MAX_PREIMAGE_SIZE,
+            });
+        }
+        if self.preimages.len() >= u8::MAX as usize {
+            return Err(ScriptError::TooManyPreimages);
+        }
+        self.preimages.push(preimage);
+        Ok(())
+    }
+
+    pub fn finalize(&self) -> Witness {
+        Witness {
+            signatures: self.signatures.clone(),
+            preimages: self.preimages.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::serialization::serialization_bijection_r;
+    use crate::transaction::{TransactionSignData, TransactionSignDataHash};
+    use chain_crypto::SecretKey;
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn witness_serialization_bijection(witness: Witness) -> TestResult {
+        serialization_bijection_r(witness)
+    }
+
+    #[test]
+    fn verify() {
+        let rng = rand_core::OsRng;
+        let sk1 = SecretKey::<account::AccountAlg>::generate(rng);
+        let sk2 = SecretKey::<account::AccountAlg>::generate(rng);
+        let block0 = key::Hash::hash_bytes(&[1, 2, 3]);
+        let sign_data_hash =
+            |data: Vec<u8>| TransactionSignDataHash::digest(&TransactionSignData::from(data));
+        let msg = WitnessScriptData::new(&block0, &sign_data_hash(vec![1]));
+        let other_msg = WitnessScriptData::new(&block0, &sign_data_hash(vec![2]));
+        let date = |epoch| BlockDate { epoch, slot_id: 0 };
+
+        let script = Script::All(vec![
+            Script::Signature(key::Hash::hash_bytes(sk1.to_public().as_ref())),
+            Script::After(date(1)),
+            Script::HashPreimage(key::Hash::hash_bytes(b"secret")),
+        ]);
+
+        let mut builder = WitnessBuilder::new();
+        builder
+            .append_signature(sk1.to_public(), sk1.sign(&msg))
+            .unwrap();
+        builder.append_preimage(b"secret".to_vec()).unwrap();
+        let witness = builder.finalize();
+        assert!(witness.verify(&script, date(1), &msg));
+        assert!(!witness.verify(&script, date(0), &msg));
+        assert!(!witness.verify(&script, date(1), &other_msg));
+
+        // an invalid extra signature invalidates the witness
+        builder
+            .append_signature(sk2.to_public(), sk2.sign(&other_msg))
+            .unwrap();
+        assert!(!builder.finalize().verify(&script, date(1), &msg));
+
+        let mut builder = WitnessBuilder::new();
+        builder
+            .append_signature(sk2.to_public(), sk2.sign(&msg))
+            .unwrap();
+        builder.append_preimage(b"secret".to_vec()).unwrap();
+        assert!(!builder.finalize().verify(&script, date(1), &msg));
+    }
+
+    #[test]
+    fn builder_limits() {
+        let mut builder = WitnessBuilder::new();
+        assert_eq!(
+            builder.append_preimage(vec![0; MAX_PREIMAGE_SIZE + 1]),
+            Err(ScriptError::PreimageTooBig {
+                size: MAX_PREIMAGE_SIZE + 1,
+                max: MAX_PREIMAGE_SIZE,
+            })
+        );
+        for _ in 0..u8::MAX {
+            builder.append_preimage(vec![0; MAX_PREIMAGE_SIZE]).unwrap();
+        }
+        assert_eq!(
+            builder.append_preimage(Vec::new()),
+            Err(ScriptError::TooManyPreimages)
+        );
+        assert_eq!(builder.finalize().preimages().len(), u8::MAX as usize);
+    }
+}
@@ -6,11 +6,12 @@ use crate::milli::Milli;
 use crate::update;
 use crate::{
     chaineval::PraosNonce,
-    chaintypes::ConsensusType,
+    chaintypes::{ConsensusType, KesAlgorithm, VrfAlgorithm},
     config::{ConfigParam, RewardParams},
     fee::LinearFee,
     key::BftLeaderId,
     rewards,
+    value::Value,
     vote::CommitteeId,
 };
 use std::convert::TryFrom;
@@ -40,8 +41,17 @@ pub struct Settings {
     pub fees_goes_to: FeesGoesTo,
     pub rewards_limit: rewards::Limit,
     pub pool_participation_capping: Option<(NonZeroU32, NonZeroU32)>,
+    pub pool_saturation: Option<rewards::PoolSaturation>,
+    /// The deposit taken by an account registration, refunded on deregistration
+    pub account_registration_deposit: Value,
+    /// The deposit taken by a pool registration, refunded on retirement
+    pub pool_registration_deposit: Value,
+    /// Reset the delegation of the accounts delegating to a pool when it retires
+    pub reset_delegation_on_pool_retirement: bool,
     pub committees: Arc<[CommitteeId]>,
     pub transaction_max_expiry_epochs: u8,
+    pub kes_algorithm: KesAlgorithm,
+    pub vrf_algorithm: VrfAlgorithm,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -127,8 +137,14 @@ impl Settings {
             fees_goes_to: FeesGoesTo::Rewards,
             rewards_limit: rewards::Limit::None,
             pool_participation_capping: None,
+            pool_saturation: None,
+            account_registration_deposit: Value::zero(),
+            pool_registration_deposit: Value::zero(),
+            reset_delegation_on_pool_retirement: false,
             committees: Arc::new([]),
             transaction_max_expiry_epochs: 1,
+            kes_algorithm: KesAlgorithm::default(),
+            vrf_algorithm: VrfAlgorithm::default(),
         }
     }
 
@@ -221,6 +237,16 @@ impl Settings {
                 ConfigParam::PoolRewardParticipationCapping(r) => {
                     new_state.pool_participation_capping = Some(*r)
                 }
+                ConfigParam::PoolRewardSaturation(s) => new_state.pool_saturation = Some(*s),
+                ConfigParam::AccountRegistrationDeposit(deposit) => {
+                    new_state.account_registration_deposit = *deposit;
+                }
+                ConfigParam::PoolRegistrationDeposit(deposit) => {
+                    new_state.pool_registration_deposit = *deposit;
+                }
+                ConfigParam::ResetDelegationOnPoolRetirement(reset) => {
+                    new_state.reset_delegation_on_pool_retirement = *reset;
+                }
                 ConfigParam::AddCommitteeId(committee_id) => {
                     // FIXME: O(n)
                     let mut v = new_state.committees.to_vec();
@@ -239,6 +265,12 @@ impl Settings {
                 ConfigParam::TransactionMaxExpiryEpochs(max_expiry_epochs) => {
                     new_state.transaction_max_expiry_epochs = *max_expiry_epochs;
                 }
+                ConfigParam::KesAlgorithm(kes_algorithm) => {
+                    new_state.kes_algorithm = *kes_algorithm;
+                }
+                ConfigParam::VrfAlgorithm(vrf_algorithm) => {
+                    new_state.vrf_algorithm = *vrf_algorithm;
+                }
             }
         }
 
@@ -274,6 +306,8 @@ impl Settings {
         params.push(ConfigParam::TransactionMaxExpiryEpochs(
             self.transaction_max_expiry_epochs,
         ));
+        params.push(ConfigParam::KesAlgorithm(self.kes_algorithm));
+        params.push(ConfigParam::VrfAlgorithm(self.vrf_algorithm));
 
         match &self.reward_params {
             Some(p) => params.push(ConfigParam::RewardParams(p.clone())),
@@ -283,6 +317,22 @@ impl Settings {
             Some(p) => params.push(ConfigParam::TreasuryParams(*p)),
             None => (),
         };
+        if let Some(s) = self.pool_saturation {
+            params.push(ConfigParam::PoolRewardSaturation(s));
+        }
+        if self.account_registration_deposit != Value::zero() {
+            params.push(ConfigParam::AccountRegistrationDeposit(
+                self.account_registration_deposit,
+            ));
+        }
+        if self.pool_registration_deposit != Value::zero() {
+            params.push(ConfigParam::PoolRegistrationDeposit(
+                self.pool_registration_deposit,
+            ));
+        }
+        if self.reset_delegation_on_pool_retirement {
+            params.push(ConfigParam::ResetDelegationOnPoolRetirement(true));
+        }
 
         debug_assert_eq!(self, &Settings::new().apply(&params).unwrap());
 
@@ -292,6 +342,7 @@ impl Settings {
     pub fn to_reward_params(&self) -> rewards::Parameters {
         let reward_drawing_limit_max = self.rewards_limit.clone();
         let pool_participation_capping = self.pool_participation_capping;
+        let pool_saturation = self.pool_saturation;
 
         match self.reward_params {
             None => rewards::Parameters::zero(),
@@ -308,6 +359,8 @@ impl Settings {
                 epoch_rate,
                 reward_drawing_limit_max,
                 pool_participation_capping,
+                pool_saturation,
+                pool_performance_adjusted: false,
             },
             Some(RewardParams::Linear {
                 constant,
@@ -322,6 +375,25 @@ impl Settings {
                 epoch_rate,
                 reward_drawing_limit_max,
                 pool_participation_capping,
+                pool_saturation,
+                pool_performance_adjusted: false,
+            },
+            Some(RewardParams::PerformanceAware {
+                compounding_type,
+                constant,
+                ratio,
+                epoch_start,
+                epoch_rate,
+            }) => rewards::Parameters {
+                initial_value: constant,
+                compounding_ratio: ratio,
+                compounding_type,
+                epoch_start,
+                epoch_rate,
+                reward_drawing_limit_max,
+                pool_participation_capping,
+                pool_saturation,
+                pool_performance_adjusted: true,
             },
         }
     }
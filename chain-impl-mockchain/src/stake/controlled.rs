@@ -25,7 +25,7 @@ impl StakeControl {
         accounts
             .iter()
             .fold(self.clone(), |sc, (identifier, account)| {
-                sc.add_to(identifier.clone(), Stake::from_value(account.value()))
+                sc.add_to(identifier.clone(), Stake::from_value(account.total_value()))
             })
     }
 
@@ -40,15 +40,12 @@ impl StakeControl {
                     // single or multisig account are not present in utxos
                     panic!("internal error: accounts in utxo")
                 }
-                Kind::Script(_) => {
-                    // scripts are not present in utxo
-                    panic!("internal error: script in utxo")
-                }
                 Kind::Group(_spending_key, account_key) => {
                     let identifier = account_key.clone().into();
                     sc.add_to(identifier, stake)
                 }
-                Kind::Single(_) => sc.add_unassigned(stake),
+                // scripts have no stake key to delegate with
+                Kind::Single(_) | Kind::Script(_) => sc.add_unassigned(stake),
             }
         })
     }
@@ -74,6 +71,11 @@ impl StakeControl {
         self.control.lookup(identifier).copied()
     }
 
+    /// iterate over the stake controlled by every account
+    pub fn iter(&self) -> impl Iterator<Item = (&Identifier, &Stake)> {
+        self.control.iter()
+    }
+
     /// get the ratio controlled by the given account
     ///
     /// the ratio is based on the total assigned stake, stake that is
@@ -39,13 +39,24 @@ impl PoolLastRewards {
 pub struct PoolState {
     pub last_rewards: PoolLastRewards,
     pub registration: Arc<PoolRegistration>,
+    /// the deposit taken by the registration, refunded on retirement
+    pub deposit: Value,
+    /// the epoch at the start of which the pool retires, once its
+    /// retirement has been scheduled
+    pub retirement_epoch: Option<Epoch>,
 }
 
 impl PoolState {
     pub fn new(reg: PoolRegistration) -> Self {
+        Self::new_with_deposit(reg, Value::zero())
+    }
+
+    pub fn new_with_deposit(reg: PoolRegistration, deposit: Value) -> Self {
         PoolState {
             last_rewards: PoolLastRewards::default(),
             registration: Arc::new(reg),
+            deposit,
+            retirement_epoch: None,
         }
     }
 
@@ -160,11 +171,46 @@ impl PoolsState {
         Ok(())
     }
 
+    /// schedule the retirement of the pool at the start of `epoch`,
+    /// replacing any retirement previously scheduled for it
+    pub fn schedule_stake_pool_retirement(
+        &mut self,
+        pool_id: &PoolId,
+        epoch: Epoch,
+    ) -> Result<(), PoolError> {
+        self.stake_pools = self
+            .stake_pools
+            .replace_with(pool_id, |st| {
+                let mut st = st.clone();
+                st.retirement_epoch = Some(epoch);
+                st
+            })
+            .map_err(|_| PoolError::NotFound(pool_id.clone()))?;
+        Ok(())
+    }
+
+    /// the pools whose retirement is due at the start of `epoch`
+    pub fn stake_pools_retiring_at(&self, epoch: Epoch) -> Vec<PoolId> {
+        self.stake_pools
+            .iter()
+            .filter(|(_, st)| matches!(st.retirement_epoch, Some(e) if e <= epoch))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
     pub fn register_stake_pool(&self, owner: PoolRegistration) -> Result<Self, PoolError> {
+        self.register_stake_pool_with_deposit(owner, Value::zero())
+    }
+
+    pub fn register_stake_pool_with_deposit(
+        &self,
+        owner: PoolRegistration,
+        deposit: Value,
+    ) -> Result<Self, PoolError> {
         let id = owner.to_id();
         let new_pools = self
             .stake_pools
-            .insert(id.clone(), PoolState::new(owner))
+            .insert(id.clone(), PoolState::new_with_deposit(owner, deposit))
             .map_err(|_| PoolError::AlreadyExists(id))?;
         Ok(PoolsState {
             stake_pools: new_pools,
@@ -211,6 +257,8 @@ mod tests {
             PoolState {
                 last_rewards: PoolLastRewards::arbitrary(gen),
                 registration,
+                deposit: Arbitrary::arbitrary(gen),
+                retirement_epoch: Arbitrary::arbitrary(gen),
             }
         }
     }
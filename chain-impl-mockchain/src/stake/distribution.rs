@@ -42,6 +42,19 @@ impl PoolStakeInformation {
     pub fn add_value(&mut self, id: &account::Identifier, s: Stake) {
         self.stake.add(id.clone(), s)
     }
+
+    /// The stake delegated to the pool by its own owners
+    pub fn pledge(&self) -> Stake {
+        match &self.registration {
+            None => Stake::zero(),
+            Some(reg) => Stake::sum(reg.owners.iter().filter_map(|owner| {
+                self.stake
+                    .accounts
+                    .get(&account::Identifier::from(owner.clone()))
+                    .copied()
+            })),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -185,7 +198,7 @@ pub fn get_distribution(
             &mut distribution,
             identifier,
             account_state.delegation(),
-            Stake::from_value(account_state.value()),
+            Stake::from_value(account_state.total_value()),
         )
     }
 
@@ -197,10 +210,6 @@ pub fn get_distribution(
                 // single or multisig account are not present in utxos
                 panic!("internal error: accounts in utxo")
             }
-            Kind::Script(_) => {
-                // scripts are not present in utxo
-                panic!("internal error: script in utxo")
-            }
             Kind::Group(_spending_key, account_key) => {
                 let identifier = account_key.clone().into();
                 // is there an account linked to this
@@ -217,7 +226,10 @@ pub fn get_distribution(
                     ),
                 }
             }
-            Kind::Single(_) => distribution.unassigned += Stake::from_value(output.value),
+            // scripts have no stake key to delegate with
+            Kind::Single(_) | Kind::Script(_) => {
+                distribution.unassigned += Stake::from_value(output.value)
+            }
         }
     }
 
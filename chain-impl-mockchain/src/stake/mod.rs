@@ -1,10 +1,12 @@
 mod controlled;
 mod delegation;
 mod distribution;
+mod snapshot;
 #[allow(clippy::module_inception)]
 mod stake;
 
 pub use controlled::StakeControl;
 pub use delegation::*;
 pub use distribution::*;
+pub use snapshot::{StakeSnapshot, StakeSnapshots};
 pub use stake::*;
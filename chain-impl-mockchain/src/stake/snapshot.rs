@@ -0,0 +1,137 @@
+use super::distribution::StakeDistribution;
+use crate::date::Epoch;
+use std::sync::Arc;
+
+/// The stake distribution as it was at the end of an epoch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakeSnapshot {
+    epoch: Epoch,
+    distribution: Arc<StakeDistribution>,
+}
+
+impl StakeSnapshot {
+    pub fn new(epoch: Epoch, distribution: StakeDistribution) -> Self {
+        StakeSnapshot {
+            epoch,
+            distribution: Arc::new(distribution),
+        }
+    }
+
+    /// The epoch at the end of which the snapshot has been taken
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    pub fn distribution(&self) -> &StakeDistribution {
+        &self.distribution
+    }
+}
+
+/// Rotating stake snapshots, taken at each epoch transition.
+///
+/// * `mark` is the snapshot taken at the last epoch transition;
+/// * `set` is the snapshot taken at the transition before;
+/// * `go` is the oldest snapshot kept.
+///
+/// The leader election of an epoch `N + 2` uses the snapshot taken at the
+/// end of the epoch `N`, so a delegation change cannot take effect in the
+/// epoch it was made nor in the following one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakeSnapshots {
+    mark: StakeSnapshot,
+    set: StakeSnapshot,
+    go: StakeSnapshot,
+}
+
+impl StakeSnapshots {
+    /// all the snapshots are the initial distribution until the first
+    /// epoch transitions
+    pub fn new(initial: StakeSnapshot) -> Self {
+        StakeSnapshots {
+            mark: initial.clone(),
+            set: initial.clone(),
+            go: initial,
+        }
+    }
+
+    pub fn from_parts(mark: StakeSnapshot, set: StakeSnapshot, go: StakeSnapshot) -> Self {
+        StakeSnapshots { mark, set, go }
+    }
+
+    pub fn mark(&self) -> &StakeSnapshot {
+        &self.mark
+    }
+
+    pub fn set(&self) -> &StakeSnapshot {
+        &self.set
+    }
+
+    pub fn go(&self) -> &StakeSnapshot {
+        &self.go
+    }
+
+    /// Take a new snapshot, dropping the oldest one
+    pub fn rotate(&self, snapshot: StakeSnapshot) -> Self {
+        StakeSnapshots {
+            mark: snapshot,
+            set: self.mark.clone(),
+            go: self.set.clone(),
+        }
+    }
+
+    /// The snapshot active during `epoch`: the most recent one taken at
+    /// least two epochs before, or the oldest one kept at the start of the
+    /// chain.
+    pub fn active_at(&self, epoch: Epoch) -> &StakeSnapshot {
+        [&self.mark, &self.set, &self.go]
+            .iter()
+            .find(|snapshot| snapshot.epoch + 2 <= epoch)
+            .copied()
+            .unwrap_or(&self.go)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stake::Stake;
+
+    fn snapshot(epoch: Epoch) -> StakeSnapshot {
+        let mut distribution = StakeDistribution::empty();
+        distribution.unassigned = Stake(epoch as u64);
+        StakeSnapshot::new(epoch, distribution)
+    }
+
+    #[test]
+    fn rotation() {
+        let snapshots = StakeSnapshots::new(snapshot(0));
+        let snapshots = snapshots.rotate(snapshot(1)).rotate(snapshot(2));
+        assert_eq!(snapshots.mark().epoch(), 2);
+        assert_eq!(snapshots.set().epoch(), 1);
+        assert_eq!(snapshots.go().epoch(), 0);
+
+        let snapshots = snapshots.rotate(snapshot(3));
+        assert_eq!(snapshots.go().epoch(), 1);
+    }
+
+    #[test]
+    fn active_snapshot_is_two_epochs_old() {
+        let snapshots = StakeSnapshots::new(snapshot(0));
+        assert_eq!(snapshots.active_at(0).epoch(), 0);
+        assert_eq!(snapshots.active_at(1).epoch(), 0);
+
+        // before the transition to epoch 3
+        let snapshots = snapshots.rotate(snapshot(1));
+        assert_eq!(snapshots.active_at(3).epoch(), 1);
+
+        // after the transition to epoch 3
+        let snapshots = snapshots.rotate(snapshot(2));
+        assert_eq!(snapshots.active_at(3).epoch(), 1);
+        assert_eq!(snapshots.active_at(3).distribution().unassigned, Stake(1));
+
+        // epochs without any block
+        let snapshots = snapshots.rotate(snapshot(7));
+        assert_eq!(snapshots.active_at(8).epoch(), 2);
+        assert_eq!(snapshots.active_at(9).epoch(), 7);
+    }
+}
@@ -66,6 +66,7 @@ impl Arbitrary for Output<Address> {
         Output {
             address: Arbitrary::arbitrary(g),
             value: Arbitrary::arbitrary(g),
+            lock: None,
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    certificate::{PoolPermissions, PoolRegistration},
+    certificate::{PoolMetadata, PoolPermissions, PoolRegistration, PoolRelay},
     key::GenesisPraosLeader,
     rewards::{Ratio, TaxType},
     testing::data::{AddressData, StakePool},
@@ -17,6 +17,8 @@ pub struct StakePoolBuilder {
     pool_permissions: Option<PoolPermissions>,
     reward_account: bool,
     tax_type: TaxType,
+    metadata: Option<PoolMetadata>,
+    relays: Vec<PoolRelay>,
     alias: String,
 }
 
@@ -42,6 +44,8 @@ impl StakePoolBuilder {
                 },
                 max_limit: None,
             },
+            metadata: None,
+            relays: Vec::new(),
         }
     }
 
@@ -91,6 +95,16 @@ impl StakePoolBuilder {
         self
     }
 
+    pub fn with_metadata(&mut self, metadata: PoolMetadata) -> &mut Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    pub fn with_relays(&mut self, relays: Vec<PoolRelay>) -> &mut Self {
+        self.relays.extend(relays);
+        self
+    }
+
     pub fn build(&self) -> StakePool {
         let mut rng = rand_core::OsRng;
 
@@ -119,9 +133,11 @@ impl StakePoolBuilder {
             rewards: self.tax_type,
             reward_account: reward_identifier,
             keys: GenesisPraosLeader {
-                vrf_public_key: pool_vrf.public_key().clone(),
+                vrf_public_key: pool_vrf.public_key().clone().into(),
                 kes_public_key: pool_kes.public_key().clone(),
             },
+            metadata: self.metadata.clone(),
+            relays: self.relays.clone(),
         };
         StakePool::new(
             &self.alias,
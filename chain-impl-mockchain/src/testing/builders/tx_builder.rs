@@ -90,6 +90,7 @@ impl TestTxBuilder {
         let outputs = vec![Output {
             address: destination.clone(),
             value: output_value,
+            lock: None,
         }];
         let tx_builder = TxBuilder::new()
             .set_payload(&NoExtra)
@@ -175,6 +175,54 @@ impl TestTxCertBuilder {
                 let tx = builder.set_payload_auth(&());
                 Fragment::VoteCast(tx)
             }
+            Certificate::VoteDelegation(vd) => {
+                let builder = self.set_initial_ios(
+                    valid_until,
+                    TxBuilder::new().set_payload(vd),
+                    funder,
+                    inputs,
+                    outputs,
+                    make_witness,
+                );
+                let tx = builder.set_payload_auth(&());
+                Fragment::VoteDelegation(tx)
+            }
+            Certificate::RewardWithdrawal(rw) => {
+                let builder = self.set_initial_ios(
+                    valid_until,
+                    TxBuilder::new().set_payload(rw),
+                    funder,
+                    inputs,
+                    outputs,
+                    make_witness,
+                );
+                let tx = builder.set_payload_auth(&());
+                Fragment::RewardWithdrawal(tx)
+            }
+            Certificate::AccountRegistration(ar) => {
+                let builder = self.set_initial_ios(
+                    valid_until,
+                    TxBuilder::new().set_payload(ar),
+                    funder,
+                    inputs,
+                    outputs,
+                    make_witness,
+                );
+                let tx = builder.set_payload_auth(&());
+                Fragment::AccountRegistration(tx)
+            }
+            Certificate::AccountDeregistration(ad) => {
+                let builder = self.set_initial_ios(
+                    valid_until,
+                    TxBuilder::new().set_payload(ad),
+                    funder,
+                    inputs,
+                    outputs,
+                    make_witness,
+                );
+                let tx = builder.set_payload_auth(&());
+                Fragment::AccountDeregistration(tx)
+            }
             Certificate::VoteTally(vt) => {
                 let builder = self.set_initial_ios(
                     valid_until,
@@ -237,6 +285,30 @@ impl TestTxCertBuilder {
         let input = funder.make_input_with_value(self.fee(certificate));
         self.fragment(valid_until, certificate, keys, &[input], &[], true, funder)
     }
+
+    /// make a transaction spending `input_value` from the funder, which also
+    /// signs the certificate, for the certificates paying a deposit or
+    /// refunding it to the outputs
+    pub fn make_transaction_with_ios(
+        self,
+        valid_until: BlockDate,
+        funder: &Wallet,
+        certificate: &Certificate,
+        input_value: Value,
+        outputs: &[OutputAddress],
+    ) -> Fragment {
+        let keys = vec![funder.private_key()];
+        let input = funder.make_input_with_value(input_value);
+        self.fragment(
+            valid_until,
+            certificate,
+            keys,
+            &[input],
+            outputs,
+            true,
+            funder,
+        )
+    }
 }
 
 pub fn tally_sign(
@@ -258,6 +258,14 @@ impl AddressDataValue {
         Self::new(address_data, value)
     }
 
+    /// the same account, spending with the given counter, to use another
+    /// spending counter lane of the account
+    pub fn with_spending_counter(&self, spending_counter: SpendingCounter) -> Self {
+        let mut account = self.clone();
+        account.address_data.spending_counter = Some(spending_counter);
+        account
+    }
+
     pub fn delegation(discrimination: Discrimination, value: Value) -> Self {
         AddressDataValue::new(AddressData::delegation(discrimination), value)
     }
@@ -302,8 +310,12 @@ impl AddressDataValue {
     }
 
     pub fn increment_spending_counter(&mut self) {
-        let counter: u32 = self.address_data.spending_counter.unwrap().into();
-        self.address_data.spending_counter = Some((counter + 1u32).into());
+        let counter = self.address_data.spending_counter.unwrap();
+        self.address_data.spending_counter = Some(
+            counter
+                .increment()
+                .expect("the spending counter lane is exhausted"),
+        );
     }
     pub fn make_witness<'a>(
         &mut self,
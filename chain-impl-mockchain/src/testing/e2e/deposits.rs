@@ -0,0 +1,281 @@
+use crate::{
+    accounting::account::{LedgerError as AccountLedgerError, SpendingCounter},
+    fee::LinearFee,
+    ledger::Error as LedgerError,
+    testing::{
+        builders::StakePoolBuilder,
+        data::{AddressData, AddressDataValue, Wallet},
+        ledger::ConfigBuilder,
+        scenario::{prepare_scenario, wallet},
+        verifiers::LedgerStateVerifier,
+    },
+    value::Value,
+};
+use chain_addr::Discrimination;
+
+const ALICE: &str = "Alice";
+const BOB: &str = "Bob";
+
+#[test]
+pub fn account_registration_deposit_is_refunded_on_deregistration() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_account_registration_deposit(Value(100)),
+        )
+        .with_initials(vec![wallet(ALICE).with(1_000)])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = Wallet::new(BOB, Value::zero());
+
+    controller
+        .registers_account(&alice, &bob, Value(100), &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after registration")
+        .pots()
+        .has_deposits_equals_to(&Value(100));
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after registration")
+        .account(bob.as_account_data())
+        .has_value(&Value::zero())
+        .and()
+        .has_deposit(&Value(100));
+
+    assert!(controller
+        .registers_account(&alice, &bob, Value(100), &mut ledger)
+        .is_err());
+
+    controller
+        .deregisters_account(&bob, &alice, Value(100), &mut ledger)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after deregistration")
+        .pots()
+        .has_deposits_equals_to(&Value::zero());
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after deregistration")
+        .account(alice.as_account_data())
+        .has_value(&Value(1_000 - 103 + 96));
+    assert!(!ledger.accounts().exists(&bob.public_key().into()));
+}
+
+#[test]
+pub fn outputs_to_unregistered_accounts_are_rejected() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(0, 0, 0))
+                .with_account_registration_deposit(Value(100)),
+        )
+        .with_initials(vec![wallet(ALICE).with(1_000)])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = Wallet::new(BOB, Value::zero());
+
+    assert_eq!(
+        controller.transfer_funds(&alice, &bob, &mut ledger, 100),
+        Err(LedgerError::AccountNotRegistered {
+            account: bob.public_key().into()
+        })
+    );
+
+    controller
+        .registers_account(&alice, &bob, Value(100), &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    controller
+        .transfer_funds(&alice, &bob, &mut ledger, 100)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("after transfer")
+        .account(bob.as_account_data())
+        .has_value(&Value(100))
+        .and()
+        .has_deposit(&Value(100));
+}
+
+#[test]
+pub fn group_outputs_create_their_account_without_registration() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(0, 0, 0))
+                .with_account_registration_deposit(Value(100)),
+        )
+        .with_initials(vec![wallet(ALICE).with(1_000)])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let group = AddressData::delegation(Discrimination::Test);
+    let bob = Wallet::from_address_data_value(AddressDataValue::new(group.clone(), Value::zero()));
+
+    controller
+        .transfer_funds(&alice, &bob, &mut ledger, 100)
+        .unwrap();
+
+    let account = ledger.accounts().get_state(&group.delegation_id()).unwrap();
+    assert_eq!(account.value(), Value::zero());
+    assert_eq!(account.deposit(), Value::zero());
+}
+
+#[test]
+pub fn transactions_cannot_be_replayed_after_registering_again() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(0, 0, 0))
+                .with_account_registration_deposit(Value(100)),
+        )
+        .with_initials(vec![wallet(ALICE).with(1_000)])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = Wallet::new(BOB, Value::zero());
+
+    controller
+        .registers_account(&alice, &bob, Value(100), &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let deregistration = controller.fragment_factory().account_deregistration(
+        ledger.date(),
+        &bob,
+        &alice,
+        Value(100),
+    );
+    ledger
+        .apply_fragment(&deregistration, ledger.date())
+        .unwrap();
+
+    controller
+        .registers_account(&alice, &bob, Value(100), &mut ledger)
+        .unwrap();
+
+    assert_eq!(
+        ledger.apply_fragment(&deregistration, ledger.date()),
+        Err(LedgerError::Account(
+            AccountLedgerError::SpendingCounterInvalid {
+                expected: SpendingCounter::new(0, 1),
+                actual: SpendingCounter::zero(),
+            }
+        ))
+    );
+    LedgerStateVerifier::new(ledger.into())
+        .info("after the replay")
+        .account(bob.as_account_data())
+        .has_value(&Value::zero())
+        .and()
+        .has_deposit(&Value(100));
+}
+
+#[test]
+pub fn pool_deposit_of_a_deregistered_owner_goes_to_the_treasury() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(0, 0, 0))
+                .with_account_registration_deposit(Value(100))
+                .with_pool_registration_deposit(Value(500)),
+        )
+        .with_initials(vec![wallet(ALICE).with(1_000)])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = Wallet::new(BOB, Value::zero());
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .build();
+
+    controller
+        .register_with_deposit(&alice, &stake_pool, Value(500), &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .retire(Some(&alice), &stake_pool, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .registers_account(&alice, &bob, Value(100), &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .deregisters_account(&alice, &bob, Value(400), &mut ledger)
+        .unwrap();
+
+    let treasury = ledger.pots().treasury_value();
+    ledger.fast_forward_to_epoch(1);
+
+    assert!(!ledger.accounts().exists(&alice.public_key().into()));
+    LedgerStateVerifier::new(ledger.into())
+        .info("after retirement")
+        .pots()
+        .has_deposits_equals_to(&Value(100))
+        .and()
+        .has_treasury_equals_to(&(treasury + Value(500)).unwrap());
+}
+
+#[test]
+pub fn pool_registration_deposit_is_refunded_on_retirement() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_pool_registration_deposit(Value(500)),
+        )
+        .with_initials(vec![wallet(ALICE).with(1_000)])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .build();
+
+    assert!(controller
+        .register_with_deposit(&alice, &stake_pool, Value(499), &mut ledger)
+        .is_err());
+
+    controller
+        .register_with_deposit(&alice, &stake_pool, Value(500), &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after registration")
+        .pots()
+        .has_deposits_equals_to(&Value(500));
+
+    controller
+        .retire(Some(&alice), &stake_pool, &mut ledger)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("retirement scheduled")
+        .pots()
+        .has_deposits_equals_to(&Value(500));
+
+    ledger.fast_forward_to_epoch(1);
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after retirement")
+        .pots()
+        .has_deposits_equals_to(&Value::zero());
+    LedgerStateVerifier::new(ledger.into())
+        .info("after retirement")
+        .account(alice.as_account_data())
+        .has_value(&Value(1_000 - 503 - 3 + 500));
+}
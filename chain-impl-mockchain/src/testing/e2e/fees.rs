@@ -49,6 +49,7 @@ pub fn per_certificate_fees() {
                     NonZeroU64::new(certificate_pool_registration_fee),
                     NonZeroU64::new(certificate_stake_delegation),
                     NonZeroU64::new(certificate_owner_stake_delegation),
+                    None,
                 )),
         )
         .with_initials(vec![
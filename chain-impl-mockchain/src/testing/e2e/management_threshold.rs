@@ -32,6 +32,13 @@ pub fn management_threshold() {
         .retire(&[alice, bob], &stake_pool, &mut ledger)
         .is_ok());
 
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after retirement")
+        .stake_pools()
+        .is_not_retired(&stake_pool);
+
+    ledger.fast_forward_to_epoch(1);
+
     LedgerStateVerifier::new(ledger.into())
         .info("after owner delegation")
         .stake_pools()
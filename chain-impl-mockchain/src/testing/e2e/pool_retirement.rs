@@ -0,0 +1,141 @@
+use crate::{
+    testing::{
+        ledger::ConfigBuilder,
+        scenario::{prepare_scenario, wallet},
+        verifiers::LedgerStateVerifier,
+    },
+    value::Value,
+};
+use std::collections::HashSet;
+
+const ALICE: &str = "Alice";
+const BOB: &str = "Bob";
+const CLARICE: &str = "Clarice";
+const STAKE_POOL: &str = "stake_pool";
+
+#[test]
+pub fn pool_retirement_is_applied_at_epoch_transition() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_initials(vec![
+            wallet(ALICE).with(1_000).owns_and_delegates_to(STAKE_POOL),
+            wallet(BOB).with(1_000).delegates_to(STAKE_POOL),
+            wallet(CLARICE).with(1_000),
+        ])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let bob = controller.wallet(BOB).unwrap();
+    let clarice = controller.wallet(CLARICE).unwrap();
+    let stake_pool = controller.stake_pool(STAKE_POOL).unwrap();
+
+    controller
+        .retire(Some(&alice), &stake_pool, &mut ledger)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("retirement scheduled")
+        .stake_pools()
+        .is_not_retired(&stake_pool);
+
+    let pool_retirements = ledger.fast_forward_to_next_epoch();
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after epoch transition")
+        .stake_pools()
+        .is_retired(&stake_pool);
+
+    assert_eq!(pool_retirements.epoch, 1);
+    assert!(!pool_retirements.delegations_reset);
+    let retired = pool_retirements
+        .stake_pools
+        .get(&stake_pool.id())
+        .expect("retired stake pool is reported");
+    assert_eq!(retired.deposit, Value::zero());
+    assert_eq!(
+        retired.accounts.iter().cloned().collect::<HashSet<_>>(),
+        vec![
+            alice.as_account_data().to_id(),
+            bob.as_account_data().to_id()
+        ]
+        .into_iter()
+        .collect()
+    );
+    assert!(!retired
+        .accounts
+        .contains(&clarice.as_account_data().to_id()));
+    assert!(retired.multisig_accounts.is_empty());
+
+    // without the reset policy, the delegators keep pointing at the retired pool
+    LedgerStateVerifier::new(ledger.into())
+        .info("after epoch transition")
+        .account(bob.as_account_data())
+        .delegation()
+        .is_fully_delegated_to(stake_pool.id());
+}
+
+#[test]
+pub fn delegations_are_reset_on_pool_retirement() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(ConfigBuilder::new().with_reset_delegation_on_pool_retirement(true))
+        .with_initials(vec![
+            wallet(ALICE).with(1_000).owns_and_delegates_to(STAKE_POOL),
+            wallet(BOB).with(1_000).delegates_to(STAKE_POOL),
+        ])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let bob = controller.wallet(BOB).unwrap();
+    let stake_pool = controller.stake_pool(STAKE_POOL).unwrap();
+
+    controller
+        .retire(Some(&alice), &stake_pool, &mut ledger)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("retirement scheduled")
+        .account(bob.as_account_data())
+        .delegation()
+        .is_fully_delegated_to(stake_pool.id());
+
+    let pool_retirements = ledger.fast_forward_to_next_epoch();
+
+    assert!(pool_retirements.delegations_reset);
+    assert_eq!(
+        pool_retirements.stake_pools[&stake_pool.id()]
+            .accounts
+            .len(),
+        2
+    );
+
+    let ledger_verifier = LedgerStateVerifier::new(ledger.into());
+    ledger_verifier.stake_pools().is_retired(&stake_pool);
+    ledger_verifier
+        .account(alice.as_account_data())
+        .delegation()
+        .is_not_delegated();
+    ledger_verifier
+        .account(bob.as_account_data())
+        .delegation()
+        .is_not_delegated();
+}
+
+#[test]
+pub fn epoch_transition_without_retirement_reports_nothing() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_initials(vec![wallet(ALICE)
+            .with(1_000)
+            .owns_and_delegates_to(STAKE_POOL)])
+        .build()
+        .unwrap();
+
+    let stake_pool = controller.stake_pool(STAKE_POOL).unwrap();
+
+    assert!(ledger.fast_forward_to_next_epoch().is_empty());
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("after epoch transition")
+        .stake_pools()
+        .is_not_retired(&stake_pool);
+}
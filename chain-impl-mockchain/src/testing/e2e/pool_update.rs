@@ -1,4 +1,5 @@
 use crate::{
+    certificate::{PoolMetadata, PoolRelay},
     fee::LinearFee,
     stake::Stake,
     testing::{
@@ -52,6 +53,48 @@ pub fn delegations_are_preserved_after_pool_update() {
         .is_fully_delegated_to(new_stake_pool.id());
 }
 
+#[test]
+pub fn pool_update_changes_metadata_and_relays() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(ConfigBuilder::new().with_fee(LinearFee::new(1, 1, 1)))
+        .with_initials(vec![wallet("Alice")
+            .with(1_000)
+            .owns_and_delegates_to("stake_pool")])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet("Alice").unwrap();
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+
+    let metadata = PoolMetadata::new(
+        "https://pool.example.com/metadata.json".to_owned(),
+        PoolMetadata::document_hash(br#"{"name":"pool"}"#),
+    );
+    let relays = vec![PoolRelay::DnsName {
+        name: "relay.pool.example.com".to_owned(),
+        port: 3000,
+    }];
+
+    let mut new_stake_pool = stake_pool.clone();
+    new_stake_pool.info_mut().metadata = Some(metadata.clone());
+    new_stake_pool.info_mut().relays = relays.clone();
+
+    assert!(controller
+        .update(
+            &stake_pool,
+            new_stake_pool.clone(),
+            vec![&alice],
+            &mut ledger
+        )
+        .is_ok());
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("stake pool metadata is updated")
+        .stake_pool(&new_stake_pool.id())
+        .metadata_eq(Some(metadata))
+        .relays_eq(relays);
+}
+
 #[test]
 pub fn pool_update_after_pool_retirement() {
     let (mut ledger, controller) = prepare_scenario()
@@ -68,6 +111,7 @@ pub fn pool_update_after_pool_retirement() {
     assert!(controller
         .retire(Some(&alice), &stake_pool, &mut ledger)
         .is_ok());
+    ledger.fast_forward_to_epoch(1);
 
     let mut new_stake_pool = stake_pool.clone();
     new_stake_pool.info_mut().serial = 111u128;
@@ -195,6 +239,7 @@ pub fn pool_update_changes_owner() {
     assert!(controller
         .retire(Some(&bob), &new_stake_pool, &mut ledger)
         .is_ok());
+    ledger.fast_forward_to_epoch(1);
 
     LedgerStateVerifier::new(ledger.into())
         .info("stake pool is retired")
@@ -259,6 +304,7 @@ pub fn pool_update_revert_changes() {
     );
 
     //assert!(controller.retire(&vec![&alice],&new_stake_pool,&mut ledger).is_ok());
+    ledger.fast_forward_to_epoch(1);
 
     LedgerStateVerifier::new(ledger.into())
         .info("stake pool is retired")
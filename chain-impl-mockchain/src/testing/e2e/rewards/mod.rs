@@ -1,14 +1,22 @@
 use crate::{
-    certificate::PoolId,
+    account::{DelegationType, Identifier},
+    certificate::{PoolId, PoolPermissions},
     config::RewardParams,
-    fee::LinearFee,
-    rewards::Ratio,
+    date::BlockDate,
+    fee::{LinearFee, PerCertificateFee},
+    ledger::{LeadersParticipationRecord, RewardsInfoParameters},
+    milli::Milli,
+    multisig::{DeclElement, Declaration},
+    rewards::{CompoundingType, Ratio},
     testing::{
-        builders::StakePoolBuilder,
-        ledger::{ConfigBuilder, TestLedger},
+        builders::{build_stake_pool_registration_cert, StakePoolBuilder, TestTxCertBuilder},
+        data::{AddressDataValue, StakePool, Wallet},
+        ledger::{ConfigBuilder, LedgerBuilder, TestLedger},
         scenario::{prepare_scenario, stake_pool, wallet},
         verifiers::LedgerStateVerifier,
+        TestGen,
     },
+    transaction::AccountIdentifier,
     value::Value,
 };
 
@@ -127,13 +135,13 @@ pub fn rewards_owners_split() {
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1003));
+        .has_total_value(&Value(1003));
     ledger_verifier
         .account(bob.as_account_data())
-        .has_value(&Value(1003));
+        .has_total_value(&Value(1003));
     ledger_verifier
         .account(clarice.as_account_data())
-        .has_value(&Value(1003));
+        .has_total_value(&Value(1003));
 }
 
 #[test]
@@ -183,13 +191,13 @@ pub fn rewards_owners_uneven_split() {
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1007));
+        .has_total_value(&Value(1007));
     ledger_verifier
         .account(bob.as_account_data())
-        .has_value(&Value(1006));
+        .has_total_value(&Value(1006));
     ledger_verifier
         .account(clarice.as_account_data())
-        .has_value(&Value(1006));
+        .has_total_value(&Value(1006));
 }
 
 #[test]
@@ -232,7 +240,7 @@ pub fn rewards_single_owner() {
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1_009));
+        .has_total_value(&Value(1_009));
 }
 
 #[test]
@@ -278,13 +286,207 @@ pub fn rewards_reward_account() {
 
     ledger_verifier
         .account(reward_account.clone())
-        .has_value(&Value(9))
+        .has_total_value(&Value(9))
         .and()
         .has_last_reward(&Value(9));
 }
 
 #[test]
-pub fn rewards_goes_to_treasury_if_stake_pool_is_retired() {
+pub fn rewards_performance_aware_underperforming_pool() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_rewards(Value(1000))
+                .with_treasury(Value(0))
+                .with_slots_per_epoch(4)
+                .with_active_slots_coeff(Milli::ONE)
+                .with_rewards_params(RewardParams::PerformanceAware {
+                    compounding_type: CompoundingType::Linear,
+                    constant: 100,
+                    ratio: Ratio {
+                        numerator: 1,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                    epoch_start: 0,
+                    epoch_rate: NonZeroU32::new(1).unwrap(),
+                }),
+        )
+        .with_initials(vec![wallet("Alice")
+            .with(1_000)
+            .owns_and_delegates_to("stake_pool")])
+        .with_stake_pools(vec![stake_pool("stake_pool")
+            .with_reward_account(true)
+            .tax_ratio(1, 1)])
+        .build()
+        .unwrap();
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+
+    // the pool holds all the stake so it is expected to lead all 4 slots
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+    ledger.distribute_rewards().unwrap();
+
+    let mut ledger_verifier = LedgerStateVerifier::new(ledger.into());
+    ledger_verifier.info("after performance aware rewards distribution");
+
+    ledger_verifier
+        .pots()
+        .has_fee_equals_to(&Value::zero())
+        .and()
+        .has_treasury_equals_to(&Value(75))
+        .and()
+        .has_remaining_rewards_equals_to(&Value(901));
+
+    let reward_account = stake_pool.reward_account().unwrap();
+
+    ledger_verifier
+        .account(reward_account.clone())
+        .has_total_value(&Value(24))
+        .and()
+        .has_last_reward(&Value(24));
+}
+
+#[test]
+pub fn rewards_performance_aware_half_of_the_expected_blocks() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_rewards(Value(10_000))
+                .with_treasury(Value(0))
+                .with_slots_per_epoch(4)
+                .with_active_slots_coeff(Milli::from_millis(750))
+                .with_rewards_params(RewardParams::PerformanceAware {
+                    compounding_type: CompoundingType::Linear,
+                    constant: 1000,
+                    ratio: Ratio {
+                        numerator: 0,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                    epoch_start: 0,
+                    epoch_rate: NonZeroU32::new(1).unwrap(),
+                }),
+        )
+        .with_initials(vec![
+            wallet("Alice")
+                .with(1_000)
+                .owns_and_delegates_to("alice_stake_pool"),
+            wallet("Bob")
+                .with(1_000)
+                .owns_and_delegates_to("bob_stake_pool"),
+        ])
+        .with_stake_pools(vec![
+            stake_pool("alice_stake_pool")
+                .with_reward_account(true)
+                .tax_ratio(1, 1),
+            stake_pool("bob_stake_pool")
+                .with_reward_account(true)
+                .tax_ratio(1, 1),
+        ])
+        .build()
+        .unwrap();
+    let alice_stake_pool = controller.stake_pool("alice_stake_pool").unwrap();
+    let bob_stake_pool = controller.stake_pool("bob_stake_pool").unwrap();
+
+    // with half of the stake and f = 0.75, each pool is expected to lead
+    // 4 * (1 - 0.25^0.5) = 2 slots. Alice's pool produces one block and
+    // Bob's pool both of its blocks
+    assert!(ledger.produce_empty_block(&alice_stake_pool).is_ok());
+    ledger.forward_date();
+    assert!(ledger.produce_empty_block(&bob_stake_pool).is_ok());
+    ledger.forward_date();
+    assert!(ledger.produce_empty_block(&bob_stake_pool).is_ok());
+    ledger.distribute_rewards().unwrap();
+
+    let mut ledger_verifier = LedgerStateVerifier::new(ledger.into());
+    ledger_verifier.info("after performance aware rewards distribution");
+
+    // each stake share is 500, Alice's pool only gets half of it
+    ledger_verifier
+        .pots()
+        .has_treasury_equals_to(&Value(250))
+        .and()
+        .has_remaining_rewards_equals_to(&Value(9_000));
+    ledger_verifier
+        .account(alice_stake_pool.reward_account().unwrap().clone())
+        .has_last_reward(&Value(250));
+    ledger_verifier
+        .account(bob_stake_pool.reward_account().unwrap().clone())
+        .has_last_reward(&Value(500));
+}
+
+#[test]
+pub fn rewards_saturated_pool() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_rewards(Value(1000))
+                .with_treasury(Value(0))
+                .with_pool_reward_saturation(
+                    4,
+                    Ratio {
+                        numerator: 1,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                )
+                .with_rewards_params(RewardParams::Linear {
+                    constant: 100,
+                    ratio: Ratio {
+                        numerator: 1,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                    epoch_start: 0,
+                    epoch_rate: NonZeroU32::new(1).unwrap(),
+                }),
+        )
+        .with_initials(vec![
+            wallet("Alice")
+                .with(1_000)
+                .owns_and_delegates_to("stake_pool"),
+            wallet("Bob").with(1_000).delegates_to("stake_pool"),
+        ])
+        .with_stake_pools(vec![stake_pool("stake_pool")
+            .with_reward_account(true)
+            .tax_ratio(1, 1)])
+        .build()
+        .unwrap();
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+
+    let (rewarded, rewards_info) = ledger
+        .ledger
+        .distribute_rewards(
+            &ledger.ledger.get_stake_distribution(),
+            &ledger.ledger.get_ledger_parameters(),
+            RewardsInfoParameters::report_all(),
+        )
+        .unwrap();
+
+    // the pool holds all the stake while saturating at a quarter of it,
+    // its pledge being above saturation it keeps a quarter of the rewards
+    assert_eq!(
+        rewards_info.stake_pools_saturation.get(&stake_pool.id()),
+        Some(&Value(75))
+    );
+    assert_eq!(rewards_info.treasury, Value(75));
+
+    let mut ledger_verifier = LedgerStateVerifier::new(rewarded);
+    ledger_verifier.info("after saturated rewards distribution");
+
+    ledger_verifier
+        .pots()
+        .has_treasury_equals_to(&Value(75))
+        .and()
+        .has_remaining_rewards_equals_to(&Value(901));
+
+    let reward_account = stake_pool.reward_account().unwrap();
+
+    ledger_verifier
+        .account(reward_account.clone())
+        .has_total_value(&Value(24));
+}
+
+#[test]
+pub fn retiring_stake_pool_is_rewarded_for_its_last_epoch() {
     let (mut ledger, controller) = prepare_scenario()
         .with_config(
             ConfigBuilder::new()
@@ -317,14 +519,16 @@ pub fn rewards_goes_to_treasury_if_stake_pool_is_retired() {
         .unwrap();
     ledger.distribute_rewards().unwrap();
 
-    let mut ledger_verifier = LedgerStateVerifier::new(ledger.into());
-    ledger_verifier.info("after rewards distribution to retired stake pool");
+    // the retirement only takes effect at the epoch transition, after the
+    // rewards of the last epoch of the pool are distributed
+    let mut ledger_verifier = LedgerStateVerifier::new(ledger.clone().into());
+    ledger_verifier.info("after rewards distribution to retiring stake pool");
 
     ledger_verifier
         .pots()
         .has_fee_equals_to(&Value::zero())
         .and()
-        .has_treasury_equals_to(&Value(99))
+        .has_treasury_equals_to(&Value(90))
         .and()
         .has_remaining_rewards_equals_to(&Value(901));
 
@@ -332,7 +536,15 @@ pub fn rewards_goes_to_treasury_if_stake_pool_is_retired() {
 
     ledger_verifier
         .account(reward_account.clone())
-        .does_not_exist();
+        .has_rewards(&Value(9));
+    ledger_verifier.stake_pools().is_not_retired(&stake_pool);
+
+    ledger.fast_forward_to_epoch(1);
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("after epoch transition")
+        .stake_pools()
+        .is_retired(&stake_pool);
 }
 
 #[test]
@@ -400,7 +612,7 @@ pub fn rewards_from_fees() {
 
     ledger_verifier
         .account(reward_account.clone())
-        .has_value(&Value(10));
+        .has_total_value(&Value(10));
 }
 
 #[test]
@@ -449,10 +661,156 @@ pub fn rewards_stake_pool_with_delegation() {
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1049));
+        .has_total_value(&Value(1049));
     ledger_verifier
         .account(bob.as_account_data())
-        .has_value(&Value(1050));
+        .has_total_value(&Value(1050));
+}
+
+#[test]
+pub fn rewards_forecast() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_rewards(Value(1000))
+                .with_treasury(Value(0))
+                .with_rewards_params(RewardParams::Linear {
+                    constant: 100,
+                    ratio: Ratio {
+                        numerator: 1,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                    epoch_start: 0,
+                    epoch_rate: NonZeroU32::new(1).unwrap(),
+                }),
+        )
+        .with_initials(vec![
+            wallet("Alice")
+                .with(1_000)
+                .owns_and_delegates_to("stake_pool"),
+            wallet("Bob").with(1_000),
+        ])
+        .with_stake_pools(vec![stake_pool("stake_pool").tax_ratio(1, 2)])
+        .build()
+        .unwrap();
+
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+    let alice: Identifier = controller.wallet("Alice").unwrap().public_key().into();
+    let bob: Identifier = controller.wallet("Bob").unwrap().public_key().into();
+    let distribution = ledger.ledger.get_stake_distribution();
+
+    // nothing to forecast until a block is produced, unless it is hypothetical
+    let forecast = ledger.ledger.forecast_rewards(&distribution, None).unwrap();
+    assert!(forecast.stake_pools.is_empty());
+
+    let mut leaders_log = LeadersParticipationRecord::new();
+    leaders_log.set_for(stake_pool.id(), 1).unwrap();
+    let hypothetical = ledger
+        .ledger
+        .forecast_rewards(&distribution, Some(&leaders_log))
+        .unwrap();
+    assert!(hypothetical.stake_pools.contains_key(&stake_pool.id()));
+    assert!(!ledger.ledger.can_distribute_reward());
+
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+
+    // the forecast matches the actual distribution and leaves the ledger untouched
+    let forecast = ledger.ledger.forecast_rewards(&distribution, None).unwrap();
+    assert!(ledger.ledger.can_distribute_reward());
+    let (_, rewards_info) = ledger
+        .ledger
+        .distribute_rewards(
+            &distribution,
+            &ledger.ledger.get_ledger_parameters(),
+            RewardsInfoParameters::report_all(),
+        )
+        .unwrap();
+    assert_eq!(forecast.stake_pools, rewards_info.stake_pools);
+    assert_eq!(forecast.accounts, rewards_info.accounts);
+    assert!(!forecast.accounts.contains_key(&bob));
+
+    // bob delegating to the pool would share the delegators rewards with alice
+    let delegated = ledger
+        .ledger
+        .forecast_stake_distribution(&bob, &DelegationType::Full(stake_pool.id()))
+        .unwrap();
+    let forecast_delegated = ledger.ledger.forecast_rewards(&delegated, None).unwrap();
+    assert!(forecast_delegated.accounts[&bob] > Value::zero());
+    assert!(forecast_delegated.accounts[&alice] < forecast.accounts[&alice]);
+}
+
+#[test]
+pub fn rewards_delegation_uses_stake_snapshot() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_rewards(Value(1000))
+                .with_treasury(Value(0))
+                .with_rewards_params(RewardParams::Linear {
+                    constant: 100,
+                    ratio: Ratio {
+                        numerator: 1,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                    epoch_start: 0,
+                    epoch_rate: NonZeroU32::new(1).unwrap(),
+                }),
+        )
+        .with_initials(vec![
+            wallet("Alice")
+                .with(1_000)
+                .owns_and_delegates_to("stake_pool"),
+            wallet("Bob").with(1_000),
+        ])
+        .with_stake_pools(vec![stake_pool("stake_pool").tax_ratio(1, 2)])
+        .build()
+        .unwrap();
+
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+    let bob_wallet = controller.wallet("Bob").unwrap();
+    let bob: Identifier = bob_wallet.public_key().into();
+
+    controller
+        .delegates(&bob_wallet, &stake_pool, &mut ledger)
+        .unwrap();
+    let bob_value = ledger.accounts().get_state(&bob).unwrap().total_value();
+
+    // the delegation is not part of the stake snapshot of this epoch
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+    ledger.distribute_rewards().unwrap();
+    assert_eq!(
+        ledger.accounts().get_state(&bob).unwrap().total_value(),
+        bob_value
+    );
+
+    // the transition to the epoch 1 takes a snapshot with the delegation
+    let last_slot = ledger.era().slots_per_epoch() - 1;
+    ledger.fast_forward_to(BlockDate {
+        epoch: 0,
+        slot_id: last_slot,
+    });
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+    let mark = ledger.ledger.stake_snapshots().mark();
+    assert_eq!(mark.epoch(), 0);
+    assert!(mark.distribution().to_pools[&stake_pool.id()]
+        .stake
+        .accounts
+        .contains_key(&bob));
+
+    ledger.distribute_rewards().unwrap();
+    assert_eq!(
+        ledger.accounts().get_state(&bob).unwrap().total_value(),
+        bob_value
+    );
+
+    // which is used for the epoch 2
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: last_slot,
+    });
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+    ledger.distribute_rewards().unwrap();
+    assert!(ledger.accounts().get_state(&bob).unwrap().total_value() > bob_value);
 }
 
 #[test]
@@ -584,15 +942,15 @@ pub fn rewards_are_propotional_to_stake_pool_effectivness_in_building_blocks() {
     ledger_verifier
         .info("after rewards distribution for alice")
         .account(alice.as_account_data())
-        .has_value(&expected_alice_reward);
+        .has_total_value(&expected_alice_reward);
     ledger_verifier
         .info("after rewards distribution for bob")
         .account(bob.as_account_data())
-        .has_value(&expected_bob_reward);
+        .has_total_value(&expected_bob_reward);
     ledger_verifier
         .info("after rewards distribution for clarice")
         .account(clarice.as_account_data())
-        .has_value(&expected_clarice_reward);
+        .has_total_value(&expected_clarice_reward);
 }
 
 fn calculate_reward(expected_total_reward: Value, pool_id: &PoolId, ledger: &TestLedger) -> Value {
@@ -615,7 +973,7 @@ pub fn rewards_owner_of_many_stake_pool() {
                         numerator: 1,
                         denominator: NonZeroU64::new(1).unwrap(),
                     },
-                    epoch_start: 0,
+                    epoch_start: 2,
                     epoch_rate: NonZeroU32::new(1).unwrap(),
                 }),
         )
@@ -644,18 +1002,20 @@ pub fn rewards_owner_of_many_stake_pool() {
         .register(&alice, &second_alice_stake_pool, &mut ledger)
         .unwrap();
 
-    // produce 2 blocks for each stake pool
     let fragment_factory = controller.fragment_factory();
 
     let fragment = fragment_factory.transaction(&bob, &clarice, &mut ledger, 100);
-    assert!(ledger
-        .produce_block(&first_alice_stake_pool, vec![fragment])
-        .is_ok());
+    ledger.apply_fragment(&fragment, ledger.date()).unwrap();
 
     let fragment = fragment_factory.transaction(&clarice, &bob, &mut ledger, 100);
-    assert!(ledger
-        .produce_block(&second_alice_stake_pool, vec![fragment])
-        .is_ok());
+    ledger.apply_fragment(&fragment, ledger.date()).unwrap();
+
+    // the stake snapshot of the epoch 0 is used in the epoch 2
+    ledger.fast_forward_to_epoch(2);
+
+    // produce a block for each stake pool
+    assert!(ledger.produce_empty_block(&first_alice_stake_pool).is_ok());
+    assert!(ledger.produce_empty_block(&second_alice_stake_pool).is_ok());
 
     ledger.distribute_rewards().unwrap();
 
@@ -675,7 +1035,7 @@ pub fn rewards_owner_of_many_stake_pool() {
     // check owner account (10 from rewards - 3 from register stake pool fee)
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1007));
+        .has_total_value(&Value(1007));
 }
 
 #[test]
@@ -692,7 +1052,7 @@ pub fn rewards_delegators_of_many_stake_pool() {
                         numerator: 1,
                         denominator: NonZeroU64::new(1).unwrap(),
                     },
-                    epoch_start: 0,
+                    epoch_start: 2,
                     epoch_rate: NonZeroU32::new(1).unwrap(),
                 }),
         )
@@ -727,18 +1087,20 @@ pub fn rewards_delegators_of_many_stake_pool() {
         )
         .unwrap();
 
-    // produce 2 blocks for each stake pool
     let fragment_factory = controller.fragment_factory();
 
     let fragment = fragment_factory.transaction(&david, &clarice, &mut ledger, 100);
-    assert!(ledger
-        .produce_block(&alice_stake_pool, vec![fragment])
-        .is_ok());
+    ledger.apply_fragment(&fragment, ledger.date()).unwrap();
 
     let fragment = fragment_factory.transaction(&clarice, &david, &mut ledger, 100);
-    assert!(ledger
-        .produce_block(&bob_stake_pool, vec![fragment])
-        .is_ok());
+    ledger.apply_fragment(&fragment, ledger.date()).unwrap();
+
+    // the stake snapshot of the epoch 0 is used in the epoch 2
+    ledger.fast_forward_to_epoch(2);
+
+    // produce a block for each stake pool
+    assert!(ledger.produce_empty_block(&alice_stake_pool).is_ok());
+    assert!(ledger.produce_empty_block(&bob_stake_pool).is_ok());
 
     ledger.distribute_rewards().unwrap();
 
@@ -758,5 +1120,145 @@ pub fn rewards_delegators_of_many_stake_pool() {
     // check owner account (94 from rewards - 3 from register stake pool fee)
     ledger_verifier
         .account(eve.as_account_data())
-        .has_value(&Value(1093));
+        .has_total_value(&Value(1093));
+}
+
+#[test]
+pub fn rewards_multisig_reward_account() {
+    let alice = Wallet::from_value(Value(1_000));
+    let mut ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .faucets_wallets(vec![&alice])
+        .build()
+        .unwrap();
+
+    let declaration = Declaration {
+        threshold: 1,
+        owners: vec![
+            DeclElement::from_publickey(&alice.public_key()),
+            DeclElement::from_publickey(&TestGen::public_key()),
+        ],
+    };
+    let account = declaration.to_identifier();
+    ledger.ledger.multisig = ledger.ledger.multisig.add_account(&declaration).unwrap();
+
+    let builder_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .with_pool_permissions(PoolPermissions::new(1))
+        .with_ratio_tax_type(1, 1, None)
+        .build();
+    let mut info = builder_pool.info();
+    info.reward_account = Some(AccountIdentifier::Multi(account.clone()));
+    let stake_pool = StakePool::new(
+        "stake_pool",
+        info.to_id(),
+        builder_pool.vrf(),
+        builder_pool.kes(),
+        info.clone(),
+        None,
+    );
+
+    let certificate = build_stake_pool_registration_cert(&info);
+    let fragment = TestTxCertBuilder::new(ledger.block0_hash, ledger.fee()).make_transaction(
+        ledger.date(),
+        &[alice],
+        &certificate,
+    );
+    ledger.apply_fragment(&fragment, ledger.date()).unwrap();
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+
+    let (rewarded, rewards_info) = ledger
+        .ledger
+        .distribute_rewards(
+            &ledger.ledger.get_stake_distribution(),
+            &ledger.ledger.get_ledger_parameters(),
+            RewardsInfoParameters::report_all(),
+        )
+        .unwrap();
+
+    let (owned, _) = rewards_info.stake_pools[&stake_pool.id()];
+    assert!(owned > Value::zero());
+    assert_eq!(rewards_info.multisig_accounts.get(&account), Some(&owned));
+    assert!(rewards_info.accounts.is_empty());
+    assert_eq!(
+        rewarded.multisig.get_state(&account).unwrap().rewards(),
+        owned
+    );
+}
+
+#[test]
+pub fn rewards_withdrawal() {
+    let mut fee = LinearFee::new(0, 0, 0);
+    fee.per_certificate_fees(PerCertificateFee::new(None, None, None, NonZeroU64::new(1)));
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(ConfigBuilder::new().with_rewards(Value(100)).with_fee(fee))
+        .with_initials(vec![wallet("Alice").with(1_000).owns("stake_pool")])
+        .with_stake_pools(vec![stake_pool("stake_pool").tax_ratio(1, 1)])
+        .build()
+        .unwrap();
+
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+    let alice = controller.wallet("Alice").unwrap();
+
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+    ledger.distribute_rewards().unwrap();
+
+    // the rewards are kept apart from the value of the account
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("after rewards distribution")
+        .account(alice.as_account_data())
+        .has_value(&Value(1_000))
+        .and()
+        .has_rewards(&Value(100));
+
+    assert!(controller
+        .withdraws_rewards(&alice, Value(101), &mut ledger)
+        .is_err());
+    controller
+        .withdraws_rewards(&alice, Value(100), &mut ledger)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("after rewards withdrawal")
+        .account(alice.as_account_data())
+        .has_value(&Value(1_099))
+        .and()
+        .has_rewards(&Value::zero())
+        .and()
+        .has_last_reward(&Value(100));
+}
+
+#[test]
+pub fn rewards_withdrawal_pays_fees_with_the_rewards() {
+    let mut fee = LinearFee::new(0, 0, 0);
+    fee.per_certificate_fees(PerCertificateFee::new(None, None, None, NonZeroU64::new(1)));
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(ConfigBuilder::new().with_rewards(Value(100)).with_fee(fee))
+        .with_initials(vec![wallet("Alice").with(1_000).owns("stake_pool")])
+        .with_stake_pools(vec![stake_pool("stake_pool")
+            .with_reward_account(true)
+            .tax_ratio(1, 1)])
+        .build()
+        .unwrap();
+
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+    let reward_account = stake_pool.reward_account().unwrap().clone();
+    let reward_wallet = Wallet::from_address_data_value(AddressDataValue::new(
+        reward_account.clone(),
+        Value::zero(),
+    ));
+
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+    ledger.distribute_rewards().unwrap();
+
+    // the reward account has no value to pay the fees of the withdrawal
+    controller
+        .withdraws_rewards(&reward_wallet, Value(100), &mut ledger)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("after rewards withdrawal")
+        .account(reward_account)
+        .has_value(&Value(99))
+        .and()
+        .has_rewards(&Value::zero());
 }
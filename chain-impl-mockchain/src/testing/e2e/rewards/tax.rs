@@ -131,7 +131,7 @@ fn verify_distribute_rewards(
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1_000 + expected_stake_pool_reward));
+        .has_total_value(&Value(1_000 + expected_stake_pool_reward));
 }
 
 #[test]
@@ -180,7 +180,7 @@ pub fn fees_go_to_with_capping_about_threshold() {
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1_000 + 50));
+        .has_total_value(&Value(1_000 + 50));
 }
 
 #[test]
@@ -229,7 +229,7 @@ pub fn fees_go_to_with_capping_below_threshold() {
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1_000 + 50));
+        .has_total_value(&Value(1_000 + 50));
 }
 
 #[test]
@@ -274,5 +274,5 @@ pub fn fees_go_to_without_any_stake_pool() {
 
     ledger_verifier
         .account(alice.as_account_data())
-        .has_value(&Value(1_000));
+        .has_total_value(&Value(1_000));
 }
@@ -15,6 +15,7 @@ use crate::{
     value::Value,
     vote::{Choice, PayloadType},
 };
+use chain_vote::{verify_transcript, TallyTranscript};
 use imhamt::UpdateError::ValueCallbackError;
 
 const ALICE: &str = "Alice";
@@ -94,6 +95,32 @@ pub fn private_vote_cast_action_transfer_to_rewards_all_shares() {
         .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
         .unwrap();
 
+    let vote_plans = ledger.ledger.active_vote_plans();
+    let vote_plan_status = vote_plans
+        .iter()
+        .find(|c_vote_plan| {
+            let vote_plan: VotePlan = vote_plan.clone().into();
+            c_vote_plan.id == vote_plan.to_id()
+        })
+        .unwrap();
+    let transcript = vote_plan_status.tally_transcript().unwrap();
+    let transcript = TallyTranscript::from_bytes(&transcript.to_bytes()).unwrap();
+    assert_eq!(verify_transcript(&transcript), Ok(()));
+    let result = vote_plan_status.proposals[0]
+        .tally
+        .as_ref()
+        .unwrap()
+        .result()
+        .unwrap();
+    assert_eq!(
+        transcript.proposals()[0].tally.votes,
+        result
+            .results()
+            .iter()
+            .map(|w| u64::from(*w))
+            .collect::<Vec<_>>()
+    );
+
     ledger.fast_forward_to(BlockDate {
         epoch: 1,
         slot_id: 1,
@@ -448,6 +448,7 @@ pub fn votes_with_fees() {
         Some(NonZeroU64::new(1).unwrap()),
         Some(NonZeroU64::new(1).unwrap()),
         Some(NonZeroU64::new(1).unwrap()),
+        None,
     );
     fees.per_certificate_fees(cert_fees);
 
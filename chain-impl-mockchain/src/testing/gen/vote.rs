@@ -83,6 +83,7 @@ impl VoteTestGen {
             VoteTestGen::proposals(3),
             vote::PayloadType::Public,
             Vec::new(),
+            vote::Weighting::Linear,
         )
     }
 
@@ -109,6 +110,7 @@ impl VoteTestGen {
             VoteTestGen::proposals(3),
             vote::PayloadType::Private,
             manager.members().iter().map(|x| x.public_key()).collect(),
+            vote::Weighting::Linear,
         )
     }
 
@@ -120,6 +122,7 @@ impl VoteTestGen {
             VoteTestGen::proposals(count),
             vote::PayloadType::Public,
             Vec::new(),
+            vote::Weighting::Linear,
         )
     }
 
@@ -4,19 +4,21 @@ use crate::{
     account::Ledger as AccountLedger,
     block::Block,
     certificate::PoolId,
-    chaintypes::{ChainLength, ConsensusType, ConsensusVersion, HeaderId},
+    chaintypes::{
+        ChainLength, ConsensusType, ConsensusVersion, HeaderId, KesAlgorithm, VrfAlgorithm,
+    },
     config::{Block0Date, ConfigParam, RewardParams},
-    date::BlockDate,
+    date::{BlockDate, Epoch},
     fee::{LinearFee, PerCertificateFee, PerVoteCertificateFee},
     fragment::{config::ConfigParams, Fragment, FragmentId},
     key::BftLeaderId,
     leadership::genesis::LeadershipData,
     ledger::{
         check::CHECK_TX_MAXIMUM_INPUTS, Error, LeadersParticipationRecord, Ledger,
-        LedgerParameters, Pots, RewardsInfoParameters,
+        LedgerParameters, PoolRetirementsInfo, Pots, RewardsInfoParameters,
     },
     milli::Milli,
-    rewards::{Ratio, TaxType},
+    rewards::{PoolSaturation, Ratio, TaxType},
     stake::PoolsState,
     testing::{
         builders::GenesisPraosBlockBuilder,
@@ -56,7 +58,13 @@ pub struct ConfigBuilder {
     block0_date: Block0Date,
     consensus_version: ConsensusVersion,
     pool_capping_ratio: Ratio,
+    pool_saturation: Option<PoolSaturation>,
+    account_registration_deposit: Value,
+    pool_registration_deposit: Value,
+    reset_delegation_on_pool_retirement: bool,
     transaction_max_expiry_epochs: Option<u8>,
+    kes_algorithm: Option<KesAlgorithm>,
+    vrf_algorithm: Option<VrfAlgorithm>,
 }
 
 impl Default for ConfigBuilder {
@@ -81,6 +89,10 @@ impl ConfigBuilder {
                 numerator: 0,
                 denominator: NonZeroU64::new(1).unwrap(),
             },
+            pool_saturation: None,
+            account_registration_deposit: Value::zero(),
+            pool_registration_deposit: Value::zero(),
+            reset_delegation_on_pool_retirement: false,
             fees_in_treasury: false,
             rewards: Value(1_000_000),
             reward_params: RewardParams::Linear {
@@ -99,6 +111,8 @@ impl ConfigBuilder {
             block0_date: Block0Date(0),
             consensus_version: ConsensusVersion::Bft,
             transaction_max_expiry_epochs: None,
+            kes_algorithm: None,
+            vrf_algorithm: None,
         }
     }
 
@@ -134,6 +148,33 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn with_pool_reward_saturation(
+        mut self,
+        target_nb_pools: u32,
+        pledge_influence: Ratio,
+    ) -> Self {
+        self.pool_saturation = Some(PoolSaturation {
+            target_nb_pools: NonZeroU32::new(target_nb_pools).unwrap(),
+            pledge_influence,
+        });
+        self
+    }
+
+    pub fn with_account_registration_deposit(mut self, deposit: Value) -> Self {
+        self.account_registration_deposit = deposit;
+        self
+    }
+
+    pub fn with_pool_registration_deposit(mut self, deposit: Value) -> Self {
+        self.pool_registration_deposit = deposit;
+        self
+    }
+
+    pub fn with_reset_delegation_on_pool_retirement(mut self, reset: bool) -> Self {
+        self.reset_delegation_on_pool_retirement = reset;
+        self
+    }
+
     pub fn with_treasury_params(mut self, tax_type: TaxType) -> Self {
         self.treasury_params = tax_type;
         self
@@ -212,6 +253,16 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn with_kes_algorithm(mut self, kes_algorithm: KesAlgorithm) -> Self {
+        self.kes_algorithm = Some(kes_algorithm);
+        self
+    }
+
+    pub fn with_vrf_algorithm(mut self, vrf_algorithm: VrfAlgorithm) -> Self {
+        self.vrf_algorithm = Some(vrf_algorithm);
+        self
+    }
+
     fn create_single_bft_leader() -> BftLeaderId {
         let leader_prv_key: SecretKey<Ed25519Extended> = SecretKey::generate(rand_core::OsRng);
         let leader_pub_key = leader_prv_key.to_public();
@@ -247,6 +298,26 @@ impl ConfigBuilder {
             )));
         }
 
+        if let Some(pool_saturation) = self.pool_saturation {
+            ie.push(ConfigParam::PoolRewardSaturation(pool_saturation));
+        }
+
+        if self.account_registration_deposit != Value::zero() {
+            ie.push(ConfigParam::AccountRegistrationDeposit(
+                self.account_registration_deposit,
+            ));
+        }
+
+        if self.pool_registration_deposit != Value::zero() {
+            ie.push(ConfigParam::PoolRegistrationDeposit(
+                self.pool_registration_deposit,
+            ));
+        }
+
+        if self.reset_delegation_on_pool_retirement {
+            ie.push(ConfigParam::ResetDelegationOnPoolRetirement(true));
+        }
+
         if let Some(linear_fee) = self.linear_fee {
             ie.push(ConfigParam::LinearFee(linear_fee));
         }
@@ -271,6 +342,14 @@ impl ConfigBuilder {
             ));
         }
 
+        if let Some(kes_algorithm) = self.kes_algorithm {
+            ie.push(ConfigParam::KesAlgorithm(kes_algorithm));
+        }
+
+        if let Some(vrf_algorithm) = self.vrf_algorithm {
+            ie.push(ConfigParam::VrfAlgorithm(vrf_algorithm));
+        }
+
         for committee_id in self.committees_ids {
             ie.push(ConfigParam::AddCommitteeId(committee_id));
         }
@@ -348,7 +427,11 @@ impl LedgerBuilder {
 
     // add a fragment that pre-fill the address with a specific value at ledger start
     pub fn prefill_address(self, address: Address, value: Value) -> Self {
-        self.prefill_output(Output { address, value })
+        self.prefill_output(Output {
+            address,
+            value,
+            lock: None,
+        })
     }
 
     pub fn prefill_output(self, output: Output<Address>) -> Self {
@@ -605,7 +688,8 @@ impl TestLedger {
 
     pub fn distribute_rewards(&mut self) -> Result<(), Error> {
         match self.ledger.distribute_rewards(
-            &self.ledger.get_stake_distribution(),
+            self.ledger
+                .active_stake_distribution(self.ledger.date().epoch),
             &self.ledger.get_ledger_parameters(),
             RewardsInfoParameters::default(),
         ) {
@@ -659,6 +743,29 @@ impl TestLedger {
         self.set_date(date);
     }
 
+    /// Move to the first slot of `epoch` as if the epochs in between had no
+    /// block. Like for a block of `epoch` applied now, a single epoch
+    /// transition is applied, taking a single stake snapshot.
+    pub fn fast_forward_to_epoch(&mut self, epoch: Epoch) {
+        if self.ledger.date.epoch < epoch {
+            self.apply_epoch_transition(epoch);
+        }
+    }
+
+    /// Move to the first slot of the next epoch, returning the report of
+    /// the stake pools retired at the epoch transition
+    pub fn fast_forward_to_next_epoch(&mut self) -> PoolRetirementsInfo {
+        let next_epoch = self.ledger.date.epoch + 1;
+        self.apply_epoch_transition(next_epoch)
+    }
+
+    fn apply_epoch_transition(&mut self, epoch: Epoch) -> PoolRetirementsInfo {
+        assert!(!self.can_distribute_reward());
+        let pool_retirements = self.ledger.apply_epoch_transition(epoch).unwrap();
+        self.ledger.date = BlockDate { epoch, slot_id: 0 };
+        pool_retirements
+    }
+
     pub fn fire_leadership_event(
         &mut self,
         stake_pools: Vec<StakePool>,
@@ -669,7 +776,7 @@ impl TestLedger {
             if selection
                 .leader(
                     &stake_pool.id(),
-                    stake_pool.vrf().private_key(),
+                    &stake_pool.vrf().private_key().clone().into(),
                     self.ledger.date(),
                 )
                 .expect("cannot calculate leader")
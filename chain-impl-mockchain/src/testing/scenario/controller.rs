@@ -13,6 +13,7 @@ use crate::{
         scenario::template::VotePlanDef,
         VoteTestGen,
     },
+    value::Value,
     vote::{Choice, Payload, PayloadType},
 };
 
@@ -130,6 +131,54 @@ impl Controller {
         test_ledger.apply_fragment(&fragment, test_ledger.date())
     }
 
+    pub fn register_with_deposit(
+        &self,
+        funder: &Wallet,
+        stake_pool: &StakePool,
+        deposit: Value,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        let fragment = self.fragment_factory.stake_pool_registration_with_deposit(
+            test_ledger.date(),
+            funder,
+            stake_pool,
+            deposit,
+        );
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    }
+
+    pub fn registers_account(
+        &self,
+        funder: &Wallet,
+        account: &Wallet,
+        deposit: Value,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        let fragment = self.fragment_factory.account_registration(
+            test_ledger.date(),
+            funder,
+            account,
+            deposit,
+        );
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    }
+
+    pub fn deregisters_account(
+        &self,
+        from: &Wallet,
+        refund_to: &Wallet,
+        value: Value,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        let fragment = self.fragment_factory.account_deregistration(
+            test_ledger.date(),
+            from,
+            refund_to,
+            value,
+        );
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    }
+
     pub fn delegates(
         &self,
         from: &Wallet,
@@ -193,6 +242,18 @@ impl Controller {
         test_ledger.apply_fragment(&fragment, test_ledger.date())
     }
 
+    pub fn withdraws_rewards(
+        &self,
+        from: &Wallet,
+        value: Value,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        let fragment = self
+            .fragment_factory
+            .reward_withdrawal(test_ledger.date(), from, value);
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    }
+
     pub fn retire<'a>(
         &'a self,
         owners: impl IntoIterator<Item = &'a Wallet>,
@@ -383,9 +444,10 @@ mod tests {
             .retire(Some(&clarice), &stake_pool, &mut ledger)
             .unwrap();
         clarice.confirm_transaction();
+        ledger.fast_forward_to_epoch(1);
         // unassigned = clarice - fee (becaue thus clarise is an onwer of the stake she did not delegates any stakes)
         // dangling = bob and alice funds (minus fees for transactions and certs)
-        // total pool = 0, because stake pool was retired
+        // total pool = 0, because stake pool was retired at the epoch transition
 
         LedgerStateVerifier::new(ledger.into())
             .distribution()
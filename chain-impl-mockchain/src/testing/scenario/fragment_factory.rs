@@ -1,10 +1,11 @@
 use crate::{
     accounting::account::{DelegationRatio, DelegationType},
     certificate::{
-        Certificate, EncryptedVoteTally, PoolId, PoolUpdate, VoteCast, VotePlan, VoteTally,
+        AccountDeregistration, AccountRegistration, Certificate, CertificatePayload,
+        EncryptedVoteTally, PoolId, PoolUpdate, RewardWithdrawal, VoteCast, VotePlan, VoteTally,
     },
     date::BlockDate,
-    fee::LinearFee,
+    fee::{FeeAlgorithm, LinearFee},
     fragment::Fragment,
     key::Hash,
     testing::{
@@ -62,6 +63,53 @@ impl FragmentFactory {
         self.transaction_with_cert(valid_until, Some(funder), &cert)
     }
 
+    pub fn stake_pool_registration_with_deposit(
+        &self,
+        valid_until: BlockDate,
+        funder: &Wallet,
+        stake_pool: &StakePool,
+        deposit: Value,
+    ) -> Fragment {
+        let cert = build_stake_pool_registration_cert(&stake_pool.info());
+        self.transaction_with_cert_and_deposit(valid_until, funder, &cert, deposit)
+    }
+
+    pub fn account_registration(
+        &self,
+        valid_until: BlockDate,
+        funder: &Wallet,
+        account: &Wallet,
+        deposit: Value,
+    ) -> Fragment {
+        let cert =
+            Certificate::AccountRegistration(AccountRegistration::new(account.public_key().into()));
+        self.transaction_with_cert_and_deposit(valid_until, funder, &cert, deposit)
+    }
+
+    /// deregister the account, spending its `value` (including the refunded
+    /// deposit) to `refund_to` after the fees
+    pub fn account_deregistration(
+        &self,
+        valid_until: BlockDate,
+        from: &Wallet,
+        refund_to: &Wallet,
+        value: Value,
+    ) -> Fragment {
+        let cert = Certificate::AccountDeregistration(AccountDeregistration::new(
+            from.public_key().into(),
+        ));
+        let payload: CertificatePayload = (&cert).into();
+        let fee = self.fee.calculate(Some(payload.as_slice()), 1, 1);
+        let output = refund_to.make_output_with_value((value - fee).unwrap());
+        TestTxCertBuilder::new(self.block0_hash, self.fee).make_transaction_with_ios(
+            valid_until,
+            from,
+            &cert,
+            value,
+            &[output],
+        )
+    }
+
     pub fn delegation(
         &self,
         valid_until: BlockDate,
@@ -116,6 +164,16 @@ impl FragmentFactory {
         self.transaction_with_cert(valid_until, Some(from), &cert)
     }
 
+    pub fn reward_withdrawal(
+        &self,
+        valid_until: BlockDate,
+        from: &Wallet,
+        value: Value,
+    ) -> Fragment {
+        let cert = Certificate::RewardWithdrawal(RewardWithdrawal::new(value));
+        self.transaction_with_cert(valid_until, Some(from), &cert)
+    }
+
     pub fn stake_pool_retire<'a>(
         &self,
         valid_until: BlockDate,
@@ -190,4 +248,16 @@ impl FragmentFactory {
             certificate,
         )
     }
+
+    fn transaction_with_cert_and_deposit(
+        &self,
+        valid_until: BlockDate,
+        funder: &Wallet,
+        certificate: &Certificate,
+        deposit: Value,
+    ) -> Fragment {
+        let builder = TestTxCertBuilder::new(self.block0_hash, self.fee);
+        let input_value = (builder.fee(certificate) + deposit).unwrap();
+        builder.make_transaction_with_ios(valid_until, funder, certificate, input_value, &[])
+    }
 }
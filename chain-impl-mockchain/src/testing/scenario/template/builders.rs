@@ -10,7 +10,7 @@ use crate::{
     testing::data::Wallet,
     testing::scenario::{scenario_builder::ScenarioBuilderError, template::StakePoolDef},
     value::Value,
-    vote::PayloadType,
+    vote::{PayloadType, Weighting},
 };
 use chain_vote::MemberPublicKey;
 use std::{
@@ -238,6 +238,7 @@ pub struct VotePlanDefBuilder {
     alias: String,
     owner_alias: Option<String>,
     payload_type: PayloadType,
+    weighting: Weighting,
     vote_date: Option<BlockDate>,
     tally_date: Option<BlockDate>,
     end_tally_date: Option<BlockDate>,
@@ -251,6 +252,7 @@ impl VotePlanDefBuilder {
             alias: alias.to_owned(),
             owner_alias: Option::None,
             payload_type: PayloadType::Public,
+            weighting: Weighting::Linear,
             vote_date: Option::None,
             tally_date: Option::None,
             end_tally_date: Option::None,
@@ -269,6 +271,11 @@ impl VotePlanDefBuilder {
         self
     }
 
+    pub fn weighting(&mut self, weighting: Weighting) -> &mut Self {
+        self.weighting = weighting;
+        self
+    }
+
     pub fn committee_keys(&mut self, committee_keys: Vec<MemberPublicKey>) -> &mut Self {
         self.committee_keys = committee_keys;
         self
@@ -316,6 +323,7 @@ impl VotePlanDefBuilder {
             alias: self.alias.clone(),
             owner_alias: self.owner_alias.unwrap(),
             payload_type: self.payload_type,
+            weighting: self.weighting,
             vote_date: self.vote_date.unwrap(),
             tally_date: self.tally_date.unwrap(),
             end_tally_date: self.end_tally_date.unwrap(),
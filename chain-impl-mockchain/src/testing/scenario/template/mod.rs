@@ -5,7 +5,7 @@ use crate::{
     header::BlockDate,
     rewards::TaxType,
     value::Value,
-    vote::{Options, PayloadType},
+    vote::{Options, PayloadType, Weighting},
 };
 pub use builders::*;
 use chain_crypto::{Ed25519, PublicKey};
@@ -98,6 +98,7 @@ pub struct VotePlanDef {
     alias: String,
     owner_alias: String,
     payload_type: PayloadType,
+    weighting: Weighting,
     vote_date: BlockDate,
     tally_date: BlockDate,
     end_tally_date: BlockDate,
@@ -150,6 +151,7 @@ impl From<VotePlanDef> for VotePlan {
             proposals,
             dto.payload_type,
             dto.committee_keys,
+            dto.weighting,
         )
     }
 }
@@ -1,7 +1,7 @@
 use crate::{
     account::{Identifier, Ledger as AccountLedger},
     accounting::account::{account_state::AccountState, DelegationType},
-    certificate::{PoolId, PoolRegistration},
+    certificate::{PoolId, PoolMetadata, PoolRegistration, PoolRelay},
     ledger::{ledger::Ledger, Pots},
     stake::PoolsState,
     stake::{Stake, StakeDistribution},
@@ -251,6 +251,52 @@ impl AccountVerifier {
         );
         self
     }
+
+    pub fn has_rewards(&self, value: &Value) -> &Self {
+        let actual_rewards = self
+            .accounts
+            .get_state(&self.address.to_id())
+            .expect("cannot find account")
+            .rewards;
+        let expected_rewards = *value;
+        assert_eq!(
+            actual_rewards, expected_rewards,
+            "incorrect account rewards {} vs {} {}",
+            actual_rewards, expected_rewards, self.info
+        );
+        self
+    }
+
+    pub fn has_deposit(&self, value: &Value) -> &Self {
+        let actual_deposit = self
+            .accounts
+            .get_state(&self.address.to_id())
+            .expect("cannot find account")
+            .deposit;
+        let expected_deposit = *value;
+        assert_eq!(
+            actual_deposit, expected_deposit,
+            "incorrect account deposit {} vs {} {}",
+            actual_deposit, expected_deposit, self.info
+        );
+        self
+    }
+
+    /// check the value and the rewards, not withdrawn yet, of the account
+    pub fn has_total_value(&self, value: &Value) -> &Self {
+        let actual_value = self
+            .accounts
+            .get_state(&self.address.to_id())
+            .expect("cannot find account")
+            .total_value();
+        let expected_value = *value;
+        assert_eq!(
+            actual_value, expected_value,
+            "incorrect account total value {} vs {} {}",
+            actual_value, expected_value, self.info
+        );
+        self
+    }
 }
 
 pub struct DelegationVerifier {
@@ -284,6 +330,16 @@ impl DelegationVerifier {
         };
         self
     }
+
+    pub fn is_not_delegated(&self) -> &Self {
+        assert_eq!(
+            self.delegation_type,
+            DelegationType::NonDelegated,
+            "{}: wrong delegation type, Expected: NonDelegated",
+            self.info
+        );
+        self
+    }
 }
 
 pub struct PotsVerifier {
@@ -327,6 +383,15 @@ impl PotsVerifier {
         );
         self
     }
+
+    pub fn has_deposits_equals_to(&self, value: &Value) -> &Self {
+        assert_eq!(
+            self.pots.deposits, *value,
+            "incorrect deposits value {}",
+            self.info
+        );
+        self
+    }
 }
 
 pub struct StakePoolsVerifier {
@@ -390,6 +455,24 @@ impl StakePoolVerifier {
         );
         self
     }
+
+    pub fn metadata_eq(&self, metadata: Option<PoolMetadata>) -> &Self {
+        assert_eq!(
+            self.stake_pool_reg.metadata, metadata,
+            "{}: stake pool ({}) has incorrect metadata",
+            self.info, self.stake_pool_id
+        );
+        self
+    }
+
+    pub fn relays_eq(&self, relays: Vec<PoolRelay>) -> &Self {
+        assert_eq!(
+            self.stake_pool_reg.relays, relays,
+            "{}: stake pool ({}) has incorrect relays",
+            self.info, self.stake_pool_id
+        );
+        self
+    }
 }
 
 pub struct DistributionVerifier {
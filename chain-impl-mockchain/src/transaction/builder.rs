@@ -3,7 +3,7 @@ use super::payload::{NoExtra, Payload};
 use super::transaction::{
     Transaction, TransactionAuthData, TransactionBindingAuthData, TransactionStruct,
 };
-use super::transfer::Output;
+use super::transfer::{write_output_lock, Output};
 use super::witness::Witness;
 use crate::date::BlockDate;
 use chain_addr::Address;
@@ -140,6 +140,7 @@ impl<P> TxBuilderState<SetIOs<P>> {
         self.tstruct.outputs = self.current_pos();
 
         for o in outputs {
+            write_output_lock(&o.lock, &mut self.data);
             self.data.extend_from_slice(&o.address.to_bytes());
             self.data.extend_from_slice(&o.value.bytes());
         }
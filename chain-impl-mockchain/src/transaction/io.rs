@@ -113,7 +113,11 @@ impl InputOutputBuilder {
         if self.outputs.len() == 255 {
             return Err(Error::TxTooManyOutputs);
         }
-        self.outputs.push(Output { address, value });
+        self.outputs.push(Output {
+            address,
+            value,
+            lock: None,
+        });
         Ok(())
     }
 
@@ -250,7 +254,11 @@ impl InputOutputBuilder {
                 match self.balance(fee) {
                     Ok(Balance::Positive(value)) => {
                         let _ = self.outputs.pop();
-                        let output = Output { address, value };
+                        let output = Output {
+                            address,
+                            value,
+                            lock: None,
+                        };
                         self.outputs.push(output.clone());
                         Ok((Balance::Zero, vec![output], self.build()))
                     }
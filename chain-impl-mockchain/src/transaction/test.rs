@@ -1,6 +1,7 @@
 use super::{
-    element::SingleAccountBindingSignature, AccountBindingSignature, AccountIdentifier, Input,
-    NoExtra, Payload, Transaction, TxBuilder, UnspecifiedAccountIdentifier, UtxoPointer, Witness,
+    element::SingleAccountBindingSignature, transfer::write_output_lock, AccountBindingSignature,
+    AccountIdentifier, Input, NoExtra, Output, OutputLock, Payload, Transaction, TxBuilder,
+    UnspecifiedAccountIdentifier, UtxoPointer, Witness,
 };
 #[cfg(test)]
 use crate::certificate::OwnerStakeDelegation;
@@ -8,6 +9,8 @@ use crate::date::BlockDate;
 use crate::key::{EitherEd25519SecretKey, SpendingSignature};
 #[cfg(test)]
 use crate::testing::serialization::serialization_bijection_r;
+use chain_addr::Address;
+use chain_core::mempack::{ReadBuf, Readable};
 use chain_crypto::{testing::arbitrary_secret_key, Ed25519, SecretKey, Signature};
 #[cfg(test)]
 use quickcheck::TestResult;
@@ -116,6 +119,25 @@ pub fn check_transaction_accessor_consistent(tx: Transaction<NoExtra>) -> TestRe
     }
 }
 
+#[quickcheck]
+pub fn output_lock_encoding(output: Output<Address>, lock: Option<OutputLock>) -> TestResult {
+    let mut bytes = Vec::new();
+    write_output_lock(&lock, &mut bytes);
+    let lock_len = bytes.len();
+    bytes.extend_from_slice(&output.address.to_bytes());
+    bytes.extend_from_slice(&output.value.bytes());
+
+    // outputs without a lock keep the original address and value encoding
+    if lock.is_none() && lock_len != 0 {
+        return TestResult::error("an unlocked output has a lock prefix");
+    }
+
+    let mut buf = ReadBuf::from(&bytes);
+    let decoded = Output::<Address>::read(&mut buf).unwrap();
+    buf.expect_end().unwrap();
+    TestResult::from_bool(decoded == Output { lock, ..output })
+}
+
 impl Arbitrary for UtxoPointer {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         UtxoPointer {
@@ -132,6 +154,16 @@ impl Arbitrary for Input {
     }
 }
 
+impl Arbitrary for OutputLock {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        if Arbitrary::arbitrary(g) {
+            OutputLock::Until(Arbitrary::arbitrary(g))
+        } else {
+            OutputLock::Slots(Arbitrary::arbitrary(g))
+        }
+    }
+}
+
 impl Arbitrary for NoExtra {
     fn arbitrary<G: Gen>(_: &mut G) -> Self {
         Self
@@ -152,9 +184,15 @@ where
         let inputs: Vec<_> = std::iter::repeat_with(|| Arbitrary::arbitrary(g))
             .take(num_inputs % 16)
             .collect();
-        let outputs: Vec<_> = std::iter::repeat_with(|| Arbitrary::arbitrary(g))
-            .take(num_outputs % 16)
-            .collect();
+        let outputs: Vec<_> = std::iter::repeat_with(|| {
+            let output: Output<_> = Arbitrary::arbitrary(g);
+            match Arbitrary::arbitrary(g) {
+                Some(lock) => output.with_lock(lock),
+                None => output,
+            }
+        })
+        .take(num_outputs % 16)
+        .collect();
         let witnesses: Vec<_> = std::iter::repeat_with(|| Arbitrary::arbitrary(g))
             .take(num_inputs % 16)
             .collect();
@@ -197,14 +235,15 @@ impl Arbitrary for TransactionSigningKey {
 
 impl Arbitrary for Witness {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let opt = u8::arbitrary(g) % 3;
+        let opt = u8::arbitrary(g) % 4;
         match opt {
             0 => Witness::Utxo(SpendingSignature::arbitrary(g)),
-            1 => Witness::Account(SpendingSignature::arbitrary(g)),
+            1 => Witness::Account(Arbitrary::arbitrary(g), SpendingSignature::arbitrary(g)),
             2 => {
                 let sk: SecretKey<Ed25519> = arbitrary_secret_key(g);
                 Witness::OldUtxo(sk.to_public(), [0u8; 32], Signature::arbitrary(g))
             }
+            3 => Witness::Script(Arbitrary::arbitrary(g), Arbitrary::arbitrary(g)),
             _ => panic!("not implemented"),
         }
     }
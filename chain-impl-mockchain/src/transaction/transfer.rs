@@ -1,26 +1,95 @@
+use crate::date::BlockDate;
 use crate::legacy::OldAddress;
 use crate::value::*;
 use chain_core::mempack::{ReadBuf, ReadError, Readable};
 
 /// Information how tokens are spent.
-/// A value of tokens is sent to the address.
+/// A value of tokens is sent to the address, optionally locked
+/// until a given date.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Output<Address> {
     pub address: Address,
     pub value: Value,
+    pub lock: Option<OutputLock>,
+}
+
+/// Lock preventing an output from being spent before a given date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputLock {
+    /// Cannot be spent before the given date
+    Until(BlockDate),
+    /// Cannot be spent before the given number of slots after the block
+    /// the output is created in. The ledger stores it as `Until`.
+    Slots(u32),
+}
+
+// A locked output is prefixed with one of these tags. They are below the
+// smallest address kind, so they can never be the first byte of an address
+// and outputs without a lock keep their original encoding.
+const OUTPUT_LOCK_TAG_UNTIL: u8 = 1;
+const OUTPUT_LOCK_TAG_SLOTS: u8 = 2;
+
+impl<Address> Output<Address> {
+    #[must_use = "this returns the result of the operation, without modifying the original"]
+    pub fn with_lock(self, lock: OutputLock) -> Self {
+        Output {
+            lock: Some(lock),
+            ..self
+        }
+    }
 }
 
 impl<Address: Readable> Output<Address> {
     pub fn from_address(address: Address, value: Value) -> Self {
-        Output { address, value }
+        Output {
+            address,
+            value,
+            lock: None,
+        }
+    }
+}
+
+pub(super) fn write_output_lock(lock: &Option<OutputLock>, bytes: &mut Vec<u8>) {
+    match lock {
+        None => {}
+        Some(OutputLock::Until(date)) => {
+            bytes.push(OUTPUT_LOCK_TAG_UNTIL);
+            bytes.extend_from_slice(&date.epoch.to_be_bytes());
+            bytes.extend_from_slice(&date.slot_id.to_be_bytes());
+        }
+        Some(OutputLock::Slots(slots)) => {
+            bytes.push(OUTPUT_LOCK_TAG_SLOTS);
+            bytes.extend_from_slice(&slots.to_be_bytes());
+        }
+    }
+}
+
+fn read_output_lock(buf: &mut ReadBuf) -> Result<Option<OutputLock>, ReadError> {
+    match buf.peek_u8()? {
+        OUTPUT_LOCK_TAG_UNTIL => {
+            buf.skip_bytes(1)?;
+            let epoch = buf.get_u32()?;
+            let slot_id = buf.get_u32()?;
+            Ok(Some(OutputLock::Until(BlockDate { epoch, slot_id })))
+        }
+        OUTPUT_LOCK_TAG_SLOTS => {
+            buf.skip_bytes(1)?;
+            Ok(Some(OutputLock::Slots(buf.get_u32()?)))
+        }
+        _ => Ok(None),
     }
 }
 
 impl<Address: Readable> Readable for Output<Address> {
     fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        let lock = read_output_lock(buf)?;
         let address = Address::read(buf)?;
         let value = Value::read(buf)?;
-        Ok(Output { address, value })
+        Ok(Output {
+            address,
+            value,
+            lock,
+        })
     }
 }
 
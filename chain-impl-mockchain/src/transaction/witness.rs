@@ -6,6 +6,7 @@ use crate::key::{
     SpendingSignature,
 };
 use crate::multisig;
+use crate::script;
 use chain_core::mempack::{ReadBuf, ReadError, Readable};
 use chain_core::property;
 use chain_crypto::{Ed25519, PublicKey, Signature};
@@ -16,24 +17,31 @@ use chain_crypto::{Ed25519, PublicKey, Signature};
 ///
 /// It's important that witness works with opaque structures
 /// and may not know the contents of the internal transaction.
+///
+/// The account and multisig witnesses carry the spending counter they
+/// signed, which selects the lane of the account being spent from.
 #[derive(Debug, Clone)]
 pub enum Witness {
     Utxo(SpendingSignature<WitnessUtxoData>),
-    Account(account::Witness),
+    Account(account::SpendingCounter, account::Witness),
     OldUtxo(
         PublicKey<Ed25519>,
         [u8; 32],
         Signature<WitnessUtxoData, Ed25519>,
     ),
-    Multisig(multisig::Witness),
+    Multisig(account::SpendingCounter, multisig::Witness),
+    Script(script::Script, script::Witness),
 }
 
 impl PartialEq for Witness {
     fn eq(&self, rhs: &Self) -> bool {
         match (self, rhs) {
             (Witness::Utxo(s1), Witness::Utxo(s2)) => s1.as_ref() == s2.as_ref(),
-            (Witness::Account(s1), Witness::Account(s2)) => s1.as_ref() == s2.as_ref(),
-            (Witness::Multisig(s1), Witness::Multisig(s2)) => s1 == s2,
+            (Witness::Account(c1, s1), Witness::Account(c2, s2)) => {
+                c1 == c2 && s1.as_ref() == s2.as_ref()
+            }
+            (Witness::Multisig(c1, s1), Witness::Multisig(c2, s2)) => c1 == c2 && s1 == s2,
+            (Witness::Script(c1, s1), Witness::Script(c2, s2)) => c1 == c2 && s1 == s2,
             (Witness::OldUtxo(p1, c1, s1), Witness::OldUtxo(p2, c2, s2)) => {
                 s1.as_ref() == s2.as_ref() && c1 == c2 && p1 == p2
             }
@@ -47,9 +55,10 @@ impl std::fmt::Display for Witness {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Witness::Utxo(_) => write!(f, "UTxO Witness"),
-            Witness::Account(_) => write!(f, "Account Witness"),
+            Witness::Account(..) => write!(f, "Account Witness"),
             Witness::OldUtxo(..) => write!(f, "Old UTxO Witness"),
-            Witness::Multisig(_) => write!(f, "Multisig Witness"),
+            Witness::Multisig(..) => write!(f, "Multisig Witness"),
+            Witness::Script(..) => write!(f, "Script Witness"),
         }
     }
 }
@@ -137,6 +146,22 @@ impl AsRef<[u8]> for WitnessMultisigData {
     }
 }
 
+pub struct WitnessScriptData(Vec<u8>);
+
+impl WitnessScriptData {
+    pub fn new(block0: &HeaderId, transaction_id: &TransactionSignDataHash) -> Self {
+        let mut v = Vec::with_capacity(65);
+        witness_data_common(&mut v, WITNESS_TAG_SCRIPT, block0, transaction_id);
+        Self(v)
+    }
+}
+
+impl AsRef<[u8]> for WitnessScriptData {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
 impl Witness {
     /// Creates new `Witness` value.
     pub fn new_utxo<F>(block0: &HeaderId, sign_data_hash: &TransactionSignDataHash, sign: F) -> Self
@@ -172,7 +197,35 @@ impl Witness {
     {
         let wud = WitnessAccountData::new(block0, sign_data_hash, spending_counter);
         let sig = sign(&wud);
-        Witness::Account(sig)
+        Witness::Account(spending_counter, sig)
+    }
+
+    pub fn new_multisig<F>(
+        block0: &HeaderId,
+        sign_data_hash: &TransactionSignDataHash,
+        spending_counter: account::SpendingCounter,
+        sign: F,
+    ) -> Self
+    where
+        F: FnOnce(&WitnessMultisigData) -> multisig::Witness,
+    {
+        let wmd = WitnessMultisigData::new(block0, sign_data_hash, spending_counter);
+        let msig = sign(&wmd);
+        Witness::Multisig(spending_counter, msig)
+    }
+
+    pub fn new_script<F>(
+        block0: &HeaderId,
+        sign_data_hash: &TransactionSignDataHash,
+        script: script::Script,
+        sign: F,
+    ) -> Self
+    where
+        F: FnOnce(&WitnessScriptData) -> script::Witness,
+    {
+        let wsd = WitnessScriptData::new(block0, sign_data_hash);
+        let witness = sign(&wsd);
+        Witness::Script(script, witness)
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -186,6 +239,7 @@ const WITNESS_TAG_OLDUTXO: u8 = 0u8;
 const WITNESS_TAG_UTXO: u8 = 1u8;
 const WITNESS_TAG_ACCOUNT: u8 = 2u8;
 const WITNESS_TAG_MULTISIG: u8 = 3u8;
+const WITNESS_TAG_SCRIPT: u8 = 4u8;
 
 impl property::Serialize for Witness {
     type Error = std::io::Error;
@@ -206,14 +260,21 @@ impl property::Serialize for Witness {
                 codec.put_u8(WITNESS_TAG_UTXO)?;
                 serialize_signature(sig, codec.into_inner())
             }
-            Witness::Account(sig) => {
+            Witness::Account(counter, sig) => {
                 codec.put_u8(WITNESS_TAG_ACCOUNT)?;
+                codec.put_u32(counter.0)?;
                 serialize_signature(sig, codec.into_inner())
             }
-            Witness::Multisig(msig) => {
+            Witness::Multisig(counter, msig) => {
                 codec.put_u8(WITNESS_TAG_MULTISIG)?;
+                codec.put_u32(counter.0)?;
                 msig.serialize(codec.into_inner())
             }
+            Witness::Script(script, witness) => {
+                codec.put_u8(WITNESS_TAG_SCRIPT)?;
+                script.serialize(&mut codec)?;
+                witness.serialize(codec.into_inner())
+            }
         }
     }
 }
@@ -228,10 +289,20 @@ impl Readable for Witness {
                 Ok(Witness::OldUtxo(pk, some_bytes, sig))
             }
             WITNESS_TAG_UTXO => deserialize_signature(buf).map(Witness::Utxo),
-            WITNESS_TAG_ACCOUNT => deserialize_signature(buf).map(Witness::Account),
+            WITNESS_TAG_ACCOUNT => {
+                let counter = account::SpendingCounter(buf.get_u32()?);
+                let sig = deserialize_signature(buf)?;
+                Ok(Witness::Account(counter, sig))
+            }
             WITNESS_TAG_MULTISIG => {
+                let counter = account::SpendingCounter(buf.get_u32()?);
                 let msig = multisig::Witness::read(buf)?;
-                Ok(Witness::Multisig(msig))
+                Ok(Witness::Multisig(counter, msig))
+            }
+            WITNESS_TAG_SCRIPT => {
+                let script = script::Script::read(buf)?;
+                let witness = script::Witness::read(buf)?;
+                Ok(Witness::Script(script, witness))
             }
             i => Err(ReadError::UnknownTag(i as u32)),
         }
@@ -67,7 +67,7 @@ impl<Extra: Clone> TransactionBuilder<Extra> {
     ///
     /// Each output may extend the size of the required fee.
     pub fn add_output(&mut self, address: Address, value: Value) {
-        self.tx.outputs.push(tx::Output { address, value })
+        self.tx.outputs.push(tx::Output { address, value, lock: None })
     }
 
     /// Calculate the fees on a given fee algorithm for the current transaction
@@ -157,13 +157,14 @@ impl<Extra: Clone> TransactionBuilder<Extra> {
                 tx.outputs.push(tx::Output {
                     address: address.clone(),
                     value: Value(0),
+                    lock: None,
                 });
                 let fee = fee_algorithm
                     .calculate(&self.tx.extra, &self.tx.inputs, &self.tx.outputs)
                     .ok_or(Error::MathErr(ValueError::Overflow))?;
                 match tx.balance(fee) {
                     Ok(Balance::Positive(value)) => {
-                        self.tx.outputs.push(tx::Output { address, value });
+                        self.tx.outputs.push(tx::Output { address, value, lock: None });
                         Ok((Balance::Zero, self.tx))
                     }
                     _ => Ok((Balance::Positive(pos), self.tx)),
@@ -212,8 +213,9 @@ impl TransactionFinalizer {
         match (self.tx.inputs[index].get_type(), &witness) {
             (tx::InputType::Utxo, tx::Witness::OldUtxo(_, _)) => (),
             (tx::InputType::Utxo, tx::Witness::Utxo(_)) => (),
-            (tx::InputType::Account, tx::Witness::Account(_)) => (),
-            (tx::InputType::Account, tx::Witness::Multisig(_)) => (),
+            (tx::InputType::Utxo, tx::Witness::Script(..)) => (),
+            (tx::InputType::Account, tx::Witness::Account(..)) => (),
+            (tx::InputType::Account, tx::Witness::Multisig(..)) => (),
             (_, _) => return Err(BuildError::WitnessMismatch { index }),
         };
 
@@ -0,0 +1,234 @@
+use crate::{certificate::VoteRepresentative, transaction::UnspecifiedAccountIdentifier};
+use imhamt::Hamt;
+use std::collections::{hash_map::DefaultHasher, HashSet};
+
+/// the voting power delegations: every account may delegate its voting
+/// power to (at most) one representative.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Delegations {
+    representatives: Hamt<DefaultHasher, UnspecifiedAccountIdentifier, VoteRepresentative>,
+}
+
+/// the delegations applying to one vote plan: the delegations specific
+/// to the vote plan take precedence over the global delegations.
+#[derive(Clone, Copy)]
+pub(crate) struct PlanDelegations<'a> {
+    pub(crate) plan: &'a Delegations,
+    pub(crate) global: &'a Delegations,
+}
+
+impl Delegations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.representatives.is_empty()
+    }
+
+    /// get the representative the given account delegated its voting power to
+    pub fn representative(
+        &self,
+        delegator: &UnspecifiedAccountIdentifier,
+    ) -> Option<&VoteRepresentative> {
+        self.representatives.lookup(delegator)
+    }
+
+    /// set (or replace) the representative of the given delegator, or remove
+    /// the delegation if `representative` is `None`.
+    #[must_use = "internal state is not modified"]
+    pub fn delegate(
+        &self,
+        delegator: UnspecifiedAccountIdentifier,
+        representative: Option<VoteRepresentative>,
+    ) -> Self {
+        let representatives = match representative {
+            Some(representative) => self.representatives.insert_or_update_simple(
+                delegator,
+                representative.clone(),
+                |_| Some(representative),
+            ),
+            None => self
+                .representatives
+                .remove(&delegator)
+                .unwrap_or_else(|_| self.representatives.clone()),
+        };
+        Self { representatives }
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&UnspecifiedAccountIdentifier, &VoteRepresentative)> {
+        self.representatives.iter()
+    }
+}
+
+impl std::iter::FromIterator<(UnspecifiedAccountIdentifier, VoteRepresentative)> for Delegations {
+    fn from_iter<I: IntoIterator<Item = (UnspecifiedAccountIdentifier, VoteRepresentative)>>(
+        iter: I,
+    ) -> Self {
+        Self {
+            representatives: Hamt::from_iter(iter),
+        }
+    }
+}
+
+impl<'a> PlanDelegations<'a> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.plan.is_empty() && self.global.is_empty()
+    }
+
+    /// the account holding the voting power of `delegator` for the vote
+    /// plan, `None` if `delegator` keeps its voting power
+    fn representative(
+        &self,
+        delegator: &UnspecifiedAccountIdentifier,
+    ) -> Option<&'a UnspecifiedAccountIdentifier> {
+        self.plan
+            .representative(delegator)
+            .or_else(|| self.global.representative(delegator))
+            .and_then(VoteRepresentative::account)
+    }
+
+    /// every account with a delegation applying to the vote plan
+    pub(crate) fn delegators(&self) -> impl Iterator<Item = &'a UnspecifiedAccountIdentifier> {
+        let plan = self.plan;
+        plan.iter()
+            .filter(|(_, representative)| representative.account().is_some())
+            .map(|(delegator, _)| delegator)
+            .chain(
+                self.global
+                    .iter()
+                    .filter(move |(delegator, representative)| {
+                        representative.account().is_some()
+                            && plan.representative(delegator).is_none()
+                    })
+                    .map(|(delegator, _)| delegator),
+            )
+    }
+
+    /// follow the delegation chain starting from `delegator` until reaching
+    /// an account that has voted.
+    ///
+    /// Returns `None` if the chain ends on an account that neither voted nor
+    /// delegated, or if the chain loops back on itself.
+    pub(crate) fn resolve<F>(
+        &self,
+        delegator: &'a UnspecifiedAccountIdentifier,
+        has_voted: F,
+    ) -> Option<&'a UnspecifiedAccountIdentifier>
+    where
+        F: Fn(&UnspecifiedAccountIdentifier) -> bool,
+    {
+        let mut visited = HashSet::new();
+        let mut current = delegator;
+        loop {
+            if has_voted(current) {
+                return Some(current);
+            }
+            if !visited.insert(current) {
+                // delegation cycle, the voting power is not accounted
+                return None;
+            }
+            current = self.representative(current)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestGen;
+
+    fn account(id: &UnspecifiedAccountIdentifier) -> VoteRepresentative {
+        VoteRepresentative::Account(id.clone())
+    }
+
+    #[test]
+    fn delegate_and_revoke() {
+        let alice = TestGen::unspecified_account_identifier();
+        let bob = TestGen::unspecified_account_identifier();
+
+        let delegations = Delegations::new().delegate(alice.clone(), Some(account(&bob)));
+        assert_eq!(delegations.representative(&alice), Some(&account(&bob)));
+        assert_eq!(delegations.representative(&bob), None);
+
+        let delegations = delegations.delegate(alice.clone(), None);
+        assert!(delegations.is_empty());
+    }
+
+    #[test]
+    fn resolve_chains() {
+        let alice = TestGen::unspecified_account_identifier();
+        let bob = TestGen::unspecified_account_identifier();
+        let carol = TestGen::unspecified_account_identifier();
+        let dave = TestGen::unspecified_account_identifier();
+
+        // alice -> bob -> carol (voted), dave -> carol (plan only)
+        let global = Delegations::new()
+            .delegate(alice.clone(), Some(account(&bob)))
+            .delegate(bob.clone(), Some(account(&carol)))
+            .delegate(dave.clone(), Some(account(&alice)));
+        let plan = Delegations::new().delegate(dave.clone(), Some(account(&carol)));
+        let delegations = PlanDelegations {
+            plan: &plan,
+            global: &global,
+        };
+
+        let has_voted = |id: &UnspecifiedAccountIdentifier| *id == carol;
+        assert_eq!(delegations.resolve(&alice, has_voted), Some(&carol));
+        assert_eq!(delegations.resolve(&dave, has_voted), Some(&carol));
+        assert_eq!(delegations.delegators().count(), 3);
+
+        // a direct vote stops the chain
+        let has_voted = |id: &UnspecifiedAccountIdentifier| *id == bob || *id == carol;
+        assert_eq!(delegations.resolve(&alice, has_voted), Some(&bob));
+
+        // nobody voted at the end of the chain
+        assert_eq!(delegations.resolve(&alice, |_| false), None);
+    }
+
+    #[test]
+    fn resolve_cycle() {
+        let alice = TestGen::unspecified_account_identifier();
+        let bob = TestGen::unspecified_account_identifier();
+        let carol = TestGen::unspecified_account_identifier();
+
+        let global = Delegations::new()
+            .delegate(alice.clone(), Some(account(&bob)))
+            .delegate(bob.clone(), Some(account(&carol)))
+            .delegate(carol.clone(), Some(account(&alice)));
+        let plan = Delegations::new();
+        let delegations = PlanDelegations {
+            plan: &plan,
+            global: &global,
+        };
+
+        assert_eq!(delegations.resolve(&alice, |_| false), None);
+    }
+
+    #[test]
+    fn plan_delegation_to_myself_overrides_global() {
+        let alice = TestGen::unspecified_account_identifier();
+        let bob = TestGen::unspecified_account_identifier();
+
+        let global = Delegations::new().delegate(alice.clone(), Some(account(&bob)));
+        let plan = Delegations::new().delegate(alice.clone(), Some(VoteRepresentative::Myself));
+        let delegations = PlanDelegations {
+            plan: &plan,
+            global: &global,
+        };
+
+        let has_voted = |id: &UnspecifiedAccountIdentifier| *id == bob;
+        assert_eq!(delegations.resolve(&alice, has_voted), None);
+        assert_eq!(delegations.delegators().count(), 0);
+
+        // without the plan delegation, the global one applies
+        let plan = Delegations::new();
+        let delegations = PlanDelegations {
+            plan: &plan,
+            global: &global,
+        };
+        assert_eq!(delegations.resolve(&alice, has_voted), Some(&bob));
+    }
+}
@@ -1,19 +1,23 @@
 use crate::certificate::EncryptedVoteTally;
 use crate::{
-    certificate::{TallyProof, VoteAction, VoteCast, VotePlan, VotePlanId, VoteTally},
+    certificate::{
+        TallyProof, VoteAction, VoteCast, VoteDelegation, VotePlan, VotePlanId, VoteRepresentative,
+        VoteTally,
+    },
     date::BlockDate,
     ledger::governance::Governance,
     stake::StakeControl,
     transaction::UnspecifiedAccountIdentifier,
-    vote::{CommitteeId, PayloadType, VoteError, VotePlanManager},
+    vote::{CommitteeId, Delegations, PayloadType, VerifiedBallots, VoteError, VotePlanManager},
 };
 use imhamt::{Hamt, InsertError, UpdateError};
-use std::collections::{hash_map::DefaultHasher, HashSet};
+use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use thiserror::Error;
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct VotePlanLedger {
     pub(crate) plans: Hamt<DefaultHasher, VotePlanId, VotePlanManager>,
+    pub(crate) delegations: Delegations,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -47,11 +51,17 @@ pub enum VotePlanLedgerError {
 
     #[error("Private vote plan must contain at least one committee member key")]
     VotePlanMissingCommitteeMemberKey,
+
+    #[error("An account cannot delegate its voting power to itself")]
+    VoteDelegationToSelf,
 }
 
 impl VotePlanLedger {
     pub fn new() -> Self {
-        Self { plans: Hamt::new() }
+        Self {
+            plans: Hamt::new(),
+            delegations: Delegations::new(),
+        }
     }
 
     /// attempt to apply the vote to the appropriate Vote Proposal
@@ -69,19 +79,117 @@ impl VotePlanLedger {
         block_date: BlockDate,
         identifier: UnspecifiedAccountIdentifier,
         vote: VoteCast,
+        verified: &VerifiedBallots,
     ) -> Result<Self, VotePlanLedgerError> {
         let id = vote.vote_plan().clone();
 
-        let r = self
-            .plans
-            .update(&id, move |v| v.vote(block_date, identifier, vote).map(Some));
+        let r = self.plans.update(&id, move |v| {
+            v.vote(block_date, identifier, vote, verified).map(Some)
+        });
 
         match r {
             Err(reason) => Err(VotePlanLedgerError::VoteError { reason, id }),
-            Ok(plans) => Ok(Self { plans }),
+            Ok(plans) => Ok(Self {
+                plans,
+                delegations: self.delegations.clone(),
+            }),
+        }
+    }
+
+    /// verify in batch the proofs of the given private vote casts
+    ///
+    /// the vote casts are grouped by vote plan, the vote casts for an
+    /// unknown vote plan are ignored.
+    pub fn verify_private_ballots(&self, casts: &[VoteCast]) -> VerifiedBallots {
+        let mut by_plan: HashMap<&VotePlanId, Vec<&VoteCast>> = HashMap::new();
+        for cast in casts {
+            by_plan.entry(cast.vote_plan()).or_default().push(cast);
+        }
+
+        let mut verified = VerifiedBallots::new();
+        for (id, casts) in by_plan {
+            if let Some(manager) = self.plans.lookup(id) {
+                verified.extend(manager.verify_private_ballots(casts));
+            }
+        }
+        verified
+    }
+
+    /// delegate the voting power of the given account to a representative
+    ///
+    /// the delegation applies to the vote plan specified in the certificate,
+    /// or to all the vote plans (including the future ones) otherwise. The
+    /// global delegations don't change the tally of the vote plans whose
+    /// vote already ended.
+    ///
+    /// # errors
+    ///
+    /// can fail if:
+    ///
+    /// * the account delegates to itself;
+    /// * the vote plan id does not exist;
+    /// * the vote of the vote plan already ended.
+    ///
+    pub fn apply_vote_delegation(
+        &self,
+        block_date: BlockDate,
+        identifier: UnspecifiedAccountIdentifier,
+        delegation: &VoteDelegation,
+    ) -> Result<Self, VotePlanLedgerError> {
+        if let Some(VoteRepresentative::Account(representative)) = delegation.representative() {
+            if representative == &identifier {
+                return Err(VotePlanLedgerError::VoteDelegationToSelf);
+            }
+        }
+
+        let representative = delegation.representative().cloned();
+
+        match delegation.vote_plan() {
+            None => Ok(Self {
+                plans: self.freeze_global_delegations(block_date),
+                delegations: self.delegations.delegate(identifier, representative),
+            }),
+            Some(id) => {
+                let r = self.plans.update(id, move |v| {
+                    v.delegate(block_date, identifier, representative).map(Some)
+                });
+
+                match r {
+                    Err(reason) => Err(VotePlanLedgerError::VoteError {
+                        reason,
+                        id: id.clone(),
+                    }),
+                    Ok(plans) => Ok(Self {
+                        plans,
+                        delegations: self.delegations.clone(),
+                    }),
+                }
+            }
         }
     }
 
+    /// keep the current global delegations in the vote plans whose vote is
+    /// finished at the given date, before changing them
+    fn freeze_global_delegations(
+        &self,
+        block_date: BlockDate,
+    ) -> Hamt<DefaultHasher, VotePlanId, VotePlanManager> {
+        let finished: Vec<VotePlanId> = self
+            .plans
+            .iter()
+            .filter(|(_, manager)| manager.needs_global_delegations(block_date))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        finished.iter().fold(self.plans.clone(), |plans, id| {
+            plans
+                .replace_with(id, |manager| {
+                    manager.with_global_delegations(&self.delegations)
+                })
+                .expect("the vote plan is in the ledger")
+        })
+    }
+
     /// add the vote plan in a new `VotePlanLedger`
     ///
     /// the given `VotePlanLedger` is not modified and instead a new `VotePlanLedger` is
@@ -124,7 +232,10 @@ impl VotePlanLedger {
 
         match self.plans.insert(id.clone(), manager) {
             Err(reason) => Err(VotePlanLedgerError::VotePlanInsertionError { id, reason }),
-            Ok(plans) => Ok(Self { plans }),
+            Ok(plans) => Ok(Self {
+                plans,
+                delegations: self.delegations.clone(),
+            }),
         }
     }
 
@@ -157,7 +268,14 @@ impl VotePlanLedger {
         };
         let r = self.plans.update(&id, move |v| match sig {
             TallyProof::Public { .. } => v
-                .public_tally(block_date, stake, governance, committee_id, f)
+                .public_tally(
+                    block_date,
+                    stake,
+                    &self.delegations,
+                    governance,
+                    committee_id,
+                    f,
+                )
                 .map(Some),
             TallyProof::Private { .. } => {
                 let shares = tally.tally_decrypted().unwrap();
@@ -167,7 +285,10 @@ impl VotePlanLedger {
 
         match r {
             Err(reason) => Err(VotePlanLedgerError::VoteError { reason, id }),
-            Ok(plans) => Ok(Self { plans }),
+            Ok(plans) => Ok(Self {
+                plans,
+                delegations: self.delegations.clone(),
+            }),
         }
     }
 
@@ -190,13 +311,16 @@ impl VotePlanLedger {
         let id = encrypted_tally.id().clone();
 
         let r = self.plans.update(&id, move |v| {
-            v.start_private_tally(block_date, stake, committee_id)
+            v.start_private_tally(block_date, stake, &self.delegations, committee_id)
                 .map(Some)
         });
 
         match r {
             Err(reason) => Err(VotePlanLedgerError::VoteError { reason, id }),
-            Ok(plans) => Ok(Self { plans }),
+            Ok(plans) => Ok(Self {
+                plans,
+                delegations: self.delegations.clone(),
+            }),
         }
     }
 }
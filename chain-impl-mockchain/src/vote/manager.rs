@@ -1,9 +1,15 @@
 use crate::{
     certificate::DecryptedPrivateTallyProposal,
-    vote::{Choice, Payload, PayloadType, TallyError},
+    vote::{
+        delegation::PlanDelegations, Choice, Delegations, Payload, PayloadType, TallyError,
+        Weighting,
+    },
 };
 use crate::{
-    certificate::{DecryptedPrivateTally, Proposal, VoteAction, VoteCast, VotePlan, VotePlanId},
+    certificate::{
+        DecryptedPrivateTally, Proposal, VoteAction, VoteCast, VotePlan, VotePlanId,
+        VoteRepresentative,
+    },
     date::BlockDate,
     ledger::governance::{Governance, GovernanceAcceptanceCriteria},
     rewards::Ratio,
@@ -15,7 +21,7 @@ use chain_vote::{committee, Ballot, Crs, ElectionPublicKey, EncryptedTally};
 use imhamt::Hamt;
 use thiserror::Error;
 
-use std::collections::{hash_map::DefaultHasher, HashSet};
+use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::num::NonZeroU64;
 use std::sync::Arc;
@@ -30,6 +36,10 @@ pub struct VotePlanManager {
     plan: Arc<VotePlan>,
     committee: Arc<HashSet<CommitteeId>>,
     proposal_managers: ProposalManagers,
+    delegations: Delegations,
+    /// the global delegations as they were at the end of the vote, once
+    /// they changed after it
+    global_delegations: Option<Delegations>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -38,6 +48,16 @@ pub enum ValidatedPayload {
     Private(Ballot),
 }
 
+/// private ballots verified ahead of time, in batch
+///
+/// the proofs of the private vote casts of a block can be verified all at
+/// once with `VotePlanManager::verify_private_ballots`, then the vote casts
+/// are applied one by one without verifying their proof again.
+#[derive(Default)]
+pub struct VerifiedBallots {
+    ballots: HashMap<VoteCast, Ballot>,
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 struct ValidatedVoteCast {
     payload: ValidatedPayload,
@@ -114,6 +134,9 @@ pub enum VoteError {
 
     #[error("Error during private tallying {0}")]
     PrivateTallyError(String),
+
+    #[error("It is not possible to delegate the voting power for the proposals, the vote ended at {end}.")]
+    NotDelegationTime { end: BlockDate },
 }
 
 impl ProposalManager {
@@ -194,9 +217,11 @@ impl ProposalManager {
         cast: VoteCast,
         crs: &Crs,
         election_pk: &ElectionPublicKey,
+        verified: &VerifiedBallots,
     ) -> Result<ValidatedPayload, VoteError> {
         self.check_already_voted(identifier)?;
 
+        let ballot = verified.get(&cast).cloned();
         let payload = cast.into_payload();
 
         match payload {
@@ -215,6 +240,8 @@ impl ProposalManager {
                         expected: expected_size,
                         actual: actual_size,
                     })
+                } else if let Some(ballot) = ballot {
+                    Ok(ValidatedPayload::Private(ballot))
                 } else {
                     Ok(ValidatedPayload::Private(Ballot::try_from_vote_and_proof(
                         encrypted_vote.as_inner().clone(),
@@ -231,6 +258,8 @@ impl ProposalManager {
     pub fn public_tally<F>(
         &self,
         stake: &StakeControl,
+        weighting: Weighting,
+        delegations: PlanDelegations,
         governance: &Governance,
         mut f: F,
     ) -> Result<Self, VoteError>
@@ -238,26 +267,25 @@ impl ProposalManager {
         F: FnMut(&VoteAction),
     {
         let mut results = TallyResult::new(self.options.clone());
+        let voting_power = self.voting_power(stake, weighting, delegations);
 
         for (id, payload) in self.votes_by_voters.iter() {
-            if let Some(account_id) = id.to_single_account() {
-                if let Some(stake) = stake.by(&account_id) {
-                    match payload {
-                        ValidatedPayload::Public(choice) => {
-                            results.add_vote(*choice, stake)?;
-                        }
-                        ValidatedPayload::Private(_) => {
-                            return Err(VoteError::InvalidPayloadType {
-                                expected: PayloadType::Public,
-                                received: PayloadType::Private,
-                            });
-                        }
+            if let Some(power) = voting_power.get(id) {
+                match payload {
+                    ValidatedPayload::Public(choice) => {
+                        results.add_vote(*choice, *power)?;
+                    }
+                    ValidatedPayload::Private(_) => {
+                        return Err(VoteError::InvalidPayloadType {
+                            expected: PayloadType::Public,
+                            received: PayloadType::Private,
+                        });
                     }
                 }
             }
         }
 
-        if self.check(stake.assigned(), governance, &results) {
+        if self.check(weighting.total(stake), governance, &results) {
             f(&self.action)
         }
 
@@ -273,34 +301,28 @@ impl ProposalManager {
     pub fn private_tally(
         &self,
         stake: &StakeControl,
+        weighting: Weighting,
+        delegations: PlanDelegations,
         election_pk: &ElectionPublicKey,
         crs: &Crs,
     ) -> Result<Self, VoteError> {
         use rayon::prelude::*;
 
         let tally_size = self.options.choice_range().clone().max().unwrap() as usize + 1;
+        let voting_power = self.voting_power(stake, weighting, delegations);
 
         let tally = self
             .votes_by_voters
             .iter()
             .par_bridge()
             .filter_map(|(id, payload)| {
-                if let Some(account_id) = id.to_single_account() {
-                    if let Some(stake) = stake.by(&account_id) {
-                        match payload {
-                            ValidatedPayload::Public(_) => {
-                                return Some(Err(VoteError::InvalidPayloadType {
-                                    expected: PayloadType::Private,
-                                    received: PayloadType::Public,
-                                }))
-                            }
-                            ValidatedPayload::Private(ballot) => {
-                                return Some(Ok((ballot, stake.0)))
-                            }
-                        }
-                    }
-                }
-                None
+                voting_power.get(id).map(|power| match payload {
+                    ValidatedPayload::Public(_) => Err(VoteError::InvalidPayloadType {
+                        expected: PayloadType::Private,
+                        received: PayloadType::Public,
+                    }),
+                    ValidatedPayload::Private(ballot) => Ok((ballot, power.0)),
+                })
             })
             .try_fold_with(
                 EncryptedTally::new(tally_size, election_pk.clone(), crs.clone()),
@@ -319,11 +341,53 @@ impl ProposalManager {
         Ok(Self {
             votes_by_voters: self.votes_by_voters.clone(),
             options: self.options.clone(),
-            tally: Some(Tally::new_private(tally, stake.assigned())),
+            tally: Some(Tally::new_private(tally, weighting.total(stake))),
             action: self.action.clone(),
         })
     }
 
+    /// compute the voting power of the accounts that voted for this proposal
+    ///
+    /// the voting power of a voter is the weight of its own stake plus the
+    /// weight of the stake of every account delegating to it, directly or
+    /// through other representatives, and that did not vote for this
+    /// proposal: a direct vote always overrides the delegation.
+    fn voting_power<'a>(
+        &'a self,
+        stake: &StakeControl,
+        weighting: Weighting,
+        delegations: PlanDelegations<'a>,
+    ) -> HashMap<&'a UnspecifiedAccountIdentifier, Stake> {
+        let stake_of = |id: &UnspecifiedAccountIdentifier| {
+            id.to_single_account()
+                .and_then(|account_id| stake.by(&account_id))
+        };
+        let has_voted = |id: &UnspecifiedAccountIdentifier| self.votes_by_voters.contains_key(id);
+
+        let mut voting_power: HashMap<_, _> = self
+            .votes_by_voters
+            .iter()
+            .filter_map(|(id, _)| stake_of(id).map(|stake| (id, weighting.weight(stake))))
+            .collect();
+
+        if delegations.is_empty() {
+            return voting_power;
+        }
+
+        for delegator in delegations.delegators() {
+            if has_voted(delegator) {
+                continue;
+            }
+            if let Some(stake) = stake_of(delegator) {
+                if let Some(voter) = delegations.resolve(delegator, has_voted) {
+                    *voting_power.entry(voter).or_default() += weighting.weight(stake);
+                }
+            }
+        }
+
+        voting_power
+    }
+
     pub fn finalize_private_tally<F>(
         &self,
         committee_pks: &[committee::MemberPublicKey],
@@ -357,7 +421,9 @@ impl ProposalManager {
             f(&self.action);
         }
 
-        let tally = tally.clone().private_set_result(result)?;
+        let tally = tally
+            .clone()
+            .private_set_result(result, decrypted_proposal.decrypt_shares.clone())?;
 
         Ok(Self {
             votes_by_voters: self.votes_by_voters.clone(),
@@ -449,6 +515,28 @@ impl ProposalManager {
     }
 }
 
+impl VerifiedBallots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ballots.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ballots.len()
+    }
+
+    fn get(&self, cast: &VoteCast) -> Option<&Ballot> {
+        self.ballots.get(cast)
+    }
+
+    pub(crate) fn extend(&mut self, other: Self) {
+        self.ballots.extend(other.ballots)
+    }
+}
+
 impl ProposalManagers {
     fn new(plan: &VotePlan) -> Self {
         let managers = plan
@@ -514,6 +602,8 @@ impl ProposalManagers {
     pub fn public_tally<F>(
         &self,
         stake: &StakeControl,
+        weighting: Weighting,
+        delegations: PlanDelegations,
         governance: &Governance,
         mut f: F,
     ) -> Result<Self, VoteError>
@@ -524,7 +614,13 @@ impl ProposalManagers {
             Self::Public { managers } => {
                 let mut proposals = Vec::with_capacity(managers.len());
                 for proposal in managers.iter() {
-                    proposals.push(proposal.public_tally(stake, governance, &mut f)?);
+                    proposals.push(proposal.public_tally(
+                        stake,
+                        weighting,
+                        delegations,
+                        governance,
+                        &mut f,
+                    )?);
                 }
                 Ok(Self::Public {
                     managers: proposals,
@@ -539,10 +635,14 @@ impl ProposalManagers {
 
     /// validate the vote against the proposal: verify that the proposal exists
     /// and the the length of the ciphertext is correct (if applicable)
+    ///
+    /// the proof of a private vote is not verified again if the ballot
+    /// is part of the `verified` ballots.
     pub fn validate_vote(
         &self,
         identifier: &UnspecifiedAccountIdentifier,
         cast: VoteCast,
+        verified: &VerifiedBallots,
     ) -> Result<ValidatedVoteCast, VoteError> {
         let proposal_index = cast.proposal_index() as usize;
         let payload = match self {
@@ -563,7 +663,7 @@ impl ProposalManagers {
                     num_proposals: managers.len(),
                     vote: cast.clone(),
                 })?
-                .validate_private_vote(identifier, cast, crs, election_pk),
+                .validate_private_vote(identifier, cast, crs, election_pk, verified),
         }?;
 
         Ok(ValidatedVoteCast {
@@ -572,7 +672,12 @@ impl ProposalManagers {
         })
     }
 
-    pub fn start_private_tally(&self, stake: &StakeControl) -> Result<Self, VoteError> {
+    pub fn start_private_tally(
+        &self,
+        stake: &StakeControl,
+        weighting: Weighting,
+        delegations: PlanDelegations,
+    ) -> Result<Self, VoteError> {
         use rayon::prelude::*;
 
         match self {
@@ -583,7 +688,9 @@ impl ProposalManagers {
             } => {
                 let proposals = managers
                     .par_iter()
-                    .map(|proposal| proposal.private_tally(stake, election_pk, crs))
+                    .map(|proposal| {
+                        proposal.private_tally(stake, weighting, delegations, election_pk, crs)
+                    })
                     .collect::<Result<_, _>>()?;
                 Ok(Self::Private {
                     managers: proposals,
@@ -649,6 +756,8 @@ impl VotePlanManager {
             plan: Arc::new(plan),
             proposal_managers,
             committee: Arc::new(committee),
+            delegations: Delegations::new(),
+            global_delegations: None,
         }
     }
 
@@ -712,6 +821,126 @@ impl VotePlanManager {
         self.committee_set().contains(id)
     }
 
+    /// verify in batch the proofs of the private ballots cast for this
+    /// vote plan
+    ///
+    /// only the ballots with a valid proof are returned, the other vote
+    /// casts are ignored: they will fail when applied with `vote`.
+    pub fn verify_private_ballots<'a, I>(&self, casts: I) -> VerifiedBallots
+    where
+        I: IntoIterator<Item = &'a VoteCast>,
+    {
+        let (managers, crs, election_pk) = match &self.proposal_managers {
+            ProposalManagers::Private {
+                managers,
+                crs,
+                election_pk,
+            } => (managers, crs, election_pk),
+            ProposalManagers::Public { .. } => return VerifiedBallots::new(),
+        };
+
+        let casts = casts
+            .into_iter()
+            .filter(|cast| cast.vote_plan() == self.id())
+            .filter_map(|cast| match cast.payload() {
+                Payload::Private {
+                    encrypted_vote,
+                    proof,
+                } => {
+                    let manager = managers.get(cast.proposal_index() as usize)?;
+                    let expected_size = manager.options.choice_range().len();
+                    if encrypted_vote.as_inner().len() == expected_size {
+                        Some((cast, encrypted_vote.as_inner(), proof.as_inner()))
+                    } else {
+                        None
+                    }
+                }
+                Payload::Public { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        let ballots = Ballot::try_from_votes_and_proofs(
+            casts
+                .iter()
+                .map(|(_, encrypted_vote, proof)| ((*encrypted_vote).clone(), *proof))
+                .collect(),
+            crs,
+            election_pk,
+        );
+
+        VerifiedBallots {
+            ballots: casts
+                .into_iter()
+                .zip(ballots)
+                .filter_map(|((cast, _, _), ballot)| ballot.ok().map(|b| (cast.clone(), b)))
+                .collect(),
+        }
+    }
+
+    /// the delegations specific to this vote plan
+    pub fn delegations(&self) -> &Delegations {
+        &self.delegations
+    }
+
+    /// the delegations applying to the tally of this vote plan: the global
+    /// delegations are taken as they were at the end of the vote
+    fn delegations_with<'a>(&'a self, global: &'a Delegations) -> PlanDelegations<'a> {
+        PlanDelegations {
+            plan: &self.delegations,
+            global: self.global_delegations.as_ref().unwrap_or(global),
+        }
+    }
+
+    /// tells if the global delegations have to be kept before changing them
+    /// at the given date, the vote being finished
+    pub(crate) fn needs_global_delegations(&self, block_date: BlockDate) -> bool {
+        self.global_delegations.is_none() && self.plan().vote_finished(block_date)
+    }
+
+    /// keep the global delegations as they are at the end of the vote, the
+    /// later changes don't apply to the tally of this vote plan
+    #[must_use = "internal state is not modified"]
+    pub(crate) fn with_global_delegations(&self, global: &Delegations) -> Self {
+        Self {
+            proposal_managers: self.proposal_managers.clone(),
+            plan: Arc::clone(&self.plan),
+            id: self.id.clone(),
+            committee: Arc::clone(&self.committee),
+            delegations: self.delegations.clone(),
+            global_delegations: Some(global.clone()),
+        }
+    }
+
+    /// delegate the voting power of the given account for the proposals
+    /// of this vote plan, or remove the delegation if `representative`
+    /// is `None`
+    ///
+    /// # errors
+    ///
+    /// * if the block_date show the vote already ended
+    ///
+    pub fn delegate(
+        &self,
+        block_date: BlockDate,
+        delegator: UnspecifiedAccountIdentifier,
+        representative: Option<VoteRepresentative>,
+    ) -> Result<Self, VoteError> {
+        if self.plan().vote_finished(block_date) {
+            return Err(VoteError::NotDelegationTime {
+                end: self.plan().vote_end(),
+            });
+        }
+
+        Ok(Self {
+            proposal_managers: self.proposal_managers.clone(),
+            plan: Arc::clone(&self.plan),
+            id: self.id.clone(),
+            committee: Arc::clone(&self.committee),
+            delegations: self.delegations.delegate(delegator, representative),
+            global_delegations: self.global_delegations.clone(),
+        })
+    }
+
     /// attempt to apply the vote to one of the proposals
     ///
     /// If the given identifier already had a vote, the previous vote will
@@ -730,6 +959,7 @@ impl VotePlanManager {
         block_date: BlockDate,
         identifier: UnspecifiedAccountIdentifier,
         cast: VoteCast,
+        verified: &VerifiedBallots,
     ) -> Result<Self, VoteError> {
         if cast.vote_plan() != self.id() {
             return Err(VoteError::InvalidVotePlan {
@@ -752,7 +982,9 @@ impl VotePlanManager {
             });
         }
 
-        let vote = self.proposal_managers.validate_vote(&identifier, cast)?;
+        let vote = self
+            .proposal_managers
+            .validate_vote(&identifier, cast, verified)?;
 
         let proposal_managers = self.proposal_managers.vote(identifier, vote)?;
 
@@ -761,6 +993,8 @@ impl VotePlanManager {
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            delegations: self.delegations.clone(),
+            global_delegations: self.global_delegations.clone(),
         })
     }
 
@@ -768,6 +1002,7 @@ impl VotePlanManager {
         &self,
         block_date: BlockDate,
         stake: &StakeControl,
+        delegations: &Delegations,
         governance: &Governance,
         sig: CommitteeId,
         f: F,
@@ -790,13 +1025,21 @@ impl VotePlanManager {
             return Err(TallyError::InvalidPrivacy.into());
         }
 
-        let proposal_managers = self.proposal_managers.public_tally(stake, governance, f)?;
+        let proposal_managers = self.proposal_managers.public_tally(
+            stake,
+            self.plan.weighting(),
+            self.delegations_with(delegations),
+            governance,
+            f,
+        )?;
 
         Ok(Self {
             proposal_managers,
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            delegations: self.delegations.clone(),
+            global_delegations: self.global_delegations.clone(),
         })
     }
 
@@ -804,6 +1047,7 @@ impl VotePlanManager {
         &self,
         block_date: BlockDate,
         stake: &StakeControl,
+        delegations: &Delegations,
         sig: CommitteeId,
     ) -> Result<Self, VoteError> {
         if !self.can_committee(block_date) {
@@ -821,13 +1065,19 @@ impl VotePlanManager {
             return Err(TallyError::InvalidPrivacy.into());
         }
 
-        let proposal_managers = self.proposal_managers.start_private_tally(stake)?;
+        let proposal_managers = self.proposal_managers.start_private_tally(
+            stake,
+            self.plan.weighting(),
+            self.delegations_with(delegations),
+        )?;
 
         Ok(Self {
             proposal_managers,
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            delegations: self.delegations.clone(),
+            global_delegations: self.global_delegations.clone(),
         })
     }
 
@@ -852,6 +1102,8 @@ impl VotePlanManager {
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            delegations: self.delegations.clone(),
+            global_delegations: self.global_delegations.clone(),
         })
     }
 }
@@ -860,7 +1112,8 @@ impl VotePlanManager {
 mod tests {
     use super::*;
     use crate::block::BlockDate;
-    use crate::certificate::TallyProof;
+    use crate::certificate::{TallyProof, VoteDelegation};
+    use crate::vote::VotePlanLedger;
 
     use crate::testing::{TestGen, VoteTestGen};
     use chain_core::property::BlockDate as BlockDateProp;
@@ -896,6 +1149,85 @@ mod tests {
     }
     use rand_core::OsRng;
 
+    #[test]
+    pub fn vote_plan_manager_verify_private_ballots() {
+        let vote_plan = VoteTestGen::private_vote_plan();
+        let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
+        let proposals = vote_plan.proposals();
+        let mut rng = OsRng;
+
+        let mut casts = (0..4)
+            .map(|i| {
+                let proposal_index = i % proposals.len();
+                VoteCast::new(
+                    vote_plan.to_id(),
+                    proposal_index as u8,
+                    VoteTestGen::private_vote_cast_payload_for(
+                        &vote_plan,
+                        proposals.get(proposal_index).unwrap(),
+                        vote::Choice::new(i as u8 % 2),
+                        &mut rng,
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // mix the encrypted vote of a cast with the proof of another one
+        let invalid = match (casts[0].payload(), casts[1].payload()) {
+            (
+                vote::Payload::Private { encrypted_vote, .. },
+                vote::Payload::Private { proof, .. },
+            ) => VoteCast::new(
+                vote_plan.to_id(),
+                0,
+                vote::Payload::Private {
+                    encrypted_vote: encrypted_vote.clone(),
+                    proof: proof.clone(),
+                },
+            ),
+            _ => unreachable!(),
+        };
+        casts.push(invalid.clone());
+        // public vote casts or vote casts for another plan are ignored
+        casts.push(VoteCast::new(
+            vote_plan.to_id(),
+            0,
+            VoteTestGen::vote_cast_payload(),
+        ));
+        casts.push(VoteCast::new(
+            VoteTestGen::private_vote_plan_with_committees_manager(
+                &VoteTestGen::committee_members_manager(2, 1),
+            )
+            .to_id(),
+            0,
+            casts[0].payload().clone(),
+        ));
+
+        let verified = vote_plan_manager.verify_private_ballots(casts.iter());
+        assert_eq!(verified.len(), 4);
+
+        let vote_date = vote_plan.vote_start();
+        for cast in casts.iter().take(4) {
+            vote_plan_manager
+                .vote(
+                    vote_date,
+                    TestGen::unspecified_account_identifier(),
+                    cast.clone(),
+                    &verified,
+                )
+                .unwrap();
+        }
+        assert!(matches!(
+            vote_plan_manager.vote(
+                vote_date,
+                TestGen::unspecified_account_identifier(),
+                invalid,
+                &verified
+            ),
+            Err(VoteError::VoteVerificationError(_))
+        ));
+    }
+
     #[test]
     pub fn proposal_manager_cast_private_vote_in_public_voting() {
         let vote_plan = VoteTestGen::private_vote_plan();
@@ -943,7 +1275,8 @@ mod tests {
                     &identifier,
                     vote_cast,
                     committee_manager.crs(),
-                    &committee_manager.election_pk()
+                    &committee_manager.election_pk(),
+                    &VerifiedBallots::new()
                 )
                 .err()
                 .unwrap(),
@@ -972,6 +1305,7 @@ mod tests {
             proposals,
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
@@ -1016,6 +1350,7 @@ mod tests {
             proposals,
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let mut committee_ids = HashSet::new();
@@ -1043,6 +1378,7 @@ mod tests {
                 vote_block_date,
                 UnspecifiedAccountIdentifier::from_single_account(committee.public_key().into()),
                 vote_cast,
+                &VerifiedBallots::new(),
             )
             .unwrap();
 
@@ -1062,6 +1398,7 @@ mod tests {
             .public_tally(
                 block_date,
                 &stake_controlled,
+                &Delegations::new(),
                 &governance,
                 committee_id,
                 |_| action_hit = true,
@@ -1087,6 +1424,7 @@ mod tests {
             proposals,
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let mut committee_ids = HashSet::new();
@@ -1117,6 +1455,7 @@ mod tests {
                 .public_tally(
                     block_date,
                     &stake_controlled,
+                    &Delegations::new(),
                     &governance,
                     committee_id,
                     |_| ()
@@ -1143,6 +1482,7 @@ mod tests {
             proposals,
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let mut committee_ids = HashSet::new();
@@ -1176,6 +1516,7 @@ mod tests {
                 .public_tally(
                     invalid_block_date,
                     &stake_controlled,
+                    &Delegations::new(),
                     &governance,
                     committee_id,
                     |_| ()
@@ -1219,6 +1560,7 @@ mod tests {
                 .public_tally(
                     block_date,
                     &stake_controlled,
+                    &Delegations::new(),
                     &governance,
                     committee_id,
                     |_| ()
@@ -1248,6 +1590,7 @@ mod tests {
             proposals,
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let mut committee_ids = HashSet::new();
@@ -1271,7 +1614,12 @@ mod tests {
 
         assert_eq!(
             vote_plan_manager
-                .start_private_tally(block_date, &stake_controlled, committee_id)
+                .start_private_tally(
+                    block_date,
+                    &stake_controlled,
+                    &Delegations::new(),
+                    committee_id,
+                )
                 .err()
                 .unwrap(),
             crate::vote::VoteError::CannotTallyVotes {
@@ -1315,6 +1663,7 @@ mod tests {
             proposals,
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let mut first_proposal_manager =
@@ -1333,6 +1682,7 @@ mod tests {
                     0,
                     VoteTestGen::vote_cast_payload_for(&favorable),
                 ),
+                &VerifiedBallots::new(),
             )
             .unwrap();
         first_proposal_manager = first_proposal_manager
@@ -1347,6 +1697,7 @@ mod tests {
                     1,
                     VoteTestGen::vote_cast_payload_for(&favorable),
                 ),
+                &VerifiedBallots::new(),
             )
             .unwrap();
         second_proposal_manager = second_proposal_manager
@@ -1367,6 +1718,261 @@ mod tests {
         vote_tally_succesful(&second_proposal_manager, &stake_controlled, &governance);
     }
 
+    #[test]
+    pub fn proposal_manager_weighted_public_tally() {
+        let favorable = Choice::new(1);
+        let rejection = Choice::new(2);
+
+        let vote_plan = VoteTestGen::vote_plan();
+        let proposal = vote_plan.proposals().first().unwrap();
+
+        let whale = TestGen::unspecified_account_identifier();
+        let minnow = TestGen::unspecified_account_identifier();
+
+        let mut proposal_manager = ProposalManager::new(proposal);
+        for (identifier, choice) in [(&whale, favorable), (&minnow, rejection)].iter() {
+            let vote_cast = VoteCast::new(
+                vote_plan.to_id(),
+                0,
+                VoteTestGen::vote_cast_payload_for(choice),
+            );
+            let payload = proposal_manager
+                .validate_public_vote(identifier, vote_cast)
+                .unwrap();
+            proposal_manager = proposal_manager
+                .vote((*identifier).clone(), payload)
+                .unwrap();
+        }
+
+        let stake_controlled = StakeControl::new()
+            .add_to(whale.to_single_account().unwrap(), Stake(10_000))
+            .add_to(minnow.to_single_account().unwrap(), Stake(100));
+
+        let cases = [
+            (Weighting::Linear, 10_000, 100),
+            (Weighting::SquareRoot, 100, 10),
+            (Weighting::Capped { max: Stake(500) }, 500, 100),
+            (Weighting::OneAccountOneVote, 1, 1),
+        ];
+        for (weighting, expected_favorable, expected_rejection) in cases.iter() {
+            let governance = Governance::default();
+            let tallied = proposal_manager
+                .public_tally(
+                    &stake_controlled,
+                    *weighting,
+                    no_delegations(),
+                    &governance,
+                    |_| {},
+                )
+                .unwrap();
+            let results = tallied.tally.unwrap().result().unwrap().clone();
+            assert_eq!(
+                results.results()[favorable.as_byte() as usize],
+                vote::Weight::from(*expected_favorable),
+                "{:?}",
+                weighting
+            );
+            assert_eq!(
+                results.results()[rejection.as_byte() as usize],
+                vote::Weight::from(*expected_rejection),
+                "{:?}",
+                weighting
+            );
+            assert_eq!(
+                weighting.total(&stake_controlled),
+                Stake(expected_favorable + expected_rejection)
+            );
+        }
+    }
+
+    #[test]
+    pub fn proposal_manager_delegated_public_tally() {
+        let favorable = Choice::new(1);
+        let rejection = Choice::new(2);
+
+        let vote_plan = VoteTestGen::vote_plan();
+        let proposal = vote_plan.proposals().first().unwrap();
+
+        let alice = TestGen::unspecified_account_identifier();
+        let bob = TestGen::unspecified_account_identifier();
+        let carol = TestGen::unspecified_account_identifier();
+        let dave = TestGen::unspecified_account_identifier();
+        let eve = TestGen::unspecified_account_identifier();
+
+        let mut proposal_manager = ProposalManager::new(proposal);
+        for (identifier, choice) in [(&alice, favorable), (&bob, rejection)].iter() {
+            let vote_cast = VoteCast::new(
+                vote_plan.to_id(),
+                0,
+                VoteTestGen::vote_cast_payload_for(choice),
+            );
+            let payload = proposal_manager
+                .validate_public_vote(identifier, vote_cast)
+                .unwrap();
+            proposal_manager = proposal_manager
+                .vote((*identifier).clone(), payload)
+                .unwrap();
+        }
+
+        let stake_controlled = StakeControl::new()
+            .add_to(alice.to_single_account().unwrap(), Stake(1))
+            .add_to(bob.to_single_account().unwrap(), Stake(10))
+            .add_to(carol.to_single_account().unwrap(), Stake(100))
+            .add_to(dave.to_single_account().unwrap(), Stake(1_000))
+            .add_to(eve.to_single_account().unwrap(), Stake(10_000));
+
+        // carol -> dave -> alice: counted for alice
+        // bob -> alice: bob voted directly, the delegation is ignored
+        // eve -> eve's plan representative dave -> alice
+        let global = Delegations::new()
+            .delegate(
+                carol.clone(),
+                Some(VoteRepresentative::Account(dave.clone())),
+            )
+            .delegate(
+                dave.clone(),
+                Some(VoteRepresentative::Account(alice.clone())),
+            )
+            .delegate(
+                bob.clone(),
+                Some(VoteRepresentative::Account(alice.clone())),
+            )
+            .delegate(eve.clone(), Some(VoteRepresentative::Account(bob.clone())));
+        let plan = Delegations::new()
+            .delegate(eve.clone(), Some(VoteRepresentative::Account(dave.clone())));
+        let delegations = PlanDelegations {
+            plan: &plan,
+            global: &global,
+        };
+
+        let tallied = proposal_manager
+            .public_tally(
+                &stake_controlled,
+                Weighting::Linear,
+                delegations,
+                &Governance::default(),
+                |_| {},
+            )
+            .unwrap();
+        let results = tallied.tally.unwrap().result().unwrap().clone();
+        assert_eq!(
+            results.results()[favorable.as_byte() as usize],
+            vote::Weight::from(11_101)
+        );
+        assert_eq!(
+            results.results()[rejection.as_byte() as usize],
+            vote::Weight::from(10)
+        );
+
+        // a delegation cycle does not count
+        let global = Delegations::new()
+            .delegate(
+                carol.clone(),
+                Some(VoteRepresentative::Account(dave.clone())),
+            )
+            .delegate(
+                dave.clone(),
+                Some(VoteRepresentative::Account(carol.clone())),
+            );
+        let plan = Delegations::new();
+        let delegations = PlanDelegations {
+            plan: &plan,
+            global: &global,
+        };
+        let tallied = proposal_manager
+            .public_tally(
+                &stake_controlled,
+                Weighting::Linear,
+                delegations,
+                &Governance::default(),
+                |_| {},
+            )
+            .unwrap();
+        let results = tallied.tally.unwrap().result().unwrap().clone();
+        assert_eq!(
+            results.results()[favorable.as_byte() as usize],
+            vote::Weight::from(1)
+        );
+    }
+
+    #[test]
+    pub fn global_delegation_changes_after_vote_end_are_ignored() {
+        let favorable = Choice::new(1);
+        let committee = Wallet::from_value(Value(100));
+        let vote_plan = VoteTestGen::vote_plan();
+        let id = vote_plan.to_id();
+
+        let alice =
+            UnspecifiedAccountIdentifier::from_single_account(committee.public_key().into());
+        let bob = TestGen::unspecified_account_identifier();
+        let stake_controlled = StakeControl::new()
+            .add_to(alice.to_single_account().unwrap(), Stake(1))
+            .add_to(bob.to_single_account().unwrap(), Stake(10));
+
+        let mut committee_ids = HashSet::new();
+        committee_ids.insert(committee.public_key().into());
+        let ledger = VotePlanLedger::new()
+            .add_vote_plan(BlockDate::first(), vote_plan.clone(), committee_ids)
+            .unwrap()
+            .apply_vote_delegation(
+                BlockDate::from_epoch_slot_id(1, 0),
+                bob.clone(),
+                &VoteDelegation::new(None, Some(VoteRepresentative::Account(alice.clone()))),
+            )
+            .unwrap()
+            .apply_vote(
+                BlockDate::from_epoch_slot_id(1, 10),
+                alice,
+                VoteCast::new(
+                    id.clone(),
+                    0,
+                    VoteTestGen::vote_cast_payload_for(&favorable),
+                ),
+                &VerifiedBallots::new(),
+            )
+            .unwrap();
+
+        // bob revokes the delegation once the vote ended, before the tally
+        let ledger = ledger
+            .apply_vote_delegation(
+                BlockDate::from_epoch_slot_id(2, 5),
+                bob,
+                &VoteDelegation::new(None, None),
+            )
+            .unwrap();
+        assert!(ledger.delegations.is_empty());
+
+        let committee_id = match get_tally_proof(vote_plan.vote_start(), &committee, id.clone()) {
+            TallyProof::Public { id, .. } => id,
+            TallyProof::Private { id, .. } => id,
+        };
+        let tallied = ledger
+            .plans
+            .lookup(&id)
+            .unwrap()
+            .public_tally(
+                BlockDate::from_epoch_slot_id(2, 10),
+                &stake_controlled,
+                &ledger.delegations,
+                &Governance::default(),
+                committee_id,
+                |_| {},
+            )
+            .unwrap();
+        let status = tallied.statuses();
+        let results = status.proposals[0]
+            .tally
+            .as_ref()
+            .unwrap()
+            .result()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            results.results()[favorable.as_byte() as usize],
+            vote::Weight::from(11)
+        );
+    }
+
     fn governance_50_percent(blank: Choice, favorable: Choice, rejection: Choice) -> Governance {
         let gov_acceptance_criteria = GovernanceAcceptanceCriteria {
             minimum_stake_participation: Some(Ratio {
@@ -1394,6 +2000,16 @@ mod tests {
         }
     }
 
+    fn no_delegations() -> PlanDelegations<'static> {
+        lazy_static::lazy_static! {
+            static ref NO_DELEGATIONS: Delegations = Delegations::new();
+        }
+        PlanDelegations {
+            plan: &NO_DELEGATIONS,
+            global: &NO_DELEGATIONS,
+        }
+    }
+
     fn proposals_vote_tally_succesful(
         proposal_managers: &ProposalManagers,
         stake_controlled: &StakeControl,
@@ -1401,9 +2017,15 @@ mod tests {
     ) {
         let mut vote_action_hit = false;
         proposal_managers
-            .public_tally(&stake_controlled, &governance, |_vote_action| {
-                vote_action_hit = true;
-            })
+            .public_tally(
+                &stake_controlled,
+                Weighting::Linear,
+                no_delegations(),
+                &governance,
+                |_vote_action| {
+                    vote_action_hit = true;
+                },
+            )
             .unwrap();
     }
 
@@ -1414,9 +2036,15 @@ mod tests {
     ) {
         let mut vote_action_hit = false;
         proposal_manager
-            .public_tally(&stake_controlled, &governance, |_vote_action| {
-                vote_action_hit = true;
-            })
+            .public_tally(
+                &stake_controlled,
+                Weighting::Linear,
+                no_delegations(),
+                &governance,
+                |_vote_action| {
+                    vote_action_hit = true;
+                },
+            )
             .unwrap();
 
         assert!(vote_action_hit);
@@ -1438,10 +2066,14 @@ mod tests {
         let identifier = TestGen::unspecified_account_identifier();
 
         let first_vote_cast_validated = proposal_managers
-            .validate_vote(&identifier, first_vote_cast)
+            .validate_vote(&identifier, first_vote_cast, &VerifiedBallots::new())
             .unwrap();
         let second_vote_cast_validated = proposal_managers
-            .validate_vote(&identifier, second_vote_cast.clone())
+            .validate_vote(
+                &identifier,
+                second_vote_cast.clone(),
+                &VerifiedBallots::new(),
+            )
             .unwrap();
 
         proposal_managers = proposal_managers
@@ -1487,6 +2119,7 @@ mod tests {
             .validate_vote(
                 &identifier,
                 VoteCast::new(vote_plan.to_id(), 2, VoteTestGen::vote_cast_payload()),
+                &VerifiedBallots::new(),
             )
             .is_err());
     }
@@ -1507,12 +2140,14 @@ mod tests {
             .validate_vote(
                 &identifier,
                 VoteCast::new(vote_plan.to_id(), 0, first_vote_cast_payload),
+                &VerifiedBallots::new(),
             )
             .unwrap();
         let second_vote_cast = proposal_managers
             .validate_vote(
                 &identifier,
                 VoteCast::new(vote_plan.to_id(), 0, second_vote_cast_payload.clone()),
+                &VerifiedBallots::new(),
             )
             .unwrap();
 
@@ -1590,7 +2225,8 @@ mod tests {
                 .vote(
                     BlockDate::first(),
                     TestGen::unspecified_account_identifier(),
-                    vote_cast.clone()
+                    vote_cast.clone(),
+                    &VerifiedBallots::new()
                 )
                 .err()
                 .unwrap(),
@@ -1612,7 +2248,8 @@ mod tests {
                 .vote(
                     vote_plan.vote_end().next_epoch(),
                     TestGen::unspecified_account_identifier(),
-                    vote_cast.clone()
+                    vote_cast.clone(),
+                    &VerifiedBallots::new()
                 )
                 .err()
                 .unwrap(),
@@ -1633,6 +2270,7 @@ mod tests {
             VoteTestGen::proposals(3),
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
@@ -1643,7 +2281,8 @@ mod tests {
                 .vote(
                     BlockDate::first(),
                     TestGen::unspecified_account_identifier(),
-                    vote_cast.clone()
+                    vote_cast.clone(),
+                    &VerifiedBallots::new()
                 )
                 .err()
                 .unwrap(),
@@ -1664,6 +2303,7 @@ mod tests {
             VoteTestGen::proposals(3),
             PayloadType::Public,
             Vec::new(),
+            Weighting::Linear,
         );
 
         let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
@@ -1673,7 +2313,8 @@ mod tests {
             .vote(
                 BlockDate::from_epoch_slot_id(1, 1),
                 TestGen::unspecified_account_identifier(),
-                vote_cast
+                vote_cast,
+                &VerifiedBallots::new()
             )
             .is_ok());
     }
@@ -5,20 +5,24 @@
 
 mod choice;
 mod committee;
+mod delegation;
 mod ledger;
 mod manager;
 mod payload;
 mod privacy;
 mod status;
 mod tally;
+mod weighting;
 
 pub use self::{
     choice::{Choice, Options},
     committee::CommitteeId,
+    delegation::Delegations,
     ledger::{VotePlanLedger, VotePlanLedgerError},
-    manager::{ValidatedPayload, VoteError, VotePlanManager},
+    manager::{ValidatedPayload, VerifiedBallots, VoteError, VotePlanManager},
     payload::{EncryptedVote, Payload, PayloadType, ProofOfCorrectVote, TryFromIntError},
     privacy::encrypt_vote,
     status::{VotePlanStatus, VoteProposalStatus},
     tally::{PrivateTallyState, Tally, TallyError, TallyResult, Weight},
+    weighting::Weighting,
 };
@@ -4,7 +4,7 @@ use crate::{
     transaction::UnspecifiedAccountIdentifier,
     vote::{Options, PayloadType, Tally, ValidatedPayload},
 };
-use chain_vote::MemberPublicKey;
+use chain_vote::{Crs, MemberPublicKey, TallyTranscript};
 use imhamt::Hamt;
 use std::collections::hash_map::DefaultHasher;
 
@@ -18,6 +18,32 @@ pub struct VotePlanStatus {
     pub proposals: Vec<VoteProposalStatus>,
 }
 
+impl VotePlanStatus {
+    /// the transcript of the decryption of the private tallies of all the
+    /// proposals, that can be checked with `chain_vote::verify_transcript`
+    /// without access to the ledger.
+    ///
+    /// returns `None` if the vote plan is public or if any of the
+    /// proposals is not decrypted yet.
+    pub fn tally_transcript(&self) -> Option<TallyTranscript> {
+        if self.payload != PayloadType::Private {
+            return None;
+        }
+
+        let proposals = self
+            .proposals
+            .iter()
+            .map(|proposal| proposal.tally.as_ref()?.private_transcript().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(TallyTranscript::new(
+            Crs::from_hash(self.id.as_ref()),
+            self.committee_public_keys.clone(),
+            proposals,
+        ))
+    }
+}
+
 pub struct VoteProposalStatus {
     pub index: u8,
     pub proposal_id: ExternalProposalId,
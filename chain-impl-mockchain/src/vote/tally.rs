@@ -3,7 +3,7 @@ use crate::{
     value::Value,
     vote::{Choice, Options},
 };
-use chain_vote::EncryptedTally;
+use chain_vote::{EncryptedTally, ProposalTranscript, TallyDecryptShare};
 use std::fmt;
 use thiserror::Error;
 
@@ -36,6 +36,8 @@ pub enum PrivateTallyState {
     },
     Decrypted {
         result: TallyResult,
+        encrypted_tally: EncryptedTally,
+        decrypt_shares: Box<[TallyDecryptShare]>,
     },
 }
 
@@ -49,6 +51,8 @@ pub enum TallyError {
     TallyAlreadyDecrypted,
     #[error("the encrypted tally was not provided yet")]
     NoEncryptedTally,
+    #[error("the private tally is not decrypted yet")]
+    NoDecryptedTally,
     #[error("bad decryption share data")]
     BadDecryptShares,
     #[error("invalid decrypted tally")]
@@ -92,7 +96,7 @@ impl Tally {
         match self {
             Self::Public { result } => Some(result),
             Self::Private {
-                state: PrivateTallyState::Decrypted { result },
+                state: PrivateTallyState::Decrypted { result, .. },
             } => Some(result),
             _ => None,
         }
@@ -126,16 +130,55 @@ impl Tally {
         }
     }
 
-    pub fn private_set_result(mut self, result: TallyResult) -> Result<Self, TallyError> {
-        match &mut self {
-            Self::Private { state } => {
-                if let PrivateTallyState::Decrypted { .. } = state {
-                    return Err(TallyError::TallyAlreadyDecrypted);
-                }
-                *state = PrivateTallyState::Decrypted { result };
-                Ok(self)
-            }
-            _ => Err(TallyError::InvalidPrivacy),
+    /// set the result of the decrypted private tally, keeping the
+    /// encrypted tally and the decryption shares so the decryption can be
+    /// verified later on (see `private_transcript`).
+    pub fn private_set_result(
+        self,
+        result: TallyResult,
+        decrypt_shares: Box<[TallyDecryptShare]>,
+    ) -> Result<Self, TallyError> {
+        match self {
+            Self::Private {
+                state:
+                    PrivateTallyState::Encrypted {
+                        encrypted_tally, ..
+                    },
+            } => Ok(Self::Private {
+                state: PrivateTallyState::Decrypted {
+                    result,
+                    encrypted_tally,
+                    decrypt_shares,
+                },
+            }),
+            Self::Private {
+                state: PrivateTallyState::Decrypted { .. },
+            } => Err(TallyError::TallyAlreadyDecrypted),
+            Self::Public { .. } => Err(TallyError::InvalidPrivacy),
+        }
+    }
+
+    /// the transcript of the decryption of a decrypted private tally
+    pub fn private_transcript(&self) -> Result<ProposalTranscript, TallyError> {
+        match self {
+            Self::Private {
+                state:
+                    PrivateTallyState::Decrypted {
+                        result,
+                        encrypted_tally,
+                        decrypt_shares,
+                    },
+            } => Ok(ProposalTranscript {
+                encrypted_tally: encrypted_tally.clone(),
+                decrypt_shares: decrypt_shares.to_vec(),
+                tally: chain_vote::Tally {
+                    votes: result.results().iter().map(|w| (*w).into()).collect(),
+                },
+            }),
+            Self::Private {
+                state: PrivateTallyState::Encrypted { .. },
+            } => Err(TallyError::NoDecryptedTally),
+            Self::Public { .. } => Err(TallyError::InvalidPrivacy),
         }
     }
 }
@@ -0,0 +1,151 @@
+use crate::stake::{Stake, StakeControl};
+use chain_core::mempack::{ReadBuf, ReadError};
+use typed_bytes::ByteBuilder;
+
+/// the rule converting the stake of a voter into the weight of its vote
+///
+/// The same rule is applied to every vote of a vote plan, for both the
+/// public and the private tally, as well as to the total voting power
+/// used to compute the participation.
+///
+/// ```
+/// use chain_impl_mockchain::{stake::Stake, vote::Weighting};
+/// assert_eq!(Weighting::Linear, Weighting::default());
+/// assert_eq!(Weighting::SquareRoot.weight(Stake(10_000)), Stake(100));
+/// ```
+///
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Weighting {
+    /// the weight of the vote is the stake of the voter
+    #[default]
+    Linear,
+    /// the weight of the vote is the integer square root of the stake
+    /// of the voter (quadratic voting)
+    SquareRoot,
+    /// the weight of the vote is the stake of the voter, up to `max`
+    Capped { max: Stake },
+    /// every voter with a non null stake has a weight of `1`
+    OneAccountOneVote,
+}
+
+impl Weighting {
+    pub(crate) const TAG_LINEAR: u8 = 0;
+    pub(crate) const TAG_SQUARE_ROOT: u8 = 1;
+    pub(crate) const TAG_CAPPED: u8 = 2;
+    pub(crate) const TAG_ONE_ACCOUNT_ONE_VOTE: u8 = 3;
+
+    /// compute the weight of a vote from the stake of the voter
+    pub fn weight(&self, stake: Stake) -> Stake {
+        match self {
+            Self::Linear => stake,
+            Self::SquareRoot => Stake(isqrt(stake.0)),
+            Self::Capped { max } => std::cmp::min(stake, *max),
+            Self::OneAccountOneVote => Stake(if stake.0 == 0 { 0 } else { 1 }),
+        }
+    }
+
+    /// compute the total voting power: the sum of the weights of every
+    /// account that could have voted
+    pub fn total(&self, stake: &StakeControl) -> Stake {
+        match self {
+            Self::Linear => stake.assigned(),
+            _ => stake.iter().fold(Stake::zero(), |acc, (_, s)| {
+                acc.wrapping_add(self.weight(*s))
+            }),
+        }
+    }
+
+    pub(crate) fn serialize_in<T>(&self, bb: ByteBuilder<T>) -> ByteBuilder<T> {
+        match self {
+            Self::Linear => bb.u8(Self::TAG_LINEAR),
+            Self::SquareRoot => bb.u8(Self::TAG_SQUARE_ROOT),
+            Self::Capped { max } => bb.u8(Self::TAG_CAPPED).u64(max.0),
+            Self::OneAccountOneVote => bb.u8(Self::TAG_ONE_ACCOUNT_ONE_VOTE),
+        }
+    }
+
+    pub(crate) fn read(buf: &mut ReadBuf) -> Result<Self, ReadError> {
+        match buf.get_u8()? {
+            Self::TAG_LINEAR => Ok(Self::Linear),
+            Self::TAG_SQUARE_ROOT => Ok(Self::SquareRoot),
+            Self::TAG_CAPPED => buf.get_u64().map(|max| Self::Capped { max: Stake(max) }),
+            Self::TAG_ONE_ACCOUNT_ONE_VOTE => Ok(Self::OneAccountOneVote),
+            t => Err(ReadError::UnknownTag(t as u32)),
+        }
+    }
+}
+
+/// integer square root, rounded down
+fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // start from an over-estimation and use Newton's method, the
+    // sequence decreases monotonically until it reaches the result
+    let mut x = 1u64 << (32 - n.leading_zeros() / 2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(any(test, feature = "property-test-api"))]
+mod property {
+    use super::*;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl Arbitrary for Weighting {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            match u8::arbitrary(g) % 4 {
+                0 => Self::Linear,
+                1 => Self::SquareRoot,
+                2 => Self::Capped {
+                    max: Stake(u64::arbitrary(g)),
+                },
+                _ => Self::OneAccountOneVote,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn isqrt_is_floor_of_square_root(n: u64) -> bool {
+        let r = isqrt(n) as u128;
+        let n = n as u128;
+        r * r <= n && (r + 1) * (r + 1) > n
+    }
+
+    #[test]
+    fn weights() {
+        let stake = Stake(1_000_000);
+        assert_eq!(Weighting::Linear.weight(stake), stake);
+        assert_eq!(Weighting::SquareRoot.weight(stake), Stake(1_000));
+        assert_eq!(
+            Weighting::Capped { max: Stake(10) }.weight(stake),
+            Stake(10)
+        );
+        assert_eq!(
+            Weighting::Capped { max: Stake(10) }.weight(Stake(3)),
+            Stake(3)
+        );
+        assert_eq!(Weighting::OneAccountOneVote.weight(stake), Stake(1));
+        assert_eq!(Weighting::OneAccountOneVote.weight(Stake(0)), Stake(0));
+    }
+
+    #[quickcheck]
+    fn serialize_deserialize(weighting: Weighting) -> bool {
+        let bytes = weighting
+            .serialize_in(ByteBuilder::<Weighting>::new())
+            .finalize_as_vec();
+        let mut buf = ReadBuf::from(&bytes);
+        Weighting::read(&mut buf).unwrap() == weighting && buf.expect_end().is_ok()
+    }
+}
@@ -233,6 +233,88 @@ impl Zkp {
         mega_check == GroupElement::zero()
     }
 
+    /// Verify several unit vector proofs, generated with the same `crs` and
+    /// `public_key`, at once.
+    ///
+    /// Every verification equation of every proof is multiplied by a fresh
+    /// random scalar, and the resulting linear combination is computed in a
+    /// single vartime multiscalar multiplication. The batch verifies if and
+    /// only if (with overwhelming probability) every proof verifies, but a
+    /// failure does not tell which proofs are invalid.
+    pub fn batch_verify<'a, I>(crs: &Crs, public_key: &PublicKey, statements: I) -> bool
+    where
+        I: IntoIterator<Item = (&'a Zkp, &'a [Ciphertext])>,
+    {
+        let ck = CommitmentKey::from(crs.clone());
+        let mut rng = thread_rng();
+        let mut batch = BatchEquation::new();
+
+        for (proof, ciphertexts) in statements {
+            let ciphertexts = Ptp::new(ciphertexts.to_vec(), Ciphertext::zero);
+            let bits = ciphertexts.bits();
+            if proof.ibas.len() != bits || proof.zwvs.len() != bits {
+                return false;
+            }
+
+            let mut cc = ChallengeContext::new(&ck, public_key, ciphertexts.as_ref());
+            let cy = cc.first_challenge(&proof.ibas);
+            let cx = cc.second_challenge(&proof.ds);
+
+            proof.batch_statements(&mut batch, &mut rng, public_key, &ciphertexts, &cx, &cy);
+        }
+
+        batch.check(&ck)
+    }
+
+    /// Add the verification equations of `verify_statements` to the `batch`,
+    /// each of them weighted by a random scalar.
+    fn batch_statements<R: RngCore + CryptoRng>(
+        &self,
+        batch: &mut BatchEquation,
+        rng: &mut R,
+        public_key: &PublicKey,
+        ciphertexts: &Ptp<Ciphertext>,
+        challenge_x: &Scalar,
+        challenge_y: &Scalar,
+    ) {
+        let bits = ciphertexts.bits();
+        let cx_pow = challenge_x.power(bits);
+
+        for (zwv, iba) in self.zwvs.iter().zip(self.ibas.iter()) {
+            let weight = Scalar::random(rng);
+            let batch_challenge = Scalar::random(rng);
+
+            batch.g = &batch.g + &weight * &zwv.z;
+            batch.h = &batch.h + &weight * (&zwv.w + &batch_challenge * &zwv.v);
+            batch.push(
+                &weight * (&batch_challenge * (&zwv.z - challenge_x) - challenge_x),
+                iba.i.clone(),
+            );
+            batch.push(weight.negate(), iba.b.clone());
+            batch.push((&weight * &batch_challenge).negate(), iba.a.clone());
+        }
+
+        let weight = Scalar::random(rng);
+        let zero = public_key.encrypt_with_r(&Scalar::zero(), &self.r);
+
+        for ((index, ctxt), cy_pow) in ciphertexts.iter().enumerate().zip(challenge_y.exp_iter()) {
+            let s = &weight * &cy_pow;
+            let z_pow = powers_z_encs(&self.zwvs, challenge_x.clone(), index, bits as u32);
+            batch.g = &batch.g - &s * z_pow;
+
+            let s = s * &cx_pow;
+            batch.push(s.clone(), ctxt.e2.clone());
+            batch.push(s, ctxt.e1.clone());
+        }
+        for (d, cx_pow) in self.ds.iter().zip(challenge_x.exp_iter()).take(bits) {
+            let s = &weight * &cx_pow;
+            batch.push(s.clone(), d.e1.clone());
+            batch.push(s, d.e2.clone());
+        }
+        batch.push(weight.negate(), zero.e1);
+        batch.push(weight.negate(), zero.e2);
+    }
+
     /// Try to generate a `Proof` from a buffer
     pub fn from_buffer(buf: &mut ReadBuf) -> Result<Self, ReadError> {
         let bits = buf.get_u8()? as usize;
@@ -308,6 +390,44 @@ impl Zkp {
     }
 }
 
+/// Linear combination of the verification equations of several proofs.
+///
+/// The coefficients of the generator and of the commitment key, shared by
+/// every equation, are accumulated instead of repeating the points.
+struct BatchEquation {
+    g: Scalar,
+    h: Scalar,
+    scalars: Vec<Scalar>,
+    points: Vec<GroupElement>,
+}
+
+impl BatchEquation {
+    fn new() -> Self {
+        BatchEquation {
+            g: Scalar::zero(),
+            h: Scalar::zero(),
+            scalars: Vec::new(),
+            points: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, scalar: Scalar, point: GroupElement) {
+        self.scalars.push(scalar);
+        self.points.push(point);
+    }
+
+    fn check(self, commitment_key: &CommitmentKey) -> bool {
+        GroupElement::vartime_multiscalar_multiplication(
+            iter::once(self.g)
+                .chain(iter::once(self.h))
+                .chain(self.scalars),
+            iter::once(GroupElement::generator())
+                .chain(iter::once(commitment_key.h.clone()))
+                .chain(self.points),
+        ) == GroupElement::zero()
+    }
+}
+
 // Computes the product of the powers of `z` given the `challenge_x`, `index` and a `bit_size`
 fn powers_z_encs(
     z: &[ResponseRandomness],
@@ -464,6 +584,63 @@ mod tests {
         assert!(!proof.verify(&crs, &public_key, &fake_encryption))
     }
 
+    #[test]
+    fn batch_verify() {
+        let mut r = ChaCha20Rng::from_seed([0u8; 32]);
+        let public_key = PublicKey {
+            pk: GroupElement::from_hash(&[1u8]),
+        };
+        let mut shared_string =
+            b"Example of a shared string. This could be the latest block hash".to_owned();
+        let crs = Crs::from_hash(&mut shared_string);
+
+        let statements = (0..5)
+            .map(|i| {
+                let unit_vector = UnitVector::new(5, i);
+                let encryption_randomness = (0..unit_vector.len())
+                    .map(|_| Scalar::random(&mut r))
+                    .collect::<Vec<_>>();
+                let ciphertexts: Vec<Ciphertext> = unit_vector
+                    .iter()
+                    .zip(encryption_randomness.iter())
+                    .map(|(i, r)| public_key.encrypt_with_r(&Scalar::from(i), r))
+                    .collect();
+                let proof = Zkp::generate(
+                    &mut r,
+                    &crs,
+                    &public_key,
+                    &unit_vector,
+                    &encryption_randomness,
+                    &ciphertexts,
+                );
+                (proof, ciphertexts)
+            })
+            .collect::<Vec<_>>();
+
+        assert!(Zkp::batch_verify(
+            &crs,
+            &public_key,
+            statements.iter().map(|(p, c)| (p, c.as_slice()))
+        ));
+        assert!(Zkp::batch_verify(&crs, &public_key, iter::empty()));
+
+        // a single invalid statement makes the whole batch fail
+        let fake_encryption = vec![Ciphertext::zero(); 5];
+        assert!(!Zkp::batch_verify(
+            &crs,
+            &public_key,
+            statements
+                .iter()
+                .map(|(p, c)| (p, c.as_slice()))
+                .chain(iter::once((&statements[0].0, fake_encryption.as_slice())))
+        ));
+        assert!(!Zkp::batch_verify(
+            &crs,
+            &public_key,
+            iter::once((&statements[0].0, statements[1].1.as_slice()))
+        ));
+    }
+
     #[test]
     fn challenge_context() {
         let mut r = ChaCha20Rng::from_seed([0u8; 32]);
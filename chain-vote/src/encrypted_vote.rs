@@ -51,6 +51,37 @@ impl Ballot {
         })
     }
 
+    /// Verify several votes at once, batching the verification of their proofs.
+    ///
+    /// Returns the result for each vote, in the same order. If the whole
+    /// batch does not verify, it is recursively split in halves to find the
+    /// invalid votes, so a few invalid votes in a large batch are found with
+    /// a logarithmic number of additional batch verifications each.
+    pub fn try_from_votes_and_proofs(
+        votes: Vec<(EncryptedVote, &ProofOfCorrectVote)>,
+        crs: &Crs,
+        pk: &ElectionPublicKey,
+    ) -> Vec<Result<Self, BallotVerificationError>> {
+        let statements = votes
+            .iter()
+            .map(|(vote, proof)| (*proof, vote.as_slice()))
+            .collect::<Vec<_>>();
+        let valid = batch_validity(&statements, crs, pk);
+        let fingerprint: ElectionFingerprint = (pk, crs).into();
+
+        votes
+            .into_iter()
+            .zip(valid)
+            .map(|((vote, _), valid)| {
+                if valid {
+                    Ok(Self { vote, fingerprint })
+                } else {
+                    Err(BallotVerificationError)
+                }
+            })
+            .collect()
+    }
+
     pub fn vote(&self) -> &EncryptedVote {
         &self.vote
     }
@@ -60,6 +91,30 @@ impl Ballot {
     }
 }
 
+fn batch_validity(
+    statements: &[(&ProofOfCorrectVote, &[Ciphertext])],
+    crs: &Crs,
+    pk: &ElectionPublicKey,
+) -> Vec<bool> {
+    if statements.is_empty() {
+        return Vec::new();
+    }
+    if ProofOfCorrectVote::batch_verify(crs, &pk.0, statements.iter().copied()) {
+        return vec![true; statements.len()];
+    }
+    if statements.len() == 1 {
+        return vec![false];
+    }
+
+    let (left, right) = statements.split_at(statements.len() / 2);
+    let (mut left, right) = rayon::join(
+        || batch_validity(left, crs, pk),
+        || batch_validity(right, crs, pk),
+    );
+    left.extend(right);
+    left
+}
+
 /// To achieve logarithmic communication complexity in the unit_vector ZKP, we represent
 /// votes as Power of Two Padded vector structures.
 #[derive(Clone)]
@@ -209,6 +264,9 @@ impl Iterator for UnitVectorIter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{MemberCommunicationKey, MemberState};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
 
     #[test]
     fn unit_vector() {
@@ -238,4 +296,54 @@ mod tests {
     fn unit_binrep() {
         assert_eq!(binrep(3, 5), &[false, false, false, true, true])
     }
+
+    #[test]
+    fn ballots_batch_verification() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = Crs::from_hash(&[0u8; 32]);
+        let mc = [MemberCommunicationKey::new(&mut rng).to_public()];
+        let member = MemberState::new(&mut rng, 1, &crs, &mc, 0);
+        let pk = ElectionPublicKey::from_participants(&[member.public_key()]);
+
+        let mut votes = (0..7)
+            .map(|i| pk.encrypt_and_prove_vote(&mut rng, &crs, Vote::new(3, i % 3)))
+            .collect::<Vec<_>>();
+
+        let ballots = Ballot::try_from_votes_and_proofs(
+            votes
+                .iter()
+                .map(|(vote, proof)| (vote.clone(), proof))
+                .collect(),
+            &crs,
+            &pk,
+        );
+        for ((vote, proof), ballot) in votes.iter().zip(ballots) {
+            assert_eq!(
+                ballot.unwrap(),
+                Ballot::try_from_vote_and_proof(vote.clone(), proof, &crs, &pk).unwrap()
+            );
+        }
+
+        // swap the proofs of two votes for different options
+        let proof = votes[1].1.clone();
+        votes[1].1 = votes[5].1.clone();
+        votes[5].1 = proof;
+
+        let ballots = Ballot::try_from_votes_and_proofs(
+            votes
+                .iter()
+                .map(|(vote, proof)| (vote.clone(), proof))
+                .collect(),
+            &crs,
+            &pk,
+        );
+        let invalid = ballots
+            .iter()
+            .enumerate()
+            .filter(|(_, ballot)| ballot.is_err())
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        assert_eq!(invalid, vec![1, 5]);
+        assert!(Ballot::try_from_votes_and_proofs(Vec::new(), &crs, &pk).is_empty());
+    }
 }
@@ -5,6 +5,7 @@ mod cryptography;
 mod encrypted_vote;
 mod math;
 pub mod tally;
+mod transcript;
 
 // re-export under a debug module
 #[doc(hidden)]
@@ -24,11 +25,14 @@ const CURVE_HRP: &str = "p256k1";
 #[cfg(crypto_backend = "__internal_ex_backend_ristretto255")]
 const CURVE_HRP: &str = "ristretto255";
 
-pub use math::babystep::BabyStepsTable as TallyOptimizationTable;
+pub use math::babystep::{
+    BabyStepsTable as TallyOptimizationTable, BabyStepsTableError as TallyOptimizationTableError,
+};
 
 pub use crate::{
     committee::{ElectionPublicKey, MemberCommunicationKey, MemberPublicKey, MemberState},
     cryptography::Ciphertext, //todo: why this?
     encrypted_vote::{Ballot, BallotVerificationError, EncryptedVote, ProofOfCorrectVote, Vote},
     tally::{Crs, EncryptedTally, Tally, TallyDecryptShare},
+    transcript::{verify_transcript, ProposalTranscript, TallyTranscript, TranscriptError},
 };
@@ -2,26 +2,64 @@
 //! a group of prime order.
 #[cfg(crypto_backend = "__internal_ex_backend_p256k1")]
 use crate::Coordinate;
-use crate::{GroupElement, Scalar};
+use crate::{GroupElement, Scalar, CURVE_HRP};
+use cryptoxide::blake2b::Blake2b;
+use cryptoxide::digest::Digest;
+use rand::Rng;
 use rayon::prelude::*;
 use std::collections::HashMap;
+use std::io::{self, Read, Write};
 
 // make steps asymmetric, in order to better use caching of baby steps.
 // balance of 2 means that baby steps are 2 time more than sqrt(max_votes)
 const DEFAULT_BALANCE: u64 = 2;
 
+#[cfg(crypto_backend = "__internal_ex_backend_p256k1")]
+const KEY_LEN: usize = Coordinate::BYTES_LEN;
+#[cfg(crypto_backend = "__internal_ex_backend_ristretto255")]
+const KEY_LEN: usize = GroupElement::BYTES_LEN;
+
+/// key of a baby step in the table, `None` being the point at infinity
+type Key = Option<[u8; KEY_LEN]>;
+
+// serialized table header
+const MAGIC: &[u8; 4] = b"BSGS";
+const VERSION: u8 = 2;
+const DIGEST_LEN: usize = 32;
+// number of baby steps, besides the first and the last ones, recomputed
+// when reading a table
+const SAMPLED_ENTRIES: usize = 8;
+
 /// Holds precomputed baby steps for the baby-stap giant-step algorithm
 /// for solving discrete log on ECC
 #[derive(Debug, Clone)]
 pub struct BabyStepsTable {
-    #[cfg(crypto_backend = "__internal_ex_backend_p256k1")]
-    table: HashMap<Option<[u8; Coordinate::BYTES_LEN]>, u64>,
-    #[cfg(crypto_backend = "__internal_ex_backend_ristretto255")]
-    table: HashMap<Option<[u8; GroupElement::BYTES_LEN]>, u64>,
+    table: HashMap<Key, u64>,
+    max_value: u64,
+    balance: u64,
     baby_step_size: u64,
     giant_step: GroupElement,
 }
 
+/// Error while loading a serialized `BabyStepsTable`
+#[derive(Debug, thiserror::Error)]
+pub enum BabyStepsTableError {
+    #[error("cannot read the table")]
+    Io(#[from] io::Error),
+    #[error("not a baby steps table")]
+    InvalidMagic,
+    #[error("unsupported table version {0}")]
+    UnsupportedVersion(u8),
+    #[error("the table was generated for the curve {found}, expected {expected}")]
+    CurveMismatch { expected: String, found: String },
+    #[error("invalid table parameters: max value {max_value}, balance {balance}")]
+    InvalidParameters { max_value: u64, balance: u64 },
+    #[error("the baby steps don't match the digest of the table")]
+    DigestMismatch,
+    #[error("invalid baby step {0}")]
+    InvalidEntry(u64),
+}
+
 impl BabyStepsTable {
     /// Generate the table with asymmetrical steps,
     /// optimized for multiple reuse of the same table.
@@ -37,33 +75,188 @@ impl BabyStepsTable {
     /// For example, a balance of 2 means that the table will precompute 2 times more
     /// baby steps than the standard O(sqrt(n)), 1 means symmetrical steps.
     pub fn generate_with_balance(max_value: u64, balance: u64) -> Self {
-        let sqrt_step_size = (max_value as f64).sqrt().ceil() as u64;
-        let baby_step_size = sqrt_step_size * balance;
+        let baby_step_size = baby_step_size(max_value, balance)
+            .expect("the max value and the balance must give a non empty table");
         let mut bs = HashMap::new();
         let gen = GroupElement::generator();
         let mut e = GroupElement::zero();
 
-        // With sec2 curves we can use the property that P and -P share a coordinate
-        #[cfg(crypto_backend = "__internal_ex_backend_p256k1")]
-        for i in 0..=baby_step_size / 2 {
-            bs.insert(e.compress().map(|(c, _sign)| c.to_bytes()), i);
-            e = e + &gen;
-        }
-        // Not with ristretto group. the ristretto group API does not allow to use the x coordinate
-        // for security properties (see [here](https://github.com/dalek-cryptography/curve25519-dalek/issues/235))
-        #[cfg(crypto_backend = "__internal_ex_backend_ristretto255")]
-        for i in 0..=baby_step_size {
-            bs.insert(Some(e.to_bytes()), i);
+        for i in 0..=last_baby_step(baby_step_size) {
+            bs.insert(key(&e), i);
             e = e + &gen;
         }
         assert!(!bs.is_empty());
-        assert!(baby_step_size > 0);
         Self {
             table: bs,
+            max_value,
+            balance,
             baby_step_size,
             giant_step: GroupElement::generator() * Scalar::from_u64(baby_step_size).negate(),
         }
     }
+
+    /// the maximum value the table was generated for
+    pub fn max_value(&self) -> u64 {
+        self.max_value
+    }
+
+    /// the balance the table was generated with
+    pub fn balance(&self) -> u64 {
+        self.balance
+    }
+
+    /// Write the table, so it can be generated once and loaded with
+    /// `read` every time a tally needs to be decrypted.
+    ///
+    /// The table starts with a header recording the curve, the max value,
+    /// the balance and a digest of the baby steps, followed by the keys of
+    /// the baby steps in order.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut keys = vec![None; self.table.len()];
+        for (key, &i) in self.table.iter() {
+            keys[i as usize] = *key;
+        }
+        let mut entries = Vec::with_capacity(keys.len() * (1 + KEY_LEN));
+        for key in keys {
+            match key {
+                None => entries.push(0),
+                Some(bytes) => {
+                    entries.push(1);
+                    entries.extend_from_slice(&bytes);
+                }
+            }
+        }
+        let mut digest = [0; DIGEST_LEN];
+        let mut hasher = Blake2b::new(DIGEST_LEN);
+        hasher.input(&entries);
+        hasher.result(&mut digest);
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, CURVE_HRP.len() as u8])?;
+        writer.write_all(CURVE_HRP.as_bytes())?;
+        writer.write_all(&self.max_value.to_be_bytes())?;
+        writer.write_all(&self.balance.to_be_bytes())?;
+        writer.write_all(&digest)?;
+        writer.write_all(&entries)
+    }
+
+    /// Read a table written with `write`.
+    ///
+    /// The header must match the curve of this build and the baby steps
+    /// must match the digest of the header. Only the first, the last and a
+    /// few random baby steps are recomputed, so reading a table stays much
+    /// cheaper than generating it.
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, BabyStepsTableError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(BabyStepsTableError::InvalidMagic);
+        }
+
+        let mut version_and_len = [0; 2];
+        reader.read_exact(&mut version_and_len)?;
+        let [version, curve_len] = version_and_len;
+        if version != VERSION {
+            return Err(BabyStepsTableError::UnsupportedVersion(version));
+        }
+        let mut curve = vec![0; curve_len as usize];
+        reader.read_exact(&mut curve)?;
+        if curve != CURVE_HRP.as_bytes() {
+            return Err(BabyStepsTableError::CurveMismatch {
+                expected: CURVE_HRP.to_string(),
+                found: String::from_utf8_lossy(&curve).into_owned(),
+            });
+        }
+
+        let max_value = read_u64(&mut reader)?;
+        let balance = read_u64(&mut reader)?;
+        let baby_step_size = baby_step_size(max_value, balance)
+            .ok_or(BabyStepsTableError::InvalidParameters { max_value, balance })?;
+        let mut digest = [0; DIGEST_LEN];
+        reader.read_exact(&mut digest)?;
+
+        let last = last_baby_step(baby_step_size);
+        let mut hasher = Blake2b::new(DIGEST_LEN);
+        let mut keys = Vec::new();
+        let mut table = HashMap::new();
+        for i in 0..=last {
+            let mut flag = [0; 1];
+            reader.read_exact(&mut flag)?;
+            hasher.input(&flag);
+            let entry = match flag[0] {
+                0 => None,
+                1 => {
+                    let mut bytes = [0; KEY_LEN];
+                    reader.read_exact(&mut bytes)?;
+                    hasher.input(&bytes);
+                    Some(bytes)
+                }
+                _ => return Err(BabyStepsTableError::InvalidEntry(i)),
+            };
+            if table.insert(entry, i).is_some() {
+                return Err(BabyStepsTableError::InvalidEntry(i));
+            }
+            keys.push(entry);
+        }
+        let mut computed = [0; DIGEST_LEN];
+        hasher.result(&mut computed);
+        if computed != digest {
+            return Err(BabyStepsTableError::DigestMismatch);
+        }
+
+        let mut rng = rand::thread_rng();
+        let sampled = (0..SAMPLED_ENTRIES).map(|_| rng.gen_range(0..=last));
+        for i in [0, last].iter().copied().chain(sampled) {
+            let e = GroupElement::generator() * Scalar::from_u64(i);
+            if keys[i as usize] != key(&e) {
+                return Err(BabyStepsTableError::InvalidEntry(i));
+            }
+        }
+
+        Ok(Self {
+            table,
+            max_value,
+            balance,
+            baby_step_size,
+            giant_step: GroupElement::generator() * Scalar::from_u64(baby_step_size).negate(),
+        })
+    }
+}
+
+/// the number of baby steps for the given parameters, `None` if the table
+/// would be empty or too large
+fn baby_step_size(max_value: u64, balance: u64) -> Option<u64> {
+    let sqrt_step_size = (max_value as f64).sqrt().ceil() as u64;
+    sqrt_step_size.checked_mul(balance).filter(|size| *size > 0)
+}
+
+// With sec2 curves we can use the property that P and -P share a coordinate
+#[cfg(crypto_backend = "__internal_ex_backend_p256k1")]
+fn last_baby_step(baby_step_size: u64) -> u64 {
+    baby_step_size / 2
+}
+
+// Not with ristretto group. the ristretto group API does not allow to use the x coordinate
+// for security properties (see [here](https://github.com/dalek-cryptography/curve25519-dalek/issues/235))
+#[cfg(crypto_backend = "__internal_ex_backend_ristretto255")]
+fn last_baby_step(baby_step_size: u64) -> u64 {
+    baby_step_size
+}
+
+#[cfg(crypto_backend = "__internal_ex_backend_p256k1")]
+fn key(e: &GroupElement) -> Key {
+    e.compress().map(|(c, _sign)| c.to_bytes())
+}
+
+#[cfg(crypto_backend = "__internal_ex_backend_ristretto255")]
+fn key(e: &GroupElement) -> Key {
+    Some(e.to_bytes())
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_be_bytes(bytes))
 }
 
 #[derive(Debug)]
@@ -84,7 +277,7 @@ pub fn baby_step_giant_step(
             let mut a = 0;
             loop {
                 #[cfg(crypto_backend = "__internal_ex_backend_p256k1")]
-                if let Some(x) = table.get(&point.compress().map(|(c, _sign)| c.to_bytes())) {
+                if let Some(x) = table.get(&key(&point)) {
                     let r = if Scalar::from_u64(*x) * GroupElement::generator() == point {
                         a * baby_step_size + x
                     } else {
@@ -94,7 +287,7 @@ pub fn baby_step_giant_step(
                 }
 
                 #[cfg(crypto_backend = "__internal_ex_backend_ristretto255")]
-                if let Some(x) = table.get(&Some(point.to_bytes())) {
+                if let Some(x) = table.get(&key(&point)) {
                     let r = a * baby_step_size + x;
                     return Ok(r);
                 }
@@ -139,6 +332,107 @@ mod tests {
         assert_eq!(votes, results);
     }
 
+    #[test]
+    fn write_read() {
+        let table = BabyStepsTable::generate_with_balance(1000, 3);
+        let mut bytes = Vec::new();
+        table.write(&mut bytes).unwrap();
+
+        let read = BabyStepsTable::read(bytes.as_slice()).unwrap();
+        assert_eq!(read.max_value(), 1000);
+        assert_eq!(read.balance(), 3);
+        assert_eq!(read.table, table.table);
+        assert_eq!(read.baby_step_size, table.baby_step_size);
+        assert_eq!(read.giant_step, table.giant_step);
+
+        let p = GroupElement::generator();
+        let votes = (0..1000).step_by(7).collect::<Vec<_>>();
+        let points = votes.iter().map(|k| &p * Scalar::from_u64(*k)).collect();
+        assert_eq!(baby_step_giant_step(points, 1000, &read).unwrap(), votes);
+    }
+
+    #[test]
+    fn read_invalid() {
+        let table = BabyStepsTable::generate_with_balance(100, 1);
+        let mut bytes = Vec::new();
+        table.write(&mut bytes).unwrap();
+
+        let mut invalid = bytes.clone();
+        invalid[0] = b'X';
+        assert!(matches!(
+            BabyStepsTable::read(invalid.as_slice()),
+            Err(BabyStepsTableError::InvalidMagic)
+        ));
+
+        let mut invalid = bytes.clone();
+        invalid[4] = VERSION + 1;
+        assert!(matches!(
+            BabyStepsTable::read(invalid.as_slice()),
+            Err(BabyStepsTableError::UnsupportedVersion(_))
+        ));
+
+        let mut invalid = bytes.clone();
+        invalid[6] ^= 1;
+        assert!(matches!(
+            BabyStepsTable::read(invalid.as_slice()),
+            Err(BabyStepsTableError::CurveMismatch { .. })
+        ));
+
+        // claim a larger table than the one written
+        let mut invalid = bytes.clone();
+        let max_value_offset = 6 + CURVE_HRP.len();
+        invalid[max_value_offset + 7] = 200;
+        assert!(BabyStepsTable::read(invalid.as_slice()).is_err());
+
+        // corrupt the last baby step
+        let mut invalid = bytes.clone();
+        *invalid.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            BabyStepsTable::read(invalid.as_slice()),
+            Err(BabyStepsTableError::DigestMismatch)
+        ));
+
+        // corrupt a baby step in the middle of the table
+        let entries_offset = max_value_offset + 16 + DIGEST_LEN;
+        let mut invalid = bytes.clone();
+        let middle = entries_offset + (bytes.len() - entries_offset) / 2;
+        invalid[middle] ^= 1;
+        assert!(matches!(
+            BabyStepsTable::read(invalid.as_slice()),
+            Err(BabyStepsTableError::DigestMismatch)
+        ));
+
+        // corrupt the last baby step along with the digest
+        let mut invalid = bytes.clone();
+        *invalid.last_mut().unwrap() ^= 1;
+        let mut hasher = Blake2b::new(DIGEST_LEN);
+        hasher.input(&invalid[entries_offset..]);
+        hasher.result(&mut invalid[entries_offset - DIGEST_LEN..entries_offset]);
+        assert!(matches!(
+            BabyStepsTable::read(invalid.as_slice()),
+            Err(BabyStepsTableError::InvalidEntry(i)) if i == last_baby_step(table.baby_step_size)
+        ));
+
+        assert!(matches!(
+            BabyStepsTable::read(&bytes[..bytes.len() - 1]),
+            Err(BabyStepsTableError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn write_read_smallest_table() {
+        let table = BabyStepsTable::generate_with_balance(1, 1);
+        let mut bytes = Vec::new();
+        table.write(&mut bytes).unwrap();
+
+        let read = BabyStepsTable::read(bytes.as_slice()).unwrap();
+        assert_eq!(read.table, table.table);
+        assert_eq!(read.baby_step_size, 1);
+
+        let points = vec![GroupElement::zero(), GroupElement::generator()];
+        assert_eq!(baby_step_giant_step(points, 1, &read).unwrap(), vec![0, 1]);
+    }
+
     fn fe_vec_generator() -> BoxGenerator<[(GroupElement, u64); 64]> {
         generator::Array64::new(generator::num::<u16>().map(|a| {
             (
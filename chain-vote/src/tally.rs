@@ -172,6 +172,15 @@ impl EncryptedTally {
         })
     }
 
+    /// Number of options of the tally
+    pub fn options(&self) -> usize {
+        self.r.len()
+    }
+
+    pub(crate) fn fingerprint(&self) -> &ElectionFingerprint {
+        &self.fingerprint
+    }
+
     /// Returns a byte array with every ciphertext in the `EncryptedTally`
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes: Vec<u8> = Vec::with_capacity(
@@ -187,7 +196,9 @@ impl EncryptedTally {
     /// Tries to generate an `EncryptedTally` out of an array of bytes. Returns `None` if the
     /// size of the byte array is not a multiply of `Ciphertext::BYTES_LEN`.
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if (bytes.len() - ElectionFingerprint::BYTES_LEN) % Ciphertext::BYTES_LEN != 0 {
+        if bytes.len() < ElectionFingerprint::BYTES_LEN
+            || (bytes.len() - ElectionFingerprint::BYTES_LEN) % Ciphertext::BYTES_LEN != 0
+        {
             return None;
         }
         let fingerprint =
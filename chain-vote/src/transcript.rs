@@ -0,0 +1,350 @@
+//! Transcript of the decryption of the tallies of an election.
+//!
+//! The transcript holds everything needed to check the results of an
+//! election without access to the ballots or to a node: the committee
+//! keys, the encrypted tally of every proposal, the decryption shares of
+//! every committee member (with their proofs of correct decryption) and
+//! the final results.
+use crate::{
+    committee::{ElectionPublicKey, MemberPublicKey},
+    tally::{Crs, ElectionFingerprint, EncryptedTally, Tally, TallyDecryptShare},
+    GroupElement, CURVE_HRP,
+};
+use std::convert::TryInto;
+
+const VERSION: u8 = 1;
+
+/// Transcript of the decryption of the tallies of all the proposals of an
+/// election, see `verify_transcript`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TallyTranscript {
+    crs: Crs,
+    committee: Vec<MemberPublicKey>,
+    proposals: Vec<ProposalTranscript>,
+}
+
+/// Decryption of the tally of one proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalTranscript {
+    pub encrypted_tally: EncryptedTally,
+    pub decrypt_shares: Vec<TallyDecryptShare>,
+    pub tally: Tally,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TranscriptError {
+    #[error("the transcript has no committee member")]
+    NoCommittee,
+    #[error("the encrypted tally of proposal {proposal} is not for this election")]
+    ElectionMismatch { proposal: usize },
+    #[error("proposal {proposal} has {actual} decryption shares, expected {expected}")]
+    InvalidSharesCount {
+        proposal: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("the decryption shares or results of proposal {proposal} do not match its encrypted tally size")]
+    InvalidOptionsCount { proposal: usize },
+    #[error("the results of proposal {proposal} are not the decryption of its encrypted tally")]
+    InvalidDecryption { proposal: usize },
+}
+
+impl TallyTranscript {
+    pub fn new(
+        crs: Crs,
+        committee: Vec<MemberPublicKey>,
+        proposals: Vec<ProposalTranscript>,
+    ) -> Self {
+        Self {
+            crs,
+            committee,
+            proposals,
+        }
+    }
+
+    pub fn crs(&self) -> &Crs {
+        &self.crs
+    }
+
+    pub fn committee(&self) -> &[MemberPublicKey] {
+        &self.committee
+    }
+
+    pub fn proposals(&self) -> &[ProposalTranscript] {
+        &self.proposals
+    }
+
+    /// Returns the versioned byte representation of the transcript
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![VERSION, CURVE_HRP.len() as u8];
+        bytes.extend_from_slice(CURVE_HRP.as_bytes());
+        bytes.extend_from_slice(&self.crs.to_bytes());
+
+        bytes.extend_from_slice(&(self.committee.len() as u32).to_be_bytes());
+        for member in self.committee.iter() {
+            put_sized(&mut bytes, &member.to_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.proposals.len() as u32).to_be_bytes());
+        for proposal in self.proposals.iter() {
+            put_sized(&mut bytes, &proposal.encrypted_tally.to_bytes());
+            bytes.extend_from_slice(&(proposal.decrypt_shares.len() as u32).to_be_bytes());
+            for share in proposal.decrypt_shares.iter() {
+                put_sized(&mut bytes, &share.to_bytes());
+            }
+            bytes.extend_from_slice(&(proposal.tally.votes.len() as u32).to_be_bytes());
+            for votes in proposal.tally.votes.iter() {
+                bytes.extend_from_slice(&votes.to_be_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Tries to read a transcript from its byte representation. Returns
+    /// `None` if the bytes are malformed, of an unknown version or for
+    /// another curve.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+
+        if reader.u8()? != VERSION {
+            return None;
+        }
+        let curve_len = reader.u8()? as usize;
+        if reader.take(curve_len)? != CURVE_HRP.as_bytes() {
+            return None;
+        }
+        let crs = GroupElement::from_bytes(reader.take(GroupElement::BYTES_LEN)?)?;
+
+        let committee = (0..reader.u32()?)
+            .map(|_| MemberPublicKey::from_bytes(reader.sized()?))
+            .collect::<Option<Vec<_>>>()?;
+
+        let proposals = (0..reader.u32()?)
+            .map(|_| {
+                let encrypted_tally = EncryptedTally::from_bytes(reader.sized()?)?;
+                let decrypt_shares = (0..reader.u32()?)
+                    .map(|_| TallyDecryptShare::from_bytes(reader.sized()?))
+                    .collect::<Option<Vec<_>>>()?;
+                let votes = (0..reader.u32()?)
+                    .map(|_| reader.u64())
+                    .collect::<Option<Vec<_>>>()?;
+                Some(ProposalTranscript {
+                    encrypted_tally,
+                    decrypt_shares,
+                    tally: Tally { votes },
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if !reader.0.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            crs,
+            committee,
+            proposals,
+        })
+    }
+}
+
+/// Verify a transcript end to end: every encrypted tally was computed for
+/// the election public key of the committee and the crs, every committee
+/// member provided a valid decryption share for every proposal, and the
+/// results are the decryption of the encrypted tallies.
+pub fn verify_transcript(transcript: &TallyTranscript) -> Result<(), TranscriptError> {
+    if transcript.committee.is_empty() {
+        return Err(TranscriptError::NoCommittee);
+    }
+    let election_pk = ElectionPublicKey::from_participants(&transcript.committee);
+    let fingerprint = ElectionFingerprint::from((&election_pk, &transcript.crs));
+
+    for (index, proposal) in transcript.proposals.iter().enumerate() {
+        if proposal.encrypted_tally.fingerprint() != &fingerprint {
+            return Err(TranscriptError::ElectionMismatch { proposal: index });
+        }
+
+        if proposal.decrypt_shares.len() != transcript.committee.len() {
+            return Err(TranscriptError::InvalidSharesCount {
+                proposal: index,
+                expected: transcript.committee.len(),
+                actual: proposal.decrypt_shares.len(),
+            });
+        }
+
+        let options = proposal.encrypted_tally.options();
+        if proposal.tally.votes.len() != options
+            || proposal
+                .decrypt_shares
+                .iter()
+                .any(|share| share.options() != options)
+        {
+            return Err(TranscriptError::InvalidOptionsCount { proposal: index });
+        }
+
+        if !proposal.tally.verify(
+            &proposal.encrypted_tally,
+            &transcript.committee,
+            &proposal.decrypt_shares,
+        ) {
+            return Err(TranscriptError::InvalidDecryption { proposal: index });
+        }
+    }
+
+    Ok(())
+}
+
+fn put_sized(bytes: &mut Vec<u8>, data: &[u8]) {
+    bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(data);
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|b| u64::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn sized(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemberCommunicationKey, MemberState, Vote};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn transcript() -> TallyTranscript {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = Crs::from_hash(b"transcript election");
+
+        let mc = [
+            MemberCommunicationKey::new(&mut rng),
+            MemberCommunicationKey::new(&mut rng),
+        ];
+        let mc = mc.iter().map(|k| k.to_public()).collect::<Vec<_>>();
+        let members = (0..mc.len())
+            .map(|i| MemberState::new(&mut rng, 2, &crs, &mc, i))
+            .collect::<Vec<_>>();
+        let committee = members.iter().map(|m| m.public_key()).collect::<Vec<_>>();
+        let election_pk = ElectionPublicKey::from_participants(&committee);
+
+        let proposals = [(0, 1), (2, 0)]
+            .iter()
+            .map(|&(first, second)| {
+                let mut encrypted_tally = EncryptedTally::new(3, election_pk.clone(), crs.clone());
+                for (choice, weight) in [(first, 3), (second, 4)].iter() {
+                    let (vote, proof) =
+                        election_pk.encrypt_and_prove_vote(&mut rng, &crs, Vote::new(3, *choice));
+                    let ballot =
+                        crate::Ballot::try_from_vote_and_proof(vote, &proof, &crs, &election_pk)
+                            .unwrap();
+                    encrypted_tally.add(&ballot, *weight);
+                }
+                let decrypt_shares = members
+                    .iter()
+                    .map(|m| encrypted_tally.partial_decrypt(&mut rng, m.secret_key()))
+                    .collect::<Vec<_>>();
+                let table = crate::TallyOptimizationTable::generate_with_balance(7, 1);
+                let tally = encrypted_tally
+                    .validate_partial_decryptions(&committee, &decrypt_shares)
+                    .unwrap()
+                    .decrypt_tally(7, &table)
+                    .unwrap();
+                ProposalTranscript {
+                    encrypted_tally,
+                    decrypt_shares,
+                    tally,
+                }
+            })
+            .collect();
+
+        TallyTranscript::new(crs, committee, proposals)
+    }
+
+    #[test]
+    fn verify() {
+        let transcript = transcript();
+        assert_eq!(transcript.proposals()[0].tally.votes, vec![3, 4, 0]);
+        assert_eq!(verify_transcript(&transcript), Ok(()));
+    }
+
+    #[test]
+    fn serialization() {
+        let transcript = transcript();
+        let bytes = transcript.to_bytes();
+        assert_eq!(TallyTranscript::from_bytes(&bytes), Some(transcript));
+
+        assert_eq!(TallyTranscript::from_bytes(&bytes[..bytes.len() - 1]), None);
+        let mut invalid = bytes.clone();
+        invalid[0] = VERSION + 1;
+        assert_eq!(TallyTranscript::from_bytes(&invalid), None);
+        let mut invalid = bytes;
+        invalid.push(0);
+        assert_eq!(TallyTranscript::from_bytes(&invalid), None);
+    }
+
+    #[test]
+    fn verify_invalid() {
+        let transcript = transcript();
+
+        let mut invalid = transcript.clone();
+        invalid.proposals[1].tally.votes[0] += 1;
+        assert_eq!(
+            verify_transcript(&invalid),
+            Err(TranscriptError::InvalidDecryption { proposal: 1 })
+        );
+
+        let mut invalid = transcript.clone();
+        invalid.proposals[0].decrypt_shares.swap(0, 1);
+        assert_eq!(
+            verify_transcript(&invalid),
+            Err(TranscriptError::InvalidDecryption { proposal: 0 })
+        );
+
+        let mut invalid = transcript.clone();
+        invalid.proposals[0].decrypt_shares.pop();
+        assert!(matches!(
+            verify_transcript(&invalid),
+            Err(TranscriptError::InvalidSharesCount { proposal: 0, .. })
+        ));
+
+        let mut invalid = transcript.clone();
+        invalid.crs = Crs::from_hash(b"another election");
+        assert_eq!(
+            verify_transcript(&invalid),
+            Err(TranscriptError::ElectionMismatch { proposal: 0 })
+        );
+
+        let mut invalid = transcript;
+        invalid.committee.clear();
+        assert_eq!(
+            verify_transcript(&invalid),
+            Err(TranscriptError::NoCommittee)
+        );
+    }
+}